@@ -1,5 +1,47 @@
 #![no_std]
 
+/// `PROCESS_NAMES_TO_MONITOR` map 的最大条目数，内核侧与用户态共用。
+/// 默认 1024，可在构建时通过环境变量 `HEALER_EBPF_MAP_CAPACITY` 调整。
+pub const PROCESS_NAME_MAP_CAPACITY: u32 = match option_env!("HEALER_EBPF_MAP_CAPACITY") {
+    Some(value) => parse_capacity(value),
+    None => 1024,
+};
+
+// 编译期解析容量，非法取值直接让构建失败
+const fn parse_capacity(value: &str) -> u32 {
+    let bytes = value.as_bytes();
+    assert!(
+        !bytes.is_empty(),
+        "HEALER_EBPF_MAP_CAPACITY must not be empty"
+    );
+    let mut result: u32 = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let digit = bytes[i];
+        assert!(
+            digit.is_ascii_digit(),
+            "HEALER_EBPF_MAP_CAPACITY must be a decimal number"
+        );
+        result = match result.checked_mul(10) {
+            Some(v) => match v.checked_add((digit - b'0') as u32) {
+                Some(v) => v,
+                None => panic!("HEALER_EBPF_MAP_CAPACITY is too large"),
+            },
+            None => panic!("HEALER_EBPF_MAP_CAPACITY is too large"),
+        };
+        i += 1;
+    }
+    assert!(result > 0, "HEALER_EBPF_MAP_CAPACITY must be positive");
+    result
+}
+
+/// `SETTINGS` map 的条目数
+pub const SETTINGS_LEN: u32 = 1;
+
+/// `SETTINGS` 中的下标：非 0 时上报所有进程的退出，由用户态按 comm 匹配方式过滤
+/// （prefix / regex 无法在内核侧通过进程名 map 预先过滤）
+pub const SETTING_REPORT_ALL_EXITS: u32 = 0;
+
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct ProcessExitEvent {
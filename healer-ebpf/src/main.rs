@@ -5,30 +5,48 @@
 #[cfg(feature = "build-ebpf")]
 mod ebpf {
     use aya_ebpf::{
-        macros::{map, tracepoint},
-        maps::{HashMap, PerfEventArray},
-        programs::TracePointContext,
+        macros::{kprobe, map, tracepoint},
+        maps::{Array, HashMap, PerfEventArray},
+        programs::{ProbeContext, TracePointContext},
         EbpfContext,
     };
     use aya_log_ebpf::info;
-    use healer_common::ProcessExitEvent;
+    use healer_common::{
+        ProcessExitEvent, PROCESS_NAME_MAP_CAPACITY, SETTINGS_LEN, SETTING_REPORT_ALL_EXITS,
+    };
 
     // 存储要监控的进程名（截断到15个字符）
     #[map]
-    static PROCESS_NAMES_TO_MONITOR: HashMap<[u8; 16], u8> = HashMap::with_max_entries(1024, 0);
+    static PROCESS_NAMES_TO_MONITOR: HashMap<[u8; 16], u8> =
+        HashMap::with_max_entries(PROCESS_NAME_MAP_CAPACITY, 0);
 
     #[map]
     static EVENTS: PerfEventArray<ProcessExitEvent> = PerfEventArray::new(0);
 
+    // 用户态写入的运行时开关，见 healer_common::SETTING_*
+    #[map]
+    static SETTINGS: Array<u32> = Array::with_max_entries(SETTINGS_LEN, 0);
+
+    // 默认挂载点：tracepoint sched:sched_process_exit
     #[tracepoint]
     pub fn healer_exit(ctx: TracePointContext) -> u32 {
-        match try_healer_exit(ctx) {
+        match try_healer_exit(&ctx) {
+            Ok(ret) => ret,
+            Err(ret) => ret,
+        }
+    }
+
+    // 备选挂载点：kprobe do_exit，用于 tracepoint 不可用或不可靠的内核
+    #[kprobe]
+    pub fn healer_exit_kprobe(ctx: ProbeContext) -> u32 {
+        match try_healer_exit(&ctx) {
             Ok(ret) => ret,
             Err(ret) => ret,
         }
     }
 
-    fn try_healer_exit(ctx: TracePointContext) -> Result<u32, u32> {
+    // 两种挂载点都在退出进程自身的上下文中执行，过滤与上报逻辑共用
+    fn try_healer_exit<C: EbpfContext>(ctx: &C) -> Result<u32, u32> {
         let pid = ctx.pid();
         let tgid = ctx.tgid();
 
@@ -41,13 +59,16 @@ mod ebpf {
             Err(_) => return Ok(0),
         };
 
-        // 检查这个进程名是否在监控列表中
-        if unsafe { PROCESS_NAMES_TO_MONITOR.get(&comm) }.is_some() {
-            info!(&ctx, "Monitored process detected");
+        // 检查这个进程名是否在监控列表中；存在 prefix / regex 匹配时所有退出都交给用户态判断
+        let report_all = SETTINGS
+            .get(SETTING_REPORT_ALL_EXITS)
+            .is_some_and(|value| *value != 0);
+        if report_all || unsafe { PROCESS_NAMES_TO_MONITOR.get(&comm) }.is_some() {
+            info!(ctx, "Monitored process detected");
 
             // 发送包含进程名的事件
             let event = ProcessExitEvent { pid, comm };
-            EVENTS.output(&ctx, &event, 0);
+            EVENTS.output(ctx, &event, 0);
         }
 
         Ok(0)
@@ -10,5 +10,7 @@ fn main() -> anyhow::Result<()> {
         .into_iter()
         .find(|cargo_metadata::Package { name, .. }| name.as_str() == "healer-ebpf")
         .ok_or_else(|| anyhow!("healer-ebpf package not found"))?;
+    // map 容量在编译期确定，变化时需要重新构建 eBPF 程序
+    println!("cargo:rerun-if-env-changed=HEALER_EBPF_MAP_CAPACITY");
     aya_build::build_ebpf([ebpf_package], Toolchain::default())
 }
@@ -0,0 +1,188 @@
+//! 库入口：在其他程序中以编程方式构建并运行 healer，而无需启动守护进程二进制。
+//!
+//! ```no_run
+//! # async fn demo(config: healer::config::AppConfig) -> anyhow::Result<()> {
+//! let handle = healer::Healer::builder().config(config).build().run().await?;
+//! // ... 运行期间可调用 handle.reload()
+//! handle.shutdown().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    config::AppConfig,
+    config_manager::ReloadStats,
+    core_logic::{CoreRuntime, RuntimeOptions},
+    health::HealthRegistry,
+    metrics::Metrics,
+    signal_handler::SignalEvent,
+    subscriber::event_history::EventHistory,
+};
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+
+/// 可嵌入的 healer 实例，通过 [`Healer::builder`] 构建。
+pub struct Healer {
+    config: Option<AppConfig>,
+    config_path: Option<PathBuf>,
+    enable_ebpf: bool,
+    foreground: bool,
+    handle_signals: bool,
+}
+
+/// [`Healer`] 的构建器。
+pub struct HealerBuilder {
+    config: Option<AppConfig>,
+    config_path: Option<PathBuf>,
+    enable_ebpf: bool,
+    foreground: bool,
+    handle_signals: bool,
+}
+
+/// 运行中的 healer 句柄，用于触发重载或关闭。
+pub struct HealerHandle {
+    config: Arc<RwLock<AppConfig>>,
+    health: HealthRegistry,
+    history: EventHistory,
+    metrics: Metrics,
+    reload_stats: ReloadStats,
+    control_tx: mpsc::Sender<SignalEvent>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl Healer {
+    pub fn builder() -> HealerBuilder {
+        HealerBuilder {
+            config: None,
+            config_path: None,
+            enable_ebpf: true,
+            foreground: true,
+            handle_signals: false,
+        }
+    }
+
+    /// 完成初始化（事件总线、后台服务、初始协调）后返回句柄，主循环在后台任务中运行。
+    pub async fn run(self) -> Result<HealerHandle> {
+        let config = self
+            .config
+            .ok_or_else(|| anyhow!("Healer: no configuration provided to the builder"))?;
+        let signals = config.signals.clone();
+        let shared_config = Arc::new(RwLock::new(config));
+
+        let options = RuntimeOptions {
+            config_path: self.config_path,
+            config_format: None,
+            enable_ebpf: self.enable_ebpf,
+            foreground: self.foreground,
+            drop_privileges: true,
+        };
+        let core = CoreRuntime::start(Arc::clone(&shared_config), options).await?;
+        let health = core.health();
+        let history = core.history();
+        let metrics = core.metrics();
+        let reload_stats = core.reload_stats();
+
+        let (control_tx, control_rx) = mpsc::channel(8);
+        if self.handle_signals {
+            crate::signal_handler::SignalHandler::spawn_forwarder(control_tx.clone(), &signals);
+        }
+        let task = tokio::spawn(core.run_until_shutdown(control_rx));
+
+        Ok(HealerHandle {
+            config: shared_config,
+            health,
+            history,
+            metrics,
+            reload_stats,
+            control_tx,
+            task,
+        })
+    }
+}
+
+impl HealerBuilder {
+    /// 运行所用的配置（必填）。
+    pub fn config(mut self, config: AppConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// 配置文件路径；设置后 [`HealerHandle::reload`] 会从该文件重新加载配置。
+    pub fn config_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_path = Some(path.into());
+        self
+    }
+
+    /// 是否尝试加载全局 eBPF 监控器（需要 root），默认开启。
+    pub fn enable_ebpf(mut self, enable: bool) -> Self {
+        self.enable_ebpf = enable;
+        self
+    }
+
+    /// 宿主程序是否拥有可供子进程继承的 stdio（影响 child_output: inherit），默认开启。
+    pub fn foreground(mut self, foreground: bool) -> Self {
+        self.foreground = foreground;
+        self
+    }
+
+    /// 是否由 healer 自行处理重载与关闭信号（见 `AppConfig::signals`），默认关闭，由宿主程序决定。
+    pub fn handle_signals(mut self, enable: bool) -> Self {
+        self.handle_signals = enable;
+        self
+    }
+
+    pub fn build(self) -> Healer {
+        Healer {
+            config: self.config,
+            config_path: self.config_path,
+            enable_ebpf: self.enable_ebpf,
+            foreground: self.foreground,
+            handle_signals: self.handle_signals,
+        }
+    }
+}
+
+impl HealerHandle {
+    /// 运行时共享的配置。
+    pub fn config(&self) -> Arc<RwLock<AppConfig>> {
+        Arc::clone(&self.config)
+    }
+
+    /// 各进程的健康状态，可区分尚未确认（Unknown）与已确认健康（Healthy）。
+    pub fn health(&self) -> HealthRegistry {
+        self.health.clone()
+    }
+
+    /// 监控器发出的最近事件（容量由 `control.event_history_capacity` 决定）。
+    pub fn event_history(&self) -> EventHistory {
+        self.history.clone()
+    }
+
+    /// 按进程统计的延迟直方图：检测到故障到执行恢复、协调器延后恢复的时长。
+    pub fn metrics(&self) -> Metrics {
+        self.metrics.clone()
+    }
+
+    /// 实际生效的重载次数与被 `reload_debounce_ms` 合并的请求数。
+    pub fn reload_stats(&self) -> ReloadStats {
+        self.reload_stats.clone()
+    }
+
+    /// 请求重新加载配置文件并重新协调监控器。
+    pub async fn reload(&self) -> Result<()> {
+        self.control_tx
+            .send(SignalEvent::ConfigReload)
+            .await
+            .map_err(|_| anyhow!("Healer: runtime is no longer running"))
+    }
+
+    /// 请求优雅关闭并等待主循环退出。
+    pub async fn shutdown(self) -> Result<()> {
+        // 主循环可能已经退出，此时直接等待其结果即可
+        let _ = self.control_tx.send(SignalEvent::Shutdown).await;
+        self.task.await?
+    }
+}
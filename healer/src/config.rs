@@ -1,21 +1,401 @@
 use crate::daemon_handler::DaemonConfig;
 use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 
 // 顶层配置结构体
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct AppConfig {
-    #[allow(dead_code)] // Reserved for future use
+    /// 日志级别：单个级别（`"debug"`）或按 target 的指令（`"info,healer::monitor=debug"`）；设置了 RUST_LOG 时被其覆盖
     pub log_level: Option<String>,
     pub log_directory: Option<PathBuf>,
     pub pid_file_directory: Option<PathBuf>,
     pub processes: Vec<ProcessConfig>,
     pub working_directory: Option<PathBuf>,
+    /// 配置文件无法加载时，启动回退到最近一次成功加载的配置（`<配置文件>.last-good`）；
+    /// 开启后每次成功加载或重载都会更新该缓存
+    #[serde(default)]
+    pub allow_config_fallback: bool,
+    /// 全局开关：对所有进程禁止在无法降权时回退为 root 运行
+    #[serde(default)]
+    pub forbid_root_fallback: bool,
+    /// 命名的重启组，组内进程共享重试预算与冷却期
+    #[serde(default)]
+    pub restart_groups: Vec<RestartGroupConfig>,
+    /// eBPF 退出检测的挂载点，仅在启动时生效
+    #[serde(default)]
+    pub ebpf_attach: EbpfAttach,
+    /// 读取 eBPF perf buffer 的任务数上限，未设置时每个在线 CPU 一个任务；仅在启动时生效
+    #[serde(default)]
+    pub ebpf_max_workers: Option<usize>,
+    /// 空闲的 eBPF worker 定期读取 perf buffer 的间隔（毫秒），默认 1000；仅在启动时生效
+    #[serde(default = "default_ebpf_poll_interval_ms")]
+    pub ebpf_poll_interval_ms: u64,
+    /// 控制套接字与事件历史
+    #[serde(default)]
+    pub control: ControlConfig,
+    /// 关键订阅者（协调器、自愈器）事件通道关闭时的处理策略，仅在启动时生效
+    #[serde(default)]
+    pub subscribers: SubscriberPolicies,
+    /// tokio 工作线程数，不设置时等于 CPU 数；仅在启动时生效
+    #[serde(default)]
+    pub runtime_worker_threads: Option<usize>,
+    /// 被恢复进程日志目录（/var/log/healer）的总大小上限（字节），超出时删除最旧的文件；不设置时不限制
+    #[serde(default)]
+    pub max_log_dir_bytes: Option<u64>,
+    /// 日志目录所在文件系统的可用空间低于该值（字节）时，被恢复进程的输出改为丢弃而不写入文件，恢复照常执行；不设置时不检查
+    #[serde(default)]
+    pub min_free_bytes: Option<u64>,
+    /// 主循环心跳超过该时长（秒）未更新时以非零退出码退出，交由 systemd 等外部监管者重启；不设置时不启用
+    #[serde(default)]
+    pub watchdog_timeout_secs: Option<u64>,
+    /// 防抖动参数的全局默认值，可在各进程的 recovery 中单独覆盖
+    #[serde(default)]
+    pub recovery_defaults: RecoveryDefaults,
+    /// 把监控事件转发到外部消息总线，不设置时不转发；仅在启动时生效
+    #[serde(default)]
+    pub event_forwarder: Option<EventForwarderConfig>,
+    /// 触发配置重载与关闭的信号，仅在启动时生效
+    #[serde(default)]
+    pub signals: SignalConfig,
+    /// 本实例的标签（如 datacenter、role、env），附加在日志、转发的事件与延迟指标上；仅在启动时生效
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// 没有启用任何进程时拒绝该配置（启动失败、重载被拒绝）；默认只输出警告
+    #[serde(default)]
+    pub require_processes: bool,
+    /// 按 target 单独设置的日志级别（target -> 级别），追加在 log_level 之后；仅在启动时生效
+    #[serde(default)]
+    pub log_targets: HashMap<String, String>,
+    /// 附加配置目录（类似 systemd 的 `*.d`）：按文件名顺序合并其中的 `*.yaml` / `*.yml`，
+    /// 同名进程由后加载的文件覆盖，新进程追加在后；相对路径相对于主配置文件所在目录
+    #[serde(default)]
+    pub include_dir: Option<PathBuf>,
+    /// 定期以 JSON 快照（进程、健康状态、最近事件、运行时长）原子地改写该文件，供只能抓取文件的监控系统读取；不设置时不写
+    #[serde(default)]
+    pub status_file: Option<PathBuf>,
+    /// 改写 status_file 的间隔（秒），默认 5
+    #[serde(default = "default_status_file_interval_secs")]
+    pub status_file_interval_secs: u64,
+    /// 初始化完成（eBPF 已挂载、套接字已绑定）后守护进程自身切换到的用户；不设置时保持启动时的身份。仅在启动时生效
+    #[serde(default)]
+    pub daemon_user: Option<String>,
+    /// 同时切换到的组，不设置时使用 daemon_user 的主组
+    #[serde(default)]
+    pub daemon_group: Option<String>,
+    /// 恢复拉起的进程经两次 fork 脱离 healer（新会话、父进程变为 init），healer 重启或升级时不随之退出；
+    /// 启动时按 PID 文件（及 reconcile_pid_file）重新登记仍在运行的进程。healer 无法再取得它们的退出状态
+    #[serde(default)]
+    pub reparent_children: bool,
+    /// 允许执行的命令：绝对路径或目录前缀（按路径分段比较）。恢复命令不在其中时拒绝恢复并按一次失败计入；
+    /// recovery_guard 与 pid_source 的命令须为其中的绝对路径，否则配置校验失败。不设置时不限制
+    #[serde(default)]
+    pub allowed_commands: Option<Vec<PathBuf>>,
+    /// 连续该时长（秒）没有任何启用的进程需要监控时正常退出（退出码 0），由 socket 激活或 cron 等在需要时重新拉起；
+    /// 期间的任何重载、控制命令都会重新计时。不设置时一直运行
+    #[serde(default)]
+    pub exit_when_idle_secs: Option<u64>,
+    /// 重载请求（信号、控制命令）的合并窗口（毫秒）：第一次请求后等待该时长再重载一次，窗口内的其余请求合并到这一次；
+    /// 0（默认）表示每个请求立即重载
+    #[serde(default)]
+    pub reload_debounce_ms: u64,
+}
+
+/// 各字段取配置文件中省略时的默认值、不含任何进程，便于以代码构造配置时只写需要的字段
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            log_level: None,
+            log_directory: None,
+            pid_file_directory: None,
+            processes: Vec::new(),
+            working_directory: None,
+            allow_config_fallback: false,
+            forbid_root_fallback: false,
+            restart_groups: Vec::new(),
+            ebpf_attach: EbpfAttach::default(),
+            ebpf_max_workers: None,
+            ebpf_poll_interval_ms: default_ebpf_poll_interval_ms(),
+            control: ControlConfig::default(),
+            subscribers: SubscriberPolicies::default(),
+            runtime_worker_threads: None,
+            max_log_dir_bytes: None,
+            min_free_bytes: None,
+            watchdog_timeout_secs: None,
+            recovery_defaults: RecoveryDefaults::default(),
+            event_forwarder: None,
+            signals: SignalConfig::default(),
+            labels: HashMap::new(),
+            require_processes: false,
+            log_targets: HashMap::new(),
+            include_dir: None,
+            status_file: None,
+            status_file_interval_secs: default_status_file_interval_secs(),
+            daemon_user: None,
+            daemon_group: None,
+            reparent_children: false,
+            allowed_commands: None,
+            exit_when_idle_secs: None,
+            reload_debounce_ms: 0,
+        }
+    }
+}
+
+/// 触发配置重载与关闭的信号，默认 SIGHUP 重载，SIGTERM / SIGINT 关闭
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SignalConfig {
+    #[serde(default = "default_reload_signals")]
+    pub reload: Vec<SignalName>,
+    #[serde(default = "default_shutdown_signals")]
+    pub shutdown: Vec<SignalName>,
+}
+
+impl Default for SignalConfig {
+    fn default() -> Self {
+        Self {
+            reload: default_reload_signals(),
+            shutdown: default_shutdown_signals(),
+        }
+    }
+}
+
+/// 信号名，接受 `SIGUSR1`、`USR1`、`usr1` 等写法
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(try_from = "String")]
+pub struct SignalName(nix::sys::signal::Signal);
+
+impl SignalName {
+    pub fn signal(self) -> nix::sys::signal::Signal {
+        self.0
+    }
+}
+
+impl std::str::FromStr for SignalName {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let upper = name.trim().to_ascii_uppercase();
+        let full = if upper.starts_with("SIG") {
+            upper
+        } else {
+            format!("SIG{}", upper)
+        };
+        full.parse()
+            .map(SignalName)
+            .map_err(|_| format!("unknown signal '{}'", name))
+    }
+}
+
+impl TryFrom<String> for SignalName {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        name.parse()
+    }
+}
+
+impl std::fmt::Display for SignalName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.as_str())
+    }
 }
 
+/// 文件创建掩码，接受 `"0022"`、`"022"`、`"0o022"` 等八进制写法
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct Umask(u32);
+
+impl Umask {
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+}
+
+impl std::str::FromStr for Umask {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let digits = value.trim();
+        let digits = digits.strip_prefix("0o").unwrap_or(digits);
+        match u32::from_str_radix(digits, 8) {
+            Ok(bits) if bits <= 0o777 => Ok(Umask(bits)),
+            _ => Err(format!(
+                "invalid umask '{}', expected an octal value between 000 and 777",
+                value
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Umask {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl std::fmt::Display for Umask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04o}", self.0)
+    }
+}
+
+/// 事件转发：以 JSON 发布监控器发出的每个事件
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EventForwarderConfig {
+    pub backend: ForwarderBackend,
+    /// 消息总线地址（`host:port`）
+    pub address: String,
+    /// channel 模板，可使用 `{host}`、`{event}`（如 process_down）与 `{process}` 占位符
+    #[serde(default = "default_forwarder_channel")]
+    pub channel: String,
+    /// 总线不可用时在本地缓存的事件条数上限，超出时丢弃新事件
+    #[serde(default = "default_forwarder_buffer_capacity")]
+    pub buffer_capacity: usize,
+    /// 只转发匹配的事件，默认全部转发
+    #[serde(default)]
+    pub filter: EventFilterConfig,
+}
+
+/// 观察者（事件转发、事件历史）只接收的事件：两项都是白名单，为空时不限制；两项同时设置时需都匹配
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilterConfig {
+    /// 进程名；设置后与具体进程无关的事件也不再接收
+    #[serde(default)]
+    pub processes: Vec<String>,
+    /// 事件类型，如 `process_down`、`process_up`（见 [`crate::event_bus::EVENT_KINDS`]）
+    #[serde(default)]
+    pub kinds: Vec<String>,
+}
+
+impl EventFilterConfig {
+    // 事件类型必须是已知的取值，拼错时不会静默地过滤掉所有事件
+    fn validate(&self, field: &str) -> Result<(), ConfigError> {
+        use crate::event_bus::EVENT_KINDS;
+        match self
+            .kinds
+            .iter()
+            .find(|kind| !EVENT_KINDS.contains(&kind.as_str()))
+        {
+            Some(kind) => Err(ConfigError::Validation(format!(
+                "{}: unknown event kind '{}', expected one of {}",
+                field,
+                kind,
+                EVENT_KINDS.join(", ")
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 事件转发的消息总线类型
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ForwarderBackend {
+    /// Redis pub/sub（`PUBLISH`）
+    Redis,
+}
+
+/// 防抖动参数；作为全局默认值时各字段可省略，也用于表示合并进程覆盖值之后的结果
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryDefaults {
+    /// 去重窗口（秒）：执行恢复后窗口内再次收到的同一进程的故障事件直接忽略，0 表示不去重
+    #[serde(default)]
+    pub dedup_window_secs: u64,
+    /// 熔断后的冷却期（秒），默认 180
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// 冷却期结束后的半开观察窗口（秒）：窗口内再次故障则重新熔断，默认 2
+    #[serde(default = "default_half_open_window_secs")]
+    pub half_open_window_secs: u64,
+}
+
+impl Default for RecoveryDefaults {
+    fn default() -> Self {
+        Self {
+            dedup_window_secs: 0,
+            cooldown_secs: default_cooldown_secs(),
+            half_open_window_secs: default_half_open_window_secs(),
+        }
+    }
+}
+
+/// 各关键订阅者的通道关闭策略与事件投递方式
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubscriberPolicies {
+    #[serde(default)]
+    pub coordinator: ClosedPolicy,
+    #[serde(default)]
+    pub healer: ClosedPolicy,
+    #[serde(default)]
+    pub delivery: EventDelivery,
+}
+
+/// 关键订阅者（协调器、自愈器）接收事件的方式
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EventDelivery {
+    /// 每个关键订阅者一个有界队列：不丢事件，消费慢时监控器发布事件会等待（背压）
+    #[default]
+    Queued,
+    /// 与非关键观察者共用 broadcast：监控器从不等待，消费慢时丢弃最旧的事件（Lagged）
+    Broadcast,
+}
+
+/// 订阅的事件通道关闭时的处理方式
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClosedPolicy {
+    /// 从仍然存活的发送端重新订阅并继续运行
+    #[default]
+    Resubscribe,
+    /// 视为致命错误：核心运行时以错误退出，由 systemd 等外部监管者重启守护进程
+    Fatal,
+}
+
+/// 控制套接字：按行收发 JSON 命令，用于查询事件历史等运行时状态
 #[derive(Deserialize, Debug, Clone)]
+pub struct ControlConfig {
+    /// Unix 套接字路径，不设置时不监听
+    #[serde(default)]
+    pub socket_path: Option<PathBuf>,
+    /// 内存中保留的最近事件条数
+    #[serde(default = "default_event_history_capacity")]
+    pub event_history_capacity: usize,
+    /// HTTP 控制接口的监听地址，不设置时不监听
+    #[serde(default)]
+    pub http_listen: Option<SocketAddr>,
+    /// HTTP 控制接口的访问令牌，请求需携带 `Authorization: Bearer <token>`
+    #[serde(default)]
+    pub http_token: Option<String>,
+    /// 是否允许控制套接字的 `simulate-failure` 命令注入合成故障（用于演练），生产环境应保持关闭
+    #[serde(default)]
+    pub allow_simulation: bool,
+    /// 只记录匹配的事件到事件历史，默认全部记录；仅在启动时生效
+    #[serde(default)]
+    pub event_history_filter: EventFilterConfig,
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            socket_path: None,
+            event_history_capacity: default_event_history_capacity(),
+            http_listen: None,
+            http_token: None,
+            allow_simulation: false,
+            event_history_filter: EventFilterConfig::default(),
+        }
+    }
+}
+
+/// 单个进程的配置。`Default` 便于以代码构造配置：name、command 为空，monitor 为 ebpf 监控，
+/// 其余字段取配置文件中省略时的默认值
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct ProcessConfig {
     pub name: String,
     pub enabled: bool,
@@ -31,6 +411,311 @@ pub struct ProcessConfig {
     pub recovery: RecoveryConfig,
     #[serde(default)]
     pub dependencies: Vec<RawDependency>,
+    /// 被恢复进程的 stdout/stderr 去向，默认写入日志文件
+    #[serde(default)]
+    pub child_output: ChildOutput,
+    /// 恢复前执行的前置检查命令，仅当其退出码为 0 时才重启进程
+    #[serde(default)]
+    pub recovery_guard: Option<HookConfig>,
+    /// run_as_root 为 false 但无法切换到 run_as_user 时拒绝启动，而不是以 root 运行
+    #[serde(default)]
+    pub forbid_root_fallback: bool,
+    /// 所属的重启组（见顶层 restart_groups），设置后使用组的共享预算代替自身的 recovery 预算
+    #[serde(default)]
+    pub restart_group: Option<String>,
+    /// 执行恢复命令前进入的命名空间（如容器内的进程），需要 root 权限
+    #[serde(default)]
+    pub nsenter: Option<NsenterConfig>,
+    /// 按故障类型选择的恢复命令，未配置的类型使用 command/args
+    #[serde(default)]
+    pub recovery_commands: HashMap<FailureKind, CommandConfig>,
+    /// 恢复过程中同步执行的外部命令（recovery_guard，以及开启 wait_for_exit 时的恢复命令）的最长执行时间（秒）。
+    /// 设置后覆盖命令自身的 timeout_secs，超时时杀死其整个进程组并按一次失败的恢复计入熔断器；
+    /// 不设置时超时只跳过本次恢复
+    #[serde(default)]
+    pub recovery_timeout_secs: Option<u64>,
+    /// 等待恢复命令退出（最长 recovery_timeout_secs，默认 60 秒），非零退出或超时按一次失败的恢复处理；
+    /// 适合 `systemctl start foo && wait-for-it` 这类自身负责编排的恢复命令。
+    /// 默认 false：命令启动成功即视为已恢复。等待期间自愈器不处理其他事件
+    #[serde(default)]
+    pub wait_for_exit: bool,
+    /// 恢复命令的 nice 值，超出 -20..=19 时截断；只有以 root 身份才能设置负值（提高优先级）
+    #[serde(default)]
+    pub nice: Option<i32>,
+    /// 恢复命令的 IO 调度类别（同 ionice -c）：1 实时（需要 root）、2 尽力而为、3 空闲
+    #[serde(default)]
+    pub ionice_class: Option<u8>,
+    /// 恢复优先级，多个进程同时故障时数值大的先恢复，默认 0
+    #[serde(default)]
+    pub priority: i32,
+    /// 就绪探针：与 monitor（存活检查）分开，只用于判断依赖本进程的进程能否被放行
+    #[serde(default)]
+    pub readiness: Option<ReadinessProbe>,
+    /// 在内存中保留被恢复子进程 stdout/stderr 的最后若干行（每行最多 1 KiB），
+    /// 可通过控制套接字的 `tail` 命令查看；输出仍按 child_output 写出。不设置时不保留
+    #[serde(default)]
+    pub output_tail_lines: Option<usize>,
+    /// 定时主动重启（不论是否健康），适合有内存泄漏的服务
+    #[serde(default)]
+    pub scheduled_restart: Option<ScheduledRestartConfig>,
+    /// 恢复方式，默认重新拉起 command；设置为 signal 时改为向仍在运行的进程发送信号，
+    /// 设置为 restart_subtree 时拉起后再按依赖顺序重启所有（传递）依赖它的进程
+    #[serde(default)]
+    pub recovery_action: RecoveryAction,
+    /// 恢复后的监控宽限期（秒）：期间监控报告的故障被忽略，避免慢启动的服务
+    /// （PID 文件尚未写入、端口尚未监听）被再次重启。默认 0，即不忽略
+    #[serde(default)]
+    pub post_restart_grace_secs: u64,
+    /// 恢复时拉起的子进程因信号崩溃时保留其 core 文件，未设置时不处理
+    #[serde(default)]
+    pub capture_core: Option<CoreCaptureConfig>,
+    /// command（及 recovery_commands 中的命令）含通配符时，匹配到多个文件的处理方式
+    #[serde(default)]
+    pub command_glob: CommandGlobPolicy,
+    /// 1 分钟平均负载（/proc/loadavg）超过该值时推迟恢复，按退避重新排队，避免在机器已经过载时加剧重启风暴
+    #[serde(default)]
+    pub max_load1: Option<f64>,
+    /// 被恢复进程的文件创建掩码（八进制字符串，如 "0022"），在 exec 前设置；不设置时沿用守护进程的 umask（0027）
+    #[serde(default)]
+    pub umask: Option<Umask>,
+}
+
+/// command 为 glob（如 `/opt/app/app-*/bin/app`）时的解析方式，恢复时重新解析；没有匹配时本次恢复失败
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandGlobPolicy {
+    /// 选择修改时间最新的文件（默认）
+    #[default]
+    Newest,
+    /// 匹配到多个文件时本次恢复失败
+    Fail,
+}
+
+/// core 文件的保留设置
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CoreCaptureConfig {
+    /// 保存 core 文件的目录，其下按进程名建立子目录
+    #[serde(default = "default_core_directory")]
+    pub directory: PathBuf,
+}
+
+fn default_core_directory() -> PathBuf {
+    PathBuf::from("/var/lib/healer/cores")
+}
+
+/// 恢复方式
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum RecoveryAction {
+    /// 按 command / recovery_commands 重新拉起进程
+    #[default]
+    Spawn,
+    /// 向进程发送信号（如 SIGHUP 重新加载、SIGUSR2 重启 worker），不重新拉起；
+    /// 找不到目标进程时按一次失败的恢复计入熔断器
+    Signal {
+        signal: SignalName,
+        pid_source: SignalPidSource,
+    },
+    /// 与 spawn 一样拉起进程，成功后按拓扑顺序重启所有传递依赖它的已启用进程：
+    /// 先停止自愈器拉起的实例（SIGTERM，超时后 SIGKILL），再执行各自的恢复。
+    /// 依赖成环或需要重启的进程超过 max_dependents 时只恢复本进程
+    #[serde(rename = "restart_subtree")]
+    RestartSubtree {
+        #[serde(default = "default_max_dependents")]
+        max_dependents: usize,
+        #[serde(default = "default_stop_timeout_secs")]
+        stop_timeout_secs: u64,
+    },
+}
+
+fn default_max_dependents() -> usize {
+    16
+}
+
+/// 信号恢复时查找目标进程的方式
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignalPidSource {
+    /// 读取 PID 文件
+    PidFile { path: PathBuf },
+    /// 按可执行文件路径扫描进程，不设置 exe 时使用 command
+    ExeScan {
+        #[serde(default)]
+        exe: Option<PathBuf>,
+    },
+}
+
+/// 定时重启：按 cron 表达式（含秒字段，本地时区）发布 ProcessDown，经依赖协调后由自愈器恢复
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledRestartConfig {
+    /// 例如 `"0 0 4 * * *"` 表示每天 4 点
+    pub cron: String,
+    /// 先向自愈器拉起的实例发送 SIGTERM，等待这么久仍未退出时发送 SIGKILL
+    #[serde(default = "default_stop_timeout_secs")]
+    pub stop_timeout_secs: u64,
+}
+
+fn default_stop_timeout_secs() -> u64 {
+    10
+}
+
+/// output_tail_lines 的上限，限制每个进程占用的内存
+pub const MAX_OUTPUT_TAIL_LINES: usize = 1000;
+
+/// 就绪探针，探测通过才视为“可以提供服务”
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ReadinessProbe {
+    /// 请求返回 2xx 即就绪
+    Http(HttpReadinessFields),
+    /// 能建立 TCP 连接即就绪
+    Tcp(TcpReadinessFields),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HttpReadinessFields {
+    pub url: String,
+    /// 单次探测的超时时间（秒）
+    #[serde(default = "default_readiness_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TcpReadinessFields {
+    /// `host:port`
+    pub address: String,
+    /// 单次探测的超时时间（秒）
+    #[serde(default = "default_readiness_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl ReadinessProbe {
+    pub fn timeout_secs(&self) -> u64 {
+        match self {
+            ReadinessProbe::Http(fields) => fields.timeout_secs,
+            ReadinessProbe::Tcp(fields) => fields.timeout_secs,
+        }
+    }
+}
+
+/// 触发恢复的故障类型
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum FailureKind {
+    /// 进程退出（pid / eBPF / systemd 监控）
+    Down,
+    /// 健康检查地址不可达（network 监控）
+    Disconnected,
+    /// 资源超限（pid 监控的 memory_growth）
+    Resource,
+    /// 被 OOM killer 终止，目前没有监控器上报，预留
+    Oom,
+}
+
+impl std::fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            FailureKind::Down => "down",
+            FailureKind::Disconnected => "disconnected",
+            FailureKind::Resource => "resource",
+            FailureKind::Oom => "oom",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+// 一条外部命令及其参数
+#[derive(Deserialize, Debug, Clone)]
+pub struct CommandConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+// 恢复命令可进入的命名空间类型，声明顺序即 setns 的顺序（mnt 最后进入）
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum NamespaceKind {
+    Ipc,
+    Uts,
+    Net,
+    Pid,
+    Mnt,
+}
+
+impl NamespaceKind {
+    /// /proc/<pid>/ns/ 下对应的文件名
+    pub fn proc_name(self) -> &'static str {
+        match self {
+            NamespaceKind::Ipc => "ipc",
+            NamespaceKind::Uts => "uts",
+            NamespaceKind::Net => "net",
+            NamespaceKind::Pid => "pid",
+            NamespaceKind::Mnt => "mnt",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NsenterConfig {
+    /// 目标进程的 PID，按 namespaces 进入 /proc/<target_pid>/ns/ 下的命名空间
+    #[serde(default)]
+    pub target_pid: Option<u32>,
+    /// 通过 target_pid 进入的命名空间，默认 mnt 与 pid
+    #[serde(default = "default_nsenter_namespaces")]
+    pub namespaces: Vec<NamespaceKind>,
+    /// 显式指定的命名空间文件（如 /run/netns/foo），优先于 target_pid 推导出的路径
+    #[serde(default)]
+    pub paths: BTreeMap<NamespaceKind, PathBuf>,
+}
+
+impl NsenterConfig {
+    /// 要进入的命名空间及其文件路径，按 setns 的顺序排列
+    pub fn resolved_paths(&self) -> Vec<(NamespaceKind, PathBuf)> {
+        let mut resolved = self.paths.clone();
+        if let Some(pid) = self.target_pid {
+            for kind in &self.namespaces {
+                resolved.entry(*kind).or_insert_with(|| {
+                    PathBuf::from(format!("/proc/{}/ns/{}", pid, kind.proc_name()))
+                });
+            }
+        }
+        resolved.into_iter().collect()
+    }
+}
+
+// 由 healer 在特定时机执行的外部命令
+#[derive(Deserialize, Debug, Clone)]
+pub struct HookConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 命令的最长执行时间（秒），超时视为失败
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ChildOutput {
+    /// 写入 /var/log/healer/<name>.restarted.log（失败时回退到 /tmp）
+    #[default]
+    File,
+    /// 丢弃输出（/dev/null）
+    Null,
+    /// 继承 healer 自身的 stdio，仅前台模式可用
+    Inherit,
+}
+
+/// eBPF 程序检测进程退出所用的挂载点
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EbpfAttach {
+    /// tracepoint `sched:sched_process_exit`
+    #[default]
+    Tracepoint,
+    /// kprobe `do_exit`，用于 tracepoint 不可用或不可靠的内核
+    Kprobe,
 }
 
 // ---------------- Dependency Config ----------------
@@ -62,6 +747,29 @@ pub enum DependencyKind {
     After,
 }
 
+impl DependencyKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DependencyKind::Requires => "requires",
+            DependencyKind::After => "after",
+        }
+    }
+}
+
+impl std::fmt::Display for DependencyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}, {}, max_wait={}s, on_failure={})",
+            self.target,
+            self.kind.as_str(),
+            if self.hard { "hard" } else { "soft" },
+            self.max_wait_secs,
+            self.on_failure.as_str()
+        )
+    }
+}
+
 #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum OnFailure {
@@ -70,6 +778,16 @@ pub enum OnFailure {
     Degrade,
 }
 
+impl OnFailure {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OnFailure::Abort => "abort",
+            OnFailure::Skip => "skip",
+            OnFailure::Degrade => "degrade",
+        }
+    }
+}
+
 fn default_kind() -> DependencyKind {
     DependencyKind::Requires
 }
@@ -82,6 +800,70 @@ fn default_max_wait_secs() -> u64 {
 fn default_on_failure() -> OnFailure {
     OnFailure::Abort
 }
+fn default_coalesce_window_secs() -> u64 {
+    10
+}
+fn default_cooldown_secs() -> u64 {
+    180
+}
+fn default_half_open_window_secs() -> u64 {
+    2
+}
+fn default_reload_signals() -> Vec<SignalName> {
+    vec![SignalName(nix::sys::signal::Signal::SIGHUP)]
+}
+fn default_shutdown_signals() -> Vec<SignalName> {
+    vec![
+        SignalName(nix::sys::signal::Signal::SIGTERM),
+        SignalName(nix::sys::signal::Signal::SIGINT),
+    ]
+}
+fn default_hook_timeout_secs() -> u64 {
+    10
+}
+fn default_readiness_timeout_secs() -> u64 {
+    2
+}
+fn default_nsenter_namespaces() -> Vec<NamespaceKind> {
+    vec![NamespaceKind::Mnt, NamespaceKind::Pid]
+}
+fn default_startup_grace_secs() -> u64 {
+    5
+}
+fn default_ebpf_poll_interval_ms() -> u64 {
+    1000
+}
+fn default_status_file_interval_secs() -> u64 {
+    5
+}
+fn default_event_history_capacity() -> usize {
+    256
+}
+fn default_forwarder_channel() -> String {
+    "healer.{host}.{event}".to_string()
+}
+fn default_forwarder_buffer_capacity() -> usize {
+    1024
+}
+/// 命令路径与 allowed_commands 的某一项相同或位于其下；相对路径与含 `..` 的路径一律拒绝，避免绕过前缀
+pub fn command_allowed(allowed: &[PathBuf], program: &Path) -> bool {
+    program.is_absolute()
+        && !program
+            .components()
+            .any(|component| component == std::path::Component::ParentDir)
+        && allowed.iter().any(|entry| program.starts_with(entry))
+}
+
+// 检查超时默认与轮询间隔一致，且至少 1 秒
+fn resolve_check_timeout_secs(check_timeout_secs: Option<u64>, interval_secs: u64) -> u64 {
+    check_timeout_secs.unwrap_or(interval_secs).max(1)
+}
+
+fn resolve_max_backoff_secs(max_backoff_secs: Option<u64>, interval_secs: u64) -> u64 {
+    max_backoff_secs
+        .unwrap_or(interval_secs.saturating_mul(8))
+        .max(interval_secs)
+}
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(tag = "type", rename_all = "lowercase")]
@@ -89,21 +871,269 @@ pub enum MonitorConfig {
     Pid(PidMonitorFields),
     Ebpf(EbpfMonitorFields),
     Network(NetworkMonitorFields),
+    Systemd(SystemdMonitorFields),
+    #[serde(rename = "unix_socket")]
+    UnixSocket(UnixSocketMonitorFields),
+}
+
+/// 默认为不需要其他字段的 ebpf 监控（`{ type: ebpf }`）
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        MonitorConfig::Ebpf(EbpfMonitorFields::default())
+    }
+}
+
+impl MonitorConfig {
+    /// 监控类型，与配置中的 `type` 相同
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MonitorConfig::Pid(_) => "pid",
+            MonitorConfig::Ebpf(_) => "ebpf",
+            MonitorConfig::Network(_) => "network",
+            MonitorConfig::Systemd(_) => "systemd",
+            MonitorConfig::UnixSocket(_) => "unix_socket",
+        }
+    }
+}
+
+impl std::fmt::Display for MonitorConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonitorConfig::Pid(fields) => {
+                write!(f, "pid (source=")?;
+                match fields.resolved_source() {
+                    Some(source) => write!(f, "{}", source)?,
+                    None => write!(f, "<missing>")?,
+                }
+                write!(
+                    f,
+                    ", interval={}s, timeout={}s)",
+                    fields.interval_secs,
+                    resolve_check_timeout_secs(fields.check_timeout_secs, fields.interval_secs)
+                )?;
+                if let Some(policy) = &fields.memory_growth {
+                    write!(
+                        f,
+                        " memory_growth(+{}% over {} samples)",
+                        policy.growth_percent, policy.window_samples
+                    )?;
+                }
+                Ok(())
+            }
+            MonitorConfig::Ebpf(fields) => {
+                write!(f, "ebpf")?;
+                match (&fields.process_comm, fields.comm_match) {
+                    (Some(comm), CommMatch::Exact) => write!(f, " (comm={})", comm),
+                    (Some(comm), strategy) => write!(f, " (comm={}, match={})", comm, strategy),
+                    (None, CommMatch::Exact) => Ok(()),
+                    (None, strategy) => write!(f, " (match={})", strategy),
+                }
+            }
+            MonitorConfig::Network(fields) => write!(
+                f,
+                "network (url={}, interval={}s, timeout={}s, max_backoff={}s)",
+                fields.target_url,
+                fields.interval_secs,
+                resolve_check_timeout_secs(fields.check_timeout_secs, fields.interval_secs),
+                resolve_max_backoff_secs(fields.max_backoff_secs, fields.interval_secs)
+            ),
+            MonitorConfig::Systemd(fields) => write!(
+                f,
+                "systemd (unit={}, interval={}s, timeout={}s)",
+                fields.unit,
+                fields.interval_secs,
+                resolve_check_timeout_secs(fields.check_timeout_secs, fields.interval_secs)
+            ),
+            MonitorConfig::UnixSocket(fields) => write!(
+                f,
+                "unix_socket (path={}, interval={}s, timeout={}s)",
+                fields.path.display(),
+                fields.interval_secs,
+                resolve_check_timeout_secs(fields.check_timeout_secs, fields.interval_secs)
+            ),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct PidMonitorFields {
-    pub pid_file_path: PathBuf,
+    /// 兼容写法，等价于 `pid_source: { type: file, path: ... }`
+    #[serde(default)]
+    pub pid_file_path: Option<PathBuf>,
+    /// PID 的来源，设置后优先于 pid_file_path
+    #[serde(default)]
+    pub pid_source: Option<PidSource>,
     pub interval_secs: u64,
+    /// 单次检查的超时时间（秒），未设置时取 interval_secs
+    #[serde(default)]
+    pub check_timeout_secs: Option<u64>,
+    /// 内存增长趋势检测（用于发现缓慢泄漏），未设置时不采样 RSS
+    #[serde(default)]
+    pub memory_growth: Option<MemoryGrowthPolicy>,
+    /// 监控启动后的宽限期（秒），期间 PID 文件缺失或为空不视为进程退出
+    #[serde(default = "default_startup_grace_secs")]
+    pub startup_grace_secs: u64,
+    /// PID 文件过期（其中的进程已不存在）时，按 command 的可执行文件路径查找仍在运行的实例；
+    /// 找到则改写 PID 文件并视为健康，避免重复拉起。仅对 PID 文件来源生效
+    #[serde(default)]
+    pub reconcile_pid_file: bool,
+    /// 按 master 所在的进程组或会话判断存活：master 退出后，只要组内仍有进程（如 fork 出的 worker）
+    /// 就视为健康，组内进程全部退出后才上报退出。未设置时只看 master 本身
+    #[serde(default)]
+    pub watch_group: Option<GroupScope>,
+}
+
+/// PID 监控判断存活时考虑的进程范围
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupScope {
+    /// 与 master 同一进程组（pgid）的进程
+    ProcessGroup,
+    /// 与 master 同一会话（sid）的进程
+    Session,
+}
+
+impl std::fmt::Display for GroupScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupScope::ProcessGroup => write!(f, "process group"),
+            GroupScope::Session => write!(f, "session"),
+        }
+    }
+}
+
+// 内存增长策略：最近 window_samples 个 RSS 采样单调不减，且相对窗口内首个采样增长超过 growth_percent 时判定为泄漏
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct MemoryGrowthPolicy {
+    #[serde(default = "default_memory_growth_window")]
+    pub window_samples: usize,
+    pub growth_percent: f64,
+}
+
+fn default_memory_growth_window() -> usize {
+    10
+}
+
+// PID 监控读取 PID 的方式
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PidSource {
+    /// 读取 PID 文件
+    File { path: PathBuf },
+    /// 读取 cgroup 的 cgroup.procs（适用于由 systemd 管理、没有 PID 文件的服务），空文件视为进程已退出
+    CgroupProcs { path: PathBuf },
+    /// 执行命令并把 stdout 解析为 PID，例如 `systemctl show -p MainPID --value foo`；输出为空或 0 视为进程已退出
+    Command {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl std::fmt::Display for PidSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PidSource::File { path } => write!(f, "PID file {}", path.display()),
+            PidSource::CgroupProcs { path } => write!(f, "cgroup procs {}", path.display()),
+            PidSource::Command { command, args } => {
+                write!(f, "command '{}", command)?;
+                for arg in args {
+                    write!(f, " {}", arg)?;
+                }
+                write!(f, "'")
+            }
+        }
+    }
+}
+
+impl PidMonitorFields {
+    pub fn resolved_source(&self) -> Option<PidSource> {
+        self.pid_source.clone().or_else(|| {
+            self.pid_file_path
+                .clone()
+                .map(|path| PidSource::File { path })
+        })
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct NetworkMonitorFields {
+    /// 可包含 `${NAME}`（环境变量）与 `{port}`（下方 port 字段）占位符，加载配置时展开
     pub target_url: String,
     pub interval_secs: u64,
+    /// 单次检查的超时时间（秒），未设置时取 interval_secs
+    #[serde(default)]
+    pub check_timeout_secs: Option<u64>,
+    /// 目标持续不可达时检查间隔退避的上限（秒），未设置时取 interval_secs 的 8 倍
+    #[serde(default)]
+    pub max_backoff_secs: Option<u64>,
+    /// 响应体中健康状态字段的 JSON Pointer（如 `/status`），需与 health_json_expect 同时设置
+    #[serde(default)]
+    pub health_json_path: Option<String>,
+    /// 健康状态字段的期望值；2xx 响应中该字段不等于期望值时视为断连
+    #[serde(default)]
+    pub health_json_expect: Option<serde_json::Value>,
+    /// 替换 target_url 中的 `{port}`
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+impl NetworkMonitorFields {
+    /// 展开占位符后的 target_url
+    pub fn resolved_url(&self) -> Result<String, crate::utils::UrlTemplateError> {
+        crate::utils::expand_url_template(&self.target_url, self.port)
+    }
+}
+#[derive(Deserialize, Debug, Clone)]
+pub struct SystemdMonitorFields {
+    /// systemd unit 名称，例如 `nginx.service`
+    pub unit: String,
+    pub interval_secs: u64,
+    /// 单次检查的超时时间（秒），未设置时取 interval_secs
+    #[serde(default)]
+    pub check_timeout_secs: Option<u64>,
 }
 #[derive(Deserialize, Debug, Clone)]
-pub struct EbpfMonitorFields {}
+pub struct UnixSocketMonitorFields {
+    /// 服务监听的 Unix 套接字路径
+    pub path: PathBuf,
+    pub interval_secs: u64,
+    /// 单次检查的超时时间（秒），未设置时取 interval_secs
+    #[serde(default)]
+    pub check_timeout_secs: Option<u64>,
+}
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct EbpfMonitorFields {
+    /// 内核中进程的 comm 名称，设置后直接用于 eBPF 匹配，而不是从 command 推导；
+    /// `comm_match: regex` 时为匹配 comm 的正则表达式
+    #[serde(default)]
+    pub process_comm: Option<String>,
+    #[serde(default)]
+    pub comm_match: CommMatch,
+}
+
+/// eBPF 事件中 comm 的匹配方式
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CommMatch {
+    /// 与（截断到 15 字节的）comm 完全相同，由内核侧 map 直接过滤
+    #[default]
+    Exact,
+    /// comm 以该名称开头；内核无法预先过滤，所有进程的退出都会上报到用户态再匹配
+    Prefix,
+    /// comm 匹配 process_comm 中的正则表达式；同样在用户态匹配
+    Regex,
+}
+
+impl std::fmt::Display for CommMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommMatch::Exact => write!(f, "exact"),
+            CommMatch::Prefix => write!(f, "prefix"),
+            CommMatch::Regex => write!(f, "regex"),
+        }
+    }
+}
 
 // #[derive(Deserialize, Debug, Clone)]
 // pub struct RecoveryConfig {
@@ -119,12 +1149,90 @@ pub enum RecoveryConfig {
     NotRegular(NotREgularHealerFields),
 }
 
+impl std::fmt::Display for RecoveryConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecoveryConfig::Regular(fields) => {
+                write!(
+                    f,
+                    "regular (retries={} within {}s, cooldown=",
+                    fields.retries, fields.retry_window_secs
+                )?;
+                match fields.cooldown_secs {
+                    Some(cooldown_secs) => write!(f, "{}s)", cooldown_secs),
+                    None => write!(f, "default)"),
+                }
+            }
+            RecoveryConfig::NotRegular(_) => write!(f, "notregular"),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct RegularHealerFields {
+    pub retries: u32,
+    pub retry_window_secs: u64,
+    /// 熔断后的冷却期（秒），不设置时使用 recovery_defaults
+    #[serde(default)]
+    pub cooldown_secs: Option<u64>,
+    /// 合并键：窗口期内共享同一个键的恢复只执行第一次，其余视为已被合并恢复
+    /// （例如多个进程共用 `systemctl restart shared.target`）
+    #[serde(default)]
+    pub coalesce_key: Option<String>,
+    /// 合并窗口（秒），默认 10
+    #[serde(default = "default_coalesce_window_secs")]
+    pub coalesce_window_secs: u64,
+    /// 覆盖 recovery_defaults.dedup_window_secs
+    #[serde(default)]
+    pub dedup_window_secs: Option<u64>,
+    /// 覆盖 recovery_defaults.half_open_window_secs
+    #[serde(default)]
+    pub half_open_window_secs: Option<u64>,
+    /// 连续熔断这么多次后隔离进程：不再自动恢复，直到手动 resume 或重新加载配置；0 表示不隔离
+    #[serde(default)]
+    pub quarantine_after_trips: u32,
+    /// 监控连续确认健康这么多秒后清空熔断器的全部历史（尝试记录与连续熔断次数），
+    /// 偶尔故障一次的服务不会逐渐累积到隔离；0 表示不清空
+    #[serde(default)]
+    pub healthy_reset_secs: u64,
+}
+
+impl RegularHealerFields {
+    /// 以全局默认值补全未单独设置的防抖动参数
+    pub fn anti_flap(&self, defaults: &RecoveryDefaults) -> RecoveryDefaults {
+        RecoveryDefaults {
+            dedup_window_secs: self.dedup_window_secs.unwrap_or(defaults.dedup_window_secs),
+            cooldown_secs: self.cooldown_secs.unwrap_or(defaults.cooldown_secs),
+            half_open_window_secs: self
+                .half_open_window_secs
+                .unwrap_or(defaults.half_open_window_secs),
+        }
+    }
+}
+// 重启组：组内所有进程的恢复尝试共同计入同一个预算，耗尽后一起进入冷却
+#[derive(Deserialize, Debug, Clone)]
+pub struct RestartGroupConfig {
+    pub name: String,
     pub retries: u32,
     pub retry_window_secs: u64,
     pub cooldown_secs: u64,
 }
+
+impl RestartGroupConfig {
+    pub fn budget(&self) -> RegularHealerFields {
+        RegularHealerFields {
+            retries: self.retries,
+            retry_window_secs: self.retry_window_secs,
+            cooldown_secs: Some(self.cooldown_secs),
+            coalesce_key: None,
+            coalesce_window_secs: default_coalesce_window_secs(),
+            dedup_window_secs: None,
+            half_open_window_secs: None,
+            quarantine_after_trips: 0,
+            healthy_reset_secs: 0,
+        }
+    }
+}
 // 占位以后没有也可以删掉
 #[derive(Deserialize, Debug, Clone)]
 pub struct NotREgularHealerFields {}
@@ -132,19 +1240,70 @@ pub struct NotREgularHealerFields {}
 #[derive(Debug, Clone)]
 pub struct PidMonitorConfig {
     pub name: String,
-    pub pid_file_path: PathBuf,
+    pub source: PidSource,
     pub interval_secs: u64,
+    pub check_timeout_secs: u64,
+    pub memory_growth: Option<MemoryGrowthPolicy>,
+    pub startup_grace_secs: u64,
+    /// 设置后，PID 文件过期时按该可执行文件路径重新查找进程
+    pub reconcile_exe: Option<PathBuf>,
+    /// 设置后，master 退出但其进程组 / 会话中仍有进程时视为健康
+    pub watch_group: Option<GroupScope>,
 }
 #[derive(Debug, Clone)]
 pub struct EbpfMonitorConfig {
     pub name: String,
     pub command: String,
+    pub process_comm: Option<String>,
+    pub comm_match: CommMatch,
+}
+impl EbpfMonitorConfig {
+    /// eBPF 匹配所用的进程名：优先使用 process_comm（未配置时可能由解释器的脚本参数推导），
+    /// 否则取 command 的可执行文件名，均截断到内核限制
+    pub fn monitored_comm(&self) -> String {
+        match &self.process_comm {
+            Some(comm) => crate::utils::truncate_process_name(comm),
+            None => crate::utils::truncate_process_name(&crate::utils::extract_executable_name(
+                &self.command,
+            )),
+        }
+    }
+}
+#[derive(Debug, Clone)]
+pub struct SystemdMonitorConfig {
+    pub name: String,
+    pub unit: String,
+    pub interval_secs: u64,
+    pub check_timeout_secs: u64,
+}
+#[derive(Debug, Clone)]
+pub struct UnixSocketMonitorConfig {
+    pub name: String,
+    pub path: PathBuf,
+    pub interval_secs: u64,
+    pub check_timeout_secs: u64,
+}
+impl UnixSocketMonitorConfig {
+    /// 上报 ProcessDisconnected 时使用的地址（`unix:<path>`）
+    pub fn url(&self) -> String {
+        format!("unix:{}", self.path.display())
+    }
 }
 #[derive(Debug, Clone)]
 pub struct NetworkMonitorConfig {
     pub name: String,
-    pub target_url: String, // 目标URL
-    pub interval_secs: u64, //检查的频率间隔
+    pub target_url: String,                   // 目标URL
+    pub interval_secs: u64,                   //检查的频率间隔
+    pub check_timeout_secs: u64,              //单次检查的超时时间
+    pub max_backoff_secs: u64,                //连续失败时检查间隔退避的上限
+    pub health_json: Option<JsonHealthCheck>, //按响应体中的 JSON 字段判断健康
+}
+
+/// 2xx 响应体的 JSON 健康检查：`pointer` 指向的值必须等于 `expect`
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonHealthCheck {
+    pub pointer: String,
+    pub expect: serde_json::Value,
 }
 impl Default for RecoveryConfig {
     fn default() -> Self {
@@ -156,17 +1315,54 @@ impl Default for RegularHealerFields {
         Self {
             retries: 3,
             retry_window_secs: 60,
-            cooldown_secs: 180,
+            cooldown_secs: None,
+            coalesce_key: None,
+            coalesce_window_secs: default_coalesce_window_secs(),
+            dedup_window_secs: None,
+            half_open_window_secs: None,
+            quarantine_after_trips: 0,
+            healthy_reset_secs: 0,
         }
     }
 }
 impl ProcessConfig {
+    /// 按故障类型选择恢复命令，未单独配置时使用 command/args
+    pub fn recovery_command_for(&self, kind: FailureKind) -> (&str, &[String]) {
+        match self.recovery_commands.get(&kind) {
+            Some(custom) => (&custom.command, &custom.args),
+            None => (&self.command, &self.args),
+        }
+    }
+
     pub fn get_pid_monitor_config(&self) -> Option<PidMonitorConfig> {
         if let MonitorConfig::Pid(pid_fields) = &self.monitor {
+            let Some(source) = pid_fields.resolved_source() else {
+                tracing::warn!(
+                    "Process '{}' uses a pid monitor without pid_file_path or pid_source",
+                    self.name
+                );
+                return None;
+            };
+            if pid_fields.reconcile_pid_file && !matches!(source, PidSource::File { .. }) {
+                tracing::warn!(
+                    "Process '{}': reconcile_pid_file only applies to pid_file_path sources, ignoring it",
+                    self.name
+                );
+            }
             Some(PidMonitorConfig {
                 name: self.name.clone(),
-                pid_file_path: pid_fields.pid_file_path.clone(),
+                source,
                 interval_secs: pid_fields.interval_secs,
+                check_timeout_secs: resolve_check_timeout_secs(
+                    pid_fields.check_timeout_secs,
+                    pid_fields.interval_secs,
+                ),
+                memory_growth: pid_fields.memory_growth.clone(),
+                startup_grace_secs: pid_fields.startup_grace_secs,
+                reconcile_exe: pid_fields
+                    .reconcile_pid_file
+                    .then(|| PathBuf::from(&self.command)),
+                watch_group: pid_fields.watch_group,
             })
         } else {
             None
@@ -174,10 +1370,16 @@ impl ProcessConfig {
     }
 
     pub fn get_ebpf_monitor_config(&self) -> Option<EbpfMonitorConfig> {
-        if let MonitorConfig::Ebpf(_ebpf_fields) = &self.monitor {
+        if let MonitorConfig::Ebpf(ebpf_fields) = &self.monitor {
             Some(EbpfMonitorConfig {
                 name: self.name.clone(),
                 command: self.command.clone(),
+                // 显式的 process_comm 优先；command 为解释器时按 args 中的脚本名匹配
+                process_comm: ebpf_fields
+                    .process_comm
+                    .clone()
+                    .or_else(|| crate::utils::interpreter_script_name(&self.command, &self.args)),
+                comm_match: ebpf_fields.comm_match,
             })
         } else {
             None
@@ -188,8 +1390,56 @@ impl ProcessConfig {
         if let MonitorConfig::Network(net_fields) = &self.monitor {
             Some(NetworkMonitorConfig {
                 name: self.name.clone(),
-                target_url: net_fields.target_url.clone(),
+                // validate 已检查过模板；运行期间环境变量被移除时退回原始字符串，由检查报告不可达
+                target_url: net_fields
+                    .resolved_url()
+                    .unwrap_or_else(|_| net_fields.target_url.clone()),
                 interval_secs: net_fields.interval_secs,
+                check_timeout_secs: resolve_check_timeout_secs(
+                    net_fields.check_timeout_secs,
+                    net_fields.interval_secs,
+                ),
+                max_backoff_secs: resolve_max_backoff_secs(
+                    net_fields.max_backoff_secs,
+                    net_fields.interval_secs,
+                ),
+                health_json: net_fields
+                    .health_json_path
+                    .clone()
+                    .zip(net_fields.health_json_expect.clone())
+                    .map(|(pointer, expect)| JsonHealthCheck { pointer, expect }),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_systemd_monitor_config(&self) -> Option<SystemdMonitorConfig> {
+        if let MonitorConfig::Systemd(systemd_fields) = &self.monitor {
+            Some(SystemdMonitorConfig {
+                name: self.name.clone(),
+                unit: systemd_fields.unit.clone(),
+                interval_secs: systemd_fields.interval_secs,
+                check_timeout_secs: resolve_check_timeout_secs(
+                    systemd_fields.check_timeout_secs,
+                    systemd_fields.interval_secs,
+                ),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_unix_socket_monitor_config(&self) -> Option<UnixSocketMonitorConfig> {
+        if let MonitorConfig::UnixSocket(socket_fields) = &self.monitor {
+            Some(UnixSocketMonitorConfig {
+                name: self.name.clone(),
+                path: socket_fields.path.clone(),
+                interval_secs: socket_fields.interval_secs,
+                check_timeout_secs: resolve_check_timeout_secs(
+                    socket_fields.check_timeout_secs,
+                    socket_fields.interval_secs,
+                ),
             })
         } else {
             None
@@ -213,13 +1463,518 @@ impl ProcessConfig {
     }
 }
 
+/// 配置内容的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigFormat {
+    #[default]
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// 按扩展名判断格式，无法判断时按 YAML 处理
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => ConfigFormat::Toml,
+            Some("json") => ConfigFormat::Json,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format = match self {
+            ConfigFormat::Yaml => "yaml",
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Json => "json",
+        };
+        write!(f, "{}", format)
+    }
+}
+
+/// 加载配置失败的原因，供嵌入方按类型区分处理
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// 读取配置文件或输入失败（文件不存在、权限不足等）
+    #[error("failed to read config: {0}")]
+    Io(#[from] std::io::Error),
+    /// 内容不符合所声明格式的语法或配置结构
+    #[error("failed to parse {format} config: {source}")]
+    Parse {
+        format: ConfigFormat,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// 语法正确但取值不合法
+    #[error("invalid config: {0}")]
+    Validation(String),
+}
+
+impl std::str::FromStr for ConfigFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Ok(ConfigFormat::Yaml),
+            "toml" => Ok(ConfigFormat::Toml),
+            "json" => Ok(ConfigFormat::Json),
+            other => Err(format!(
+                "unknown config format '{}', expected yaml, toml or json",
+                other
+            )),
+        }
+    }
+}
+
+/// include_dir 中的附加配置文件：只合并进程列表，全局设置以主配置文件为准
+#[derive(Deserialize, Debug, Default)]
+struct ConfigOverlay {
+    #[serde(default)]
+    processes: Vec<ProcessConfig>,
+}
+
 impl AppConfig {
-    pub fn load_from_file(config_file_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+    /// 从文件加载配置，格式按扩展名判断（默认 YAML）
+    #[allow(dead_code)]
+    pub fn load_from_file(config_file_path: &Path) -> Result<Self, ConfigError> {
+        Self::load_from_file_as(config_file_path, ConfigFormat::from_path(config_file_path))
+    }
+
+    pub fn load_from_file_as(
+        config_file_path: &Path,
+        format: ConfigFormat,
+    ) -> Result<Self, ConfigError> {
         let config_content = fs::read_to_string(config_file_path)?;
-        let loaded_config: AppConfig = serde_yaml::from_str(&config_content)?;
+        Self::parse_at(&config_content, format, config_file_path)
+    }
+
+    /// 从任意输入（如标准输入）读取全部内容并解析，相对路径的 include_dir 相对于当前目录
+    pub fn load_from_reader(
+        mut reader: impl std::io::Read,
+        format: ConfigFormat,
+    ) -> Result<Self, ConfigError> {
+        let mut config_content = String::new();
+        reader.read_to_string(&mut config_content)?;
+        // 没有配置文件所在目录可作基准，相对路径的 include_dir 相对于当前目录
+        let current_dir = std::env::current_dir()?;
+        Self::parse_relative_to(&config_content, format, Some(&current_dir))
+    }
+
+    pub fn parse(config_content: &str, format: ConfigFormat) -> Result<Self, ConfigError> {
+        let loaded_config = Self::deserialize(config_content, format)?;
+        loaded_config.validate()?;
         Ok(loaded_config)
     }
 
+    /// 解析位于 `config_path` 的配置原文，并合并 include_dir 中的附加配置后再校验
+    pub fn parse_at(
+        config_content: &str,
+        format: ConfigFormat,
+        config_path: &Path,
+    ) -> Result<Self, ConfigError> {
+        Self::parse_relative_to(config_content, format, config_path.parent())
+    }
+
+    // 合并 include_dir 后再校验，相对路径的 include_dir 以 base_dir 为基准
+    fn parse_relative_to(
+        config_content: &str,
+        format: ConfigFormat,
+        base_dir: Option<&Path>,
+    ) -> Result<Self, ConfigError> {
+        let mut loaded_config = Self::deserialize(config_content, format)?;
+        if let Some(include_dir) = loaded_config.include_dir.clone() {
+            let include_dir = match base_dir {
+                Some(base) if include_dir.is_relative() => base.join(include_dir),
+                _ => include_dir,
+            };
+            loaded_config.merge_include_dir(&include_dir)?;
+        }
+        loaded_config.validate()?;
+        Ok(loaded_config)
+    }
+
+    fn deserialize(config_content: &str, format: ConfigFormat) -> Result<Self, ConfigError> {
+        let parsed: Result<AppConfig, Box<dyn std::error::Error + Send + Sync>> = match format {
+            ConfigFormat::Yaml => serde_yaml::from_str(config_content).map_err(Into::into),
+            ConfigFormat::Toml => toml::from_str(config_content).map_err(Into::into),
+            ConfigFormat::Json => serde_json::from_str(config_content).map_err(Into::into),
+        };
+        parsed.map_err(|source| ConfigError::Parse { format, source })
+    }
+
+    // 按文件名顺序合并目录中的附加配置；目录不存在时只记录警告
+    fn merge_include_dir(&mut self, include_dir: &Path) -> Result<(), ConfigError> {
+        let entries = match fs::read_dir(include_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tracing::warn!(
+                    "Config: include_dir {:?} does not exist, skipping",
+                    include_dir
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && matches!(
+                        path.extension().and_then(|ext| ext.to_str()),
+                        Some("yaml" | "yml")
+                    )
+            })
+            .collect();
+        files.sort();
+        for file in files {
+            let content = fs::read_to_string(&file)?;
+            let overlay: ConfigOverlay =
+                serde_yaml::from_str(&content).map_err(|e| ConfigError::Parse {
+                    format: ConfigFormat::Yaml,
+                    source: format!("{}: {}", file.display(), e).into(),
+                })?;
+            self.merge_processes(overlay.processes, &file);
+        }
+        Ok(())
+    }
+
+    /// 合并附加配置中的进程：同名进程整体替换原定义（保留原位置），新进程追加在末尾
+    pub fn merge_processes(&mut self, processes: Vec<ProcessConfig>, origin: &Path) {
+        for process in processes {
+            match self.processes.iter_mut().find(|p| p.name == process.name) {
+                Some(existing) => {
+                    tracing::warn!(
+                        "Config: process '{}' is overridden by {:?}",
+                        process.name,
+                        origin
+                    );
+                    *existing = process;
+                }
+                None => {
+                    tracing::debug!("Config: process '{}' added by {:?}", process.name, origin);
+                    self.processes.push(process);
+                }
+            }
+        }
+    }
+
+    /// 已启用（`enabled: true`）的进程数
+    pub fn enabled_process_count(&self) -> usize {
+        self.processes.iter().filter(|p| p.enabled).count()
+    }
+
+    /// 配置文件中的日志过滤指令：log_level（默认 info）在前，log_targets 按 target 排序追加在后
+    pub fn log_directives(&self) -> String {
+        let mut directives = vec![self
+            .log_level
+            .clone()
+            .filter(|level| !level.trim().is_empty())
+            .unwrap_or_else(|| "info".to_string())];
+        let mut targets: Vec<String> = self
+            .log_targets
+            .iter()
+            .map(|(target, level)| format!("{}={}", target, level))
+            .collect();
+        targets.sort();
+        directives.extend(targets);
+        directives.join(",")
+    }
+
+    /// 检查反序列化无法表达的约束，错误信息指明出错的进程
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let directives = self.log_directives();
+        if let Err(e) = tracing_subscriber::EnvFilter::try_new(&directives) {
+            return Err(ConfigError::Validation(format!(
+                "invalid log_level/log_targets '{}': {}",
+                directives, e
+            )));
+        }
+        if self.require_processes && self.enabled_process_count() == 0 {
+            return Err(ConfigError::Validation(
+                "require_processes is set but no enabled processes are configured".into(),
+            ));
+        }
+        if self.ebpf_max_workers == Some(0) {
+            return Err(ConfigError::Validation(
+                "ebpf_max_workers must be at least 1".into(),
+            ));
+        }
+        if self.ebpf_poll_interval_ms == 0 {
+            return Err(ConfigError::Validation(
+                "ebpf_poll_interval_ms must be at least 1".into(),
+            ));
+        }
+        if self.runtime_worker_threads == Some(0) {
+            return Err(ConfigError::Validation(
+                "runtime_worker_threads must be at least 1".into(),
+            ));
+        }
+        if self.watchdog_timeout_secs == Some(0) {
+            return Err(ConfigError::Validation(
+                "watchdog_timeout_secs must be at least 1".into(),
+            ));
+        }
+        if let Some(entry) = self
+            .allowed_commands
+            .iter()
+            .flatten()
+            .find(|entry| !entry.is_absolute())
+        {
+            return Err(ConfigError::Validation(format!(
+                "allowed_commands entry '{}' must be an absolute path",
+                entry.display()
+            )));
+        }
+        if let Some(allowed) = &self.allowed_commands {
+            for process in &self.processes {
+                let guard = process
+                    .recovery_guard
+                    .as_ref()
+                    .map(|guard| ("recovery_guard", &guard.command));
+                let pid_source = match &process.monitor {
+                    MonitorConfig::Pid(fields) => match &fields.pid_source {
+                        Some(PidSource::Command { command, .. }) => Some(("pid_source", command)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if let Some((field, command)) = guard
+                    .into_iter()
+                    .chain(pid_source)
+                    .find(|(_, command)| !command_allowed(allowed, Path::new(command)))
+                {
+                    return Err(ConfigError::Validation(format!(
+                        "process '{}': {} command '{}' is not in allowed_commands",
+                        process.name, field, command
+                    )));
+                }
+            }
+        }
+        if self.exit_when_idle_secs == Some(0) {
+            return Err(ConfigError::Validation(
+                "exit_when_idle_secs must be at least 1".into(),
+            ));
+        }
+        if self.status_file_interval_secs == 0 {
+            return Err(ConfigError::Validation(
+                "status_file_interval_secs must be at least 1".into(),
+            ));
+        }
+        if self
+            .event_forwarder
+            .as_ref()
+            .is_some_and(|forwarder| forwarder.buffer_capacity == 0)
+        {
+            return Err(ConfigError::Validation(
+                "event_forwarder.buffer_capacity must be at least 1".into(),
+            ));
+        }
+        if let Some(process) = self
+            .processes
+            .iter()
+            .find(|p| p.max_load1.is_some_and(|max| max.is_nan() || max <= 0.0))
+        {
+            return Err(ConfigError::Validation(format!(
+                "Process '{}': max_load1 must be a positive number",
+                process.name
+            )));
+        }
+        self.validate_signals()?;
+        self.control
+            .event_history_filter
+            .validate("control.event_history_filter")?;
+        if let Some(forwarder) = &self.event_forwarder {
+            forwarder.filter.validate("event_forwarder.filter")?;
+        }
+        if self.control.http_listen.is_some()
+            && self.control.http_token.as_deref().unwrap_or("").is_empty()
+        {
+            return Err(ConfigError::Validation(
+                "control.http_listen requires a non-empty control.http_token".into(),
+            ));
+        }
+        for process in &self.processes {
+            let interval_secs = match &process.monitor {
+                MonitorConfig::Pid(fields) => Some(fields.interval_secs),
+                MonitorConfig::Network(fields) => Some(fields.interval_secs),
+                MonitorConfig::Systemd(fields) => Some(fields.interval_secs),
+                MonitorConfig::UnixSocket(fields) => Some(fields.interval_secs),
+                MonitorConfig::Ebpf(_) => None,
+            };
+            if interval_secs == Some(0) {
+                return Err(ConfigError::Validation(format!(
+                    "process '{}': monitor interval_secs must be at least 1",
+                    process.name
+                )));
+            }
+            if let Some(class) = process.ionice_class {
+                if !(1..=3).contains(&class) {
+                    return Err(ConfigError::Validation(format!(
+                        "process '{}': ionice_class must be 1, 2 or 3, got {}",
+                        process.name, class
+                    )));
+                }
+            }
+            if let Some(group) = &process.restart_group {
+                if self.get_restart_group(group).is_none() {
+                    return Err(ConfigError::Validation(format!(
+                        "process '{}': restart_group '{}' is not defined in restart_groups",
+                        process.name, group
+                    )));
+                }
+            }
+            if let MonitorConfig::Pid(fields) = &process.monitor {
+                if fields.resolved_source().is_none() {
+                    return Err(ConfigError::Validation(format!(
+                        "process '{}': pid monitor requires pid_file_path or pid_source",
+                        process.name
+                    )));
+                }
+            }
+            if matches!(
+                process.recovery_action,
+                RecoveryAction::RestartSubtree {
+                    max_dependents: 0,
+                    ..
+                }
+            ) {
+                return Err(ConfigError::Validation(format!(
+                    "process '{}': restart_subtree max_dependents must be at least 1",
+                    process.name
+                )));
+            }
+            if let Some(schedule) = &process.scheduled_restart {
+                if let Err(e) = schedule.cron.parse::<cron::Schedule>() {
+                    return Err(ConfigError::Validation(format!(
+                        "process '{}': invalid scheduled_restart cron '{}': {}",
+                        process.name, schedule.cron, e
+                    )));
+                }
+            }
+            if let Some(lines) = process.output_tail_lines {
+                if lines == 0 || lines > MAX_OUTPUT_TAIL_LINES {
+                    return Err(ConfigError::Validation(format!(
+                        "process '{}': output_tail_lines must be between 1 and {}",
+                        process.name, MAX_OUTPUT_TAIL_LINES
+                    )));
+                }
+            }
+            if process
+                .readiness
+                .as_ref()
+                .is_some_and(|probe| probe.timeout_secs() == 0)
+            {
+                return Err(ConfigError::Validation(format!(
+                    "process '{}': readiness timeout_secs must be at least 1",
+                    process.name
+                )));
+            }
+            if let MonitorConfig::Network(fields) = &process.monitor {
+                if let Err(e) = fields.resolved_url() {
+                    return Err(ConfigError::Validation(format!(
+                        "process '{}': target_url: {}",
+                        process.name, e
+                    )));
+                }
+                if fields
+                    .max_backoff_secs
+                    .is_some_and(|max| max < fields.interval_secs)
+                {
+                    return Err(ConfigError::Validation(format!(
+                        "process '{}': max_backoff_secs must not be less than interval_secs",
+                        process.name
+                    )));
+                }
+                match (&fields.health_json_path, &fields.health_json_expect) {
+                    (Some(pointer), Some(_)) => {
+                        if !pointer.is_empty() && !pointer.starts_with('/') {
+                            return Err(ConfigError::Validation(format!(
+                                "process '{}': health_json_path '{}' must be a JSON pointer starting with '/'",
+                                process.name, pointer
+                            )));
+                        }
+                    }
+                    (None, None) => {}
+                    _ => {
+                        return Err(ConfigError::Validation(format!(
+                            "process '{}': health_json_path and health_json_expect must be set together",
+                            process.name
+                        )));
+                    }
+                }
+            }
+            if let MonitorConfig::Ebpf(fields) = &process.monitor {
+                if fields.comm_match == CommMatch::Regex {
+                    let pattern = fields.process_comm.as_deref().ok_or_else(|| {
+                        ConfigError::Validation(format!(
+                            "process '{}': comm_match 'regex' requires process_comm",
+                            process.name
+                        ))
+                    })?;
+                    if let Err(e) = regex::Regex::new(pattern) {
+                        return Err(ConfigError::Validation(format!(
+                            "process '{}': invalid process_comm regex: {}",
+                            process.name, e
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_signals(&self) -> Result<(), ConfigError> {
+        use nix::sys::signal::Signal;
+        if self.signals.shutdown.is_empty() {
+            return Err(ConfigError::Validation(
+                "signals.shutdown must list at least one signal".into(),
+            ));
+        }
+        for name in self.signals.reload.iter().chain(&self.signals.shutdown) {
+            // 无法捕获的信号、同步故障信号，以及僵尸进程回收使用的 SIGCHLD
+            if matches!(
+                name.signal(),
+                Signal::SIGKILL
+                    | Signal::SIGSTOP
+                    | Signal::SIGILL
+                    | Signal::SIGFPE
+                    | Signal::SIGSEGV
+                    | Signal::SIGBUS
+                    | Signal::SIGCHLD
+            ) {
+                return Err(ConfigError::Validation(format!(
+                    "signals: {} cannot be used for reload or shutdown",
+                    name
+                )));
+            }
+        }
+        if let Some(name) = self
+            .signals
+            .reload
+            .iter()
+            .find(|name| self.signals.shutdown.contains(name))
+        {
+            return Err(ConfigError::Validation(format!(
+                "signals: {} is configured for both reload and shutdown",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    /// 实际使用的 tokio 工作线程数：配置值，未配置时为 CPU 数
+    pub fn worker_threads(&self) -> usize {
+        self.runtime_worker_threads
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            })
+            .max(1)
+    }
+
     pub fn get_process_config_for(&self, process_name: &str) -> Option<&ProcessConfig> {
         self.processes
             .iter()
@@ -227,6 +1982,21 @@ impl AppConfig {
             .map(|p_config| p_config)
     }
 
+    /// 在内存中启用/停用进程（不写回配置文件），进程不存在时返回 false
+    pub fn set_process_enabled(&mut self, process_name: &str, enabled: bool) -> bool {
+        match self.processes.iter_mut().find(|p| p.name == process_name) {
+            Some(process) => {
+                process.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_restart_group(&self, group_name: &str) -> Option<&RestartGroupConfig> {
+        self.restart_groups.iter().find(|g| g.name == group_name)
+    }
+
     pub fn to_daemonize_config(&self) -> DaemonConfig {
         let daemon_config = DaemonConfig {
             pid_file: self
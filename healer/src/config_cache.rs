@@ -0,0 +1,58 @@
+//! 最近一次成功加载的配置（last-known-good）缓存：配置文件在两次启动之间损坏时，
+//! 启动可以回退到缓存而不是无法运行。缓存与配置文件放在同一目录，内容为配置原文。
+//! 是否回退由缓存中的 `allow_config_fallback` 决定（损坏的主配置无法读出该选项）。
+//! 只缓存主配置文件：回退时仍读取当前的 include_dir，附加配置损坏时回退同样会失败。
+
+use crate::config::{AppConfig, ConfigFormat};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// 配置文件对应的缓存路径：`<配置文件名>.last-good`
+pub fn cache_path(config_path: &Path) -> PathBuf {
+    let mut name = config_path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    name.push(".last-good");
+    config_path.with_file_name(name)
+}
+
+/// 按刚成功加载的配置更新缓存：开启 `allow_config_fallback` 时写入原文，否则删除旧缓存。
+/// 写入失败只记录警告，不影响配置本身的加载
+pub fn update(config_path: &Path, config: &AppConfig, source: &str) {
+    let path = cache_path(config_path);
+    let result = if config.allow_config_fallback {
+        write_atomically(&path, source)
+    } else {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    };
+    match result {
+        Ok(()) => debug!("ConfigCache: Updated last-known-good config {:?}", path),
+        Err(e) => warn!(
+            "ConfigCache: Failed to update last-known-good config {:?}: {}",
+            path, e
+        ),
+    }
+}
+
+/// 读取缓存的配置；缓存不存在、无法解析或其中未开启 `allow_config_fallback` 时返回 None
+pub fn load_fallback(config_path: &Path, format: ConfigFormat) -> Option<AppConfig> {
+    let source = fs::read_to_string(cache_path(config_path)).ok()?;
+    AppConfig::parse_at(&source, format, config_path)
+        .ok()
+        .filter(|config| config.allow_config_fallback)
+}
+
+// 先写临时文件再改名，避免写入中断时留下截断的缓存
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path)
+}
@@ -0,0 +1,254 @@
+//! 配置检查（`healer check`）：加载并校验配置，再检查依赖关系、引用的可执行文件与运行用户，
+//! 以 JSON 输出发现的问题，并按问题类别返回不同的退出码，便于在 CI 中使用。
+
+use crate::config::{AppConfig, ConfigError, ProcessConfig};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// 问题类别；同时存在多类问题时，退出码取数值最小的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckCategory {
+    /// 配置文件无法读取
+    ConfigRead,
+    /// 配置内容无法解析
+    ConfigParse,
+    /// 配置未通过 `AppConfig::validate`
+    ConfigInvalid,
+    /// 依赖了不存在的进程
+    UnknownDependency,
+    /// 依赖关系存在环
+    DependencyCycle,
+    /// 命令不存在或没有可执行权限
+    MissingBinary,
+    /// run_as_user 指定的用户不存在
+    UnknownUser,
+}
+
+impl CheckCategory {
+    /// 该类别对应的退出码（避开 clap 参数错误使用的 2）
+    pub fn exit_code(self) -> i32 {
+        match self {
+            CheckCategory::ConfigRead => 10,
+            CheckCategory::ConfigParse => 11,
+            CheckCategory::ConfigInvalid => 12,
+            CheckCategory::UnknownDependency => 13,
+            CheckCategory::DependencyCycle => 14,
+            CheckCategory::MissingBinary => 15,
+            CheckCategory::UnknownUser => 16,
+        }
+    }
+}
+
+/// 检查发现的一个问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckProblem {
+    pub category: CheckCategory,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process: Option<String>,
+    pub message: String,
+}
+
+/// 检查结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CheckReport {
+    pub ok: bool,
+    pub processes: usize,
+    pub problems: Vec<CheckProblem>,
+}
+
+impl CheckReport {
+    /// 没有问题时为 0，否则为最靠前类别的退出码
+    pub fn exit_code(&self) -> i32 {
+        self.problems
+            .iter()
+            .map(|problem| problem.category)
+            .min()
+            .map_or(0, CheckCategory::exit_code)
+    }
+
+    /// 配置加载失败时的结果，加载之后的检查都无法进行
+    pub fn load_failed(error: &ConfigError) -> Self {
+        let category = match error {
+            ConfigError::Io(_) => CheckCategory::ConfigRead,
+            ConfigError::Parse { .. } => CheckCategory::ConfigParse,
+            ConfigError::Validation(_) => CheckCategory::ConfigInvalid,
+        };
+        Self {
+            ok: false,
+            processes: 0,
+            problems: vec![CheckProblem {
+                category,
+                process: None,
+                message: error.to_string(),
+            }],
+        }
+    }
+}
+
+/// 检查已加载（已通过校验）的配置
+pub fn check(config: &AppConfig) -> CheckReport {
+    let mut problems = Vec::new();
+    check_dependencies(config, &mut problems);
+    for process in &config.processes {
+        check_binaries(process, &mut problems);
+        if let Some(user) = &process.run_as_user {
+            if users::get_user_by_name(user).is_none() {
+                problems.push(CheckProblem {
+                    category: CheckCategory::UnknownUser,
+                    process: Some(process.name.clone()),
+                    message: format!("run_as_user '{}' does not exist", user),
+                });
+            }
+        }
+    }
+    CheckReport {
+        ok: problems.is_empty(),
+        processes: config.processes.len(),
+        problems,
+    }
+}
+
+fn check_dependencies(config: &AppConfig, problems: &mut Vec<CheckProblem>) {
+    let names: HashSet<&str> = config.processes.iter().map(|p| p.name.as_str()).collect();
+    // 按名称排序，使环的报告顺序稳定
+    let mut graph: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for process in &config.processes {
+        let mut targets = Vec::new();
+        for dependency in process.resolved_dependencies() {
+            if names.contains(dependency.target.as_str()) {
+                targets.push(dependency.target);
+            } else {
+                problems.push(CheckProblem {
+                    category: CheckCategory::UnknownDependency,
+                    process: Some(process.name.clone()),
+                    message: format!("depends on unknown process '{}'", dependency.target),
+                });
+            }
+        }
+        graph.insert(process.name.clone(), targets);
+    }
+
+    for cycle in find_cycles(&graph) {
+        problems.push(CheckProblem {
+            category: CheckCategory::DependencyCycle,
+            process: cycle.first().cloned(),
+            message: format!("dependency cycle: {}", cycle.join(" -> ")),
+        });
+    }
+}
+
+/// 深度优先搜索，每个环只报告一次（从首次进入环的节点开始，首尾相同）
+pub fn find_cycles(graph: &BTreeMap<String, Vec<String>>) -> Vec<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Visiting,
+        Done,
+    }
+
+    fn visit(
+        node: &str,
+        graph: &BTreeMap<String, Vec<String>>,
+        states: &mut HashMap<String, State>,
+        path: &mut Vec<String>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        states.insert(node.to_string(), State::Visiting);
+        path.push(node.to_string());
+        for next in graph.get(node).into_iter().flatten() {
+            match states.get(next.as_str()) {
+                Some(State::Visiting) => {
+                    let start = path.iter().position(|n| n == next).unwrap_or(0);
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(next.clone());
+                    cycles.push(cycle);
+                }
+                Some(State::Done) => {}
+                None => visit(next, graph, states, path, cycles),
+            }
+        }
+        path.pop();
+        states.insert(node.to_string(), State::Done);
+    }
+
+    let mut states = HashMap::new();
+    let mut cycles = Vec::new();
+    for node in graph.keys() {
+        if !states.contains_key(node) {
+            visit(node, graph, &mut states, &mut Vec::new(), &mut cycles);
+        }
+    }
+    cycles
+}
+
+fn check_binaries(process: &ProcessConfig, problems: &mut Vec<CheckProblem>) {
+    // 进入其他挂载命名空间后命令在目标文件系统中解析，宿主机上无法检查
+    if process.nsenter.is_some() {
+        return;
+    }
+    let mut commands = vec![("command".to_string(), process.command.as_str())];
+    if let Some(guard) = &process.recovery_guard {
+        commands.push(("recovery_guard".to_string(), guard.command.as_str()));
+    }
+    let mut recovery_commands: Vec<_> = process.recovery_commands.iter().collect();
+    recovery_commands.sort_by_key(|(kind, _)| **kind);
+    for (kind, command) in recovery_commands {
+        commands.push((
+            format!("recovery_commands.{}", kind),
+            command.command.as_str(),
+        ));
+    }
+
+    for (label, command) in commands {
+        let resolved = match crate::utils::resolve_command(command, process.command_glob) {
+            Ok(resolved) => resolved.to_string_lossy().into_owned(),
+            Err(e) => {
+                problems.push(CheckProblem {
+                    category: CheckCategory::MissingBinary,
+                    process: Some(process.name.clone()),
+                    message: format!("{} {}", label, e),
+                });
+                continue;
+            }
+        };
+        if let Err(reason) = resolve_executable(&resolved) {
+            problems.push(CheckProblem {
+                category: CheckCategory::MissingBinary,
+                process: Some(process.name.clone()),
+                message: format!("{} '{}' {}", label, command, reason),
+            });
+        }
+    }
+}
+
+/// 按 spawn 的规则解析命令：含 `/` 时视为路径，否则在 PATH 中查找；要求是带可执行位的普通文件
+pub fn resolve_executable(command: &str) -> Result<PathBuf, String> {
+    if command.contains('/') {
+        return executable_file(Path::new(command)).map(|()| PathBuf::from(command));
+    }
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let mut last_error = "was not found in PATH".to_string();
+    for dir in std::env::split_paths(&path) {
+        let candidate = dir.join(command);
+        match executable_file(&candidate) {
+            Ok(()) => return Ok(candidate),
+            // 存在但不可执行时记下原因，继续查找后面的目录
+            Err(reason) if candidate.exists() => last_error = reason,
+            Err(_) => {}
+        }
+    }
+    Err(last_error)
+}
+
+fn executable_file(path: &Path) -> Result<(), String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("cannot be accessed: {}", e))?;
+    if !metadata.is_file() {
+        return Err("is not a regular file".to_string());
+    }
+    if metadata.permissions().mode() & 0o111 == 0 {
+        return Err("is not executable".to_string());
+    }
+    Ok(())
+}
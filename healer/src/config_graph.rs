@@ -0,0 +1,277 @@
+//! 依赖关系图导出（`healer graph`）：按各进程的 `resolved_dependencies()` 生成依赖图，
+//! 以 Graphviz DOT 或 JSON 输出，便于检查依赖配置。成环的边会被标出。
+//! 同一张图也用于 `restart_subtree`：计算依赖某个进程的全部进程及其重启顺序。
+
+use crate::config::AppConfig;
+use crate::config_check::find_cycles;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    Json,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" => Ok(GraphFormat::Dot),
+            "json" => Ok(GraphFormat::Json),
+            other => Err(format!(
+                "unknown graph format '{}', expected dot or json",
+                other
+            )),
+        }
+    }
+}
+
+/// 图中的进程；`missing` 表示被依赖但没有配置的进程
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphNode {
+    pub name: String,
+    pub enabled: bool,
+    pub missing: bool,
+}
+
+/// 一条依赖：`from` 依赖 `to`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: &'static str,
+    pub hard: bool,
+    pub max_wait_secs: u64,
+    pub on_failure: &'static str,
+    pub in_cycle: bool,
+}
+
+/// 依赖图，节点按配置顺序排列，未配置的依赖目标排在最后
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DependencyGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl DependencyGraph {
+    pub fn from_config(config: &AppConfig) -> Self {
+        let mut nodes: Vec<GraphNode> = config
+            .processes
+            .iter()
+            .map(|process| GraphNode {
+                name: process.name.clone(),
+                enabled: process.enabled,
+                missing: false,
+            })
+            .collect();
+        let mut edges = Vec::new();
+        let mut adjacency: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for process in &config.processes {
+            let mut targets = Vec::new();
+            for dependency in process.resolved_dependencies() {
+                targets.push(dependency.target.clone());
+                edges.push(GraphEdge {
+                    from: process.name.clone(),
+                    to: dependency.target,
+                    kind: dependency.kind.as_str(),
+                    hard: dependency.hard,
+                    max_wait_secs: dependency.max_wait_secs,
+                    on_failure: dependency.on_failure.as_str(),
+                    in_cycle: false,
+                });
+            }
+            adjacency.insert(process.name.clone(), targets);
+        }
+
+        for edge in &edges {
+            if !nodes.iter().any(|node| node.name == edge.to) {
+                nodes.push(GraphNode {
+                    name: edge.to.clone(),
+                    enabled: false,
+                    missing: true,
+                });
+            }
+        }
+
+        let cycles = find_cycles(&adjacency);
+        let cycle_edges: HashSet<(&str, &str)> = cycles
+            .iter()
+            .flat_map(|cycle| cycle.windows(2))
+            .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+            .collect();
+        for edge in &mut edges {
+            edge.in_cycle = cycle_edges.contains(&(edge.from.as_str(), edge.to.as_str()));
+        }
+
+        Self {
+            nodes,
+            edges,
+            cycles,
+        }
+    }
+
+    /// Graphviz DOT 格式，可直接交给 `dot -Tsvg`；边从依赖方指向被依赖方
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph healer {\n    rankdir=LR;\n    node [shape=box];\n");
+        for node in &self.nodes {
+            let mut attrs = Vec::new();
+            if node.missing {
+                attrs.push("style=dashed".to_string());
+                attrs.push("color=red".to_string());
+                attrs.push(format!(
+                    "label={}",
+                    quote(&format!("{}\\n(missing)", node.name))
+                ));
+            } else if !node.enabled {
+                attrs.push("style=dashed".to_string());
+                attrs.push("fontcolor=gray".to_string());
+            }
+            let _ = writeln!(out, "    {}{};", quote(&node.name), attr_list(&attrs));
+        }
+        for edge in &self.edges {
+            let label = format!(
+                "{}, {}, on_failure={}",
+                edge.kind,
+                if edge.hard { "hard" } else { "soft" },
+                edge.on_failure
+            );
+            let mut attrs = vec![format!("label={}", quote(&label))];
+            if !edge.hard {
+                attrs.push("style=dashed".to_string());
+            }
+            if edge.in_cycle {
+                attrs.push("color=red".to_string());
+                attrs.push("penwidth=2".to_string());
+            }
+            let _ = writeln!(
+                out,
+                "    {} -> {}{};",
+                quote(&edge.from),
+                quote(&edge.to),
+                attr_list(&attrs)
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+// DOT 的双引号字符串；`\n` 等转义已由调用方写入，这里只转义引号
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\\\""))
+}
+
+fn attr_list(attrs: &[String]) -> String {
+    if attrs.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", attrs.join(", "))
+    }
+}
+
+/// 无法确定子树重启顺序的原因
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SubtreeError {
+    #[error("dependency cycle among {}", .0.join(", "))]
+    Cycle(Vec<String>),
+    #[error("{count} dependents exceed the limit of {max}")]
+    TooLarge { count: usize, max: usize },
+}
+
+/// 传递依赖 `root` 的已启用进程，按拓扑顺序排列（每个进程排在它依赖的进程之后，
+/// 无先后约束的按配置顺序），不含 `root` 本身。依赖成环或数量超过 `max` 时返回错误
+pub fn subtree_restart_order(
+    config: &AppConfig,
+    root: &str,
+    max: usize,
+) -> Result<Vec<String>, SubtreeError> {
+    let position: HashMap<&str, usize> = config
+        .processes
+        .iter()
+        .enumerate()
+        .map(|(index, process)| (process.name.as_str(), index))
+        .collect();
+    // 被依赖方 -> 依赖它的已启用进程
+    let mut dependents: HashMap<String, Vec<&str>> = HashMap::new();
+    let mut dependencies: HashMap<&str, Vec<String>> = HashMap::new();
+    for process in config.processes.iter().filter(|p| p.enabled) {
+        let targets: Vec<String> = process
+            .resolved_dependencies()
+            .into_iter()
+            .map(|dependency| dependency.target)
+            .collect();
+        for target in &targets {
+            dependents
+                .entry(target.clone())
+                .or_default()
+                .push(process.name.as_str());
+        }
+        dependencies.insert(process.name.as_str(), targets);
+    }
+
+    let mut members: HashSet<&str> = HashSet::new();
+    let mut queue = VecDeque::from([root]);
+    while let Some(name) = queue.pop_front() {
+        for &dependent in dependents.get(name).into_iter().flatten() {
+            if dependent == root {
+                return Err(SubtreeError::Cycle(vec![root.to_string()]));
+            }
+            if members.insert(dependent) {
+                queue.push_back(dependent);
+            }
+        }
+    }
+    if members.len() > max {
+        return Err(SubtreeError::TooLarge {
+            count: members.len(),
+            max,
+        });
+    }
+
+    // Kahn 算法：只计算子树内部的依赖，root 与子树外的进程视为已就绪
+    let mut pending: HashMap<&str, usize> = members
+        .iter()
+        .map(|&name| {
+            let count = dependencies[name]
+                .iter()
+                .filter(|target| members.contains(target.as_str()))
+                .count();
+            (name, count)
+        })
+        .collect();
+    let mut order = Vec::with_capacity(members.len());
+    loop {
+        let mut ready: Vec<&str> = pending
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(&name, _)| name)
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+        ready.sort_by_key(|name| position[name]);
+        for name in ready {
+            pending.remove(name);
+            for &dependent in dependents.get(name).into_iter().flatten() {
+                if let Some(count) = pending.get_mut(dependent) {
+                    *count -= 1;
+                }
+            }
+            order.push(name.to_string());
+        }
+    }
+    if !pending.is_empty() {
+        let mut remaining: Vec<&str> = pending.into_keys().collect();
+        remaining.sort_by_key(|name| position[name]);
+        return Err(SubtreeError::Cycle(
+            remaining.into_iter().map(str::to_string).collect(),
+        ));
+    }
+    Ok(order)
+}
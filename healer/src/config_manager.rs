@@ -1,80 +1,184 @@
-use crate::config::AppConfig;
+use crate::config::{AppConfig, ConfigError, ConfigFormat};
+use crate::config_cache;
+use crate::utils;
 use anyhow::Result;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::time::Instant;
 use tracing::{debug, error, info, warn};
 
+/// 配置重载的计数，克隆后共享同一组计数
+#[derive(Debug, Clone, Default)]
+pub struct ReloadStats {
+    reloads: Arc<AtomicU64>,
+    throttled: Arc<AtomicU64>,
+}
+
+impl ReloadStats {
+    /// 实际生效的重载次数
+    #[allow(dead_code)]
+    pub fn reloads(&self) -> u64 {
+        self.reloads.load(Ordering::Relaxed)
+    }
+
+    /// 落在 reload_debounce_ms 窗口内、被合并到待执行重载中的请求数
+    #[allow(dead_code)]
+    pub fn throttled(&self) -> u64 {
+        self.throttled.load(Ordering::Relaxed)
+    }
+}
+
 // 配置管理器，负责配置的加载和热更新
 pub struct ConfigManager {
     config: Arc<RwLock<AppConfig>>,
     config_path: std::path::PathBuf,
+    format: ConfigFormat,
+    // 最近一次 load_staged 读到的配置原文，commit 时写入 last-known-good 缓存
+    staged_source: Mutex<Option<String>>,
+    // reload_debounce_ms 窗口内待执行的重载及其执行时间
+    pending_reload: Mutex<Option<Instant>>,
+    stats: ReloadStats,
 }
 
 impl ConfigManager {
+    /// 配置格式默认按文件扩展名判断
     pub fn new(config: Arc<RwLock<AppConfig>>, config_path: std::path::PathBuf) -> Self {
         Self {
             config,
+            format: ConfigFormat::from_path(&config_path),
             config_path,
+            staged_source: Mutex::new(None),
+            pending_reload: Mutex::new(None),
+            stats: ReloadStats::default(),
+        }
+    }
+
+    /// 显式指定配置格式（如命令行的 --config-format）
+    pub fn with_format(mut self, format: ConfigFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 重载计数
+    pub fn stats(&self) -> ReloadStats {
+        self.stats.clone()
+    }
+
+    /// 登记一次重载请求（信号、控制命令等）。reload_debounce_ms 为 0 时返回 true，由调用方立即重载；
+    /// 否则在第一次请求后的窗口结束时只重载一次，窗口内的后续请求合并进来，返回 false。
+    /// 窗口从第一次请求起算、不随后续请求顺延，持续的重载风暴也不会让重载一直推迟
+    pub async fn request_reload(&self) -> bool {
+        let debounce_ms = self.config.read().await.reload_debounce_ms;
+        if debounce_ms == 0 {
+            return true;
+        }
+        let now = Instant::now();
+        let mut pending = utils::lock_or_recover(&self.pending_reload, "pending reload");
+        match *pending {
+            Some(deadline) => {
+                self.stats.throttled.fetch_add(1, Ordering::Relaxed);
+                info!(
+                    "ConfigManager: Reload requested again within reload_debounce_ms ({} ms), throttling: collapsed into the reload due in {} ms.",
+                    debounce_ms,
+                    deadline.saturating_duration_since(now).as_millis()
+                );
+            }
+            None => {
+                *pending = Some(now + Duration::from_millis(debounce_ms));
+                debug!("ConfigManager: Reload scheduled in {} ms.", debounce_ms);
+            }
+        }
+        false
+    }
+
+    /// 待执行重载的执行时间
+    pub fn pending_reload(&self) -> Option<Instant> {
+        *utils::lock_or_recover(&self.pending_reload, "pending reload")
+    }
+
+    /// 待执行的重载已到期时清除并返回 true，调用方随后执行重载
+    pub fn take_pending_reload(&self) -> bool {
+        let mut pending = utils::lock_or_recover(&self.pending_reload, "pending reload");
+        if pending.is_some_and(|deadline| deadline <= Instant::now()) {
+            *pending = None;
+            return true;
         }
+        false
     }
 
-    // 重新加载配置文件
+    // 重新加载配置文件并立即替换
+    #[allow(dead_code)]
     pub async fn reload_config(&self) -> Result<()> {
+        let new_config = self.load_staged()?;
+        self.commit(new_config).await;
+        Ok(())
+    }
+
+    /// 从文件加载并校验新配置，但不替换当前配置；调用方确认可以应用后再调用 [`Self::commit`]
+    pub fn load_staged(&self) -> Result<AppConfig> {
         info!(
             "ConfigManager: Reloading configuration from {:?}",
             self.config_path
         );
         // 先加载到临时变量，避免持锁期间做IO
         let load_start = std::time::Instant::now();
-        let load_result = AppConfig::load_from_file(&self.config_path);
+        let load_result = std::fs::read_to_string(&self.config_path)
+            .map_err(ConfigError::from)
+            .and_then(|source| {
+                let config = AppConfig::parse_at(&source, self.format, &self.config_path)?;
+                *utils::lock_or_recover(&self.staged_source, "staged config source") = Some(source);
+                Ok(config)
+            });
         debug!(
             elapsed_ms = load_start.elapsed().as_millis() as u64,
             "ConfigManager: load_from_file completed"
         );
+        load_result.map_err(|e| {
+            error!("ConfigManager: Failed to reload configuration: {}", e);
+            anyhow::anyhow!("Failed to reload configuration: {}", e)
+        })
+    }
 
-        match load_result {
-            Ok(new_config) => {
-                debug!("ConfigManager: Attempting to acquire write lock for config swap");
-                let lock_start = std::time::Instant::now();
-                let mut config_guard = match tokio::time::timeout(
-                    std::time::Duration::from_secs(3),
-                    self.config.write(),
-                )
-                .await
-                {
-                    Ok(g) => g,
-                    Err(_) => {
-                        warn!("ConfigManager: Timeout waiting for write lock (possible read-lock held across await). Falling back to blocking acquire with periodic logging.");
-                        let mut waited_ms = 0u64;
-                        loop {
-                            match self.config.try_write() {
-                                Ok(g) => break g,
-                                Err(_) => {
-                                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-                                    waited_ms += 50;
-                                    if waited_ms % 500 == 0 {
-                                        debug!(
-                                            waited_ms,
-                                            "ConfigManager: still waiting for write lock..."
-                                        );
-                                    }
-                                }
+    /// 用已加载的配置替换当前配置，并更新 last-known-good 缓存
+    pub async fn commit(&self, new_config: AppConfig) {
+        let source = utils::lock_or_recover(&self.staged_source, "staged config source").take();
+        if let Some(source) = source {
+            config_cache::update(&self.config_path, &new_config, &source);
+        }
+        debug!("ConfigManager: Attempting to acquire write lock for config swap");
+        let lock_start = std::time::Instant::now();
+        let mut config_guard = match tokio::time::timeout(
+            std::time::Duration::from_secs(3),
+            self.config.write(),
+        )
+        .await
+        {
+            Ok(g) => g,
+            Err(_) => {
+                warn!("ConfigManager: Timeout waiting for write lock (possible read-lock held across await). Falling back to blocking acquire with periodic logging.");
+                let mut waited_ms = 0u64;
+                loop {
+                    match self.config.try_write() {
+                        Ok(g) => break g,
+                        Err(_) => {
+                            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                            waited_ms += 50;
+                            if waited_ms % 500 == 0 {
+                                debug!(waited_ms, "ConfigManager: still waiting for write lock...");
                             }
                         }
                     }
-                };
-                debug!(
-                    wait_ms = lock_start.elapsed().as_millis() as u64,
-                    "ConfigManager: Acquired write lock, swapping config"
-                );
-                *config_guard = new_config;
-                info!("ConfigManager: Configuration reloaded successfully.");
-                Ok(())
+                }
             }
-            Err(e) => {
-                error!("ConfigManager: Failed to reload configuration: {}", e);
-                Err(anyhow::anyhow!("Failed to reload configuration: {}", e))
-            }
-        }
+        };
+        debug!(
+            wait_ms = lock_start.elapsed().as_millis() as u64,
+            "ConfigManager: Acquired write lock, swapping config"
+        );
+        *config_guard = new_config;
+        self.stats.reloads.fetch_add(1, Ordering::Relaxed);
+        info!("ConfigManager: Configuration reloaded successfully.");
     }
 }
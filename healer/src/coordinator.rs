@@ -1 +1,2 @@
 pub mod dependency_coordinator;
+pub mod readiness;
@@ -1,6 +1,9 @@
+use super::readiness::{ProbeReadinessChecker, ReadinessChecker};
 use crate::{
     config::{AppConfig, DependencyConfig, DependencyKind, OnFailure},
-    event_bus::ProcessEvent,
+    event_bus::{EventReceiver, EventSender, ProcessEvent},
+    health::HealthRegistry,
+    metrics::{LatencyKind, Metrics},
     publisher::Publisher,
     subscriber::Subscriber,
 };
@@ -16,17 +19,25 @@ use tokio::sync::RwLock;
 
 pub struct DependencyCoordinator {
     /// 下游事件总线（发给 Healer 等消费者）
-    pub out_tx: broadcast::Sender<ProcessEvent>,
+    pub out_tx: EventSender,
     /// 上游事件接收器（来自各 Monitor）
-    pub in_rx: broadcast::Receiver<ProcessEvent>,
+    pub in_rx: EventReceiver,
     pub app_config: Arc<RwLock<AppConfig>>,
     // 受管目标集合（来自配置 processes.name），用于区分已托管与未知目标
     managed_targets: HashSet<String>,
     deferred: HashMap<String, DeferredState>, // 延迟恢复状态表
+    /// 正在后台探测依赖、尚未决定放行还是延迟的进程（期间的重复事件被忽略）
+    probing: HashSet<String>,
     retry_tx: UnboundedSender<InternalMsg>,
     retry_rx: UnboundedReceiver<InternalMsg>,
     /// 处于recovering的目标及其过期时间（用于为简单依赖提供阻塞）
     recovering_until: HashMap<String, Instant>,
+    /// 查询依赖目标是否就绪（配置了 readiness 探针时）
+    readiness: Arc<dyn ReadinessChecker>,
+    /// 记录每次延后恢复的时长
+    metrics: Metrics,
+    /// 对外公布当前被延后的进程及其等待的依赖（供状态导出）
+    health: HealthRegistry,
 }
 
 #[derive(Debug, Clone)]
@@ -57,28 +68,70 @@ enum DepWaitStatus {
 #[derive(Debug, Clone)]
 enum InternalMsg {
     Retry(String),
+    /// 后台就绪探测完成：依赖目标 -> 探测结果（未配置探针时为 None）
+    Probed {
+        name: String,
+        stage: ProbeStage,
+        results: HashMap<String, Option<bool>>,
+    },
+}
+
+/// 发起就绪探测的时机
+#[derive(Debug, Clone)]
+enum ProbeStage {
+    /// 首次收到故障事件，探测后决定立即放行还是延迟
+    Initial {
+        event: ProcessEvent,
+        manual_deps: Vec<DependencyConfig>,
+    },
+    /// 延迟中的进程到期重新评估
+    Retry,
 }
 
 impl DependencyCoordinator {
     pub fn new(
-        in_rx: broadcast::Receiver<ProcessEvent>,
-        out_tx: broadcast::Sender<ProcessEvent>,
+        in_rx: impl Into<EventReceiver>,
+        out_tx: EventSender,
         app_config: Arc<RwLock<AppConfig>>,
     ) -> Self {
         let (retry_tx, retry_rx) = unbounded_channel();
+        let readiness = Arc::new(ProbeReadinessChecker::new(Arc::clone(&app_config)));
         Self {
             out_tx,
-            in_rx,
+            in_rx: in_rx.into(),
             app_config,
             managed_targets: HashSet::new(),
             deferred: HashMap::new(),
+            probing: HashSet::new(),
             retry_tx,
             retry_rx,
             recovering_until: HashMap::new(),
+            readiness,
+            metrics: Metrics::new(),
+            health: HealthRegistry::new(),
         }
     }
 
-    /// 用于阻塞窗口（秒）：把收到 Down/Disconnected 的目标短暂标记为recovering
+    /// 替换就绪检查的实现（默认按配置中的 readiness 探针探测）
+    #[allow(dead_code)]
+    pub fn with_readiness_checker(mut self, readiness: Arc<dyn ReadinessChecker>) -> Self {
+        self.readiness = readiness;
+        self
+    }
+
+    /// 使用共享的延迟指标表
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 使用共享的健康状态表，延后的恢复会登记在其中
+    pub fn with_health(mut self, health: HealthRegistry) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// 用于阻塞窗口（秒）：把收到 Down/Disconnected/ResourceExceeded 的目标短暂标记为recovering
     const RECOVERING_HOLD_SECS: u64 = 10;
 
     fn mark_recovering_until(&mut self, name: &str, now: Instant) {
@@ -95,6 +148,35 @@ impl DependencyCoordinator {
             .unwrap_or(false)
     }
 
+    /// 依赖是否仍在阻塞：配置了就绪探针时以探测结果为准（进程存在但未就绪同样阻塞），
+    /// 否则看是否处于recovering窗口
+    fn is_blocking(&self, target: &str, ready: Option<bool>, now: Instant) -> bool {
+        match ready {
+            Some(ready) => !ready,
+            None => self.is_recovering(target, now),
+        }
+    }
+
+    /// 在后台任务中并发探测依赖目标，结果经内部通道交回 run_loop，慢探针不阻塞其他进程的事件
+    fn spawn_probes(&self, name: String, targets: Vec<String>, stage: ProbeStage) {
+        let readiness = Arc::clone(&self.readiness);
+        let tx = self.retry_tx.clone();
+        tokio::spawn(async move {
+            let checks = targets
+                .iter()
+                .map(|target| async { (target.clone(), readiness.check(target).await) });
+            let results = futures::future::join_all(checks)
+                .await
+                .into_iter()
+                .collect();
+            let _ = tx.send(InternalMsg::Probed {
+                name,
+                stage,
+                results,
+            });
+        });
+    }
+
     fn prune_recovering(&mut self) {
         let now = Instant::now();
         self.recovering_until.retain(|_, &mut until| now < until);
@@ -115,7 +197,8 @@ impl DependencyCoordinator {
     async fn decide_and_publish(&mut self, evt: &ProcessEvent) {
         match evt {
             ProcessEvent::ProcessDown { name, .. }
-            | ProcessEvent::ProcessDisconnected { name, .. } => {
+            | ProcessEvent::ProcessDisconnected { name, .. }
+            | ProcessEvent::ProcessResourceExceeded { name, .. } => {
                 // 刷新一次快照
                 self.refresh_snapshot().await;
 
@@ -123,8 +206,8 @@ impl DependencyCoordinator {
                 let now = Instant::now();
                 self.mark_recovering_until(name, now);
 
-                // 已存在延迟状态则忽略重复原始事件（监控高频触发），仅记录日志
-                if self.deferred.contains_key(name) {
+                // 已存在延迟状态或正在探测依赖则忽略重复原始事件（监控高频触发），仅记录日志
+                if self.deferred.contains_key(name) || self.probing.contains(name) {
                     tracing::debug!(target="dep_coord", process=%name, "event ignored (already deferred)");
                     return;
                 }
@@ -147,32 +230,58 @@ impl DependencyCoordinator {
                 if deps.is_empty() {
                     // 没有受管依赖，直接放行
                     tracing::info!(target="dep_coord", process=%name, "no managed dependencies -> forward now");
-                    let _ = self.publish(evt.clone());
+                    let _ = self.publish(evt.clone()).await;
                     return;
                 }
 
-                // 计算阻塞：依赖中是否有目标处于recovering窗口
+                // 探测依赖是否就绪，结果回到 run_loop 后再决定放行还是延迟（见 handle_probed）
+                let targets = deps.into_iter().filter(|d| d != name).collect();
+                self.probing.insert(name.clone());
+                self.spawn_probes(
+                    name.clone(),
+                    targets,
+                    ProbeStage::Initial {
+                        event: evt.clone(),
+                        manual_deps,
+                    },
+                );
+            }
+            // 其它事件（例如恢复成功/失败）目前直接透传
+            _ => {
+                let _ = self.publish(evt.clone()).await;
+            }
+        }
+    }
+
+    async fn handle_probed(
+        &mut self,
+        name: String,
+        stage: ProbeStage,
+        results: HashMap<String, Option<bool>>,
+    ) {
+        match stage {
+            ProbeStage::Initial { event, manual_deps } => {
+                self.probing.remove(&name);
+                // 计算阻塞：依赖中是否有目标未就绪或处于recovering窗口
+                let now = Instant::now();
+                let deps: Vec<String> = manual_deps.iter().map(|d| d.target.clone()).collect();
                 let blocking: Vec<String> = deps
                     .iter()
                     .filter(|d| d.as_str() != name)
-                    .filter(|d| self.is_recovering(d, now))
+                    .filter(|d| self.is_blocking(d, results.get(*d).copied().flatten(), now))
                     .cloned()
                     .collect();
 
                 if blocking.is_empty() {
                     // 首次出现依赖但当前无阻塞 -> 放行并提示
                     tracing::info!(target="dep_coord", process=%name, deps=?deps, "dependencies present, none blocking -> forward");
-                    let _ = self.publish(evt.clone());
+                    let _ = self.publish(event).await;
                 } else {
                     // 进入延迟（记录每个依赖的 max_wait_secs / on_failure）
-                    self.defer_process(name.clone(), evt.clone(), manual_deps, blocking)
-                        .await;
+                    self.defer_process(name, event, manual_deps, blocking).await;
                 }
             }
-            // 其它事件（例如恢复成功/失败）目前直接透传
-            _ => {
-                let _ = self.publish(evt.clone());
-            }
+            ProbeStage::Retry => self.finish_retry(name, results).await,
         }
     }
 
@@ -219,6 +328,7 @@ impl DependencyCoordinator {
             waiting_on: blocking.clone(),
         };
         self.deferred.insert(name.clone(), state);
+        self.health.set_deferred(&name, blocking.clone());
         tracing::warn!(target="dep_coord", process=%name, deps=?deps, waiting_on=?blocking, backoff_s=5, "deferred recovery (blocking dependencies)" );
         // 通过内部通道安排一次定时重试（见 run_loop 的 retry 分支）
         self.schedule_retry(name, Duration::from_secs(5));
@@ -243,6 +353,29 @@ impl DependencyCoordinator {
     }
 
     async fn handle_retry(&mut self, name: String) {
+        self.refresh_snapshot().await;
+        // 只探测仍在等待的受管依赖，结果回到 run_loop 后继续评估（见 finish_retry）
+        let Some(state) = self.deferred.get(&name) else {
+            return;
+        };
+        let targets = state
+            .deps
+            .iter()
+            .filter(|d| self.is_waiting_on(&name, d))
+            .map(|d| d.cfg.target.clone())
+            .collect();
+        self.spawn_probes(name, targets, ProbeStage::Retry);
+    }
+
+    fn is_waiting_on(&self, name: &str, dep: &PerDepState) -> bool {
+        dep.status == DepWaitStatus::Waiting
+            && dep.cfg.kind == DependencyKind::Requires
+            && dep.cfg.hard
+            && dep.cfg.target != name
+            && self.managed_targets.contains(&dep.cfg.target)
+    }
+
+    async fn finish_retry(&mut self, name: String, results: HashMap<String, Option<bool>>) {
         let mut remove_and_forward = None;
         let mut drop_due_to_abort = false;
         // 拿到当前状态的克隆关键信息（避免持有可变引用期间再借 self）
@@ -261,20 +394,14 @@ impl DependencyCoordinator {
             return;
         }
 
-        self.refresh_snapshot().await;
         // 评估当前哪些依赖仍然在阻塞（仍处于 deferred 集合中）
         let mut currently_blocking: HashSet<String> = HashSet::new();
         if let Some(state) = self.deferred.get(&name) {
-            // 使用recovering窗口作为阻塞依据
+            // 使用就绪探针或recovering窗口作为阻塞依据
             let now = Instant::now();
             for d in &state.deps {
-                if d.status == DepWaitStatus::Waiting
-                    && d.cfg.kind == DependencyKind::Requires
-                    && d.cfg.hard
-                    && d.cfg.target != name
-                    && self.managed_targets.contains(&d.cfg.target)
-                    && self.is_recovering(&d.cfg.target, now)
-                {
+                let ready = results.get(&d.cfg.target).copied().flatten();
+                if self.is_waiting_on(&name, d) && self.is_blocking(&d.cfg.target, ready, now) {
                     currently_blocking.insert(d.cfg.target.clone());
                 }
             }
@@ -316,6 +443,7 @@ impl DependencyCoordinator {
         if drop_due_to_abort {
             // 丢弃事件并移除延迟状态（Abort 策略）
             self.deferred.remove(&name);
+            self.health.clear_deferred(&name);
             return;
         }
 
@@ -332,13 +460,17 @@ impl DependencyCoordinator {
 
         if still_blocking.is_empty() {
             if let Some(state) = self.deferred.get(&name) {
-                tracing::info!(target="dep_coord", process=%name, deferred_for=?state.first_deferred_at.elapsed(), "release deferred process (no more blocking or timed out per policy)");
+                let deferred_for = state.first_deferred_at.elapsed();
+                tracing::info!(target="dep_coord", process=%name, deferred_for=?deferred_for, "release deferred process (no more blocking or timed out per policy)");
+                self.metrics
+                    .observe(LatencyKind::CoordinatorDeferral, &name, deferred_for);
             }
             remove_and_forward = orig_event_opt;
         } else {
             if let Some(state) = self.deferred.get_mut(&name) {
                 state.deferred_count += 1;
                 state.waiting_on = still_blocking.clone();
+                self.health.set_deferred(&name, still_blocking);
                 state.last_eval_at = Instant::now();
                 let backoff = Self::compute_backoff(state.deferred_count);
                 state.next_retry_at = Instant::now() + backoff;
@@ -348,7 +480,8 @@ impl DependencyCoordinator {
         }
         if let Some(evt) = remove_and_forward {
             self.deferred.remove(&name);
-            let _ = self.publish(evt);
+            self.health.clear_deferred(&name);
+            let _ = self.publish(evt).await;
         }
     }
 
@@ -358,11 +491,16 @@ impl DependencyCoordinator {
             tokio::select! {
                 biased;
                 maybe_msg = self.retry_rx.recv() => {
-                    if let Some(InternalMsg::Retry(name)) = maybe_msg {
+                    match maybe_msg {
                         // 接收内部重试消息，进入一次评估/重试周期
-                        self.handle_retry(name).await;
-                    } else if maybe_msg.is_none() {
-                        tracing::warn!(target="dep_coord", "internal retry channel closed");
+                        Some(InternalMsg::Retry(name)) => self.handle_retry(name).await,
+                        // 后台就绪探测完成，继续首次评估或重试
+                        Some(InternalMsg::Probed { name, stage, results }) => {
+                            self.handle_probed(name, stage, results).await;
+                        }
+                        None => {
+                            tracing::warn!(target="dep_coord", "internal retry channel closed");
+                        }
                     }
                 }
                 recv_res = self.in_rx.recv() => {
@@ -382,12 +520,13 @@ impl DependencyCoordinator {
     }
 }
 
+#[async_trait]
 impl Publisher for DependencyCoordinator {
-    fn publish(
+    async fn publish(
         &self,
         event: ProcessEvent,
     ) -> Result<usize, broadcast::error::SendError<ProcessEvent>> {
-        self.out_tx.send(event)
+        self.out_tx.send(event).await
     }
 }
 
@@ -0,0 +1,61 @@
+//! 就绪检查：依赖协调器在放行被延迟的恢复前查询依赖目标是否“可以提供服务”。
+//! 目标配置了 `readiness` 探针时以探针结果为准，未配置时由协调器按存活状态（恢复窗口）判断。
+
+use crate::config::{AppConfig, ReadinessProbe};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+
+#[async_trait]
+pub trait ReadinessChecker: Send + Sync {
+    /// 目标配置了就绪探针时返回本次探测是否通过；未配置时返回 None
+    async fn check(&self, process: &str) -> Option<bool>;
+}
+
+/// 按配置中的 `readiness` 探针实际探测
+pub struct ProbeReadinessChecker {
+    app_config: Arc<RwLock<AppConfig>>,
+    client: reqwest::Client,
+}
+
+impl ProbeReadinessChecker {
+    pub fn new(app_config: Arc<RwLock<AppConfig>>) -> Self {
+        Self {
+            app_config,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ReadinessChecker for ProbeReadinessChecker {
+    async fn check(&self, process: &str) -> Option<bool> {
+        // 探测前释放配置锁，避免慢探针阻塞热重载
+        let probe = {
+            let cfg = self.app_config.read().await;
+            cfg.get_process_config_for(process)?.readiness.clone()?
+        };
+        let ready = probe_once(&self.client, &probe).await;
+        tracing::debug!(target="dep_coord", process=%process, ready, "readiness probe");
+        Some(ready)
+    }
+}
+
+/// 执行一次探测，超时视为未就绪
+pub async fn probe_once(client: &reqwest::Client, probe: &ReadinessProbe) -> bool {
+    let timeout = Duration::from_secs(probe.timeout_secs());
+    match probe {
+        ReadinessProbe::Http(fields) => {
+            match tokio::time::timeout(timeout, client.get(&fields.url).send()).await {
+                Ok(Ok(response)) => response.status().is_success(),
+                _ => false,
+            }
+        }
+        ReadinessProbe::Tcp(fields) => matches!(
+            tokio::time::timeout(timeout, TcpStream::connect(fields.address.as_str())).await,
+            Ok(Ok(_))
+        ),
+    }
+}
@@ -1,25 +1,63 @@
 use crate::{
-    config::AppConfig,
-    config_manager::ConfigManager,
-    event_bus,
+    config::{AppConfig, ConfigFormat},
+    config_manager::{ConfigManager, ReloadStats},
+    control::{ControlServer, ControlState},
+    daemon_handler::DaemonIdentity,
+    event_bus::EventSender,
+    health::HealthRegistry,
+    http_api::{HttpApiServer, HttpApiState},
+    metrics::Metrics,
+    monitor::ebpf_monitor::EbpfWorkerOptions,
     monitor_manager::MonitorManager,
-    service_manager::ServiceManager,
+    service_manager::{CriticalSupervision, ServiceManager},
     signal_handler::{SignalEvent, SignalHandler},
+    subscriber::{child_registry::ChildRegistry, event_history::EventHistory},
+    watchdog::{Heartbeat, Watchdog, HEARTBEAT_INTERVAL},
 };
-use anyhow::Result;
-use std::{path::PathBuf, sync::Arc};
-use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use anyhow::{anyhow, Result};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
 
-pub fn async_runtime(app_config: Arc<RwLock<AppConfig>>, config_path: PathBuf) {
-    println!("Async runtime: Starting process monitoring");
+/// 核心运行时的启动选项
+#[derive(Debug, Clone)]
+pub struct RuntimeOptions {
+    /// 配置文件路径，为空时不支持重载
+    pub config_path: Option<PathBuf>,
+    /// 重载时使用的配置格式，为空时按扩展名判断
+    pub config_format: Option<ConfigFormat>,
+    /// 是否加载全局 eBPF 监控器
+    pub enable_ebpf: bool,
+    /// 是否运行在前台（有终端）
+    pub foreground: bool,
+    /// 为 false 时恢复命令不切换 uid/gid（--no-drop-privileges，仅用于调试）
+    pub drop_privileges: bool,
+}
 
-    let rt = match tokio::runtime::Builder::new_multi_thread()
+/// 构建守护进程使用的多线程 runtime，`worker_threads` 至少为 1
+pub fn build_runtime(worker_threads: usize) -> std::io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_multi_thread()
         .enable_all()
-        .worker_threads(4)
+        .worker_threads(worker_threads.max(1))
         .thread_name("healer")
         .build()
-    {
+}
+
+pub fn async_runtime(
+    app_config: Arc<RwLock<AppConfig>>,
+    config_path: Option<PathBuf>,
+    config_format: Option<ConfigFormat>,
+    foreground: bool,
+    drop_privileges: bool,
+    worker_threads: usize,
+) {
+    println!(
+        "Async runtime: Starting process monitoring with {} worker thread(s)",
+        worker_threads
+    );
+
+    let rt = match build_runtime(worker_threads) {
         Ok(r) => r,
         Err(e) => {
             eprintln!("Async runtime: Error from {}", e);
@@ -28,98 +66,451 @@ pub fn async_runtime(app_config: Arc<RwLock<AppConfig>>, config_path: PathBuf) {
     };
 
     rt.block_on(async {
-        if let Err(e) = daemon_core_logic(app_config, config_path).await {
+        if let Err(e) = daemon_core_logic(
+            app_config,
+            config_path,
+            config_format,
+            foreground,
+            drop_privileges,
+        )
+        .await
+        {
             error!("Core logic error: {}", e);
             std::process::exit(1);
         }
     });
 }
 
-async fn daemon_core_logic(config: Arc<RwLock<AppConfig>>, config_path: PathBuf) -> Result<()> {
-    info!("Application Core Logic: Starting up and initializing components...");
+async fn daemon_core_logic(
+    config: Arc<RwLock<AppConfig>>,
+    config_path: Option<PathBuf>,
+    config_format: Option<ConfigFormat>,
+    foreground: bool,
+    drop_privileges: bool,
+) -> Result<()> {
+    if config_path.is_none() {
+        warn!("Application Core Logic: Configuration was read from stdin, reload signals are ignored.");
+    }
+    let options = RuntimeOptions {
+        config_path,
+        config_format,
+        enable_ebpf: true,
+        foreground,
+        drop_privileges,
+    };
+    let (watchdog_timeout_secs, signals, identity) = {
+        let cfg = config.read().await;
+        // 在做任何事之前解析 daemon_user / daemon_group，用户不存在时直接失败
+        let identity =
+            DaemonIdentity::resolve(cfg.daemon_user.as_deref(), cfg.daemon_group.as_deref())
+                .map_err(|e| anyhow!("Invalid daemon_user/daemon_group: {:?}", e))?;
+        (cfg.watchdog_timeout_secs, cfg.signals.clone(), identity)
+    };
+    let core = CoreRuntime::start(Arc::clone(&config), options).await?;
 
-    // 1. 创建事件总线
-    // 事件通道拆分：monitors -> coordinator_in, coordinator_out -> healer
-    let monitor_event_sender = event_bus::create_event_sender();
-    let coordinator_event_sender = event_bus::create_event_sender();
-    info!("Application Core Logic: Event bus created.");
+    // eBPF 已挂载、套接字已绑定，不再需要特权
+    if let Some(identity) = identity {
+        drop_daemon_privileges(identity, &config).await?;
+    }
+
+    // 自我看门狗（可选）：主循环卡死时退出，由外部监管者重启
+    if let Some(timeout_secs) = watchdog_timeout_secs {
+        if let Err(e) = Watchdog::spawn(
+            core.heartbeat(),
+            Duration::from_secs(timeout_secs),
+            Watchdog::exit_process,
+        ) {
+            error!("Application Core Logic: Failed to start watchdog: {}", e);
+        }
+    }
 
-    // 2. 初始化各个管理器，包括配置管理器喝监视器管理器
-    let config_manager = ConfigManager::new(Arc::clone(&config), config_path);
-    let mut monitor_manager = MonitorManager::new(monitor_event_sender.clone()).await?;
+    // 守护进程模式下由系统信号驱动重载与关闭
+    let (control_tx, control_rx) = mpsc::channel(8);
+    SignalHandler::spawn_forwarder(control_tx, &signals);
+    core.run_until_shutdown(control_rx).await?;
 
-    // 3. 启动持久性后台服务
-    ServiceManager::spawn_persistent_services(
-        &monitor_event_sender,
-        &coordinator_event_sender,
-        &config,
-    );
-    info!("Application Core Logic: Persistent services started.");
+    // 7. 确保进程正确退出
+    info!("Application Core Logic: Exiting process...");
+    std::process::exit(0);
+}
 
-    // 4. 进行初始配置协调
+// 等到给定时间；为 None 时永不完成（select! 中由条件禁用）
+async fn sleep_until_deadline(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
 
-    {
-        let processes_snapshot = {
-            let guard = config.read().await;
-            debug!(
-                "Initial load: {} process configurations",
-                guard.processes.len()
+// 切换守护进程自身的身份，并提示切换后无法按配置身份恢复的进程
+async fn drop_daemon_privileges(
+    identity: DaemonIdentity,
+    config: &Arc<RwLock<AppConfig>>,
+) -> Result<()> {
+    identity
+        .apply()
+        .map_err(|e| anyhow!("Failed to drop daemon privileges: {:?}", e))?;
+    info!(
+        "Application Core Logic: Dropped daemon privileges to uid {} gid {}.",
+        identity.uid, identity.gid
+    );
+    if identity.uid == 0 {
+        return Ok(());
+    }
+    for process in config.read().await.processes.iter().filter(|p| p.enabled) {
+        let recoverable = !process.run_as_root
+            && process
+                .run_as_user
+                .as_deref()
+                .and_then(users::get_user_by_name)
+                .is_some_and(|user| user.uid() == identity.uid);
+        if !recoverable {
+            warn!(
+                "Application Core Logic: Process '{}' is not configured to run as the daemon user, its recovery will fail now that privileges are dropped.",
+                process.name
             );
-            guard.processes.clone()
+        }
+    }
+    Ok(())
+}
+
+/// 已完成初始化的核心运行时：事件总线、后台服务与监控器均已就绪。
+/// 守护进程与库入口（`crate::app::Healer`）共用这一套启动/关闭流程。
+pub(crate) struct CoreRuntime {
+    config: Arc<RwLock<AppConfig>>,
+    config_manager: Option<ConfigManager>,
+    monitor_manager: MonitorManager,
+    service_handles: Vec<JoinHandle<()>>,
+    health: HealthRegistry,
+    history: EventHistory,
+    metrics: Metrics,
+    heartbeat: Heartbeat,
+    // 运行时内部（如控制套接字）发来的请求
+    runtime_rx: mpsc::Receiver<SignalEvent>,
+}
+
+impl CoreRuntime {
+    pub(crate) async fn start(
+        config: Arc<RwLock<AppConfig>>,
+        options: RuntimeOptions,
+    ) -> Result<Self> {
+        info!("Application Core Logic: Starting up and initializing components...");
+
+        // 1. 创建事件总线
+        // 事件通道拆分：monitors -> coordinator_in, coordinator_out -> healer
+        // 两条通道都在关键订阅者订阅后才放行，早到的事件等待而不是因无人订阅而丢失
+        let monitor_event_sender = EventSender::gated();
+        let coordinator_event_sender = EventSender::gated();
+        info!("Application Core Logic: Event bus created.");
+
+        // 2. 初始化各个管理器，包括配置管理器喝监视器管理器
+        let config_manager = options.config_path.map(|path| {
+            let manager = ConfigManager::new(Arc::clone(&config), path);
+            match options.config_format {
+                Some(format) => manager.with_format(format),
+                None => manager,
+            }
+        });
+        let health = HealthRegistry::new();
+        let mut monitor_manager = if options.enable_ebpf {
+            // 挂载点与 worker 设置只在启动时选择，重载配置不会改变
+            let (ebpf_attach, ebpf_workers) = {
+                let config = config.read().await;
+                (config.ebpf_attach, EbpfWorkerOptions::from_config(&config))
+            };
+            MonitorManager::new(monitor_event_sender.clone(), ebpf_attach, ebpf_workers).await?
+        } else {
+            MonitorManager::new_without_ebpf(monitor_event_sender.clone())
+        }
+        .with_health(health.clone());
+
+        // 3. 启动持久性后台服务
+        let control_config = config.read().await.control.clone();
+        let history = EventHistory::new(control_config.event_history_capacity);
+        let children = ChildRegistry::new();
+        let labels = config.read().await.labels.clone();
+        let metrics = Metrics::new().with_labels(labels.clone());
+        let (runtime_tx, runtime_rx) = mpsc::channel(8);
+        let supervision = CriticalSupervision {
+            policies: config.read().await.subscribers,
+            runtime_tx: runtime_tx.clone(),
         };
-        for (i, process_config) in processes_snapshot.iter().enumerate() {
-            debug!(
-                "Process {}: name='{}', command='{}'",
-                i + 1,
-                process_config.name,
-                process_config.command
-            );
-            if let Some(ebpf_config) = process_config.get_ebpf_monitor_config() {
-                let executable_name = crate::utils::extract_executable_name(&ebpf_config.command);
-                let truncated_name = crate::utils::truncate_process_name(&executable_name);
+        if !options.drop_privileges {
+            error!("Application Core Logic: Privilege dropping is DISABLED (--no-drop-privileges): every recovery command runs as the daemon user, ignoring run_as_user. Use only for debugging.");
+        }
+        let mut service_handles = ServiceManager::spawn_persistent_services(
+            &monitor_event_sender,
+            &coordinator_event_sender,
+            &config,
+            options.foreground,
+            options.drop_privileges,
+            &health,
+            &history,
+            &children,
+            &metrics,
+            &supervision,
+        );
+        info!("Application Core Logic: Persistent services started.");
+        if let Some(forwarder_config) = config.read().await.event_forwarder.clone() {
+            service_handles.extend(ServiceManager::spawn_event_forwarder(
+                &monitor_event_sender,
+                forwarder_config,
+                labels,
+            ));
+        }
+
+        // 控制套接字与 HTTP 接口（均可选），启动失败不影响守护进程本身
+        let control_state = ControlState {
+            history: history.clone(),
+            config: Arc::clone(&config),
+            runtime_tx,
+            children,
+            event_tx: monitor_event_sender.clone(),
+            health: health.clone(),
+        };
+        if let Some(socket_path) = &control_config.socket_path {
+            match ControlServer::spawn(socket_path, control_state.clone()) {
+                Ok(handle) => service_handles.push(handle),
+                Err(e) => error!("Application Core Logic: Control socket disabled: {:#}", e),
+            }
+        }
+        if let (Some(addr), Some(token)) = (control_config.http_listen, &control_config.http_token)
+        {
+            let state = HttpApiState {
+                control: control_state,
+                health: health.clone(),
+                event_tx: monitor_event_sender.clone(),
+                token: token.clone(),
+            };
+            match HttpApiServer::spawn(addr, state).await {
+                Ok((_, handle)) => service_handles.push(handle),
+                Err(e) => error!("Application Core Logic: HTTP API disabled: {:#}", e),
+            }
+        }
+
+        // 4. 进行初始配置协调
+
+        {
+            let processes_snapshot = {
+                let guard = config.read().await;
                 debug!(
-                    "  eBPF monitoring enabled: executable='{}', truncated='{}'",
-                    executable_name, truncated_name
+                    "Initial load: {} process configurations",
+                    guard.processes.len()
                 );
+                guard.processes.clone()
+            };
+            for (i, process_config) in processes_snapshot.iter().enumerate() {
+                debug!(
+                    "Process {}: name='{}', command='{}'",
+                    i + 1,
+                    process_config.name,
+                    process_config.command
+                );
+                if let Some(ebpf_config) = process_config.get_ebpf_monitor_config() {
+                    debug!(
+                        "  eBPF monitoring enabled: comm='{}'",
+                        ebpf_config.monitored_comm()
+                    );
+                }
             }
+            monitor_manager.reconcile(&processes_snapshot).await?;
         }
-        monitor_manager.reconcile(&processes_snapshot).await?;
+        info!("Application Core Logic: Initial reconciliation completed.");
+
+        Ok(Self {
+            config,
+            config_manager,
+            monitor_manager,
+            service_handles,
+            health,
+            history,
+            metrics,
+            heartbeat: Heartbeat::new(),
+            runtime_rx,
+        })
     }
-    info!("Application Core Logic: Initial reconciliation completed.");
 
-    // 5. 主事件循环 - 等待信号并处理
-    loop {
-        match SignalHandler::wait_for_signal().await? {
-            SignalEvent::ConfigReload => {
-                info!("Core Logic: Processing configuration reload...");
+    /// 各进程的健康状态表
+    #[allow(dead_code)]
+    pub(crate) fn health(&self) -> HealthRegistry {
+        self.health.clone()
+    }
 
-                // 重新加载配置
-                if let Err(e) = config_manager.reload_config().await {
-                    error!("Core Logic: Failed to reload config: {}", e);
-                    continue;
-                }
+    /// 最近事件的历史记录
+    #[allow(dead_code)]
+    pub(crate) fn history(&self) -> EventHistory {
+        self.history.clone()
+    }
 
-                // 重新协调监控器（同样避免持 read 锁跨 await）
-                let processes_snapshot = {
-                    let guard = config.read().await;
-                    guard.processes.clone()
-                };
-                if let Err(e) = monitor_manager.reconcile(&processes_snapshot).await {
-                    error!("Core Logic: Failed to reconcile monitors: {}", e);
-                }
+    /// 按进程统计的恢复延迟
+    #[allow(dead_code)]
+    pub(crate) fn metrics(&self) -> Metrics {
+        self.metrics.clone()
+    }
+
+    /// 配置重载的计数；没有配置文件路径时计数始终为 0
+    #[allow(dead_code)]
+    pub(crate) fn reload_stats(&self) -> ReloadStats {
+        self.config_manager
+            .as_ref()
+            .map(ConfigManager::stats)
+            .unwrap_or_default()
+    }
+
+    /// 主循环的心跳计数器
+    pub(crate) fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
+    // 开启 exit_when_idle_secs 且当前没有启用的进程时，从现在起计时的退出时间
+    async fn idle_deadline(&self) -> Option<tokio::time::Instant> {
+        let config = self.config.read().await;
+        let idle_secs = config.exit_when_idle_secs?;
+        (config.enabled_process_count() == 0)
+            .then(|| tokio::time::Instant::now() + Duration::from_secs(idle_secs))
+    }
+
+    // 重新加载配置文件：先校验新配置并构造监控器，都成功后才替换当前配置
+    async fn reload(&mut self) {
+        info!("Core Logic: Processing configuration reload...");
+        let Some(config_manager) = &self.config_manager else {
+            return;
+        };
+        // 先加载并校验新配置，此时旧配置仍然生效
+        let new_config = match config_manager.load_staged() {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Core Logic: Failed to reload config: {}", e);
+                return;
             }
-            SignalEvent::Shutdown => {
-                info!("Core Logic: Initiating graceful shutdown...");
-                break;
+        };
+
+        // 针对新配置构造监控器；失败时保留旧配置与正在运行的旧监控器
+        let plan = match self.monitor_manager.plan(&new_config.processes).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                error!(
+                    "Core Logic: New configuration rejected, keeping the current one: {}",
+                    e
+                );
+                return;
             }
+        };
+        if new_config.enabled_process_count() == 0 {
+            warn!("Core Logic: Reloaded configuration has no enabled processes, nothing will be monitored.");
+        }
+        config_manager.commit(new_config).await;
+        for name in self.health.resume_all() {
+            info!(
+                "Core Logic: Configuration reloaded, quarantine of process '{}' lifted.",
+                name
+            );
+        }
+        if let Err(e) = self.monitor_manager.apply(plan).await {
+            error!("Core Logic: Failed to reconcile monitors: {}", e);
         }
     }
 
-    // 6. 关闭
-    monitor_manager.shutdown().await;
-    info!("Application Core Logic: Shutdown completed.");
+    /// 主事件循环：处理重载/关闭请求，直到收到关闭请求或控制通道关闭。
+    pub(crate) async fn run_until_shutdown(
+        mut self,
+        mut control_rx: mpsc::Receiver<SignalEvent>,
+    ) -> Result<()> {
+        // 5. 主事件循环 - 等待信号并处理
+        let mut outcome = Ok(());
+        let mut heartbeat_ticker = tokio::time::interval(HEARTBEAT_INTERVAL);
+        // exit_when_idle_secs：没有启用的进程时的退出时间，处理完每个事件后重新计时
+        let mut idle_deadline = None;
+        let mut idle_stale = true;
+        loop {
+            if idle_stale {
+                idle_deadline = self.idle_deadline().await;
+                idle_stale = false;
+            }
+            let pending_reload = self
+                .config_manager
+                .as_ref()
+                .and_then(ConfigManager::pending_reload);
+            let event = tokio::select! {
+                event = control_rx.recv() => event,
+                Some(event) = self.runtime_rx.recv() => Some(event),
+                _ = heartbeat_ticker.tick() => {
+                    self.heartbeat.beat();
+                    continue;
+                }
+                _ = sleep_until_deadline(pending_reload), if pending_reload.is_some() => {
+                    if self
+                        .config_manager
+                        .as_ref()
+                        .is_some_and(ConfigManager::take_pending_reload)
+                    {
+                        self.heartbeat.clone().beat_while(self.reload()).await;
+                    }
+                    idle_stale = true;
+                    continue;
+                }
+                _ = sleep_until_deadline(idle_deadline), if idle_deadline.is_some() => {
+                    // 控制命令可能绕过主循环修改了配置，退出前再确认一次
+                    if self.idle_deadline().await.is_none() {
+                        idle_deadline = None;
+                        continue;
+                    }
+                    info!("Core Logic: No enabled processes to monitor for exit_when_idle_secs, shutting down.");
+                    break;
+                }
+            };
+            idle_stale = true;
+            match event {
+                Some(SignalEvent::Reconcile) => {
+                    info!("Core Logic: Reconciling monitors with the in-memory configuration...");
+                    let processes_snapshot = {
+                        let guard = self.config.read().await;
+                        guard.processes.clone()
+                    };
+                    let reconcile = self.monitor_manager.reconcile(&processes_snapshot);
+                    if let Err(e) = self.heartbeat.beat_while(reconcile).await {
+                        error!("Core Logic: Failed to reconcile monitors: {}", e);
+                    }
+                }
+                Some(SignalEvent::ConfigReload) => {
+                    let Some(config_manager) = &self.config_manager else {
+                        warn!("Core Logic: No configuration file path, reload is unavailable.");
+                        continue;
+                    };
+                    // reload_debounce_ms 窗口内的请求合并，到期后由上面的分支执行一次
+                    if config_manager.request_reload().await {
+                        self.heartbeat.clone().beat_while(self.reload()).await;
+                    }
+                }
+                Some(SignalEvent::Shutdown) => {
+                    info!("Core Logic: Initiating graceful shutdown...");
+                    break;
+                }
+                Some(SignalEvent::SubscriberLost(name)) => {
+                    error!(
+                        "Core Logic: Critical subscriber '{}' stopped, shutting down with an error.",
+                        name
+                    );
+                    outcome = Err(anyhow!(
+                        "critical subscriber '{}' lost its event channel",
+                        name
+                    ));
+                    break;
+                }
+                None => {
+                    warn!("Core Logic: Control channel closed, initiating shutdown...");
+                    break;
+                }
+            }
+        }
 
-    // 7. 确保进程正确退出
-    info!("Application Core Logic: Exiting process...");
-    std::process::exit(0);
+        // 6. 关闭
+        self.heartbeat.disarm();
+        self.monitor_manager.shutdown().await;
+        for handle in self.service_handles.drain(..) {
+            handle.abort();
+        }
+        info!("Application Core Logic: Shutdown completed.");
+        outcome
+    }
 }
@@ -1,13 +1,21 @@
 use crate::config::AppConfig;
 use crate::logger;
 use daemonize::Daemonize;
+use nix::unistd::{setgid, setgroups, setuid, Gid, Uid};
 use std::path::PathBuf;
+use users::{get_group_by_name, get_user_by_name};
 
 #[derive(Debug)]
 #[allow(dead_code)] // Error fields preserved for error context
 pub enum DaemonError {
     Io(std::io::Error),
     Daemonize(daemonize::Error),
+    /// daemon_user 指定的用户不存在
+    UnknownUser(String),
+    /// daemon_group 指定的组不存在
+    UnknownGroup(String),
+    /// 切换 uid/gid 失败（通常是因为守护进程本身不是 root）
+    Privileges(nix::Error),
 }
 impl From<std::io::Error> for DaemonError {
     fn from(err: std::io::Error) -> DaemonError {
@@ -20,6 +28,61 @@ impl From<daemonize::Error> for DaemonError {
     }
 }
 
+impl From<nix::Error> for DaemonError {
+    fn from(err: nix::Error) -> DaemonError {
+        DaemonError::Privileges(err)
+    }
+}
+
+/// 守护进程在初始化完成后切换到的身份（daemon_user / daemon_group）。
+///
+/// 切换是不可逆的：之后守护进程不再持有任何特权，包括 CAP_SETUID / CAP_SETGID、CAP_KILL 与 CAP_SYS_ADMIN。因此
+/// - 恢复命令只能以 daemon_user 自身运行，配置了其他 run_as_user 或 run_as_root 的进程切换身份会失败，按一次失败的恢复计入熔断器；
+/// - 只能向属于 daemon_user 的进程发送信号（stop、recovery_action: signal），nsenter 无法进入其他进程的命名空间；
+/// - 已挂载的 eBPF 程序与已绑定的控制套接字 / HTTP 端口继续可用，但重载后需要新挂载的 eBPF 程序会失败；
+/// - 配置文件（重载时）、PID 文件目录与日志目录需要对 daemon_user 可读写。
+///
+/// 需要以不同用户拉起子进程的部署不要设置 daemon_user。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DaemonIdentity {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl DaemonIdentity {
+    /// 解析 daemon_user / daemon_group，都未设置时返回 None；只设置 daemon_group 时保留当前 uid，只切换组
+    pub fn resolve(user: Option<&str>, group: Option<&str>) -> Result<Option<Self>, DaemonError> {
+        if user.is_none() && group.is_none() {
+            return Ok(None);
+        }
+        let (uid, primary_gid) = match user {
+            Some(name) => {
+                let user =
+                    get_user_by_name(name).ok_or_else(|| DaemonError::UnknownUser(name.into()))?;
+                (user.uid(), user.primary_group_id())
+            }
+            None => (Uid::current().as_raw(), Gid::current().as_raw()),
+        };
+        let gid = match group {
+            Some(name) => get_group_by_name(name)
+                .ok_or_else(|| DaemonError::UnknownGroup(name.into()))?
+                .gid(),
+            None => primary_gid,
+        };
+        Ok(Some(Self { uid, gid }))
+    }
+
+    /// 依次切换附加组、gid 与 uid。glibc 会把 setgid/setuid 同步到进程内的所有线程，可在 tokio 运行时启动后调用；
+    /// 只调用系统调用，也可在 fork 之后的子进程中调用
+    pub fn apply(self) -> Result<(), DaemonError> {
+        let gid = Gid::from_raw(self.gid);
+        setgroups(&[gid])?;
+        setgid(gid)?;
+        setuid(Uid::from_raw(self.uid))?;
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct DaemonConfig {
     pub pid_file: PathBuf,
@@ -43,9 +106,13 @@ pub fn run_as_daemon<F>(
 where
     F: FnOnce() + Send + 'static,
 {
-    let daemon_config = {
+    let (daemon_config, labels, log_directives) = {
         let config_guard = config.blocking_read();
-        config_guard.to_daemonize_config()
+        (
+            config_guard.to_daemonize_config(),
+            config_guard.labels.clone(),
+            config_guard.log_directives(),
+        )
     };
 
     println!("Starting the daemon process");
@@ -66,13 +133,14 @@ where
     match daemonizer.start() {
         Ok(_) => {
             let log_file_path = &daemon_config.log_directory;
-            let log_guard = match logger::init_daemon_logging(log_file_path) {
-                Ok(guard) => guard,
-                Err(e) => {
-                    tracing::error!("Failed to initialize logging: {}. Exiting.", e);
-                    std::process::exit(1);
-                }
-            };
+            let log_guard =
+                match logger::init_daemon_logging(log_file_path, &labels, &log_directives) {
+                    Ok(guard) => guard,
+                    Err(e) => {
+                        tracing::error!("Failed to initialize logging: {}. Exiting.", e);
+                        std::process::exit(1);
+                    }
+                };
 
             // 执行核心逻辑
             core_logic_fn();
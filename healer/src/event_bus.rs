@@ -1,27 +1,104 @@
 use std::path::PathBuf;
-use tokio::sync::broadcast;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tokio::sync::{broadcast, mpsc, watch};
+use tracing::warn;
 
 const CHANNEL_CAPACITY: usize = 128;
+// 关键订阅者的有界队列容量，队列满时发布方等待而不是丢弃
+const CRITICAL_CHANNEL_CAPACITY: usize = 64;
 
+/// 总线上的事件。每个事件都带有 `at`：由监控器在检测到状态时填写的墙钟时间，
+/// 下游（审计、指标、去重）应使用它而不是接收时间。
 #[derive(Clone, Debug)]
 pub enum ProcessEvent {
     ProcessDown {
         name: String,
         pid: u32,
+        at: SystemTime,
     },
     ProcessDisconnected {
         name: String,
         url: String,
+        at: SystemTime,
+    },
+    ProcessResourceExceeded {
+        name: String,
+        pid: u32,
+        at: SystemTime,
+    },
+    // 监控确认进程健康（仅在首次确认或从故障中恢复时发送）
+    ProcessUp {
+        name: String,
+        at: SystemTime,
     },
     #[allow(dead_code)]
     ProcessDependencyDetected {
         name: String,
         dependencies: Vec<String>,
+        at: SystemTime,
     },
     #[allow(dead_code)]
-    ProcessRestartSuccess {},
+    ProcessRestartSuccess {
+        at: SystemTime,
+    },
     #[allow(dead_code)]
-    ProcessRestartFailed {},
+    ProcessRestartFailed {
+        at: SystemTime,
+    },
+}
+
+/// [`ProcessEvent::kind`] 的所有取值
+pub const EVENT_KINDS: [&str; 7] = [
+    "process_down",
+    "process_disconnected",
+    "process_resource_exceeded",
+    "process_up",
+    "process_dependency_detected",
+    "process_restart_success",
+    "process_restart_failed",
+];
+
+impl ProcessEvent {
+    /// 事件的检测时间
+    #[allow(dead_code)]
+    pub fn at(&self) -> SystemTime {
+        match self {
+            ProcessEvent::ProcessDown { at, .. }
+            | ProcessEvent::ProcessDisconnected { at, .. }
+            | ProcessEvent::ProcessResourceExceeded { at, .. }
+            | ProcessEvent::ProcessUp { at, .. }
+            | ProcessEvent::ProcessDependencyDetected { at, .. }
+            | ProcessEvent::ProcessRestartSuccess { at }
+            | ProcessEvent::ProcessRestartFailed { at } => *at,
+        }
+    }
+
+    /// 事件类型的短名称（如 `process_down`），用于 JSON 输出、转发的 channel 名与事件过滤
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ProcessEvent::ProcessDown { .. } => "process_down",
+            ProcessEvent::ProcessDisconnected { .. } => "process_disconnected",
+            ProcessEvent::ProcessResourceExceeded { .. } => "process_resource_exceeded",
+            ProcessEvent::ProcessUp { .. } => "process_up",
+            ProcessEvent::ProcessDependencyDetected { .. } => "process_dependency_detected",
+            ProcessEvent::ProcessRestartSuccess { .. } => "process_restart_success",
+            ProcessEvent::ProcessRestartFailed { .. } => "process_restart_failed",
+        }
+    }
+
+    /// 事件所属的进程名，与具体进程无关的事件返回 None
+    pub fn process_name(&self) -> Option<&str> {
+        match self {
+            ProcessEvent::ProcessDown { name, .. }
+            | ProcessEvent::ProcessDisconnected { name, .. }
+            | ProcessEvent::ProcessResourceExceeded { name, .. }
+            | ProcessEvent::ProcessUp { name, .. }
+            | ProcessEvent::ProcessDependencyDetected { name, .. } => Some(name.as_str()),
+            ProcessEvent::ProcessRestartSuccess { .. }
+            | ProcessEvent::ProcessRestartFailed { .. } => None,
+        }
+    }
 }
 #[allow(dead_code)]
 pub struct RestartProcessConfig {
@@ -30,7 +107,197 @@ pub struct RestartProcessConfig {
     pub args: Vec<String>,
     pub workding_dir: Option<PathBuf>,
 }
-pub fn create_event_sender() -> broadcast::Sender<ProcessEvent> {
-    let (tx, _rx_initial) = broadcast::channel(CHANNEL_CAPACITY);
-    tx
+
+/// 事件总线的发送端。
+///
+/// 非关键观察者（指标、通知等）通过 [`EventSender::subscribe`] 走 broadcast，落后时会丢弃最旧的事件；
+/// 关键订阅者（协调器、自愈器）通过 [`EventSender::subscribe_critical`] 获得独立的有界队列，
+/// 队列满时 [`EventSender::send`] 会等待，从而把背压传递给监控器而不是丢事件。
+/// 配置 `subscribers.delivery: broadcast` 时关键订阅者也改为走 broadcast，以丢事件换取监控器从不等待。
+///
+/// 通过 [`EventSender::gated`] 创建的发送端在 [`EventSender::mark_ready`] 之前会让 `send` 等待，
+/// 避免启动时关键订阅者尚未就绪、事件因没有订阅者而丢失。
+#[derive(Clone, Debug)]
+pub struct EventSender {
+    observers: broadcast::Sender<ProcessEvent>,
+    critical: Arc<Mutex<Vec<mpsc::Sender<ProcessEvent>>>>,
+    ready: Arc<watch::Sender<bool>>,
+}
+
+impl EventSender {
+    pub fn new() -> Self {
+        Self::with_ready(true)
+    }
+
+    /// 创建处于未就绪状态的发送端：`send` 会等到 [`EventSender::mark_ready`] 后才投递
+    pub fn gated() -> Self {
+        Self::with_ready(false)
+    }
+
+    fn with_ready(ready: bool) -> Self {
+        let (observers, _rx_initial) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            observers,
+            critical: Arc::new(Mutex::new(Vec::new())),
+            ready: Arc::new(watch::Sender::new(ready)),
+        }
+    }
+
+    /// 关键订阅者已全部订阅，放行等待中的和之后的 `send`
+    pub fn mark_ready(&self) {
+        self.ready.send_replace(true);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        *self.ready.borrow()
+    }
+
+    /// 以非关键观察者身份订阅（可能丢事件）
+    pub fn subscribe(&self) -> broadcast::Receiver<ProcessEvent> {
+        self.observers.subscribe()
+    }
+
+    /// 以关键订阅者身份订阅（不丢事件，消费慢时发布方会被阻塞）
+    pub fn subscribe_critical(&self) -> mpsc::Receiver<ProcessEvent> {
+        let (tx, rx) = mpsc::channel(CRITICAL_CHANNEL_CAPACITY);
+        self.critical
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(tx);
+        rx
+    }
+
+    /// 以关键订阅者身份订阅；通道关闭时在 [`EventReceiver::recv`] 内部自动重新订阅，
+    /// 关闭前已排队的事件仍会被取走，关闭到重新订阅之间发布的事件会丢失
+    pub fn subscribe_critical_resubscribing(&self, name: &'static str) -> EventReceiver {
+        EventReceiver::Resubscribing {
+            name,
+            sender: self.clone(),
+            rx: self.subscribe_critical(),
+        }
+    }
+
+    /// 断开所有关键订阅者：订阅端取完已排队的事件后收到 `Closed`
+    #[allow(dead_code)]
+    pub fn close_critical(&self) {
+        self.critical
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// 发布事件，返回收到事件的订阅者数量；没有任何订阅者时返回错误
+    pub async fn send(
+        &self,
+        event: ProcessEvent,
+    ) -> Result<usize, broadcast::error::SendError<ProcessEvent>> {
+        if !self.is_ready() {
+            // 发送端与等待者都持有同一个 watch，wait_for 不会因通道关闭而失败
+            let _ = self.ready.subscribe().wait_for(|ready| *ready).await;
+        }
+
+        // 不持锁跨 await：先拷贝一份关键订阅者列表
+        let critical = self
+            .critical
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+
+        let mut delivered = 0;
+        let mut closed = false;
+        for tx in &critical {
+            match tx.send(event.clone()).await {
+                Ok(()) => delivered += 1,
+                Err(_) => closed = true,
+            }
+        }
+        if closed {
+            // 清理已关闭的关键订阅者
+            self.critical
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .retain(|tx| !tx.is_closed());
+        }
+
+        match self.observers.send(event) {
+            Ok(n) => Ok(delivered + n),
+            Err(e) if delivered == 0 => Err(e),
+            Err(_) => Ok(delivered),
+        }
+    }
+}
+
+impl Default for EventSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 订阅端：统一 broadcast 与关键订阅者的有界队列，错误语义沿用 broadcast
+pub enum EventReceiver {
+    Broadcast(broadcast::Receiver<ProcessEvent>),
+    Critical(mpsc::Receiver<ProcessEvent>),
+    /// 关键订阅者，持有发送端以便通道关闭后重新订阅，因此不会返回 `Closed`
+    Resubscribing {
+        name: &'static str,
+        sender: EventSender,
+        rx: mpsc::Receiver<ProcessEvent>,
+    },
+}
+
+impl EventReceiver {
+    pub async fn recv(&mut self) -> Result<ProcessEvent, broadcast::error::RecvError> {
+        match self {
+            EventReceiver::Broadcast(rx) => rx.recv().await,
+            EventReceiver::Critical(rx) => {
+                rx.recv().await.ok_or(broadcast::error::RecvError::Closed)
+            }
+            EventReceiver::Resubscribing { name, sender, rx } => loop {
+                if let Some(event) = rx.recv().await {
+                    return Ok(event);
+                }
+                warn!(
+                    "EventBus: Event channel of critical subscriber '{}' closed, re-subscribing.",
+                    name
+                );
+                *rx = sender.subscribe_critical();
+            },
+        }
+    }
+}
+
+impl EventReceiver {
+    /// 不等待地取出一个已到达的事件，没有时返回 None；broadcast 落后时跳过丢失的部分
+    pub fn try_recv(&mut self) -> Option<ProcessEvent> {
+        match self {
+            EventReceiver::Broadcast(rx) => loop {
+                match rx.try_recv() {
+                    Ok(event) => return Some(event),
+                    Err(broadcast::error::TryRecvError::Lagged(n)) => {
+                        warn!("EventBus: Receiver lagged, missed {} messages", n);
+                    }
+                    Err(_) => return None,
+                }
+            },
+            EventReceiver::Critical(rx) | EventReceiver::Resubscribing { rx, .. } => {
+                rx.try_recv().ok()
+            }
+        }
+    }
+}
+
+impl From<broadcast::Receiver<ProcessEvent>> for EventReceiver {
+    fn from(rx: broadcast::Receiver<ProcessEvent>) -> Self {
+        EventReceiver::Broadcast(rx)
+    }
+}
+
+impl From<mpsc::Receiver<ProcessEvent>> for EventReceiver {
+    fn from(rx: mpsc::Receiver<ProcessEvent>) -> Self {
+        EventReceiver::Critical(rx)
+    }
+}
+
+pub fn create_event_sender() -> EventSender {
+    EventSender::new()
 }
@@ -0,0 +1,360 @@
+//! 进程健康状态：区分“尚未确认”与“已确认健康”，由监控事件与自愈结果共同更新。
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Instant, SystemTime};
+
+/// 单个进程的健康状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthState {
+    /// 还没有收到任何监控结论
+    Unknown,
+    /// 监控已确认进程正常
+    Healthy,
+    /// 监控报告进程异常，尚未开始恢复
+    Down,
+    /// 已执行恢复命令，等待监控再次确认健康
+    Recovering,
+    /// 熔断器打开，冷却期内不再恢复
+    CircuitOpen,
+    /// 反复熔断后被隔离，手动 resume 或重新加载配置前不再恢复
+    Quarantined,
+    /// 被控制命令 stop 停止，start 之前不再恢复
+    Stopped,
+}
+
+impl std::fmt::Display for HealthState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            HealthState::Unknown => "unknown",
+            HealthState::Healthy => "healthy",
+            HealthState::Down => "down",
+            HealthState::Recovering => "recovering",
+            HealthState::CircuitOpen => "circuit_open",
+            HealthState::Quarantined => "quarantined",
+            HealthState::Stopped => "stopped",
+        };
+        write!(f, "{}", state)
+    }
+}
+
+/// 熔断器状态机的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// 正常恢复，统计重试窗口内的尝试次数
+    Closed,
+    /// 重试次数用尽，冷却期内不再恢复
+    Open,
+    /// 冷却期已过，放行一次试探性的恢复
+    HalfOpen,
+    /// 连续熔断次数达到 quarantine_after_trips，恢复被永久暂停
+    Quarantined,
+}
+
+/// 熔断器的详细记录，由 ProcessHealer 每次评估熔断器后写入
+#[derive(Debug, Clone)]
+pub struct CircuitRecord {
+    pub state: CircuitState,
+    /// 进入当前状态的时间
+    pub since: Instant,
+    /// 冷却期的结束时间，仅在 Open 状态下有意义
+    pub cooldown_until: Option<Instant>,
+    /// 重试窗口内的恢复尝试时间
+    pub attempts: Vec<SystemTime>,
+}
+
+/// 熔断器在查询时刻的快照
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CircuitSnapshot {
+    pub state: CircuitState,
+    /// 处于当前状态的时长（秒）
+    pub in_state_secs: u64,
+    /// 剩余的冷却时间（秒），不在冷却期内时为 0
+    pub remaining_cooldown_secs: u64,
+    /// 重试窗口内的恢复尝试时间（RFC 3339，UTC）
+    pub recent_attempts: Vec<String>,
+}
+
+impl CircuitRecord {
+    pub fn snapshot(&self) -> CircuitSnapshot {
+        let now = Instant::now();
+        let remaining_cooldown_secs = match self.state {
+            CircuitState::Open => self
+                .cooldown_until
+                .map_or(0, |until| until.saturating_duration_since(now).as_secs()),
+            CircuitState::Closed | CircuitState::HalfOpen | CircuitState::Quarantined => 0,
+        };
+        CircuitSnapshot {
+            state: self.state,
+            in_state_secs: now.saturating_duration_since(self.since).as_secs(),
+            remaining_cooldown_secs,
+            recent_attempts: self
+                .attempts
+                .iter()
+                .map(|at| DateTime::<Utc>::from(*at).to_rfc3339())
+                .collect(),
+        }
+    }
+}
+
+/// 所有进程健康状态的共享表，克隆后指向同一份数据。
+#[derive(Debug, Clone, Default)]
+pub struct HealthRegistry {
+    states: Arc<RwLock<HashMap<String, HealthState>>>,
+    // 熔断器打开的进程及其冷却期结束时间
+    circuit_open_until: Arc<RwLock<HashMap<String, Instant>>>,
+    // 刚恢复的进程及其监控宽限期结束时间，期间监控报告的故障被忽略
+    monitor_grace_until: Arc<RwLock<HashMap<String, Instant>>>,
+    // 各进程熔断器的详细记录
+    circuits: Arc<RwLock<HashMap<String, CircuitRecord>>>,
+    // 正在运行的监控器：进程名 -> 监控类型，由 MonitorManager 每次协调后整体替换
+    monitors: Arc<RwLock<HashMap<String, &'static str>>>,
+    // 被依赖协调器延后恢复的进程及其仍在等待的依赖
+    deferred: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    // 各监控器最近一次完成检查的时间，用于发现停止运转（卡住）的监控器
+    last_checks: Arc<RwLock<HashMap<String, SystemTime>>>,
+    // 被隔离的进程，由 ProcessHealer 写入，resume 或重新加载配置时清除
+    quarantined: Arc<RwLock<HashSet<String>>>,
+    // 被 stop 命令停止、暂停恢复的进程
+    paused: Arc<RwLock<HashSet<String>>>,
+}
+
+impl HealthRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 进程当前的健康状态，没有记录时为 Unknown
+    #[allow(dead_code)]
+    pub fn get(&self, name: &str) -> HealthState {
+        let states = self.states.read().unwrap_or_else(|e| e.into_inner());
+        states.get(name).copied().unwrap_or(HealthState::Unknown)
+    }
+
+    pub fn set(&self, name: &str, state: HealthState) {
+        let mut states = self.states.write().unwrap_or_else(|e| e.into_inner());
+        states.insert(name.to_string(), state);
+    }
+
+    /// 熔断器打开，冷却期持续到 `until`
+    pub fn set_circuit_open(&self, name: &str, until: Instant) {
+        self.set(name, HealthState::CircuitOpen);
+        let mut open = self
+            .circuit_open_until
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        open.insert(name.to_string(), until);
+    }
+
+    /// 进程是否处于熔断冷却期内；冷却期已过时即使状态仍为 CircuitOpen 也返回 false
+    pub fn is_circuit_open(&self, name: &str) -> bool {
+        if self.get(name) != HealthState::CircuitOpen {
+            return false;
+        }
+        let open = self
+            .circuit_open_until
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        open.get(name).is_some_and(|until| *until > Instant::now())
+    }
+
+    /// 记录熔断器最近一次评估后的状态
+    pub fn set_circuit(&self, name: &str, record: CircuitRecord) {
+        let mut circuits = self.circuits.write().unwrap_or_else(|e| e.into_inner());
+        circuits.insert(name.to_string(), record);
+    }
+
+    /// 进程熔断器的快照；尚未尝试过恢复时为 None
+    pub fn circuit(&self, name: &str) -> Option<CircuitSnapshot> {
+        let circuits = self.circuits.read().unwrap_or_else(|e| e.into_inner());
+        circuits.get(name).map(CircuitRecord::snapshot)
+    }
+
+    /// 所有已有记录的熔断器快照，按进程名排序
+    pub fn circuits(&self) -> BTreeMap<String, CircuitSnapshot> {
+        let circuits = self.circuits.read().unwrap_or_else(|e| e.into_inner());
+        circuits
+            .iter()
+            .map(|(name, record)| (name.clone(), record.snapshot()))
+            .collect()
+    }
+
+    /// 替换正在运行的监控器列表（进程名 -> 监控类型），已停止的监控器的检查时间一并移除
+    pub fn set_monitors(&self, running: HashMap<String, &'static str>) {
+        let mut last_checks = self.last_checks.write().unwrap_or_else(|e| e.into_inner());
+        last_checks.retain(|name, _| running.contains_key(name));
+        drop(last_checks);
+        let mut monitors = self.monitors.write().unwrap_or_else(|e| e.into_inner());
+        *monitors = running;
+    }
+
+    /// 监控器完成了一次检查（无论结果如何）
+    pub fn record_check(&self, name: &str) {
+        let mut last_checks = self.last_checks.write().unwrap_or_else(|e| e.into_inner());
+        last_checks.insert(name.to_string(), SystemTime::now());
+    }
+
+    /// 监控器最近一次完成检查的时间；尚未检查过或不是轮询型监控器（eBPF）时为 None
+    pub fn last_check(&self, name: &str) -> Option<SystemTime> {
+        let last_checks = self.last_checks.read().unwrap_or_else(|e| e.into_inner());
+        last_checks.get(name).copied()
+    }
+
+    /// 所有监控器最近一次完成检查的时间，按进程名排序
+    pub fn last_checks(&self) -> BTreeMap<String, SystemTime> {
+        let last_checks = self.last_checks.read().unwrap_or_else(|e| e.into_inner());
+        last_checks
+            .iter()
+            .map(|(name, at)| (name.clone(), *at))
+            .collect()
+    }
+
+    /// 正在运行的监控器，按进程名排序
+    pub fn monitors(&self) -> BTreeMap<String, &'static str> {
+        let monitors = self.monitors.read().unwrap_or_else(|e| e.into_inner());
+        monitors
+            .iter()
+            .map(|(name, kind)| (name.clone(), *kind))
+            .collect()
+    }
+
+    /// 恢复因依赖阻塞被延后，`waiting_on` 为仍在阻塞的依赖
+    pub fn set_deferred(&self, name: &str, waiting_on: Vec<String>) {
+        let mut deferred = self.deferred.write().unwrap_or_else(|e| e.into_inner());
+        deferred.insert(name.to_string(), waiting_on);
+    }
+
+    /// 延后的恢复已放行或放弃
+    pub fn clear_deferred(&self, name: &str) {
+        let mut deferred = self.deferred.write().unwrap_or_else(|e| e.into_inner());
+        deferred.remove(name);
+    }
+
+    /// 被延后恢复的进程及其等待的依赖，按进程名排序
+    pub fn deferred(&self) -> BTreeMap<String, Vec<String>> {
+        let deferred = self.deferred.read().unwrap_or_else(|e| e.into_inner());
+        deferred
+            .iter()
+            .map(|(name, waiting_on)| (name.clone(), waiting_on.clone()))
+            .collect()
+    }
+
+    /// 进程刚被恢复，`until` 之前忽略其监控报告的故障
+    pub fn suppress_monitor_until(&self, name: &str, until: Instant) {
+        let mut grace = self
+            .monitor_grace_until
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        grace.insert(name.to_string(), until);
+    }
+
+    /// 提前结束监控宽限期（如监控已确认进程恢复健康）
+    pub fn clear_monitor_grace(&self, name: &str) {
+        let mut grace = self
+            .monitor_grace_until
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        grace.remove(name);
+    }
+
+    /// 进程是否处于恢复后的监控宽限期内
+    pub fn in_monitor_grace(&self, name: &str) -> bool {
+        let grace = self
+            .monitor_grace_until
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        grace.get(name).is_some_and(|until| *until > Instant::now())
+    }
+
+    /// 隔离进程：在 resume 之前不再自动恢复
+    pub fn quarantine(&self, name: &str) {
+        self.set(name, HealthState::Quarantined);
+        let mut quarantined = self.quarantined.write().unwrap_or_else(|e| e.into_inner());
+        quarantined.insert(name.to_string());
+    }
+
+    pub fn is_quarantined(&self, name: &str) -> bool {
+        let quarantined = self.quarantined.read().unwrap_or_else(|e| e.into_inner());
+        quarantined.contains(name)
+    }
+
+    /// 解除隔离，返回进程此前是否处于隔离状态
+    pub fn resume(&self, name: &str) -> bool {
+        let mut quarantined = self.quarantined.write().unwrap_or_else(|e| e.into_inner());
+        if !quarantined.remove(name) {
+            return false;
+        }
+        drop(quarantined);
+        self.set(name, HealthState::Down);
+        true
+    }
+
+    /// 解除所有隔离（重新加载配置时），返回被解除的进程名
+    pub fn resume_all(&self) -> Vec<String> {
+        let names: Vec<String> = {
+            let quarantined = self.quarantined.read().unwrap_or_else(|e| e.into_inner());
+            quarantined.iter().cloned().collect()
+        };
+        names.into_iter().filter(|name| self.resume(name)).collect()
+    }
+
+    /// 暂停进程的恢复（stop 命令），故障事件一律跳过
+    pub fn pause(&self, name: &str) {
+        self.set(name, HealthState::Stopped);
+        let mut paused = self.paused.write().unwrap_or_else(|e| e.into_inner());
+        paused.insert(name.to_string());
+    }
+
+    pub fn is_paused(&self, name: &str) -> bool {
+        let paused = self.paused.read().unwrap_or_else(|e| e.into_inner());
+        paused.contains(name)
+    }
+
+    /// 恢复被暂停的进程，返回此前是否处于暂停状态
+    pub fn unpause(&self, name: &str) -> bool {
+        let mut paused = self.paused.write().unwrap_or_else(|e| e.into_inner());
+        if !paused.remove(name) {
+            return false;
+        }
+        drop(paused);
+        self.set(name, HealthState::Down);
+        true
+    }
+
+    /// 进程已不在配置中时移除其记录
+    pub fn remove(&self, name: &str) {
+        let mut states = self.states.write().unwrap_or_else(|e| e.into_inner());
+        states.remove(name);
+        let mut open = self
+            .circuit_open_until
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        open.remove(name);
+        drop(open);
+        self.clear_monitor_grace(name);
+        let mut circuits = self.circuits.write().unwrap_or_else(|e| e.into_inner());
+        circuits.remove(name);
+        drop(circuits);
+        let mut last_checks = self.last_checks.write().unwrap_or_else(|e| e.into_inner());
+        last_checks.remove(name);
+        drop(last_checks);
+        let mut quarantined = self.quarantined.write().unwrap_or_else(|e| e.into_inner());
+        quarantined.remove(name);
+        drop(quarantined);
+        let mut paused = self.paused.write().unwrap_or_else(|e| e.into_inner());
+        paused.remove(name);
+    }
+
+    /// 按进程名排序的状态快照
+    #[allow(dead_code)]
+    pub fn snapshot(&self) -> BTreeMap<String, HealthState> {
+        let states = self.states.read().unwrap_or_else(|e| e.into_inner());
+        states
+            .iter()
+            .map(|(name, state)| (name.clone(), *state))
+            .collect()
+    }
+}
@@ -0,0 +1,300 @@
+//! HTTP 控制接口：在 TCP 上提供最小的 HTTP/1.1 服务，每个连接处理一个请求后关闭。
+//! 所有请求都需要携带 `Authorization: Bearer <control.http_token>`。
+//!
+//! ```text
+//! $ curl -X POST -H 'Authorization: Bearer secret' http://127.0.0.1:9100/processes/web/restart
+//! {"ok":true,"process":"web"}
+//! $ curl -X POST -H 'Authorization: Bearer secret' http://127.0.0.1:9100/reload
+//! {"ok":true}
+//! $ curl -H 'Authorization: Bearer secret' http://127.0.0.1:9100/processes/web/circuit
+//! {"ok":true,"process":"web","circuit":{"state":"open","in_state_secs":12,"remaining_cooldown_secs":48,"recent_attempts":[]}}
+//! $ curl -H 'Authorization: Bearer secret' http://127.0.0.1:9100/monitors
+//! {"ok":true,"monitors":{"web":{"type":"pid","last_check_at":"2024-05-01T08:00:00+00:00","secs_since_last_check":2}}}
+//! ```
+
+use crate::control::ControlState;
+use crate::event_bus::{EventSender, ProcessEvent};
+use crate::health::HealthRegistry;
+use crate::signal_handler::SignalEvent;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+// 请求头的总长度上限，超过时直接关闭连接
+const MAX_HEAD_BYTES: u64 = 8 * 1024;
+// 读取请求头的超时时间，避免慢连接长期占用任务
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+// 同时处理的连接数上限，达到上限时暂停 accept，新连接在内核队列中等待
+const MAX_CONNECTIONS: usize = 64;
+// accept 失败（如文件描述符耗尽）后的等待时间，避免空转占满 CPU 并刷屏日志
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// HTTP 接口可访问的运行时状态
+#[derive(Debug, Clone)]
+pub struct HttpApiState {
+    pub control: ControlState,
+    pub health: HealthRegistry,
+    /// 发布合成 `ProcessDown` 的事件总线（监控器一侧，经过依赖协调器）
+    pub event_tx: EventSender,
+    pub token: String,
+}
+
+impl HttpApiState {
+    /// 处理一个请求，返回状态码与 JSON 响应体
+    pub async fn handle(
+        &self,
+        method: &str,
+        path: &str,
+        authorization: Option<&str>,
+    ) -> (u16, Value) {
+        let authorized = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| token_matches(token.trim(), &self.token));
+        if !authorized {
+            return (401, json!({ "ok": false, "error": "unauthorized" }));
+        }
+
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match segments.as_slice() {
+            ["reload"] if method == "POST" => self.reload().await,
+            ["processes", name, "restart"] if method == "POST" => self.restart(name).await,
+            ["processes", name, "circuit"] if method == "GET" => self.circuit(name).await,
+            ["monitors"] if method == "GET" => self.monitors(),
+            ["reload"]
+            | ["processes", _, "restart"]
+            | ["processes", _, "circuit"]
+            | ["monitors"] => (405, json!({ "ok": false, "error": "method not allowed" })),
+            _ => (404, json!({ "ok": false, "error": "not found" })),
+        }
+    }
+
+    async fn reload(&self) -> (u16, Value) {
+        info!("HttpApi: Configuration reload requested.");
+        match self
+            .control
+            .runtime_tx
+            .send(SignalEvent::ConfigReload)
+            .await
+        {
+            Ok(()) => (202, json!({ "ok": true })),
+            Err(_) => (
+                503,
+                json!({ "ok": false, "error": "runtime is no longer running" }),
+            ),
+        }
+    }
+
+    // 熔断器详情；尚未尝试过恢复的进程 circuit 为 null
+    async fn circuit(&self, name: &str) -> (u16, Value) {
+        let known = self
+            .control
+            .config
+            .read()
+            .await
+            .get_process_config_for(name)
+            .is_some();
+        if !known {
+            return (
+                404,
+                json!({ "ok": false, "error": format!("unknown process '{}'", name) }),
+            );
+        }
+        (
+            200,
+            json!({ "ok": true, "process": name, "circuit": self.health.circuit(name) }),
+        )
+    }
+
+    // 正在运行的监控器及其最近一次检查的时间；secs_since_last_check 远大于检查间隔说明监控器已停止运转。
+    // eBPF 监控由内核事件驱动，没有检查时间
+    fn monitors(&self) -> (u16, Value) {
+        let now = SystemTime::now();
+        let monitors: serde_json::Map<String, Value> = self
+            .health
+            .monitors()
+            .into_iter()
+            .map(|(name, kind)| {
+                let last_check = self.health.last_check(&name);
+                let entry = json!({
+                    "type": kind,
+                    "last_check_at": last_check
+                        .map(|at| chrono::DateTime::<chrono::Utc>::from(at).to_rfc3339()),
+                    "secs_since_last_check": last_check
+                        .map(|at| now.duration_since(at).unwrap_or_default().as_secs()),
+                });
+                (name, entry)
+            })
+            .collect();
+        (200, json!({ "ok": true, "monitors": monitors }))
+    }
+
+    // 发布合成的 ProcessDown，由自愈器按正常流程恢复（同样受熔断器约束）
+    async fn restart(&self, name: &str) -> (u16, Value) {
+        let enabled = self
+            .control
+            .config
+            .read()
+            .await
+            .get_process_config_for(name)
+            .map(|p| p.enabled);
+        match enabled {
+            None => {
+                return (
+                    404,
+                    json!({ "ok": false, "error": format!("unknown process '{}'", name) }),
+                )
+            }
+            Some(false) => {
+                return (
+                    409,
+                    json!({ "ok": false, "error": format!("process '{}' is disabled", name) }),
+                )
+            }
+            Some(true) => {}
+        }
+        if self.health.is_circuit_open(name) {
+            return (
+                409,
+                json!({ "ok": false, "error": format!("circuit breaker of '{}' is open", name) }),
+            );
+        }
+
+        info!("HttpApi: Restart of process '{}' requested.", name);
+        let event = ProcessEvent::ProcessDown {
+            name: name.to_string(),
+            pid: 0,
+            at: SystemTime::now(),
+        };
+        match self.event_tx.send(event).await {
+            Ok(_) => (202, json!({ "ok": true, "process": name })),
+            Err(_) => (
+                503,
+                json!({ "ok": false, "error": "no subscriber is handling events" }),
+            ),
+        }
+    }
+}
+
+// 逐字节比较全部内容，耗时不依赖于第一个不同字节的位置
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// HTTP 控制接口服务
+pub struct HttpApiServer;
+
+impl HttpApiServer {
+    /// 绑定地址并在后台接受连接，返回实际监听的地址（便于使用端口 0）
+    pub async fn spawn(
+        addr: SocketAddr,
+        state: HttpApiState,
+    ) -> Result<(SocketAddr, JoinHandle<()>)> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind HTTP API on {}", addr))?;
+        let local_addr = listener.local_addr()?;
+        info!("HttpApi: Listening on {}", local_addr);
+
+        let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+        Ok((
+            local_addr,
+            tokio::spawn(async move {
+                loop {
+                    let Ok(permit) = connections.clone().acquire_owned().await else {
+                        return;
+                    };
+                    match listener.accept().await {
+                        Ok((stream, _)) => {
+                            let state = state.clone();
+                            tokio::spawn(async move {
+                                let _permit = permit;
+                                if let Err(e) = serve_connection(stream, state).await {
+                                    debug!("HttpApi: connection closed with error: {}", e);
+                                }
+                            });
+                        }
+                        Err(e) => {
+                            warn!("HttpApi: accept on {} failed: {}", local_addr, e);
+                            tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                        }
+                    }
+                }
+            }),
+        ))
+    }
+}
+
+async fn serve_connection(stream: TcpStream, state: HttpApiState) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let head = tokio::time::timeout(READ_TIMEOUT, read_head(reader))
+        .await
+        .context("timed out reading request")??;
+
+    // 只解析请求行与 Authorization 头；请求体不使用，随连接一起丢弃
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (status, body) = match (request_line.next(), request_line.next()) {
+        (Some(method), Some(path)) => {
+            let authorization = lines.find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.trim()
+                    .eq_ignore_ascii_case("authorization")
+                    .then_some(value.trim())
+            });
+            state.handle(method, path, authorization).await
+        }
+        _ => (400, json!({ "ok": false, "error": "bad request" })),
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason_phrase(status),
+        body.len(),
+        body
+    );
+    writer.write_all(response.as_bytes()).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+// 读取到空行为止的请求头
+async fn read_head(reader: tokio::net::tcp::OwnedReadHalf) -> Result<String> {
+    let mut lines = BufReader::new(reader.take(MAX_HEAD_BYTES)).lines();
+    let mut head = String::new();
+    while let Some(line) = lines.next_line().await? {
+        if line.is_empty() {
+            return Ok(head);
+        }
+        head.push_str(&line);
+        head.push('\n');
+    }
+    anyhow::bail!("connection closed before the end of the request head")
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
@@ -0,0 +1,80 @@
+//! 单实例保护：启动时对 PID 文件加 flock，同一份 PID 文件只允许一个 healer 持有，
+//! 避免两个实例同时监管同一批进程。锁随进程退出（或 [`InstanceLock`] 被丢弃）自动释放。
+
+use nix::fcntl::{Flock, FlockArg};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+/// 获取实例锁失败的原因
+#[derive(Debug, thiserror::Error)]
+pub enum InstanceLockError {
+    /// 另一个 healer 已持有锁；`pid` 为 PID 文件中记录的进程号（无法读取时为 None）
+    #[error(
+        "another healer is already running with pid file {path:?} (pid {})",
+        .pid.map_or_else(|| "unknown".to_string(), |pid| pid.to_string())
+    )]
+    Held { path: PathBuf, pid: Option<u32> },
+    /// 无法打开或写入 PID 文件
+    #[error("failed to lock pid file {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// 已持有的实例锁，丢弃时释放
+pub struct InstanceLock {
+    _lock: Flock<File>,
+    path: PathBuf,
+}
+
+impl InstanceLock {
+    /// 以非阻塞方式锁定 `path`（不存在时创建），成功后写入当前进程号
+    pub fn acquire(path: &Path) -> Result<Self, InstanceLockError> {
+        let io_error = |source| InstanceLockError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        // 不截断：加锁失败时还要从中读取持有者的进程号
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(io_error)?;
+        let mut lock = match Flock::lock(file, FlockArg::LockExclusiveNonblock) {
+            Ok(lock) => lock,
+            Err((mut file, nix::errno::Errno::EWOULDBLOCK)) => {
+                let mut content = String::new();
+                let pid = file
+                    .read_to_string(&mut content)
+                    .ok()
+                    .and_then(|_| content.trim().parse().ok());
+                return Err(InstanceLockError::Held {
+                    path: path.to_path_buf(),
+                    pid,
+                });
+            }
+            Err((_, errno)) => return Err(io_error(io::Error::from(errno))),
+        };
+        lock.set_len(0).map_err(io_error)?;
+        lock.rewind().map_err(io_error)?;
+        writeln!(lock, "{}", std::process::id()).map_err(io_error)?;
+        Ok(Self {
+            _lock: lock,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// 被锁定的 PID 文件
+    #[allow(dead_code)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
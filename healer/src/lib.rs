@@ -1,14 +1,31 @@
+pub mod app;
 pub mod config;
+pub mod config_cache;
+pub mod config_check;
+pub mod config_graph;
 pub mod config_manager;
+pub mod control;
 pub mod coordinator;
 pub mod core_logic;
 pub mod daemon_handler;
 pub mod event_bus;
+pub mod health;
+pub mod http_api;
+pub mod instance_lock;
+pub mod log_janitor;
 pub mod logger;
+pub mod metrics;
 pub mod monitor;
 pub mod monitor_manager;
+pub mod oneshot;
 pub mod publisher;
+pub mod reaper;
+pub mod restart_scheduler;
 pub mod service_manager;
 pub mod signal_handler;
+pub mod status_file;
 pub mod subscriber;
 pub mod utils;
+pub mod watchdog;
+
+pub use app::{Healer, HealerBuilder, HealerHandle};
@@ -0,0 +1,101 @@
+//! 被恢复进程日志目录的全局容量限制：定期统计目录大小，超出 `max_log_dir_bytes` 时从最旧的文件开始删除。
+
+use crate::config::AppConfig;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 被恢复进程的输出日志目录（`ChildOutput::File`）
+pub const CHILD_LOG_DIR: &str = "/var/log/healer";
+
+// 两次清理之间的间隔
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 清理中删除的文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedLog {
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+/// 日志目录清理任务
+pub struct LogJanitor;
+
+impl LogJanitor {
+    /// 在后台定期清理 `dir`；每次清理时重新读取 `max_log_dir_bytes`，未设置时跳过
+    pub fn spawn(dir: PathBuf, config: Arc<RwLock<AppConfig>>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!("LogJanitor: Watching {:?}.", dir);
+            let mut ticker = tokio::time::interval(SWEEP_INTERVAL);
+            loop {
+                ticker.tick().await;
+                let Some(budget) = config.read().await.max_log_dir_bytes else {
+                    continue;
+                };
+                let sweep_dir = dir.clone();
+                match tokio::task::spawn_blocking(move || Self::trim(&sweep_dir, budget)).await {
+                    Ok(removed) if !removed.is_empty() => {
+                        debug!("LogJanitor: Removed {} file(s) this sweep.", removed.len());
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("LogJanitor: Sweep task failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// 从最旧（修改时间最早）的文件开始删除，直到目录内普通文件的总大小不超过 `budget`
+    pub fn trim(dir: &Path, budget: u64) -> Vec<RemovedLog> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                debug!("LogJanitor: Cannot read {:?}: {}", dir, e);
+                return Vec::new();
+            }
+        };
+        let mut files: Vec<(SystemTime, PathBuf, u64)> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let meta = entry.metadata().ok()?;
+                meta.is_file().then(|| {
+                    let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    (modified, entry.path(), meta.len())
+                })
+            })
+            .collect();
+        let mut total: u64 = files.iter().map(|(_, _, bytes)| bytes).sum();
+        if total <= budget {
+            return Vec::new();
+        }
+        files.sort();
+
+        let mut removed = Vec::new();
+        for (_, path, bytes) in files {
+            if total <= budget {
+                break;
+            }
+            // 仍被运行中的子进程打开的文件删除后，空间要等它关闭文件才会释放
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    info!(
+                        "LogJanitor: Removed {:?} ({} bytes), directory was over its {} byte budget.",
+                        path, bytes, budget
+                    );
+                    total = total.saturating_sub(bytes);
+                    removed.push(RemovedLog { path, bytes });
+                }
+                Err(e) => warn!("LogJanitor: Failed to remove {:?}: {}", path, e),
+            }
+        }
+        if total > budget {
+            warn!(
+                "LogJanitor: {:?} still holds {} bytes, over its {} byte budget.",
+                dir, total, budget
+            );
+        }
+        removed
+    }
+}
@@ -1,23 +1,86 @@
+use std::collections::HashMap;
 use std::path::Path;
+use tracing::{Event, Subscriber};
 use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::fmt::format::{Format, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::EnvFilter;
 
+/// 在每行日志前加上实例标签（如 `[env=prod role=db] `）的格式器，标签为空时与默认格式相同
+pub struct LabeledFormat<F = Format> {
+    inner: F,
+    prefix: String,
+}
+
+impl LabeledFormat {
+    pub fn new(labels: &HashMap<String, String>) -> Self {
+        let mut pairs: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        pairs.sort();
+        let prefix = if pairs.is_empty() {
+            String::new()
+        } else {
+            format!("[{}] ", pairs.join(" "))
+        };
+        Self {
+            inner: Format::default(),
+            prefix,
+        }
+    }
+}
+
+impl<S, N, F> FormatEvent<S, N> for LabeledFormat<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> std::fmt::Result {
+        writer.write_str(&self.prefix)?;
+        self.inner.format_event(ctx, writer, event)
+    }
+}
+
+/// 日志过滤指令：RUST_LOG 设置且非空时以其为准，否则使用配置文件中的指令（`AppConfig::log_directives`）
+pub fn filter_directives(rust_log: Option<String>, config_directives: &str) -> String {
+    rust_log
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| config_directives.to_string())
+}
+
+/// 按 RUST_LOG 与配置文件构造日志过滤器，同时返回实际使用的指令
+pub fn env_filter(config_directives: &str) -> Result<(EnvFilter, String), String> {
+    let directives = filter_directives(std::env::var("RUST_LOG").ok(), config_directives);
+    let filter = EnvFilter::try_new(&directives)
+        .map_err(|e| format!("Failed to parse log filter '{}': {}", directives, e))?;
+    Ok((filter, directives))
+}
+
 pub fn init_daemon_logging(
     log_directory: &Path,
+    labels: &HashMap<String, String>,
+    config_directives: &str,
 ) -> Result<WorkerGuard, Box<dyn std::error::Error>> {
     let log_file_name_prefix = "healer.log";
 
     let file_appender = tracing_appender::rolling::daily(log_directory, log_file_name_prefix);
     let (non_blocking_writer, guard) = tracing_appender::non_blocking(file_appender);
 
-    let env_filter_str = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
-    let env_filter = EnvFilter::try_new(&env_filter_str)
-        .map_err(|e| format!("Failed to parse RUST_LOG value '{}': {}", env_filter_str, e))?;
+    let (env_filter, env_filter_str) = env_filter(config_directives)?;
 
     tracing_subscriber::fmt()
         .with_env_filter(env_filter)
         .with_writer(non_blocking_writer)
         .with_ansi(false)
+        .event_format(LabeledFormat::new(labels))
         .try_init() // try_init() 返回 Result, init() 会 panic on error
         .map_err(|e| format!("Failed to initialize tracing subscriber: {}", e))?;
 
@@ -25,7 +88,7 @@ pub fn init_daemon_logging(
         "Logging system initialized. Log directory: {}",
         log_directory.display()
     );
-    tracing::info!("Log level configured via RUST_LOG='{}'", env_filter_str);
+    tracing::info!("Log filter configured as '{}'", env_filter_str);
 
     Ok(guard)
 }
@@ -1,40 +1,299 @@
 mod config;
+mod config_cache;
+mod config_check;
+mod config_graph;
 mod config_manager;
+mod control;
 mod coordinator; // expose dependency coordinator
 mod core_logic;
 mod daemon_handler;
 mod event_bus;
+mod health;
+mod http_api;
+mod instance_lock;
+mod log_janitor;
 mod logger;
+mod metrics;
 mod monitor;
 mod monitor_manager;
+mod oneshot;
 mod publisher;
+mod reaper;
+mod restart_scheduler;
 mod service_manager;
 mod signal_handler;
+mod status_file;
 mod subscriber;
 mod utils;
-use config::AppConfig;
+mod watchdog;
+use config::{AppConfig, ConfigError, ConfigFormat};
+use config_graph::{DependencyGraph, GraphFormat};
 use daemon_handler::run_as_daemon;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::sync::RwLock;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 /// Command line options for healer
 #[derive(Debug, Parser)]
 #[command(author, version, about = "Process self-healing daemon", long_about = None)]
 struct Cli {
-    /// Path to configuration file (YAML). If not provided, search order applies.
+    /// Path to configuration file (YAML/TOML/JSON), or `-` to read it from stdin.
+    /// If not provided, search order applies.
     #[arg(short, long)]
     config: Option<PathBuf>,
 
+    /// Configuration format: yaml, toml or json. Defaults to the file extension (YAML if unknown);
+    /// needed when the configuration comes from stdin and is not YAML.
+    #[arg(long)]
+    config_format: Option<ConfigFormat>,
+
     /// Run in foreground (do not daemonize). Equivalent to env HEALER_NO_DAEMON=1
     #[arg(long)]
     foreground: bool,
 
+    /// Debugging only, unsafe in production: run every recovery command as the daemon user
+    /// instead of switching to run_as_user. Equivalent to env HEALER_NO_DROP_PRIVILEGES=1
+    #[arg(long)]
+    no_drop_privileges: bool,
+
     /// Print the path that was selected for configuration and exit
     #[arg(long)]
     print_config_path: bool,
+
+    /// When the configuration is missing or invalid at startup, retry loading it this many
+    /// times before giving up (e.g. when it is deployed slightly after healer starts)
+    #[arg(long, default_value_t = 0)]
+    config_retries: u32,
+
+    /// Seconds to wait between configuration load retries
+    #[arg(long, default_value_t = 5)]
+    config_retry_interval: u64,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the configured processes with their monitor/recovery settings and exit
+    List,
+    /// Check every process once, recover the ones that are down, then exit.
+    /// The exit status is the number of processes found down (capped at 255).
+    Oneshot,
+    /// Load and validate the configuration, check dependencies, commands and users,
+    /// print the result as JSON and exit with a code per failure category (0 when clean).
+    Check,
+    /// Print the dependency graph (processes and their dependencies) and exit.
+    /// DOT output can be piped into Graphviz, e.g. `healer graph | dot -Tsvg > deps.svg`.
+    Graph {
+        /// Output format: dot or json
+        #[arg(long, default_value = "dot")]
+        format: GraphFormat,
+    },
+}
+
+/// 启动时配置无法加载的退出码（sysexits 的 EX_CONFIG），便于与崩溃区分
+const EXIT_CONFIG: i32 = 78;
+
+fn print_process_list(config: &AppConfig) {
+    if config.processes.is_empty() {
+        println!("No processes configured.");
+        return;
+    }
+    for process in &config.processes {
+        println!(
+            "{} [{}]",
+            process.name,
+            if process.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+        let mut command_line = process.command.clone();
+        for arg in &process.args {
+            command_line.push(' ');
+            command_line.push_str(arg);
+        }
+        println!("  command:    {}", command_line);
+        println!("  monitor:    {}", process.monitor);
+        println!("  recovery:   {}", process.recovery);
+        if let Some(group) = &process.restart_group {
+            println!("  group:      {}", group);
+        }
+        if let Some(guard) = &process.recovery_guard {
+            println!("  guard:      {} {}", guard.command, guard.args.join(" "));
+        }
+        let mut recovery_commands: Vec<_> = process.recovery_commands.iter().collect();
+        recovery_commands.sort_by_key(|(kind, _)| **kind);
+        for (kind, command) in recovery_commands {
+            println!(
+                "  on {}: {} {}",
+                kind,
+                command.command,
+                command.args.join(" ")
+            );
+        }
+        if let Some(nsenter) = &process.nsenter {
+            let namespaces = nsenter
+                .resolved_paths()
+                .into_iter()
+                .map(|(kind, path)| format!("{}={}", kind.proc_name(), path.display()))
+                .collect::<Vec<_>>();
+            println!("  nsenter:    {}", namespaces.join(" "));
+        }
+        let dependencies = process.resolved_dependencies();
+        if dependencies.is_empty() {
+            println!("  depends on: -");
+        }
+        for dependency in dependencies {
+            println!("  depends on: {}", dependency);
+        }
+    }
+}
+
+// 环境变量是否开启（1 / true / yes，不区分大小写）
+fn env_flag(name: &str) -> bool {
+    matches!(
+        env::var(name)
+            .unwrap_or_else(|_| "0".into())
+            .to_ascii_lowercase()
+            .as_str(),
+        "1" | "true" | "yes"
+    )
+}
+
+// 读取并解析配置文件，同时返回原文
+fn load_config_file(
+    path: &Path,
+    format: Option<ConfigFormat>,
+) -> Result<(AppConfig, String), ConfigError> {
+    let source = std::fs::read_to_string(path)?;
+    let format = format.unwrap_or_else(|| ConfigFormat::from_path(path));
+    let config = AppConfig::parse_at(&source, format, path)?;
+    Ok((config, source))
+}
+
+// 启动时的配置加载失败后，每隔 interval 重试，最多 retries 次；仍失败时尝试 last-known-good 缓存
+fn load_startup_config(
+    path: &Path,
+    format: Option<ConfigFormat>,
+    mut error: ConfigError,
+    retries: u32,
+    interval: std::time::Duration,
+) -> Result<AppConfig, ConfigError> {
+    for attempt in 1..=retries {
+        eprintln!(
+            "Config {:?} could not be loaded ({}), retrying in {}s ({}/{})",
+            path,
+            error,
+            interval.as_secs(),
+            attempt,
+            retries
+        );
+        std::thread::sleep(interval);
+        match load_config_file(path, format) {
+            Ok((config, source)) => {
+                config_cache::update(path, &config, &source);
+                return Ok(config);
+            }
+            Err(e) => error = e,
+        }
+    }
+    fall_back_to_cached_config(path, format, error)
+}
+
+// 打印启动配置错误并以 EXIT_CONFIG 退出，不再 panic
+fn exit_config_error(path: &Path, error: &ConfigError) -> ! {
+    eprintln!(
+        "Error: cannot start, config {:?} could not be loaded: {}",
+        path, error
+    );
+    std::process::exit(EXIT_CONFIG);
+}
+
+// 配置文件无法加载时尝试 last-known-good 缓存（需缓存中开启 allow_config_fallback）
+fn fall_back_to_cached_config(
+    path: &Path,
+    format: Option<ConfigFormat>,
+    error: ConfigError,
+) -> Result<AppConfig, ConfigError> {
+    let format = format.unwrap_or_else(|| ConfigFormat::from_path(path));
+    match config_cache::load_fallback(path, format) {
+        Some(config) => {
+            eprintln!(
+                "WARNING: config {:?} could not be loaded ({}); starting with the last-known-good config {:?}. Fix the config file and reload.",
+                path,
+                error,
+                config_cache::cache_path(path)
+            );
+            Ok(config)
+        }
+        None => Err(error),
+    }
+}
+
+// 前台与 oneshot 模式的日志过滤器；RUST_LOG 无法解析时提示并回退到 info
+fn foreground_filter(config: &AppConfig) -> tracing_subscriber::EnvFilter {
+    match logger::env_filter(&config.log_directives()) {
+        Ok((filter, _)) => filter,
+        Err(e) => {
+            eprintln!("Warning: {}, falling back to 'info'", e);
+            tracing_subscriber::EnvFilter::new("info")
+        }
+    }
+}
+
+fn run_oneshot(config: AppConfig) -> i32 {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(foreground_filter(&config))
+        .with_writer(std::io::stderr)
+        .try_init();
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Oneshot: Error from {}", e);
+            return 1;
+        }
+    };
+    let report = rt.block_on(oneshot::run_once(std::sync::Arc::new(RwLock::new(config))));
+    println!(
+        "Oneshot: checked {} process(es), {} down",
+        report.checked.len(),
+        report.down.len()
+    );
+    for name in &report.down {
+        println!("  down: {}", name);
+    }
+    report.down.len().min(255) as i32
+}
+
+// 输出 JSON 报告并返回退出码
+fn print_check_report(report: &config_check::CheckReport) -> i32 {
+    match serde_json::to_string_pretty(report) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Error: failed to serialize check report: {}", e),
+    }
+    report.exit_code()
+}
+
+fn print_dependency_graph(config: &AppConfig, format: GraphFormat) {
+    let graph = DependencyGraph::from_config(config);
+    match format {
+        GraphFormat::Dot => print!("{}", graph.to_dot()),
+        GraphFormat::Json => match serde_json::to_string_pretty(&graph) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error: failed to serialize dependency graph: {}", e),
+        },
+    }
+    for cycle in &graph.cycles {
+        eprintln!("Warning: dependency cycle: {}", cycle.join(" -> "));
+    }
 }
 
 fn candidate_config_paths(explicit: Option<PathBuf>) -> Vec<PathBuf> {
@@ -85,49 +344,170 @@ fn main() {
 
     // Determine final config path
     let raw_config_path = resolve_config_path(&cli);
-    println!("Config resolution: using {:?}", raw_config_path);
+    let checking = matches!(cli.command, Some(Command::Check));
+    // 守护进程与 oneshot 需要可用的配置才能启动，其余子命令只读取配置
+    let starting = matches!(cli.command, None | Some(Command::Oneshot));
+    if checking || matches!(cli.command, Some(Command::Graph { .. })) {
+        // check / graph 的标准输出只包含报告或图，便于交给其他工具处理
+        eprintln!("Config resolution: using {:?}", raw_config_path);
+    } else {
+        println!("Config resolution: using {:?}", raw_config_path);
+    }
 
     if cli.print_config_path {
         println!("{:?}", raw_config_path);
         return;
     }
 
-    // Expand & canonicalize for safety
-    let absolute_config_path = match std::fs::canonicalize(&raw_config_path) {
-        Ok(path) => path,
+    // `-` 表示从标准输入读取配置；此时没有文件可供重载
+    let absolute_config_path = if raw_config_path == Path::new("-") {
+        None
+    } else {
+        // Expand & canonicalize for safety
+        match std::fs::canonicalize(&raw_config_path) {
+            Ok(path) => Some(path),
+            Err(e) if checking => {
+                let report = config_check::CheckReport::load_failed(&e.into());
+                std::process::exit(print_check_report(&report));
+            }
+            // 配置可能稍后才部署，先按原路径继续，由启动时的重试处理
+            Err(_) if starting && cli.config_retries > 0 => Some(raw_config_path.clone()),
+            Err(e) => {
+                eprintln!("Error: cannot access config {:?}: {}", raw_config_path, e);
+                std::process::exit(if starting { EXIT_CONFIG } else { 1 });
+            }
+        }
+    };
+    let config_format = cli.config_format;
+    // 标准输入只能读取一次，因此先加载再分派子命令
+    let mut config_source = None;
+    let loaded_config = match &absolute_config_path {
+        Some(path) => load_config_file(path, config_format).map(|(config, source)| {
+            // 保留原文，启动时写入 last-known-good 缓存
+            config_source = Some(source);
+            config
+        }),
+        None => {
+            AppConfig::load_from_reader(std::io::stdin().lock(), config_format.unwrap_or_default())
+        }
+    };
+
+    if let Some(Command::List) = cli.command {
+        match loaded_config {
+            Ok(config) => print_process_list(&config),
+            Err(e) => {
+                eprintln!("Error: invalid config {:?}: {}", raw_config_path, e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::Graph { format }) = cli.command {
+        match loaded_config {
+            Ok(config) => print_dependency_graph(&config, format),
+            Err(e) => {
+                eprintln!("Error: invalid config {:?}: {}", raw_config_path, e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if checking {
+        let report = match &loaded_config {
+            Ok(config) => config_check::check(config),
+            Err(e) => config_check::CheckReport::load_failed(e),
+        };
+        std::process::exit(print_check_report(&report));
+    }
+
+    let (initial_config, absolute_config_path) = match (loaded_config, absolute_config_path) {
+        (Ok(config), Some(path)) => {
+            if let Some(source) = &config_source {
+                config_cache::update(&path, &config, source);
+            }
+            (config, Some(path))
+        }
+        (Ok(config), None) => (config, None),
+        (Err(e), Some(path)) => {
+            let retry_interval = std::time::Duration::from_secs(cli.config_retry_interval);
+            match load_startup_config(&path, config_format, e, cli.config_retries, retry_interval) {
+                // 重试前可能尚不存在，成功后再解析为绝对路径，供重载使用
+                Ok(config) => (config, Some(std::fs::canonicalize(&path).unwrap_or(path))),
+                Err(e) => exit_config_error(&raw_config_path, &e),
+            }
+        }
+        (Err(e), None) => exit_config_error(&raw_config_path, &e),
+    };
+
+    // 没有启用的进程时照常启动但什么也不做，容易被误认为运行正常
+    if initial_config.enabled_process_count() == 0 {
+        eprintln!(
+            "Warning: no enabled processes in config {:?}, nothing will be monitored \
+             (set require_processes: true to refuse such a config)",
+            raw_config_path
+        );
+    }
+
+    if let Some(Command::Oneshot) = cli.command {
+        std::process::exit(run_oneshot(initial_config));
+    }
+    // 工作线程数只在启动时读取，重载配置不会改变
+    let worker_threads = initial_config.worker_threads();
+    let labels = initial_config.labels.clone();
+    let log_filter = foreground_filter(&initial_config);
+    // 同一份 PID 文件只允许一个实例监管，避免两个 healer 同时拉起同一批进程
+    let instance_lock = match instance_lock::InstanceLock::acquire(
+        &initial_config.to_daemonize_config().pid_file,
+    ) {
+        Ok(lock) => lock,
         Err(e) => {
-            eprintln!("Error: cannot access config {:?}: {}", raw_config_path, e);
+            eprintln!("Error: {}", e);
             std::process::exit(1);
         }
     };
-
-    let initial_config =
-        AppConfig::load_from_file(&absolute_config_path).expect("初始配置加载失败");
     let shared_config = std::sync::Arc::new(RwLock::new(initial_config));
 
     // Detect foreground from either flag or env
-    let env_foreground = matches!(
-        env::var("HEALER_NO_DAEMON")
-            .unwrap_or_else(|_| "0".into())
-            .to_ascii_lowercase()
-            .as_str(),
-        "1" | "true" | "yes"
-    );
-    let run_foreground = cli.foreground || env_foreground;
+    let run_foreground = cli.foreground || env_flag("HEALER_NO_DAEMON");
+    let drop_privileges = !(cli.no_drop_privileges || env_flag("HEALER_NO_DROP_PRIVILEGES"));
+    if !drop_privileges {
+        eprintln!("WARNING: privilege dropping is disabled, all recovery commands will run as the daemon user. Do not use this in production.");
+    }
 
     if run_foreground {
         let _ = tracing_subscriber::fmt()
-            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .with_env_filter(log_filter)
             .with_ansi(true)
+            .event_format(logger::LabeledFormat::new(&labels))
             .try_init();
-        core_logic::async_runtime(std::sync::Arc::clone(&shared_config), absolute_config_path);
+        core_logic::async_runtime(
+            std::sync::Arc::clone(&shared_config),
+            absolute_config_path,
+            config_format,
+            true,
+            drop_privileges,
+            worker_threads,
+        );
+        drop(instance_lock);
         return;
     }
 
     let config_for_closure = std::sync::Arc::clone(&shared_config);
     let path_for_closure = absolute_config_path.clone();
-    let core_logic_closure =
-        move || core_logic::async_runtime(config_for_closure, path_for_closure);
+    let core_logic_closure = move || {
+        core_logic::async_runtime(
+            config_for_closure,
+            path_for_closure,
+            config_format,
+            false,
+            drop_privileges,
+            worker_threads,
+        )
+    };
+    // daemonize 会在子进程中重新锁定 PID 文件并一直持有，这里先释放
+    drop(instance_lock);
     match run_as_daemon(shared_config, core_logic_closure) {
         Ok(_) => println!("Main program: Core logic quit"),
         Err(e) => println!("Main program: Core logic error with {:?}", e),
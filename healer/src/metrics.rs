@@ -0,0 +1,183 @@
+//! 延迟指标：按进程统计的直方图，回答“从检测到故障到真正执行恢复用了多久”。
+//!
+//! 延迟从事件的检测时间（`ProcessEvent::at`）算起，因此包含事件在总线与协调器中排队、被延后的时间。
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tracing::warn;
+
+/// 直方图各桶的上界（毫秒），超过最后一个上界的样本计入溢出桶
+pub const LATENCY_BUCKETS_MS: [u64; 11] = [
+    10, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000,
+];
+
+// 标签值超过该长度时视为可能的高基数标签
+const MAX_LABEL_VALUE_LEN: usize = 64;
+
+/// 看起来像唯一标识（UUID、长数字串、超长值）的标签名，作为指标标签会导致基数失控
+pub fn high_cardinality_labels(labels: &HashMap<String, String>) -> Vec<String> {
+    let mut suspicious: Vec<String> = labels
+        .iter()
+        .filter(|(_, value)| {
+            let digits = value.bytes().filter(u8::is_ascii_digit).count();
+            let is_uuid = value.len() == 36
+                && value.bytes().filter(|&b| b == b'-').count() == 4
+                && value.bytes().all(|b| b == b'-' || b.is_ascii_hexdigit());
+            value.len() > MAX_LABEL_VALUE_LEN || is_uuid || (digits >= 8 && digits == value.len())
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+    suspicious.sort();
+    suspicious
+}
+
+/// 延迟指标的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LatencyKind {
+    /// 监控器检测到故障到自愈器拉起恢复命令（或发出恢复信号）
+    DetectionToRecovery,
+    /// 协调器因依赖阻塞而延后一次恢复的时长
+    CoordinatorDeferral,
+}
+
+impl std::fmt::Display for LatencyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            LatencyKind::DetectionToRecovery => "detection_to_recovery",
+            LatencyKind::CoordinatorDeferral => "coordinator_deferral",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// 固定分桶的延迟直方图
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    // 与 LATENCY_BUCKETS_MS 一一对应，最后多出的一项为溢出桶
+    buckets: Vec<u64>,
+    count: u64,
+    sum: Duration,
+    max: Duration,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; LATENCY_BUCKETS_MS.len() + 1],
+            count: 0,
+            sum: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+}
+
+impl Histogram {
+    pub fn observe(&mut self, latency: Duration) {
+        let millis = latency.as_millis();
+        let index = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&upper| millis <= upper as u128)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[index] += 1;
+        self.count += 1;
+        self.sum += latency;
+        self.max = self.max.max(latency);
+    }
+
+    /// 样本数
+    #[allow(dead_code)]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// 所有样本之和
+    #[allow(dead_code)]
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// 最大的样本
+    #[allow(dead_code)]
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// 各桶的（上界毫秒, 样本数），溢出桶的上界为 None；样本数不累加
+    #[allow(dead_code)]
+    pub fn buckets(&self) -> Vec<(Option<u64>, u64)> {
+        LATENCY_BUCKETS_MS
+            .iter()
+            .map(|&upper| Some(upper))
+            .chain(std::iter::once(None))
+            .zip(self.buckets.iter().copied())
+            .collect()
+    }
+}
+
+/// 从检测时间 `at` 到 `now` 的延迟；时钟回拨导致 `at` 晚于 `now` 时按 0 计
+pub fn latency_between(at: SystemTime, now: SystemTime) -> Duration {
+    now.duration_since(at).unwrap_or_default()
+}
+
+/// 按（种类, 进程名）划分的延迟直方图共享表，克隆后指向同一份数据
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    histograms: Arc<RwLock<HashMap<(LatencyKind, String), Histogram>>>,
+    // 实例标签，导出指标时附加在每条指标上
+    labels: Arc<HashMap<String, String>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 附加实例标签；看起来是高基数的标签只记录警告，仍然保留
+    pub fn with_labels(mut self, labels: HashMap<String, String>) -> Self {
+        for key in high_cardinality_labels(&labels) {
+            warn!(label = %key, "Label value looks unique per instance or per event; using it as a metrics label may create unbounded cardinality.");
+        }
+        self.labels = Arc::new(labels);
+        self
+    }
+
+    /// 实例标签
+    #[allow(dead_code)]
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    /// 记录一个延迟样本
+    pub fn observe(&self, kind: LatencyKind, name: &str, latency: Duration) {
+        let mut histograms = self.histograms.write().unwrap_or_else(|e| e.into_inner());
+        histograms
+            .entry((kind, name.to_string()))
+            .or_default()
+            .observe(latency);
+    }
+
+    /// 记录从检测时间 `at` 到现在的延迟，返回记录的值
+    pub fn observe_since(&self, kind: LatencyKind, name: &str, at: SystemTime) -> Duration {
+        let latency = latency_between(at, SystemTime::now());
+        self.observe(kind, name, latency);
+        latency
+    }
+
+    /// 某进程某种延迟的直方图，尚无样本时为 None
+    #[allow(dead_code)]
+    pub fn histogram(&self, kind: LatencyKind, name: &str) -> Option<Histogram> {
+        let histograms = self.histograms.read().unwrap_or_else(|e| e.into_inner());
+        histograms.get(&(kind, name.to_string())).cloned()
+    }
+
+    /// 所有直方图的快照，按种类与进程名排序
+    #[allow(dead_code)]
+    pub fn snapshot(&self) -> BTreeMap<(LatencyKind, String), Histogram> {
+        let histograms = self.histograms.read().unwrap_or_else(|e| e.into_inner());
+        histograms
+            .iter()
+            .map(|(key, histogram)| (key.clone(), histogram.clone()))
+            .collect()
+    }
+}
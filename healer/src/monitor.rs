@@ -1,14 +1,149 @@
+use crate::config::{MonitorConfig, ProcessConfig};
+use crate::event_bus::ProcessEvent;
+use crate::health::HealthRegistry;
 use async_trait::async_trait;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
 pub mod ebpf_monitor;
+pub mod memory_trend;
 pub mod network_monitor;
 pub mod pid_monitor;
+pub mod systemd_monitor;
+pub mod unix_socket_monitor;
+/// 配置要求的监控类型没有编译进当前的 healer（对应的 cargo feature 未启用）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorNotCompiled {
+    pub process: String,
+    pub monitor: &'static str,
+    pub feature: &'static str,
+}
+
+impl fmt::Display for MonitorNotCompiled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "process '{}' uses a {} monitor, but healer was built without the '{}' feature",
+            self.process, self.monitor, self.feature
+        )
+    }
+}
+
+impl std::error::Error for MonitorNotCompiled {}
+
+/// 检查进程的监控类型是否编译进了当前的 healer。
+/// systemd 监控的分发始终可用，连接 D-Bus 的默认状态来源由 `systemd` feature 控制
+pub fn check_compiled(process: &ProcessConfig) -> Result<(), MonitorNotCompiled> {
+    let (monitor, feature, compiled) = match &process.monitor {
+        MonitorConfig::Pid(_) => ("pid", "monitor-pid", cfg!(feature = "monitor-pid")),
+        MonitorConfig::Network(_) => (
+            "network",
+            "monitor-network",
+            cfg!(feature = "monitor-network"),
+        ),
+        MonitorConfig::Ebpf(_) => ("ebpf", "monitor-ebpf", cfg!(feature = "monitor-ebpf")),
+        MonitorConfig::UnixSocket(_) => (
+            "unix_socket",
+            "monitor-unix-socket",
+            cfg!(feature = "monitor-unix-socket"),
+        ),
+        MonitorConfig::Systemd(_) => return Ok(()),
+    };
+    if compiled {
+        return Ok(());
+    }
+    Err(MonitorNotCompiled {
+        process: process.name.clone(),
+        monitor,
+        feature,
+    })
+}
+
+// interval 为 0 时 tokio::time::interval 无法工作，构造监控器时兜底为 1 秒
+pub(crate) fn normalize_interval_secs(name: &str, interval_secs: u64) -> u64 {
+    if interval_secs == 0 {
+        warn!("[{}] interval_secs is 0, using 1 second instead.", name);
+        return 1;
+    }
+    interval_secs
+}
+
 #[async_trait]
 pub trait Monitor: Send + Sync {
     // 启动并运行监控任务。
     // 这个方法需要包含一个无限循环，因此它本身不会返回。
     // 它应该被作为一个独立的并发任务来 spawn。
     // 但是目前eBPF不需要run。后续考虑重新抽象Monitor trait (Todo)
-    async fn run(self);
+    // shutdown 被取消时，监控任务应在下一个 tick 边界处干净地退出。
+    async fn run(self, shutdown: CancellationToken);
+
+    // 只执行一次（带超时的）健康检查，进程异常时返回本应发布的事件，不进入循环。
+    // 供 oneshot 模式使用；事件驱动的 eBPF 监控没有可主动执行的检查，返回 None。
+    async fn check_once(&self) -> Option<ProcessEvent>;
 
     fn name(&self) -> String;
 }
+
+// 一次健康检查的结论
+pub(crate) enum Probe {
+    // 确认进程健康
+    Healthy,
+    // 发现故障，附带需要上报的事件
+    Failed(ProcessEvent),
+    // 本轮无法判断（读取失败、超时等）
+    Unknown,
+}
+
+impl Probe {
+    pub(crate) fn into_failure(self) -> Option<ProcessEvent> {
+        match self {
+            Probe::Failed(event) => Some(event),
+            _ => None,
+        }
+    }
+}
+
+// 把检查结论转换为需要发布的事件：故障每次都上报，健康只在首次确认或故障之后上报一次 ProcessUp
+#[derive(Default)]
+pub(crate) struct HealthEdge {
+    up_reported: AtomicBool,
+    // 与自愈器共享，用于判断进程是否处于恢复后的宽限期
+    health: HealthRegistry,
+}
+
+impl HealthEdge {
+    pub(crate) fn new(health: HealthRegistry) -> Self {
+        Self {
+            up_reported: AtomicBool::new(false),
+            health,
+        }
+    }
+
+    pub(crate) fn observe(&self, probe: Probe, name: &str) -> Option<ProcessEvent> {
+        // 每一轮检查都会经过这里，记录时间以便发现不再运转的监控器
+        self.health.record_check(name);
+        // 刚恢复的服务可能仍在启动，宽限期内的故障不上报，避免再次触发重启
+        if matches!(probe, Probe::Failed(_)) && self.health.in_monitor_grace(name) {
+            debug!(
+                "[{}] Ignoring failure during post-restart grace period.",
+                name
+            );
+            return None;
+        }
+        match probe {
+            Probe::Healthy => {
+                (!self.up_reported.swap(true, Ordering::Relaxed)).then(|| ProcessEvent::ProcessUp {
+                    name: name.to_string(),
+                    at: SystemTime::now(),
+                })
+            }
+            Probe::Failed(event) => {
+                self.up_reported.store(false, Ordering::Relaxed);
+                Some(event)
+            }
+            Probe::Unknown => None,
+        }
+    }
+}
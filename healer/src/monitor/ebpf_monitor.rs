@@ -1,181 +1,684 @@
 use super::Monitor;
-use crate::{config::EbpfMonitorConfig, event_bus::ProcessEvent, publisher::Publisher, utils};
+use crate::{
+    config::{AppConfig, CommMatch, EbpfAttach, EbpfMonitorConfig},
+    event_bus::{EventSender, ProcessEvent},
+    publisher::Publisher,
+    utils,
+};
 use anyhow::anyhow;
 use anyhow::Result;
 use async_trait::async_trait;
-use aya::{maps::PerfEventArray, programs::TracePoint, util::online_cpus, Ebpf};
+use aya::{
+    maps::{perf::PerfEventArrayBuffer, MapData, MapError, PerfEventArray},
+    programs::{KProbe, TracePoint},
+    util::online_cpus,
+    Ebpf,
+};
 use bytes::BytesMut;
-use healer_common::ProcessExitEvent;
-use std::time::Duration;
+use futures::future::{select_all, BoxFuture};
+use healer_common::{ProcessExitEvent, PROCESS_NAME_MAP_CAPACITY, SETTING_REPORT_ALL_EXITS};
+use regex::bytes::Regex;
+use std::borrow::BorrowMut;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::time::{Duration, SystemTime};
 use std::{
-    collections,
+    collections::{self, HashSet},
     sync::{Arc, Mutex},
 };
-use std::{
-    os::unix::io::AsRawFd,
-    sync::atomic::{AtomicBool, Ordering},
+use tokio::{
+    io::unix::AsyncFd,
+    sync::{broadcast, watch},
 };
-use tokio::{io::unix::AsyncFd, sync::broadcast, time::timeout};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 pub struct EbpfMonitor {
     bpf: Arc<Mutex<Ebpf>>,
-    process_name_mapping: Arc<Mutex<collections::HashMap<String, String>>>, // truncated_name -> full_config_name
-    task_handles: Vec<tokio::task::JoinHandle<()>>,                         // 保存后台任务句柄
-    shutdown_flag: Arc<AtomicBool>,                                         // 关闭标志
-    out_tx: broadcast::Sender<ProcessEvent>,                                // 发布通道
+    comm_matcher: Arc<Mutex<CommMatcher>>, // comm -> 配置名
+    task_handles: Vec<tokio::task::JoinHandle<()>>, // 保存后台任务句柄
+    shutdown: CancellationToken,           // 关闭时唤醒所有 worker
+    out_tx: EventSender,                   // 发布通道
+    readiness: EbpfReadiness,              // 监控列表就绪状态
+}
+
+/// 把事件中的 comm 解析为配置名。exact 匹配已由内核侧 map 过滤，这里只做反查；
+/// prefix / regex 匹配需要内核上报所有进程的退出，再由这里逐个判断，
+/// 代价是每次进程退出都要经过 perf buffer 进入用户态。
+/// 匹配基于内核上报的原始 comm 字节：多字节进程名截断到 15 字节后可能不是合法的 UTF-8。
+#[derive(Debug, Default)]
+pub struct CommMatcher {
+    // 截断后的 comm（与内核 map 的键相同）-> 配置名
+    exact: collections::HashMap<[u8; 16], String>,
+    // (配置名, 匹配规则)，按登记顺序检查
+    patterns: Vec<(String, CommPattern)>,
+}
+
+#[derive(Debug)]
+enum CommPattern {
+    // 按字节截断到 15 字节，与内核中的 comm 一致
+    Prefix(Vec<u8>),
+    Regex(Regex),
+}
+
+impl CommPattern {
+    fn matches(&self, comm: &[u8]) -> bool {
+        match self {
+            CommPattern::Prefix(prefix) => comm.starts_with(prefix),
+            CommPattern::Regex(regex) => regex.is_match(comm),
+        }
+    }
+}
+
+/// comm 中第一个 NUL 之前的字节
+pub fn comm_bytes(comm: &[u8; 16]) -> &[u8] {
+    &comm[..comm.iter().position(|&b| b == 0).unwrap_or(comm.len())]
+}
+
+impl CommMatcher {
+    /// 登记 exact 匹配的进程
+    pub fn insert_exact(&mut self, comm: String, name: String) {
+        self.exact.insert(utils::process_name_key(&comm), name);
+    }
+
+    pub fn remove_exact(&mut self, comm: &str) {
+        self.exact.remove(&utils::process_name_key(comm));
+    }
+
+    pub fn contains_exact(&self, comm: &str) -> bool {
+        self.exact.contains_key(&utils::process_name_key(comm))
+    }
+
+    /// 已写入内核 map 的 comm 数量
+    pub fn exact_len(&self) -> usize {
+        self.exact.len()
+    }
+
+    /// 登记 prefix / regex 匹配的进程，替换同名进程之前的规则
+    pub fn insert_pattern(&mut self, config: &EbpfMonitorConfig) -> Result<()> {
+        let pattern = match config.comm_match {
+            CommMatch::Exact => {
+                return Err(anyhow!(
+                    "process '{}' uses exact comm matching, which is filtered in the kernel",
+                    config.name
+                ))
+            }
+            CommMatch::Prefix => CommPattern::Prefix(
+                comm_bytes(&utils::process_name_key(&config.monitored_comm())).to_vec(),
+            ),
+            CommMatch::Regex => {
+                let source = config.process_comm.as_deref().ok_or_else(|| {
+                    anyhow!(
+                        "process '{}': comm_match 'regex' requires process_comm",
+                        config.name
+                    )
+                })?;
+                CommPattern::Regex(Regex::new(source)?)
+            }
+        };
+        self.remove_pattern(&config.name);
+        self.patterns.push((config.name.clone(), pattern));
+        Ok(())
+    }
+
+    /// 移除进程的 prefix / regex 规则，返回是否存在
+    pub fn remove_pattern(&mut self, name: &str) -> bool {
+        let before = self.patterns.len();
+        self.patterns.retain(|(existing, _)| existing != name);
+        self.patterns.len() != before
+    }
+
+    /// 是否需要内核上报所有进程的退出
+    pub fn reports_all_exits(&self) -> bool {
+        !self.patterns.is_empty()
+    }
+
+    /// comm 对应的所有配置名：先查 exact，再按登记顺序检查 prefix / regex
+    #[allow(dead_code)]
+    pub fn resolve(&self, comm: &str) -> Vec<String> {
+        self.resolve_raw(&utils::process_name_key(comm))
+    }
+
+    /// 同 [`CommMatcher::resolve`]，直接使用内核事件中的 comm 字节
+    pub fn resolve_raw(&self, comm: &[u8; 16]) -> Vec<String> {
+        // NUL 之后的内容不属于进程名，统一清零后再作为键查找
+        let mut key = [0u8; 16];
+        let bytes = comm_bytes(comm);
+        key[..bytes.len()].copy_from_slice(bytes);
+        let mut names: Vec<String> = self.exact.get(&key).cloned().into_iter().collect();
+        names.extend(
+            self.patterns
+                .iter()
+                .filter(|(_, pattern)| pattern.matches(bytes))
+                .map(|(name, _)| name.clone()),
+        );
+        names
+    }
+}
+
+/// eBPF 监控列表的就绪状态：期望监控的进程全部写入 `PROCESS_NAMES_TO_MONITOR` 后视为就绪。
+#[derive(Clone)]
+pub struct EbpfReadiness {
+    state: watch::Sender<ReadinessState>,
+}
+
+#[derive(Default)]
+struct ReadinessState {
+    expected: HashSet<String>,
+    populated: HashSet<String>,
+}
+
+impl ReadinessState {
+    fn is_ready(&self) -> bool {
+        self.expected.is_subset(&self.populated)
+    }
+}
+
+impl Default for EbpfReadiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EbpfReadiness {
+    pub fn new() -> Self {
+        let (state, _) = watch::channel(ReadinessState::default());
+        Self { state }
+    }
+
+    /// 设置本轮协调期望监控的进程（配置名），替换之前的期望集合
+    pub fn expect(&self, names: impl IntoIterator<Item = String>) {
+        let expected: HashSet<String> = names.into_iter().collect();
+        self.state.send_modify(|state| state.expected = expected);
+    }
+
+    /// 进程名已写入 eBPF map
+    pub fn mark_populated(&self, name: &str) {
+        self.state.send_modify(|state| {
+            state.populated.insert(name.to_string());
+        });
+    }
+
+    /// 进程不再被监控（已移除或写入失败），同时从期望集合中去掉，避免永远无法就绪
+    pub fn mark_removed(&self, name: &str) {
+        self.state.send_modify(|state| {
+            state.expected.remove(name);
+            state.populated.remove(name);
+        });
+    }
+
+    /// 期望监控的进程全部写入 eBPF map 时完成
+    pub fn wait_ready(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut rx = self.state.subscribe();
+        async move {
+            let _ = rx.wait_for(ReadinessState::is_ready).await;
+        }
+    }
+}
+
+/// `PROCESS_NAMES_TO_MONITOR` 已满，无法再加入新的进程名
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EbpfMapFull {
+    pub comm: String,
+    pub capacity: u32,
+}
+
+impl fmt::Display for EbpfMapFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "eBPF map 'PROCESS_NAMES_TO_MONITOR' is full ({} entries), cannot watch comm '{}'",
+            self.capacity, self.comm
+        )
+    }
+}
+
+impl std::error::Error for EbpfMapFull {}
+
+/// 进程名 map 的最小操作集合，写入/删除后通过它回读校验内核侧的实际内容
+pub trait ProcessNameMap {
+    fn contains(&self, key: &[u8; 16]) -> io::Result<bool>;
+    fn insert(&mut self, key: [u8; 16]) -> io::Result<()>;
+    fn remove(&mut self, key: &[u8; 16]) -> io::Result<()>;
+}
+
+impl<T: BorrowMut<MapData>> ProcessNameMap for aya::maps::HashMap<T, [u8; 16], u8> {
+    fn contains(&self, key: &[u8; 16]) -> io::Result<bool> {
+        match self.get(key, 0) {
+            Ok(_) => Ok(true),
+            Err(MapError::KeyNotFound) => Ok(false),
+            Err(e) => Err(map_error_to_io(e)),
+        }
+    }
+
+    fn insert(&mut self, key: [u8; 16]) -> io::Result<()> {
+        aya::maps::HashMap::insert(self, key, 1, 0).map_err(map_error_to_io)
+    }
+
+    fn remove(&mut self, key: &[u8; 16]) -> io::Result<()> {
+        aya::maps::HashMap::remove(self, key).map_err(map_error_to_io)
+    }
+}
+
+// 保留系统调用的 errno（如 E2BIG），便于上层识别 map 已满
+fn map_error_to_io(e: MapError) -> io::Error {
+    if matches!(e, MapError::KeyNotFound) {
+        return io::Error::from(io::ErrorKind::NotFound);
+    }
+    let mut source = std::error::Error::source(&e);
+    while let Some(err) = source {
+        if let Some(code) = err
+            .downcast_ref::<io::Error>()
+            .and_then(io::Error::raw_os_error)
+        {
+            return io::Error::from_raw_os_error(code);
+        }
+        source = err.source();
+    }
+    io::Error::other(e.to_string())
+}
+
+/// 写入进程名并回读确认；map 已满时返回 [`EbpfMapFull`]
+pub fn insert_verified(
+    map: &mut impl ProcessNameMap,
+    key: [u8; 16],
+    comm: &str,
+    capacity: u32,
+) -> Result<()> {
+    if let Err(e) = map.insert(key) {
+        // 哈希表条目数达到 max_entries 时内核返回 E2BIG
+        if e.raw_os_error() == Some(nix::errno::Errno::E2BIG as i32) {
+            return Err(EbpfMapFull {
+                comm: comm.to_string(),
+                capacity,
+            }
+            .into());
+        }
+        return Err(anyhow!(
+            "Failed to insert process name '{}' into eBPF map: {}",
+            comm,
+            e
+        ));
+    }
+    let present = map
+        .contains(&key)
+        .map_err(|e| anyhow!("Failed to read back process name '{}': {}", comm, e))?;
+    if !present {
+        return Err(anyhow!(
+            "Process name '{}' is missing from eBPF map after insert",
+            comm
+        ));
+    }
+    Ok(())
+}
+
+/// 删除进程名并回读确认已不在 map 中
+pub fn remove_verified(map: &mut impl ProcessNameMap, key: [u8; 16], comm: &str) -> Result<()> {
+    map.remove(&key).map_err(|e| {
+        anyhow!(
+            "Failed to remove process name '{}' from eBPF map: {}",
+            comm,
+            e
+        )
+    })?;
+    let present = map
+        .contains(&key)
+        .map_err(|e| anyhow!("Failed to read back process name '{}': {}", comm, e))?;
+    if present {
+        return Err(anyhow!(
+            "Process name '{}' is still present in eBPF map after removal",
+            comm
+        ));
+    }
+    Ok(())
+}
+
+/// 管理 eBPF 监控列表的接口，由 [`EbpfMonitor`] 实现；
+/// 抽象成 trait 便于在没有 root 权限的环境中替换为测试实现。
+#[async_trait]
+pub trait EbpfWatcher: Send {
+    /// 设置本轮协调期望监控的进程（配置名）
+    fn expect_watched(&self, names: Vec<String>);
+    /// 期望监控的进程全部写入 map 时完成
+    fn ready(&self) -> BoxFuture<'static, ()>;
+    async fn watch_config(&mut self, ebpf_config: EbpfMonitorConfig) -> Result<()>;
+    async fn unwatch_config(&mut self, ebpf_config: EbpfMonitorConfig) -> Result<()>;
+    async fn shutdown(&mut self);
 }
 
 #[derive(Clone)]
 struct TxPublisher {
-    tx: broadcast::Sender<ProcessEvent>,
+    tx: EventSender,
 }
 
+#[async_trait]
 impl Publisher for TxPublisher {
-    fn publish(
+    async fn publish(
         &self,
         event: ProcessEvent,
     ) -> Result<usize, broadcast::error::SendError<ProcessEvent>> {
-        self.tx.send(event)
+        self.tx.send(event).await
+    }
+}
+
+/// perf buffer 读取任务的设置，仅在启动时生效
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EbpfWorkerOptions {
+    /// worker 数上限，None 时每个在线 CPU 一个
+    pub max_workers: Option<usize>,
+    /// 空闲的 worker 定期醒来读取其全部 buffer 的间隔；关闭不依赖于它
+    pub poll_interval: Duration,
+}
+
+impl EbpfWorkerOptions {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            max_workers: config.ebpf_max_workers,
+            poll_interval: Duration::from_millis(config.ebpf_poll_interval_ms),
+        }
+    }
+}
+
+/// eBPF 程序加载与挂载的重试策略：开机较早时 tracefs 等可能尚未就绪，短暂的失败在退避后重试
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EbpfLoadRetry {
+    /// 总尝试次数（含第一次）
+    pub attempts: u32,
+    /// 第一次失败后的等待时间，之后每次翻倍
+    pub initial_backoff: Duration,
+    /// 等待时间的上限
+    pub max_backoff: Duration,
+}
+
+impl Default for EbpfLoadRetry {
+    fn default() -> Self {
+        Self {
+            attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+/// 执行 `op`，失败时按 `retry` 退避后重试，每次失败记录日志；用尽尝试次数后返回最后一次的错误
+pub async fn retry_with_backoff<T>(
+    what: &str,
+    retry: EbpfLoadRetry,
+    mut op: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let attempts = retry.attempts.max(1);
+    let mut backoff = retry.initial_backoff;
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => {
+                if attempt > 1 {
+                    info!(
+                        "[EbpfMonitor] Succeeded to {} on attempt {}/{}.",
+                        what, attempt, attempts
+                    );
+                }
+                return Ok(value);
+            }
+            Err(e) if attempt < attempts => {
+                warn!(
+                    "[EbpfMonitor] Failed to {} (attempt {}/{}): {}. Retrying in {:?}.",
+                    what, attempt, attempts, e, backoff
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(retry.max_backoff);
+                attempt += 1;
+            }
+            Err(e) => {
+                error!(
+                    "[EbpfMonitor] Failed to {} after {} attempts, giving up: {}",
+                    what, attempts, e
+                );
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// 加载 eBPF 程序并挂载到进程退出点；失败时已加载的部分随返回的错误一起释放
+pub fn load_and_attach(attach: EbpfAttach) -> Result<Ebpf> {
+    let mut bpf = aya::Ebpf::load(aya::include_bytes_aligned!(concat!(
+        env!("OUT_DIR"),
+        "/healer"
+    )))?;
+    match attach {
+        EbpfAttach::Tracepoint => {
+            let program: &mut TracePoint = bpf
+                .program_mut("healer_exit")
+                .ok_or_else(|| anyhow!("Program 'healer_exit' not found"))?
+                .try_into()?;
+            program.load()?;
+            program.attach("sched", "sched_process_exit")?;
+            info!("[EbpfMonitor] Tracepoint sched:sched_process_exit attached successfully.");
+        }
+        EbpfAttach::Kprobe => {
+            let program: &mut KProbe = bpf
+                .program_mut("healer_exit_kprobe")
+                .ok_or_else(|| anyhow!("Program 'healer_exit_kprobe' not found"))?
+                .try_into()?;
+            program.load()?;
+            program.attach("do_exit", 0)?;
+            info!("[EbpfMonitor] Kprobe do_exit attached successfully.");
+        }
+    }
+    Ok(bpf)
+}
+
+/// worker 被唤醒的原因
+#[derive(Debug)]
+pub enum WorkerWake<T> {
+    /// 监控器正在关闭
+    Shutdown,
+    /// 有 buffer 可读
+    Ready(T),
+    /// 到了定期读取的时间
+    Poll,
+}
+
+/// 等待 `ready` 完成、监控器关闭或定期读取时间到达中最先发生的一个；同时发生时关闭优先
+pub async fn wait_for_work<F: Future>(
+    shutdown: &CancellationToken,
+    ready: F,
+    poll_interval: Duration,
+) -> WorkerWake<F::Output> {
+    tokio::select! {
+        biased;
+        _ = shutdown.cancelled() => WorkerWake::Shutdown,
+        output = ready => WorkerWake::Ready(output),
+        _ = tokio::time::sleep(poll_interval) => WorkerWake::Poll,
+    }
+}
+
+/// 把在线 CPU 分给不超过 `max_workers` 个 worker（轮流分配，各组数量最多相差 1），
+/// 每个 CPU 的 perf buffer 都有且只有一个 worker 读取；未设置或为 0 时每个 CPU 一个 worker
+pub fn assign_cpus(cpus: &[u32], max_workers: Option<usize>) -> Vec<Vec<u32>> {
+    let workers = max_workers
+        .filter(|&max| max > 0)
+        .map_or(cpus.len(), |max| max.min(cpus.len()));
+    let mut groups = vec![Vec::new(); workers];
+    for (i, cpu_id) in cpus.iter().enumerate() {
+        groups[i % workers].push(*cpu_id);
+    }
+    groups
+}
+
+// 读取一个 CPU 的 perf buffer 中已就绪的全部事件并发布；连接出错需要退出 worker 时返回 true
+async fn read_perf_events(
+    cpu_id: u32,
+    perf_buf: &mut PerfEventArrayBuffer<MapData>,
+    matcher: &Mutex<CommMatcher>,
+    publisher: &TxPublisher,
+) -> bool {
+    let mut bufs: [BytesMut; 1] = [BytesMut::with_capacity(1024)];
+    // 每次最多读出 bufs.len() 个事件，读到空为止，避免残留的事件要等下一次可读通知
+    loop {
+        let events_read = match perf_buf.read_events(&mut bufs) {
+            Ok(events_read) => events_read,
+            Err(e) => {
+                debug!(
+                    "[Worker] Perf buffer read error on CPU {}: {}, continuing",
+                    cpu_id, e
+                );
+                let error_str = e.to_string();
+                if error_str.contains("broken pipe") || error_str.contains("connection") {
+                    warn!(
+                        "[Worker] Perf buffer connection issue on CPU {}, exiting",
+                        cpu_id
+                    );
+                    return true;
+                }
+                return false;
+            }
+        };
+        if events_read.read == 0 {
+            return false;
+        }
+        for buf in bufs.iter().take(events_read.read) {
+            let event = unsafe { (buf.as_ptr() as *const ProcessExitEvent).read_unaligned() };
+
+            // 仅用于日志；匹配使用原始字节，非 UTF-8 的 comm 同样能匹配
+            let comm_str = String::from_utf8_lossy(comm_bytes(&event.comm));
+            let process_names = {
+                let matcher = utils::lock_or_recover(matcher, "eBPF comm matcher");
+                let names = matcher.resolve_raw(&event.comm);
+                // 只有 exact 匹配时事件都来自监控列表，查不到配置名（刚被移除）时沿用 comm
+                if names.is_empty() && !matcher.reports_all_exits() {
+                    vec![comm_str.to_string()]
+                } else {
+                    names
+                }
+            };
+            // 上报所有退出时，大部分事件与任何配置都不匹配
+            if process_names.is_empty() {
+                continue;
+            }
+
+            info!(
+                "(CPU {}) Received Event: PID {} (comm: {}) has exited.",
+                cpu_id, event.pid, comm_str
+            );
+            for process_name in process_names {
+                let send_result = publisher
+                    .publish(ProcessEvent::ProcessDown {
+                        name: process_name.clone(),
+                        pid: event.pid,
+                        at: SystemTime::now(),
+                    })
+                    .await;
+
+                match send_result {
+                    Ok(_) => {
+                        debug!(
+                            "(CPU {}) Sent ProcessDown event for '{}'",
+                            cpu_id, process_name
+                        );
+                    }
+                    Err(e) => {
+                        warn!("(CPU {}) Failed to send event: {} - continuing", cpu_id, e);
+                    }
+                }
+            }
+        }
     }
 }
 
 impl EbpfMonitor {
-    pub async fn new(event_tx: broadcast::Sender<ProcessEvent>) -> Result<Self> {
+    /// `workers` 设置读取 perf buffer 的任务数上限与定期读取间隔
+    pub async fn new(
+        event_tx: EventSender,
+        attach: EbpfAttach,
+        workers: EbpfWorkerOptions,
+    ) -> Result<Self> {
         info!("[EbpfMonitor] Initializing and launching the global eBPF monitor...");
 
-        let mut bpf = aya::Ebpf::load(aya::include_bytes_aligned!(concat!(
-            env!("OUT_DIR"),
-            "/healer"
-        )))?;
-        let program: &mut TracePoint = bpf
-            .program_mut("healer_exit")
-            .ok_or_else(|| anyhow!("Program 'healer_exit' not found"))?
-            .try_into()?;
-        program.load()?;
-        program.attach("sched", "sched_process_exit")?;
-        info!("[EbpfMonitor] Tracepoint attached successfully.");
+        let mut bpf = retry_with_backoff(
+            "load and attach the eBPF program",
+            EbpfLoadRetry::default(),
+            || load_and_attach(attach),
+        )
+        .await?;
 
         let events_map = bpf
             .take_map("EVENTS")
             .ok_or_else(|| anyhow!("Failed to take ownership of 'EVENTS' map"))?;
         let mut events = PerfEventArray::try_from(events_map)?;
         let mut task_handles = Vec::new();
-        let shutdown_flag = Arc::new(AtomicBool::new(false));
-
-        // 创建进程名映射的共享引用
-        let process_name_mapping = Arc::new(Mutex::new(collections::HashMap::new()));
-        for cpu_id in online_cpus().unwrap() {
-            let perf_buf = events.open(cpu_id, None)?;
+        let shutdown = CancellationToken::new();
+
+        // 创建 comm 匹配器的共享引用
+        let comm_matcher = Arc::new(Mutex::new(CommMatcher::default()));
+        let cpus = online_cpus().map_err(|(_, e)| anyhow!("Failed to list online CPUs: {}", e))?;
+        let groups = assign_cpus(&cpus, workers.max_workers);
+        let poll_interval = workers.poll_interval;
+        for (worker_id, group) in groups.into_iter().enumerate() {
+            // 每个 worker 轮询一组 CPU 的 perf buffer；fd 与 buffer 分开存放，等待可读时只借用 fd
+            let mut buffers = Vec::with_capacity(group.len());
+            let mut fds = Vec::with_capacity(group.len());
+            for cpu_id in group {
+                let perf_buf = events.open(cpu_id, None)?;
+                fds.push(AsyncFd::new(perf_buf.as_raw_fd())?);
+                buffers.push((cpu_id, perf_buf));
+            }
             let publisher = TxPublisher {
                 tx: event_tx.clone(),
             };
-            let fd = perf_buf.as_raw_fd();
-            let async_fd = AsyncFd::new(fd)?;
-            let shutdown_flag_clone = shutdown_flag.clone();
-            let mapping_clone = Arc::clone(&process_name_mapping);
+            let shutdown_clone = shutdown.clone();
+            let matcher_clone = Arc::clone(&comm_matcher);
 
             let handle = tokio::spawn(async move {
-                info!("[Worker] Listener task for CPU {} started.", cpu_id);
-                let mut local_perf_buf = perf_buf;
-
-                while !shutdown_flag_clone.load(Ordering::SeqCst) {
-                    let readable_result =
-                        timeout(Duration::from_secs(1), async_fd.readable()).await;
-                    match readable_result {
-                        Ok(Ok(mut guard)) => {
-                            // 确保在作用域结束时清理可读状态
-                            let mut should_break = false;
-                            let mut bufs: [BytesMut; 1] = [BytesMut::with_capacity(1024)];
-                            match local_perf_buf.read_events(&mut bufs) {
-                                Ok(events_read) => {
-                                    if events_read.read > 0 {
-                                        for buf in bufs.iter().take(events_read.read) {
-                                            let event = unsafe {
-                                                (buf.as_ptr() as *const ProcessExitEvent)
-                                                    .read_unaligned()
-                                            };
-
-                                            let comm_str = std::str::from_utf8(
-                                                &event.comm[..event
-                                                    .comm
-                                                    .iter()
-                                                    .position(|&x| x == 0)
-                                                    .unwrap_or(16)],
-                                            )
-                                            .unwrap_or("unknown");
-                                            let process_name = {
-                                                let mapping = mapping_clone.lock().unwrap();
-                                                mapping
-                                                    .get(comm_str)
-                                                    .cloned()
-                                                    .unwrap_or_else(|| comm_str.to_string())
-                                            };
-
-                                            info!(
-                                                "(CPU {}) Received Event: PID {} (comm: {}) has exited.",
-                                                cpu_id, event.pid, comm_str
-                                            );
-                                            let send_result =
-                                                publisher.publish(ProcessEvent::ProcessDown {
-                                                    name: process_name.clone(),
-                                                    pid: event.pid,
-                                                });
-
-                                            match send_result {
-                                                Ok(_) => {
-                                                    debug!(
-                                                        "(CPU {}) Sent ProcessDown event for '{}'",
-                                                        cpu_id, process_name
-                                                    );
-                                                }
-                                                Err(e) => {
-                                                    warn!(
-                                                        "(CPU {}) Failed to send event: {} - continuing",
-                                                        cpu_id, e
-                                                    );
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    debug!(
-                                        "[Worker] Perf buffer read error on CPU {}: {}, continuing",
-                                        cpu_id, e
-                                    );
-                                    let error_str = e.to_string();
-                                    if error_str.contains("broken pipe")
-                                        || error_str.contains("connection")
-                                    {
-                                        warn!(
-                                            "[Worker] Perf buffer connection issue on CPU {}, exiting",
-                                            cpu_id
-                                        );
-                                        should_break = true;
-                                    }
-                                }
-                            }
+                let cpu_ids: Vec<u32> = buffers.iter().map(|(cpu_id, _)| *cpu_id).collect();
+                info!(
+                    "[Worker {}] Listener task for CPU(s) {:?} started.",
+                    worker_id, cpu_ids
+                );
+
+                loop {
+                    let readable = select_all(fds.iter().map(|fd| Box::pin(fd.readable())));
+                    match wait_for_work(&shutdown_clone, readable, poll_interval).await {
+                        WorkerWake::Shutdown => break,
+                        WorkerWake::Ready((Ok(mut guard), index, _)) => {
+                            let (cpu_id, perf_buf) = &mut buffers[index];
+                            let should_break =
+                                read_perf_events(*cpu_id, perf_buf, &matcher_clone, &publisher)
+                                    .await;
                             // 明确清理可读状态
                             guard.clear_ready();
                             if should_break {
                                 break;
                             }
                         }
-                        Ok(Err(e)) => {
+                        WorkerWake::Ready((Err(e), index, _)) => {
                             warn!(
-                                "[Worker] AsyncFd error on CPU {}: {}, continuing",
-                                cpu_id, e
+                                "[Worker {}] AsyncFd error on CPU {}: {}, continuing",
+                                worker_id, buffers[index].0, e
                             );
                             tokio::time::sleep(Duration::from_millis(100)).await;
                         }
-                        Err(_) => {
-                            // 超时是预料之中的，会继续进行shutdown_falg_clone的检查。用于在外界关闭本守护进程可以在1s内响应
-                            continue;
+                        WorkerWake::Poll => {
+                            // 定期读取全部 buffer，兜底可读通知之外残留的事件
+                            let mut should_break = false;
+                            for (cpu_id, perf_buf) in buffers.iter_mut() {
+                                should_break |=
+                                    read_perf_events(*cpu_id, perf_buf, &matcher_clone, &publisher)
+                                        .await;
+                            }
+                            if should_break {
+                                break;
+                            }
                         }
                     }
                 }
-                info!("[Worker] Listener task for CPU {} shutting down.", cpu_id);
+                info!(
+                    "[Worker {}] Listener task for CPU(s) {:?} shutting down.",
+                    worker_id, cpu_ids
+                );
             });
             task_handles.push(handle);
         }
@@ -187,39 +690,59 @@ impl EbpfMonitor {
 
         Ok(Self {
             bpf: Arc::new(Mutex::new(bpf)),
-            process_name_mapping,
+            comm_matcher,
             task_handles,
-            shutdown_flag,
+            shutdown,
             out_tx: event_tx,
+            readiness: EbpfReadiness::new(),
         })
     }
+
+    /// 设置期望监控的进程，之后可通过 [`EbpfMonitor::ready`] 等待它们全部写入 eBPF map
+    pub fn expect_watched(&self, names: impl IntoIterator<Item = String>) {
+        self.readiness.expect(names);
+    }
+
+    /// 期望监控的进程全部写入 eBPF map 时完成的 future
+    pub fn ready(&self) -> impl Future<Output = ()> + Send + 'static {
+        self.readiness.wait_ready()
+    }
     pub async fn wait_and_publish(&mut self) {}
     pub async fn watch_config(&mut self, ebpf_config: EbpfMonitorConfig) -> anyhow::Result<()> {
-        // 从命令路径中提取可执行文件名
-        let executable_name = utils::extract_executable_name(&ebpf_config.command);
-        let truncated_name = utils::truncate_process_name(&executable_name);
+        if ebpf_config.comm_match != CommMatch::Exact {
+            return self.watch_pattern(ebpf_config).await;
+        }
+
+        // 匹配名优先取配置的 process_comm，否则从命令路径中提取可执行文件名
+        let truncated_name = ebpf_config.monitored_comm();
 
         info!(
-            "[EbpfMonitor] Adding process '{}' (truncated: '{}') to watch list.",
-            executable_name, truncated_name
+            "[EbpfMonitor] Adding process '{}' (comm: '{}') to watch list.",
+            ebpf_config.name, truncated_name
         );
 
-        // 更新进程名映射
-        let mut mapping = self.process_name_mapping.lock().unwrap();
-        mapping.insert(truncated_name.clone(), ebpf_config.name.clone());
-        drop(mapping);
+        // 容量保护：map 已满且该进程名尚未写入时直接报错，不再发起注定失败的系统调用
+        {
+            let matcher = utils::lock_or_recover(&self.comm_matcher, "eBPF comm matcher");
+            if !matcher.contains_exact(&truncated_name)
+                && matcher.exact_len() >= PROCESS_NAME_MAP_CAPACITY as usize
+            {
+                self.readiness.mark_removed(&ebpf_config.name);
+                return Err(EbpfMapFull {
+                    comm: truncated_name,
+                    capacity: PROCESS_NAME_MAP_CAPACITY,
+                }
+                .into());
+            }
+        }
 
-        // 将进程名添加到 eBPF map 中
-        let mut process_name_bytes = [0u8; 16];
-        let truncated_bytes = truncated_name.as_bytes();
-        let copy_len = truncated_bytes.len().min(15); // 保留一个字节作为null终止符
-        process_name_bytes[..copy_len].copy_from_slice(&truncated_bytes[..copy_len]);
+        // 将进程名添加到 eBPF map 中，成功后再更新进程名映射，保证两者一致
+        let process_name_bytes = utils::process_name_key(&truncated_name);
 
         let bpf_clone = Arc::clone(&self.bpf);
+        let comm = truncated_name.clone();
         let insert_result = tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-            let mut bpf_guard = bpf_clone
-                .lock()
-                .map_err(|e| anyhow!("Mutex was poisoned: {}", e))?;
+            let mut bpf_guard = utils::lock_or_recover(&bpf_clone, "eBPF handle");
 
             let map_handle = bpf_guard
                 .map_mut("PROCESS_NAMES_TO_MONITOR")
@@ -229,15 +752,12 @@ impl EbpfMonitor {
                 aya::maps::HashMap::try_from(map_handle)
                     .map_err(|e| anyhow!("Failed to create HashMap view from eBPF map: {}", e))?;
 
-            names_map.insert(process_name_bytes, 1, 0).map_err(|e| {
-                anyhow!(
-                    "Failed to insert process name {:?} into eBPF map: {}",
-                    process_name_bytes,
-                    e
-                )
-            })?;
-
-            Ok(())
+            insert_verified(
+                &mut names_map,
+                process_name_bytes,
+                &comm,
+                PROCESS_NAME_MAP_CAPACITY,
+            )
         })
         .await;
 
@@ -248,45 +768,49 @@ impl EbpfMonitor {
                         "Successfully added process name '{}' to eBPF monitoring.",
                         truncated_name
                     );
+                    utils::lock_or_recover(&self.comm_matcher, "eBPF comm matcher")
+                        .insert_exact(truncated_name.clone(), ebpf_config.name.clone());
+                    self.readiness.mark_populated(&ebpf_config.name);
                     Ok(())
                 }
                 Err(e) => {
                     error!(error = ?e, process_name = %truncated_name, "Task to update eBPF map failed.");
+                    self.readiness.mark_removed(&ebpf_config.name);
                     Err(e)
                 }
             },
             Err(e) => {
                 error!(error = ?e, "The spawned blocking task itself failed.");
+                self.readiness.mark_removed(&ebpf_config.name);
                 Err(e.into())
             }
         }
     }
 
     pub async fn unwatch_config(&mut self, ebpf_config: EbpfMonitorConfig) -> anyhow::Result<()> {
-        let executable_name = utils::extract_executable_name(&ebpf_config.command);
-        let truncated_name = utils::truncate_process_name(&executable_name);
+        if ebpf_config.comm_match != CommMatch::Exact {
+            return self.unwatch_pattern(ebpf_config).await;
+        }
+
+        let truncated_name = ebpf_config.monitored_comm();
 
         info!(
-            "[EbpfMonitor] Removing process '{}' (truncated: '{}') from watch list.",
-            executable_name, truncated_name
+            "[EbpfMonitor] Removing process '{}' (comm: '{}') from watch list.",
+            ebpf_config.name, truncated_name
         );
 
         // 移除内部状态
-        let mut mapping = self.process_name_mapping.lock().unwrap();
-        mapping.remove(&truncated_name);
-        drop(mapping);
+        self.readiness.mark_removed(&ebpf_config.name);
+        utils::lock_or_recover(&self.comm_matcher, "eBPF comm matcher")
+            .remove_exact(&truncated_name);
 
         // 从 eBPF map 中移除进程名
-        let mut process_name_bytes = [0u8; 16];
-        let truncated_bytes = truncated_name.as_bytes();
-        let copy_len = truncated_bytes.len().min(15); // 保留一个字节作为null终止符
-        process_name_bytes[..copy_len].copy_from_slice(&truncated_bytes[..copy_len]);
+        let process_name_bytes = utils::process_name_key(&truncated_name);
 
         let bpf_clone = Arc::clone(&self.bpf);
+        let comm = truncated_name.clone();
         let remove_result = tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-            let mut bpf_guard = bpf_clone
-                .lock()
-                .map_err(|e| anyhow!("Mutex was poisoned: {}", e))?;
+            let mut bpf_guard = utils::lock_or_recover(&bpf_clone, "eBPF handle");
 
             let map_handle = bpf_guard
                 .map_mut("PROCESS_NAMES_TO_MONITOR")
@@ -296,15 +820,7 @@ impl EbpfMonitor {
                 aya::maps::HashMap::try_from(map_handle)
                     .map_err(|e| anyhow!("Failed to create HashMap view from eBPF map: {}", e))?;
 
-            names_map.remove(&process_name_bytes).map_err(|e| {
-                anyhow!(
-                    "Failed to remove process name {:?} from eBPF map: {}",
-                    process_name_bytes,
-                    e
-                )
-            })?;
-
-            Ok(())
+            remove_verified(&mut names_map, process_name_bytes, &comm)
         })
         .await;
 
@@ -328,12 +844,80 @@ impl EbpfMonitor {
             }
         }
     }
+
+    // prefix / regex：登记用户态匹配规则，并让内核上报所有进程的退出
+    async fn watch_pattern(&mut self, ebpf_config: EbpfMonitorConfig) -> anyhow::Result<()> {
+        info!(
+            "[EbpfMonitor] Adding process '{}' ({} match on comm '{}') to watch list, all process exits are now reported to userspace.",
+            ebpf_config.name,
+            ebpf_config.comm_match,
+            ebpf_config
+                .process_comm
+                .clone()
+                .unwrap_or_else(|| ebpf_config.monitored_comm())
+        );
+        let inserted = utils::lock_or_recover(&self.comm_matcher, "eBPF comm matcher")
+            .insert_pattern(&ebpf_config);
+        if let Err(e) = inserted {
+            self.readiness.mark_removed(&ebpf_config.name);
+            return Err(e);
+        }
+        match self.set_report_all_exits(true).await {
+            Ok(()) => {
+                self.readiness.mark_populated(&ebpf_config.name);
+                Ok(())
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to enable reporting of all process exits.");
+                utils::lock_or_recover(&self.comm_matcher, "eBPF comm matcher")
+                    .remove_pattern(&ebpf_config.name);
+                self.readiness.mark_removed(&ebpf_config.name);
+                Err(e)
+            }
+        }
+    }
+
+    // 移除匹配规则；不再有 prefix / regex 规则时恢复内核侧过滤
+    async fn unwatch_pattern(&mut self, ebpf_config: EbpfMonitorConfig) -> anyhow::Result<()> {
+        info!(
+            "[EbpfMonitor] Removing process '{}' ({} match) from watch list.",
+            ebpf_config.name, ebpf_config.comm_match
+        );
+        self.readiness.mark_removed(&ebpf_config.name);
+        let still_reporting = {
+            let mut matcher = utils::lock_or_recover(&self.comm_matcher, "eBPF comm matcher");
+            matcher.remove_pattern(&ebpf_config.name);
+            matcher.reports_all_exits()
+        };
+        if still_reporting {
+            return Ok(());
+        }
+        self.set_report_all_exits(false).await
+    }
+
+    // 写入 SETTINGS map 中的 SETTING_REPORT_ALL_EXITS
+    async fn set_report_all_exits(&self, enabled: bool) -> anyhow::Result<()> {
+        let bpf_clone = Arc::clone(&self.bpf);
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let mut bpf_guard = utils::lock_or_recover(&bpf_clone, "eBPF handle");
+            let map_handle = bpf_guard
+                .map_mut("SETTINGS")
+                .ok_or_else(|| anyhow!("eBPF map 'SETTINGS' not found"))?;
+            let mut settings: aya::maps::Array<_, u32> = aya::maps::Array::try_from(map_handle)
+                .map_err(|e| anyhow!("Failed to create Array view from eBPF map: {}", e))?;
+            settings
+                .set(SETTING_REPORT_ALL_EXITS, u32::from(enabled), 0)
+                .map_err(|e| anyhow!("Failed to update eBPF setting: {}", e))
+        })
+        .await?
+    }
+
     // 关闭 eBPF 监控器
     pub async fn shutdown(&mut self) {
         info!("[EbpfMonitor] Initiating shutdown...");
 
-        // 设置关闭标志，使循环内的定期超时检查可以关闭对应线程
-        self.shutdown_flag.store(true, Ordering::SeqCst);
+        // 取消后所有 worker 立即醒来退出，不必等到下一次定期读取
+        self.shutdown.cancel();
 
         // 等待所有任务完成，设置超时
         let mut completed_tasks = 0;
@@ -355,20 +939,47 @@ impl EbpfMonitor {
     }
 }
 
+#[async_trait]
+impl EbpfWatcher for EbpfMonitor {
+    fn expect_watched(&self, names: Vec<String>) {
+        EbpfMonitor::expect_watched(self, names);
+    }
+
+    fn ready(&self) -> BoxFuture<'static, ()> {
+        Box::pin(EbpfMonitor::ready(self))
+    }
+
+    async fn watch_config(&mut self, ebpf_config: EbpfMonitorConfig) -> Result<()> {
+        EbpfMonitor::watch_config(self, ebpf_config).await
+    }
+
+    async fn unwatch_config(&mut self, ebpf_config: EbpfMonitorConfig) -> Result<()> {
+        EbpfMonitor::unwatch_config(self, ebpf_config).await
+    }
+
+    async fn shutdown(&mut self) {
+        EbpfMonitor::shutdown(self).await
+    }
+}
+
 impl Drop for EbpfMonitor {
     fn drop(&mut self) {
-        // 设置关闭标志，即使 shutdown 没有被调用
-        self.shutdown_flag.store(true, Ordering::SeqCst);
-        info!("[EbpfMonitor] Monitor dropped, shutdown flag set.");
+        // 即使 shutdown 没有被调用，也让 worker 退出
+        self.shutdown.cancel();
+        info!("[EbpfMonitor] Monitor dropped, workers cancelled.");
     }
 }
 #[async_trait]
 impl Monitor for EbpfMonitor {
     // eBPF的monitor的run方法是空的，不应该被调用
     // eBPF的执行逻辑应该是在new创建函数里就进行了
-    async fn run(mut self) {
+    async fn run(mut self, _shutdown: CancellationToken) {
         self.wait_and_publish().await;
     }
+    // 退出事件由内核推送，没有可主动执行的一次性检查
+    async fn check_once(&self) -> Option<ProcessEvent> {
+        None
+    }
     // 监控器的名字或标识
     fn name(&self) -> String {
         // self.config.name.clone()
@@ -376,11 +987,12 @@ impl Monitor for EbpfMonitor {
     }
 }
 
+#[async_trait]
 impl Publisher for EbpfMonitor {
-    fn publish(
+    async fn publish(
         &self,
         event: ProcessEvent,
     ) -> Result<usize, broadcast::error::SendError<ProcessEvent>> {
-        self.out_tx.send(event)
+        self.out_tx.send(event).await
     }
 }
@@ -0,0 +1,58 @@
+// src/monitor/memory_trend.rs
+
+use crate::config::MemoryGrowthPolicy;
+use std::collections::VecDeque;
+
+/// 基于滑动窗口的 RSS 增长趋势检测器。
+///
+/// 只有当窗口已满、窗口内采样单调不减，并且最新采样相对窗口内首个采样（基线）的增长
+/// 超过 `growth_percent` 时才判定为泄漏；偶尔的回落会让趋势重新计算，避免把正常波动当成泄漏。
+#[derive(Debug)]
+pub struct MemoryTrend {
+    policy: MemoryGrowthPolicy,
+    samples: VecDeque<u64>,
+}
+
+impl MemoryTrend {
+    pub fn new(policy: MemoryGrowthPolicy) -> Self {
+        let capacity = policy.window_samples.max(2);
+        Self {
+            policy,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// 记录一个 RSS 采样（kB），返回是否满足增长判定；判定成立后清空窗口重新建立基线。
+    pub fn push(&mut self, rss_kb: u64) -> bool {
+        let window = self.policy.window_samples.max(2);
+        if self.samples.len() == window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(rss_kb);
+        if self.samples.len() < window {
+            return false;
+        }
+
+        let monotonic = self
+            .samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .all(|(prev, next)| next >= prev);
+        let baseline = *self.samples.front().unwrap_or(&0);
+        let latest = *self.samples.back().unwrap_or(&0);
+        let grown = baseline > 0
+            && latest.saturating_sub(baseline) as f64 * 100.0 / baseline as f64
+                >= self.policy.growth_percent;
+
+        if monotonic && grown {
+            self.samples.clear();
+            return true;
+        }
+        false
+    }
+
+    /// 被监控的进程发生变化（例如已被重启）时丢弃旧的采样
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
@@ -1,33 +1,119 @@
 use crate::publisher::Publisher;
-use crate::{config::NetworkMonitorConfig, event_bus::ProcessEvent, monitor::Monitor};
+use crate::{
+    config::{JsonHealthCheck, NetworkMonitorConfig},
+    event_bus::{EventSender, ProcessEvent},
+    health::HealthRegistry,
+    monitor::{normalize_interval_secs, HealthEdge, Monitor, Probe},
+};
 use async_trait::async_trait;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, SystemTime};
 use tokio::{sync::broadcast, time};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
+
+// JSON 健康检查最多读取的响应体字节数，超出部分被截断（通常导致解析失败并视为不健康）
+const MAX_HEALTH_BODY_BYTES: usize = 64 * 1024;
+
 pub struct NetworkMonitor {
     config: NetworkMonitorConfig,
-    event_tx: broadcast::Sender<ProcessEvent>,
+    event_tx: EventSender,
+    health_edge: HealthEdge,
+    // 连续确认不可达的次数，健康后清零
+    consecutive_failures: AtomicU32,
 }
 impl NetworkMonitor {
-    pub fn new(config: NetworkMonitorConfig, event_tx: broadcast::Sender<ProcessEvent>) -> Self {
-        Self { config, event_tx }
+    pub fn new(mut config: NetworkMonitorConfig, event_tx: EventSender) -> Self {
+        config.interval_secs = normalize_interval_secs(&config.name, config.interval_secs);
+        config.max_backoff_secs = config.max_backoff_secs.max(config.interval_secs);
+        Self {
+            config,
+            event_tx,
+            health_edge: HealthEdge::default(),
+            consecutive_failures: AtomicU32::new(0),
+        }
+    }
+    /// 与自愈器共享健康状态表，使恢复后的宽限期内不上报故障
+    pub fn with_health(mut self, health: HealthRegistry) -> Self {
+        self.health_edge = HealthEdge::new(health);
+        self
     }
     pub fn check_interval(&self) -> u64 {
         self.config.interval_secs
     }
-    async fn check_and_publish(&self) {
+    /// 连续检查失败的次数
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures.load(Ordering::Relaxed)
+    }
+    /// 当前的检查间隔（不含抖动）：每次连续失败翻倍，不超过 max_backoff_secs，健康后恢复为 interval_secs
+    pub fn effective_interval(&self) -> Duration {
+        let failures = self.consecutive_failures().min(16);
+        let secs = self
+            .config
+            .interval_secs
+            .saturating_mul(1u64 << failures)
+            .min(self.config.max_backoff_secs);
+        Duration::from_secs(secs)
+    }
+    // 退避期间加入最多 20% 的随机抖动，避免多个监控器同时重试
+    fn next_delay(&self) -> Duration {
+        let interval = self.effective_interval();
+        if self.consecutive_failures() == 0 {
+            return interval;
+        }
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        interval + interval.mul_f64(f64::from(nanos % 1000) / 5000.0)
+    }
+    // 根据检查结论更新连续失败次数；无法判断的结果不改变退避状态
+    fn record(&self, probe: &Probe) {
+        match probe {
+            Probe::Healthy => {
+                let failures = self.consecutive_failures.swap(0, Ordering::Relaxed);
+                if failures > 0 {
+                    info!(
+                        "[NetMonitor] {} recovered after {} failed checks, interval back to {}s.",
+                        self.config.name, failures, self.config.interval_secs
+                    );
+                }
+            }
+            Probe::Failed(_) => {
+                let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                debug!(
+                    "[NetMonitor] {} failed {} consecutive checks, next check in {:?}.",
+                    self.config.name,
+                    failures,
+                    self.effective_interval()
+                );
+            }
+            Probe::Unknown => {}
+        }
+    }
+    pub fn check_timeout(&self) -> u64 {
+        self.config.check_timeout_secs
+    }
+    // 执行一次健康检查，目标不可达时需要上报断连；返回非成功状态码时无法判断
+    async fn check(&self) -> Probe {
         let client = reqwest::Client::new();
         let check_result = client.get(&self.config.target_url).send().await;
         match check_result {
             Ok(response) => match response.status().is_success() {
-                true => {
-                    debug!("[NetMonitor] {} is healthy", self.config.name);
-                }
+                true => match &self.config.health_json {
+                    Some(health_json) => self.check_json_body(response, health_json).await,
+                    None => {
+                        debug!("[NetMonitor] {} is healthy", self.config.name);
+                        Probe::Healthy
+                    }
+                },
                 false => {
                     warn!(
                         "[NetMonitor] {} is unhealthy, status: {}",
                         self.config.name,
                         response.status()
                     );
+                    Probe::Unknown
                 }
             },
             Err(e) => {
@@ -41,42 +127,134 @@ impl NetworkMonitor {
                         self.config.name, e
                     );
                 }
-                self.publish_process_disconnected();
                 //TODO 不能确定这几个事件究竟是否是需要重连，考虑设置成多个不同event发送
+                Probe::Failed(self.disconnected_event())
             }
         }
     }
-    async fn monitor_task_loop(&self) {
-        let mut interval = time::interval(time::Duration::from_secs(self.check_interval()));
+    // 按响应体中的 JSON 字段判断健康：字段值不符、缺失或响应体不是 JSON 都视为断连
+    async fn check_json_body(
+        &self,
+        mut response: reqwest::Response,
+        health_json: &JsonHealthCheck,
+    ) -> Probe {
+        let mut body = Vec::new();
+        while body.len() < MAX_HEALTH_BODY_BYTES {
+            match response.chunk().await {
+                Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+                Ok(None) => break,
+                Err(e) => {
+                    warn!(
+                        "[NetMonitor] {} failed to read health response body: {}",
+                        self.config.name, e
+                    );
+                    return Probe::Failed(self.disconnected_event());
+                }
+            }
+        }
+        body.truncate(MAX_HEALTH_BODY_BYTES);
+        let value = match serde_json::from_slice::<serde_json::Value>(&body) {
+            Ok(value) => value,
+            Err(e) => {
+                warn!(
+                    "[NetMonitor] {} health response is not valid JSON, treating as unhealthy: {}",
+                    self.config.name, e
+                );
+                return Probe::Failed(self.disconnected_event());
+            }
+        };
+        match value.pointer(&health_json.pointer) {
+            Some(actual) if *actual == health_json.expect => {
+                debug!("[NetMonitor] {} is healthy", self.config.name);
+                Probe::Healthy
+            }
+            Some(actual) => {
+                warn!(
+                    "[NetMonitor] {} reports {} = {}, expected {}",
+                    self.config.name, health_json.pointer, actual, health_json.expect
+                );
+                Probe::Failed(self.disconnected_event())
+            }
+            None => {
+                warn!(
+                    "[NetMonitor] {} health response has no field at {}",
+                    self.config.name, health_json.pointer
+                );
+                Probe::Failed(self.disconnected_event())
+            }
+        }
+    }
+    // 单次检查加超时，超时视为本轮无法判断
+    async fn timed_check(&self) -> Probe {
+        let timeout_secs = self.check_timeout();
+        match time::timeout(time::Duration::from_secs(timeout_secs), self.check()).await {
+            Ok(probe) => {
+                self.record(&probe);
+                probe
+            }
+            Err(_) => {
+                warn!(
+                    "[NetMonitor] {} health check timed out after {}s, skipping to next tick.",
+                    self.config.name, timeout_secs
+                );
+                Probe::Unknown
+            }
+        }
+    }
+    async fn monitor_task_loop(&self, shutdown: CancellationToken) {
+        // 与固定间隔不同，下一次检查的时间在每轮检查后按退避状态重新计算
+        let mut next_check = time::Instant::now();
 
         info!("[NetMonitor] Task for '{}' started.", self.config.name);
         loop {
-            interval.tick().await;
-            self.check_and_publish().await;
+            tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => {
+                    info!("[NetMonitor] Task for '{}' stopped cleanly.", self.config.name);
+                    break;
+                }
+                _ = time::sleep_until(next_check) => {}
+            }
+            let started = time::Instant::now();
+            let probe = self.timed_check().await;
+            next_check = started + self.next_delay();
+            // 发布不计入检查超时：关键订阅者繁忙时在此等待（背压），但仍响应关闭
+            if let Some(event) = self.health_edge.observe(probe, &self.config.name) {
+                tokio::select! {
+                    _ = shutdown.cancelled() => {
+                        info!("[NetMonitor] Task for '{}' stopped cleanly.", self.config.name);
+                        break;
+                    }
+                    _ = self.publish_event(event) => {}
+                }
+            }
         }
     }
-    fn publish_process_disconnected(&self) {
-        let event = ProcessEvent::ProcessDisconnected {
+    fn disconnected_event(&self) -> ProcessEvent {
+        ProcessEvent::ProcessDisconnected {
             name: self.config.name.clone(), //name是被检测的进程的name
             url: self.config.target_url.clone(),
             //和PidMonitor比起，稍微不太一样的是Pid的config内部含的是pid file的地址。需要去读取才可以用，而target_url是可以直接使用的
-        };
+            at: SystemTime::now(),
+        }
+    }
+    async fn publish_event(&self, event: ProcessEvent) {
         debug!(
-            "[{}] Publishing ProcessDisconnected event for HTTP {}",
-            self.config.name, self.config.target_url
+            "[{}] Publishing {:?} for HTTP {}",
+            self.config.name, event, self.config.target_url
         );
 
-        match self.publish(event) {
+        match self.publish(event).await {
             Ok(receiver_count) => {
                 debug!(
-                    "[{}] Sent ProcessDisconnected event for HTTP {} to {} receivers",
+                    "[{}] Sent event for HTTP {} to {} receivers",
                     self.config.name, self.config.target_url, receiver_count
                 );
             }
-            Err(_) => {
+            Err(e) => {
                 warn!(
-                    "[{}] Failed to publish ProcessDisconnected event for HTTP {}: no active subscribers",
-                    self.config.name, self.config.target_url
+                    "[{}] Failed to publish {:?} for HTTP {}: no active subscribers",
+                    self.config.name, e.0, self.config.target_url
                 );
             }
         }
@@ -85,19 +263,23 @@ impl NetworkMonitor {
 
 #[async_trait]
 impl Monitor for NetworkMonitor {
-    async fn run(self) {
-        self.monitor_task_loop().await;
+    async fn run(self, shutdown: CancellationToken) {
+        self.monitor_task_loop(shutdown).await;
+    }
+    async fn check_once(&self) -> Option<ProcessEvent> {
+        self.timed_check().await.into_failure()
     }
     fn name(&self) -> String {
         self.config.name.clone()
     }
 }
 
+#[async_trait]
 impl Publisher for NetworkMonitor {
-    fn publish(
+    async fn publish(
         &self,
         event: ProcessEvent,
     ) -> Result<usize, broadcast::error::SendError<ProcessEvent>> {
-        self.event_tx.send(event)
+        self.event_tx.send(event).await
     }
 }
@@ -5,54 +5,180 @@ use nix::errno::Errno;
 use nix::sys::signal::kill;
 use nix::unistd::Pid;
 use tokio::fs;
+use tokio::process::Command;
 use tokio::sync::broadcast;
 use tokio::time::{self, Duration as TokioDuration};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, warn};
 // 从 config 模块引入 PidMonitor 所需的、具体的配置结构体
-use super::Monitor;
-use crate::config::PidMonitorConfig;
-use crate::event_bus::ProcessEvent;
+use super::{memory_trend::MemoryTrend, normalize_interval_secs, HealthEdge, Monitor, Probe};
+use crate::config::{CommandGlobPolicy, PidMonitorConfig, PidSource};
+use crate::event_bus::{EventSender, ProcessEvent};
+use crate::health::HealthRegistry;
 use crate::publisher::Publisher;
+use crate::reaper;
+use crate::utils;
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime};
 use tracing::info;
+// 读取 PID 的结果
+enum PidLookup {
+    // 读到了一个候选 PID，需要进一步检查是否存活
+    Found(i32),
+    // 来源明确表示没有进程（如空的 cgroup.procs）
+    Empty,
+    // 无法判断（读取或解析失败），本轮跳过
+    Unknown,
+}
+
+// 内存增长检测的状态，PID 变化时重新建立基线
+struct MemoryWatch {
+    pid: i32,
+    trend: MemoryTrend,
+}
+
 pub struct PidMonitor {
     config: PidMonitorConfig,
-    event_tx: broadcast::Sender<ProcessEvent>,
+    event_tx: EventSender,
+    memory_watch: Option<Mutex<MemoryWatch>>,
+    // 监控循环第一次 tick 的时间，用于计算启动宽限期；单次检查（check_once）不设置
+    first_tick: OnceLock<Instant>,
+    health_edge: HealthEdge,
+    // 开启 watch_group 时最近一次观察到的 (master PID, 进程组 / 会话 ID)
+    group: Mutex<Option<(i32, i32)>>,
 }
 
 impl PidMonitor {
-    pub fn new(config: PidMonitorConfig, event_tx: broadcast::Sender<ProcessEvent>) -> Self {
-        Self { config, event_tx }
+    pub fn new(mut config: PidMonitorConfig, event_tx: EventSender) -> Self {
+        config.interval_secs = normalize_interval_secs(&config.name, config.interval_secs);
+        let memory_watch = config.memory_growth.clone().map(|policy| {
+            Mutex::new(MemoryWatch {
+                pid: 0,
+                trend: MemoryTrend::new(policy),
+            })
+        });
+        Self {
+            config,
+            event_tx,
+            memory_watch,
+            first_tick: OnceLock::new(),
+            health_edge: HealthEdge::default(),
+            group: Mutex::new(None),
+        }
+    }
+    /// 与自愈器共享健康状态表，使恢复后的宽限期内不上报故障
+    pub fn with_health(mut self, health: HealthRegistry) -> Self {
+        self.health_edge = HealthEdge::new(health);
+        self
     }
     pub fn check_interval(&self) -> u64 {
         self.config.interval_secs
     }
-    fn publish_process_down(&self, pid: u32) {
-        let event = ProcessEvent::ProcessDown {
+    pub fn check_timeout(&self) -> u64 {
+        self.config.check_timeout_secs
+    }
+    // 是否仍处于启动宽限期：服务可能刚启动、尚未写入 PID 文件
+    fn in_startup_grace(&self) -> bool {
+        self.first_tick.get().is_some_and(|first| {
+            first.elapsed() < TokioDuration::from_secs(self.config.startup_grace_secs)
+        })
+    }
+    // 进程已退出（pid 为 0 表示来源中没有进程）
+    fn process_down(&self, pid: u32) -> Probe {
+        Probe::Failed(ProcessEvent::ProcessDown {
             name: self.config.name.clone(), //name是被检测的进程的name
             pid,
+            at: SystemTime::now(),
+        })
+    }
+    // PID 文件过期时按可执行文件路径查找仍在运行的实例，找到则改写 PID 文件
+    async fn rediscover_pid(&self) -> Option<i32> {
+        let PidSource::File { path } = &self.config.source else {
+            return None;
         };
-        debug!(
-            "[{}] Publishing ProcessDown event for PID {}",
-            self.config.name, pid
-        );
+        // command 为 glob 时按最新安装的版本查找
+        let pattern = self
+            .config
+            .reconcile_exe
+            .as_ref()?
+            .to_string_lossy()
+            .into_owned();
+        let exe = utils::resolve_command(&pattern, CommandGlobPolicy::Newest).ok()?;
+        let lookup_exe = exe.clone();
+        let pid = tokio::task::spawn_blocking(move || utils::find_pid_by_exe(&lookup_exe, false))
+            .await
+            .ok()??;
+        match fs::write(path, format!("{}\n", pid)).await {
+            Ok(()) => info!(
+                "[{}] PID file {} was stale; found {} running as PID {} and updated the file.",
+                self.config.name,
+                path.display(),
+                exe.display(),
+                pid
+            ),
+            Err(e) => warn!(
+                "[{}] Found {} running as PID {} but failed to update PID file {}: {}",
+                self.config.name,
+                exe.display(),
+                pid,
+                path.display(),
+                e
+            ),
+        }
+        Some(pid as i32)
+    }
+    // 记录 master 当前所在的进程组 / 会话，master 退出后据此查找组内剩余的进程
+    fn remember_group(&self, pid: i32) {
+        let Some(scope) = self.config.watch_group else {
+            return;
+        };
+        if let Some(id) = utils::group_id_of(pid, scope) {
+            *self.group.lock().unwrap_or_else(|e| e.into_inner()) = Some((pid, id));
+        }
+    }
+    // master 已退出时，其进程组 / 会话中仍存在的进程数；未开启 watch_group 或无法确定组时为 None。
+    // 没有观察到 master 的组时假定 master 是组长（组 ID 等于其 PID）
+    async fn surviving_group_members(&self, master: Option<i32>) -> Option<usize> {
+        let scope = self.config.watch_group?;
+        let remembered = *self.group.lock().unwrap_or_else(|e| e.into_inner());
+        let id = match (master, remembered) {
+            (Some(pid), Some((seen, id))) if seen == pid => id,
+            (Some(pid), _) => pid,
+            (None, Some((_, id))) => id,
+            (None, None) => return None,
+        };
+        let count = tokio::task::spawn_blocking(move || utils::count_group_members(scope, id))
+            .await
+            .ok()?;
+        if count > 0 {
+            debug!(
+                "[{}] Master process is gone but {} process(es) remain in its {} {}.",
+                self.config.name, count, scope, id
+            );
+        }
+        Some(count)
+    }
+    async fn publish_event(&self, event: ProcessEvent) {
+        debug!("[{}] Publishing {:?}", self.config.name, event);
 
-        match self.publish(event) {
+        match self.publish(event).await {
             Ok(receiver_count) => {
                 debug!(
-                    "[{}] Sent ProcessDown event for PID {} to {} receivers",
-                    self.config.name, pid, receiver_count
+                    "[{}] Sent event to {} receivers",
+                    self.config.name, receiver_count
                 );
             }
-            Err(_) => {
+            Err(e) => {
                 warn!(
-                    "[{}] Failed to publish ProcessDown event for PID {}: no active subscribers",
-                    self.config.name, pid
+                    "[{}] Failed to publish {:?}: no active subscribers",
+                    self.config.name, e.0
                 );
             }
         }
     }
 
-    async fn monitor_task_loop(&self) {
+    async fn monitor_task_loop(&self, shutdown: CancellationToken) {
         let monitor_name = self.name();
         let interval_secs = self.check_interval();
         let mut interval = time::interval(TokioDuration::from_secs(interval_secs));
@@ -61,45 +187,210 @@ impl PidMonitor {
             monitor_name, interval_secs
         );
         loop {
-            interval.tick().await;
-            self.check_and_publish().await;
+            tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => {
+                    info!("[Monitor] Task for '{}' stopped cleanly.", monitor_name);
+                    break;
+                }
+                _ = interval.tick() => {}
+            }
+            self.first_tick.get_or_init(Instant::now);
+            let probe = self.timed_check().await;
+            // 发布不计入检查超时：关键订阅者繁忙时在此等待（背压），但仍响应关闭
+            if let Some(event) = self.health_edge.observe(probe, &monitor_name) {
+                tokio::select! {
+                    _ = shutdown.cancelled() => {
+                        info!("[Monitor] Task for '{}' stopped cleanly.", monitor_name);
+                        break;
+                    }
+                    _ = self.publish_event(event) => {}
+                }
+            }
         }
     }
 
-    async fn check_and_publish(&self) {
-        let monitor_name = &self.config.name;
-        let pid_file_path = &self.config.pid_file_path;
-        debug!(
-            "[{}] Performing health check on PID file: {}",
-            monitor_name,
-            pid_file_path.display()
-        );
-        // 异步读取 PID 文件内容
-        let pid_str = match fs::read_to_string(pid_file_path).await {
-            Ok(content) => content,
-            Err(e) => {
+    // 单次检查加超时，避免挂起的文件系统等卡住整个监控循环
+    async fn timed_check(&self) -> Probe {
+        let timeout_secs = self.check_timeout();
+        match time::timeout(TokioDuration::from_secs(timeout_secs), self.check()).await {
+            Ok(probe) => probe,
+            Err(_) => {
                 warn!(
-                    "[{}] Failed to read PID file {}: {}. Assuming process is down.",
-                    monitor_name,
-                    pid_file_path.display(),
-                    e
+                    "[{}] Health check timed out after {}s, skipping to next tick.",
+                    self.config.name, timeout_secs
                 );
-                return;
+                Probe::Unknown
+            }
+        }
+    }
+
+    // 按配置的来源读取 PID
+    async fn lookup_pid(&self) -> PidLookup {
+        let monitor_name = &self.config.name;
+        match &self.config.source {
+            PidSource::File { path } => {
+                // 异步读取 PID 文件内容
+                let pid_str = match fs::read_to_string(path).await {
+                    Ok(content) => content,
+                    // PID 文件不存在说明进程未运行（或尚未写入，由启动宽限期兜底）
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        debug!(
+                            "[{}] PID file {} does not exist.",
+                            monitor_name,
+                            path.display()
+                        );
+                        return PidLookup::Empty;
+                    }
+                    Err(e) => {
+                        warn!(
+                            "[{}] Failed to read PID file {}: {}. Assuming process is down.",
+                            monitor_name,
+                            path.display(),
+                            e
+                        );
+                        return PidLookup::Unknown;
+                    }
+                };
+                if pid_str.trim().is_empty() {
+                    return PidLookup::Empty;
+                }
+                // 解析 PID
+                match pid_str.trim().parse::<i32>() {
+                    Ok(p) if p > 0 => PidLookup::Found(p),
+                    _ => {
+                        warn!(
+                            "[{}] Failed to parse a valid PID from file {}. Content: '{}'. Assuming process is down.",
+                            monitor_name,
+                            path.display(),
+                            pid_str
+                        );
+                        PidLookup::Unknown
+                    }
+                }
             }
+            PidSource::CgroupProcs { path } => {
+                let content = match fs::read_to_string(path).await {
+                    Ok(content) => content,
+                    Err(e) => {
+                        warn!(
+                            "[{}] Failed to read cgroup procs {}: {}. Unable to determine status.",
+                            monitor_name,
+                            path.display(),
+                            e
+                        );
+                        return PidLookup::Unknown;
+                    }
+                };
+                // cgroup.procs 每行一个 PID，取第一个有效值；为空说明 cgroup 中已没有进程
+                match content
+                    .lines()
+                    .filter_map(|line| line.trim().parse::<i32>().ok())
+                    .find(|p| *p > 0)
+                {
+                    Some(p) => PidLookup::Found(p),
+                    None => PidLookup::Empty,
+                }
+            }
+            PidSource::Command { command, args } => {
+                let mut lookup = Command::new(command);
+                lookup
+                    .args(args)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .kill_on_drop(true);
+                // 在取得输出之前不能被僵尸进程回收服务回收，否则等待失败（ECHILD）
+                let output = match reaper::spawn(|| lookup.spawn()) {
+                    Ok((child, _exemption)) => child.wait_with_output().await,
+                    Err(e) => Err(e),
+                };
+                let output = match output {
+                    Ok(output) => output,
+                    Err(e) => {
+                        warn!(
+                            "[{}] Failed to run PID command '{}': {}. Unable to determine status.",
+                            monitor_name, command, e
+                        );
+                        return PidLookup::Unknown;
+                    }
+                };
+                if !output.status.success() {
+                    warn!(
+                        "[{}] PID command '{}' exited with {}. Unable to determine status.",
+                        monitor_name, command, output.status
+                    );
+                    return PidLookup::Unknown;
+                }
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                // systemd 的 MainPID 在服务未运行时输出 0
+                match stdout.trim() {
+                    "" | "0" => PidLookup::Empty,
+                    text => match text.parse::<i32>() {
+                        Ok(p) if p > 0 => PidLookup::Found(p),
+                        _ => {
+                            warn!(
+                                "[{}] Failed to parse a valid PID from command '{}' output: '{}'.",
+                                monitor_name, command, text
+                            );
+                            PidLookup::Unknown
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    // 采样一次 RSS 并判断是否满足内存增长策略；未配置策略或无法读取时返回 false
+    async fn memory_growth_exceeded(&self, pid: i32) -> bool {
+        let Some(memory_watch) = &self.memory_watch else {
+            return false;
+        };
+        let Some(rss_kb) = read_rss_kb(pid).await else {
+            return false;
         };
+        let mut watch = memory_watch.lock().unwrap_or_else(|e| e.into_inner());
+        if watch.pid != pid {
+            watch.pid = pid;
+            watch.trend.reset();
+        }
+        debug!(
+            "[{}] RSS sample for PID {}: {} kB",
+            self.config.name, pid, rss_kb
+        );
+        watch.trend.push(rss_kb)
+    }
 
-        // 解析 PID
-        let pid = match pid_str.trim().parse::<i32>() {
-            Ok(p) if p > 0 => p,
-            _ => {
-                warn!(
-                    "[{}] Failed to parse a valid PID from file {}. Content: '{}'. Assuming process is down.",
-                    monitor_name,
-                    pid_file_path.display(),
-                    pid_str
+    // 执行一次健康检查
+    async fn check(&self) -> Probe {
+        let monitor_name = &self.config.name;
+        debug!(
+            "[{}] Performing health check on {}",
+            monitor_name, self.config.source
+        );
+        let pid = match self.lookup_pid().await {
+            PidLookup::Found(pid) => pid,
+            PidLookup::Empty if self.in_startup_grace() => {
+                debug!(
+                    "[{}] No process listed by {} yet, still within the {}s startup grace period.",
+                    monitor_name, self.config.source, self.config.startup_grace_secs
+                );
+                return Probe::Unknown;
+            }
+            PidLookup::Empty => {
+                if self.surviving_group_members(None).await.unwrap_or(0) > 0 {
+                    return Probe::Healthy;
+                }
+                if self.rediscover_pid().await.is_some() {
+                    return Probe::Healthy;
+                }
+                info!(
+                    "[{}] No process listed by {}. Process has exited.",
+                    monitor_name, self.config.source
                 );
-                return;
+                return self.process_down(0);
             }
+            PidLookup::Unknown => return Probe::Unknown,
         };
 
         // 使用信号检查进程是否存在
@@ -107,39 +398,77 @@ impl PidMonitor {
         match kill(process_pid, None) {
             Ok(_) => {
                 debug!("[{}] Process (PID: {}) is alive.", monitor_name, pid);
+                self.remember_group(pid);
+                if !self.memory_growth_exceeded(pid).await {
+                    return Probe::Healthy;
+                }
+                // 不在这里终止进程：由自愈器在决定执行恢复后终止仍在运行的实例再拉起，
+                // 恢复被跳过（熔断、暂停等）时进程保持运行
+                warn!(
+                    "[{}] RSS of process (PID: {}) kept growing beyond the configured limit. Reporting it for recovery.",
+                    monitor_name, pid
+                );
+                Probe::Failed(ProcessEvent::ProcessDown {
+                    name: monitor_name.clone(),
+                    pid: pid as u32,
+                    at: SystemTime::now(),
+                })
             }
             Err(Errno::ESRCH) => {
+                if self.surviving_group_members(Some(pid)).await.unwrap_or(0) > 0 {
+                    return Probe::Healthy;
+                }
+                if self.rediscover_pid().await.is_some() {
+                    return Probe::Healthy;
+                }
                 info!(
                     "[{}] Process (PID: {}) not found (ESRCH). Process has exited.",
                     monitor_name, pid
                 );
-                self.publish_process_down(pid as u32);
+                self.process_down(pid as u32)
             }
             Err(e) => {
                 warn!(
                     "[{}] Error checking process (PID: {}): {}. Unable to determine status.",
                     monitor_name, pid, e
                 );
+                Probe::Unknown
             }
         }
     }
 }
 
+// 从 /proc/<pid>/status 读取 VmRSS（kB）
+async fn read_rss_kb(pid: i32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid))
+        .await
+        .ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|kb| kb.parse().ok())
+}
+
 #[async_trait]
 impl Monitor for PidMonitor {
     fn name(&self) -> String {
         self.config.name.clone()
     }
-    async fn run(self) {
-        self.monitor_task_loop().await;
+    async fn run(self, shutdown: CancellationToken) {
+        self.monitor_task_loop(shutdown).await;
+    }
+    async fn check_once(&self) -> Option<ProcessEvent> {
+        self.timed_check().await.into_failure()
     }
 }
 
+#[async_trait]
 impl Publisher for PidMonitor {
-    fn publish(
+    async fn publish(
         &self,
         event: ProcessEvent,
     ) -> Result<usize, broadcast::error::SendError<ProcessEvent>> {
-        self.event_tx.send(event)
+        self.event_tx.send(event).await
     }
 }
@@ -0,0 +1,235 @@
+// src/monitor/systemd_monitor.rs
+
+use super::{normalize_interval_secs, HealthEdge, Monitor, Probe};
+use crate::config::SystemdMonitorConfig;
+use crate::event_bus::{EventSender, ProcessEvent};
+use crate::health::HealthRegistry;
+use crate::publisher::Publisher;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::broadcast;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// 查询 systemd unit 的 ActiveState（如 `active`、`failed`、`inactive`）。
+/// 抽象成 trait 便于在没有 systemd 的环境中替换为测试实现。
+#[async_trait]
+pub trait UnitStateSource: Send + Sync {
+    async fn active_state(&self, unit: &str) -> anyhow::Result<String>;
+}
+
+/// 通过系统总线上的 `org.freedesktop.systemd1` 查询 unit 状态
+#[cfg(feature = "systemd")]
+pub struct DbusUnitStateSource {
+    connection: zbus::Connection,
+}
+
+#[cfg(feature = "systemd")]
+impl DbusUnitStateSource {
+    pub async fn connect() -> anyhow::Result<Self> {
+        Ok(Self {
+            connection: zbus::Connection::system().await?,
+        })
+    }
+}
+
+#[cfg(feature = "systemd")]
+#[async_trait]
+impl UnitStateSource for DbusUnitStateSource {
+    async fn active_state(&self, unit: &str) -> anyhow::Result<String> {
+        let manager = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            "/org/freedesktop/systemd1",
+            "org.freedesktop.systemd1.Manager",
+        )
+        .await?;
+        // LoadUnit 对未加载的 unit 也能返回对象路径（其状态为 inactive）
+        let unit_path: zbus::zvariant::OwnedObjectPath = manager.call("LoadUnit", &(unit,)).await?;
+        let unit_proxy = zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.systemd1",
+            unit_path.as_str(),
+            "org.freedesktop.systemd1.Unit",
+        )
+        .await?;
+        Ok(unit_proxy.get_property::<String>("ActiveState").await?)
+    }
+}
+
+/// 返回默认的 unit 状态来源；未启用 `systemd` feature 时返回错误
+pub async fn default_unit_state_source() -> anyhow::Result<Arc<dyn UnitStateSource>> {
+    #[cfg(feature = "systemd")]
+    {
+        Ok(Arc::new(DbusUnitStateSource::connect().await?))
+    }
+    #[cfg(not(feature = "systemd"))]
+    {
+        Err(anyhow::anyhow!(
+            "healer was built without the 'systemd' feature"
+        ))
+    }
+}
+
+pub struct SystemdMonitor {
+    config: SystemdMonitorConfig,
+    event_tx: EventSender,
+    source: Arc<dyn UnitStateSource>,
+    health_edge: HealthEdge,
+}
+
+impl SystemdMonitor {
+    pub fn new(
+        config: SystemdMonitorConfig,
+        event_tx: EventSender,
+        source: Arc<dyn UnitStateSource>,
+    ) -> Self {
+        let mut config = config;
+        config.interval_secs = normalize_interval_secs(&config.name, config.interval_secs);
+        Self {
+            config,
+            event_tx,
+            source,
+            health_edge: HealthEdge::default(),
+        }
+    }
+    /// 与自愈器共享健康状态表，使恢复后的宽限期内不上报故障
+    pub fn with_health(mut self, health: HealthRegistry) -> Self {
+        self.health_edge = HealthEdge::new(health);
+        self
+    }
+    pub fn check_interval(&self) -> u64 {
+        self.config.interval_secs
+    }
+    pub fn check_timeout(&self) -> u64 {
+        self.config.check_timeout_secs
+    }
+
+    // 执行一次健康检查：failed / inactive 视为已停止，active 视为健康，其余过渡状态无法判断
+    async fn check(&self) -> Probe {
+        match self.source.active_state(&self.config.unit).await {
+            Ok(state) => match state.as_str() {
+                "failed" | "inactive" => {
+                    info!(
+                        "[SystemdMonitor] Unit '{}' of '{}' is {}.",
+                        self.config.unit, self.config.name, state
+                    );
+                    // systemd unit 没有可用的 PID，按约定使用 0
+                    Probe::Failed(ProcessEvent::ProcessDown {
+                        name: self.config.name.clone(),
+                        pid: 0,
+                        at: SystemTime::now(),
+                    })
+                }
+                "active" => {
+                    debug!(
+                        "[SystemdMonitor] Unit '{}' of '{}' is active.",
+                        self.config.unit, self.config.name
+                    );
+                    Probe::Healthy
+                }
+                _ => {
+                    debug!(
+                        "[SystemdMonitor] Unit '{}' of '{}' is {}.",
+                        self.config.unit, self.config.name, state
+                    );
+                    Probe::Unknown
+                }
+            },
+            Err(e) => {
+                warn!(
+                    "[SystemdMonitor] Failed to query unit '{}' of '{}': {}. Unable to determine status.",
+                    self.config.unit, self.config.name, e
+                );
+                Probe::Unknown
+            }
+        }
+    }
+
+    // 单次检查加超时，超时视为本轮无法判断
+    async fn timed_check(&self) -> Probe {
+        let timeout_secs = self.check_timeout();
+        match time::timeout(time::Duration::from_secs(timeout_secs), self.check()).await {
+            Ok(probe) => probe,
+            Err(_) => {
+                warn!(
+                    "[SystemdMonitor] {} health check timed out after {}s, skipping to next tick.",
+                    self.config.name, timeout_secs
+                );
+                Probe::Unknown
+            }
+        }
+    }
+
+    async fn monitor_task_loop(&self, shutdown: CancellationToken) {
+        let mut interval = time::interval(time::Duration::from_secs(self.check_interval()));
+
+        info!(
+            "[SystemdMonitor] Task for '{}' (unit '{}') started.",
+            self.config.name, self.config.unit
+        );
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => {
+                    info!("[SystemdMonitor] Task for '{}' stopped cleanly.", self.config.name);
+                    break;
+                }
+                _ = interval.tick() => {}
+            }
+            let probe = self.timed_check().await;
+            if let Some(event) = self.health_edge.observe(probe, &self.config.name) {
+                // 发布不计入检查超时：关键订阅者繁忙时在此等待（背压），但仍响应关闭
+                tokio::select! {
+                    _ = shutdown.cancelled() => {
+                        info!("[SystemdMonitor] Task for '{}' stopped cleanly.", self.config.name);
+                        break;
+                    }
+                    _ = self.publish_event(event) => {}
+                }
+            }
+        }
+    }
+
+    async fn publish_event(&self, event: ProcessEvent) {
+        match self.publish(event).await {
+            Ok(receiver_count) => {
+                debug!(
+                    "[{}] Sent event for unit {} to {} receivers",
+                    self.config.name, self.config.unit, receiver_count
+                );
+            }
+            Err(e) => {
+                warn!(
+                    "[{}] Failed to publish {:?} for unit {}: no active subscribers",
+                    self.config.name, e.0, self.config.unit
+                );
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Monitor for SystemdMonitor {
+    async fn run(self, shutdown: CancellationToken) {
+        self.monitor_task_loop(shutdown).await;
+    }
+    async fn check_once(&self) -> Option<ProcessEvent> {
+        self.timed_check().await.into_failure()
+    }
+    fn name(&self) -> String {
+        self.config.name.clone()
+    }
+}
+
+#[async_trait]
+impl Publisher for SystemdMonitor {
+    async fn publish(
+        &self,
+        event: ProcessEvent,
+    ) -> Result<usize, broadcast::error::SendError<ProcessEvent>> {
+        self.event_tx.send(event).await
+    }
+}
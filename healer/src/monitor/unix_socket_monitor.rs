@@ -0,0 +1,191 @@
+// src/monitor/unix_socket_monitor.rs
+
+use super::{normalize_interval_secs, HealthEdge, Monitor, Probe};
+use crate::config::UnixSocketMonitorConfig;
+use crate::event_bus::{EventSender, ProcessEvent};
+use crate::health::HealthRegistry;
+use crate::publisher::Publisher;
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::SystemTime;
+use tokio::net::UnixStream;
+use tokio::sync::broadcast;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// 一次连接尝试的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketState {
+    /// 连接成功，有进程在监听
+    Listening,
+    /// 套接字文件不存在（服务未启动或已清理）
+    Missing,
+    /// 文件存在但没有进程在监听（服务崩溃后遗留的套接字文件）
+    Refused,
+    /// 其他错误（如权限不足），无法判断服务状态
+    Unreachable(String),
+}
+
+/// 尝试连接 `path` 一次，连接成功后立即关闭
+pub async fn probe_socket(path: &Path) -> SocketState {
+    match UnixStream::connect(path).await {
+        Ok(_) => SocketState::Listening,
+        Err(e) if e.kind() == ErrorKind::NotFound => SocketState::Missing,
+        Err(e) if e.kind() == ErrorKind::ConnectionRefused => SocketState::Refused,
+        Err(e) => SocketState::Unreachable(e.to_string()),
+    }
+}
+
+pub struct UnixSocketMonitor {
+    config: UnixSocketMonitorConfig,
+    event_tx: EventSender,
+    health_edge: HealthEdge,
+}
+
+impl UnixSocketMonitor {
+    pub fn new(config: UnixSocketMonitorConfig, event_tx: EventSender) -> Self {
+        let mut config = config;
+        config.interval_secs = normalize_interval_secs(&config.name, config.interval_secs);
+        Self {
+            config,
+            event_tx,
+            health_edge: HealthEdge::default(),
+        }
+    }
+    /// 与自愈器共享健康状态表，使恢复后的宽限期内不上报故障
+    pub fn with_health(mut self, health: HealthRegistry) -> Self {
+        self.health_edge = HealthEdge::new(health);
+        self
+    }
+    pub fn check_interval(&self) -> u64 {
+        self.config.interval_secs
+    }
+    pub fn check_timeout(&self) -> u64 {
+        self.config.check_timeout_secs
+    }
+
+    // 执行一次健康检查：文件缺失与无人监听都视为断连，其余错误无法判断
+    async fn check(&self) -> Probe {
+        let path = &self.config.path;
+        match probe_socket(path).await {
+            SocketState::Listening => {
+                debug!(
+                    "[UnixSocketMonitor] Socket {:?} of '{}' is accepting connections.",
+                    path, self.config.name
+                );
+                return Probe::Healthy;
+            }
+            SocketState::Missing => {
+                info!(
+                    "[UnixSocketMonitor] Socket {:?} of '{}' does not exist.",
+                    path, self.config.name
+                );
+            }
+            SocketState::Refused => {
+                info!(
+                    "[UnixSocketMonitor] Socket {:?} of '{}' exists but nothing is listening (connection refused).",
+                    path, self.config.name
+                );
+            }
+            SocketState::Unreachable(e) => {
+                warn!(
+                    "[UnixSocketMonitor] Failed to connect to {:?} of '{}': {}. Unable to determine status.",
+                    path, self.config.name, e
+                );
+                return Probe::Unknown;
+            }
+        }
+        Probe::Failed(ProcessEvent::ProcessDisconnected {
+            name: self.config.name.clone(),
+            url: self.config.url(),
+            at: SystemTime::now(),
+        })
+    }
+
+    // 单次检查加超时，超时视为本轮无法判断
+    async fn timed_check(&self) -> Probe {
+        let timeout_secs = self.check_timeout();
+        match time::timeout(time::Duration::from_secs(timeout_secs), self.check()).await {
+            Ok(probe) => probe,
+            Err(_) => {
+                warn!(
+                    "[UnixSocketMonitor] {} health check timed out after {}s, skipping to next tick.",
+                    self.config.name, timeout_secs
+                );
+                Probe::Unknown
+            }
+        }
+    }
+
+    async fn monitor_task_loop(&self, shutdown: CancellationToken) {
+        let mut interval = time::interval(time::Duration::from_secs(self.check_interval()));
+
+        info!(
+            "[UnixSocketMonitor] Task for '{}' (socket {:?}) started.",
+            self.config.name, self.config.path
+        );
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => {
+                    info!("[UnixSocketMonitor] Task for '{}' stopped cleanly.", self.config.name);
+                    break;
+                }
+                _ = interval.tick() => {}
+            }
+            let probe = self.timed_check().await;
+            if let Some(event) = self.health_edge.observe(probe, &self.config.name) {
+                // 发布不计入检查超时：关键订阅者繁忙时在此等待（背压），但仍响应关闭
+                tokio::select! {
+                    _ = shutdown.cancelled() => {
+                        info!("[UnixSocketMonitor] Task for '{}' stopped cleanly.", self.config.name);
+                        break;
+                    }
+                    _ = self.publish_event(event) => {}
+                }
+            }
+        }
+    }
+
+    async fn publish_event(&self, event: ProcessEvent) {
+        match self.publish(event).await {
+            Ok(receiver_count) => {
+                debug!(
+                    "[{}] Sent event for socket {:?} to {} receivers",
+                    self.config.name, self.config.path, receiver_count
+                );
+            }
+            Err(e) => {
+                warn!(
+                    "[{}] Failed to publish {:?} for socket {:?}: no active subscribers",
+                    self.config.name, e.0, self.config.path
+                );
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Monitor for UnixSocketMonitor {
+    async fn run(self, shutdown: CancellationToken) {
+        self.monitor_task_loop(shutdown).await;
+    }
+    async fn check_once(&self) -> Option<ProcessEvent> {
+        self.timed_check().await.into_failure()
+    }
+    fn name(&self) -> String {
+        self.config.name.clone()
+    }
+}
+
+#[async_trait]
+impl Publisher for UnixSocketMonitor {
+    async fn publish(
+        &self,
+        event: ProcessEvent,
+    ) -> Result<usize, broadcast::error::SendError<ProcessEvent>> {
+        self.event_tx.send(event).await
+    }
+}
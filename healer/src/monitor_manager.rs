@@ -1,52 +1,146 @@
 use crate::{
-    config::ProcessConfig,
-    event_bus::ProcessEvent,
+    config::{EbpfAttach, ProcessConfig},
+    event_bus::EventSender,
+    health::HealthRegistry,
     monitor::{
-        ebpf_monitor::EbpfMonitor, network_monitor::NetworkMonitor, pid_monitor::PidMonitor,
+        self,
+        ebpf_monitor::{EbpfMapFull, EbpfMonitor, EbpfWatcher, EbpfWorkerOptions},
+        network_monitor::NetworkMonitor,
+        pid_monitor::PidMonitor,
+        systemd_monitor::{self, SystemdMonitor, UnitStateSource},
+        unix_socket_monitor::UnixSocketMonitor,
         Monitor,
     },
 };
 use anyhow::Result;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
+// 等待 eBPF 监控列表写入完成的最长时间
+const EBPF_READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 非 eBPF 监控任务的句柄与其关闭令牌
+struct RunningMonitor {
+    handle: JoinHandle<()>,
+    shutdown: CancellationToken,
+}
+
+impl RunningMonitor {
+    fn spawn<M: Monitor + 'static>(monitor: M) -> Self {
+        let shutdown = CancellationToken::new();
+        let handle = tokio::spawn(monitor.run(shutdown.clone()));
+        Self { handle, shutdown }
+    }
+
+    // 取消并等待任务在 tick 边界退出，超时后才强制 abort
+    async fn stop(self, name: &str) {
+        self.shutdown.cancel();
+        let abort_handle = self.handle.abort_handle();
+        if tokio::time::timeout(Duration::from_secs(2), self.handle)
+            .await
+            .is_err()
+        {
+            warn!(
+                "MonitorManager: not-ebpf monitor '{}' did not stop within timeout, aborting",
+                name
+            );
+            abort_handle.abort();
+        }
+    }
+}
+
+// 已构造但尚未启动的非 eBPF 监控器
+enum PreparedMonitor {
+    Pid(PidMonitor),
+    Network(NetworkMonitor),
+    Systemd(SystemdMonitor),
+    UnixSocket(UnixSocketMonitor),
+}
+
+impl PreparedMonitor {
+    fn kind(&self) -> &'static str {
+        match self {
+            PreparedMonitor::Pid(_) => "PID",
+            PreparedMonitor::Network(_) => "Network",
+            PreparedMonitor::Systemd(_) => "systemd",
+            PreparedMonitor::UnixSocket(_) => "Unix socket",
+        }
+    }
+
+    fn spawn(self) -> RunningMonitor {
+        match self {
+            PreparedMonitor::Pid(monitor) => RunningMonitor::spawn(monitor),
+            PreparedMonitor::Network(monitor) => RunningMonitor::spawn(monitor),
+            PreparedMonitor::Systemd(monitor) => RunningMonitor::spawn(monitor),
+            PreparedMonitor::UnixSocket(monitor) => RunningMonitor::spawn(monitor),
+        }
+    }
+}
+
+/// 一次协调的执行计划：需要新启动的监控器均已构造成功，[`MonitorManager::apply`] 时不会再因此失败
+pub struct ReconcilePlan {
+    processes: Vec<ProcessConfig>,
+    to_start: Vec<(String, PreparedMonitor)>,
+}
+
 // 监控器管理器，负责统一管理不同类型的监控器
 pub struct MonitorManager {
     // eBPF 监控器 - 全局单例，始终运行
-    ebpf_monitor: Option<EbpfMonitor>,
+    ebpf_monitor: Option<Box<dyn EbpfWatcher>>,
     // 当前被 eBPF 监控的进程配置
     watched_ebpf_configs: HashMap<String, ProcessConfig>,
+    // 最近一次协调中未能加入 eBPF 监控的进程及原因
+    unwatched_ebpf: HashMap<String, String>,
     // PID 监控器 - 按需启停
-    running_monitors: HashMap<String, JoinHandle<()>>,
+    running_monitors: HashMap<String, RunningMonitor>,
     // 网络监控器 - 按需启停
     // running_network_monitors: HashMap<String, JoinHandle<()>>,
     // 事件发送器
-    event_sender: broadcast::Sender<ProcessEvent>,
+    event_sender: EventSender,
+    // systemd unit 状态来源，首次需要时再连接 D-Bus
+    unit_state_source: Option<Arc<dyn UnitStateSource>>,
+    // 与自愈器共享的健康状态表，监控器据此忽略恢复后宽限期内的故障
+    health: HealthRegistry,
 }
 
 impl MonitorManager {
-    pub async fn new(event_sender: broadcast::Sender<ProcessEvent>) -> Result<Self> {
+    pub async fn new(
+        event_sender: EventSender,
+        ebpf_attach: EbpfAttach,
+        ebpf_workers: EbpfWorkerOptions,
+    ) -> Result<Self> {
+        if !cfg!(feature = "monitor-ebpf") {
+            info!(
+                "MonitorManager: Built without the 'monitor-ebpf' feature, eBPF monitor disabled."
+            );
+            return Ok(Self::new_without_ebpf(event_sender));
+        }
         // 初始化全局 eBPF 监控器
-        let ebpf_monitor = match EbpfMonitor::new(event_sender.clone()).await {
-            Ok(monitor) => {
-                info!("MonitorManager: eBPF Monitor initialized successfully.");
-                Some(monitor)
-            }
-            Err(e) => {
-                error!("MonitorManager: Failed to initialize eBPF Monitor: {}", e);
-                None
-            }
-        };
+        let ebpf_monitor =
+            match EbpfMonitor::new(event_sender.clone(), ebpf_attach, ebpf_workers).await {
+                Ok(monitor) => {
+                    info!("MonitorManager: eBPF Monitor initialized successfully.");
+                    Some(Box::new(monitor) as Box<dyn EbpfWatcher>)
+                }
+                Err(e) => {
+                    error!("MonitorManager: Failed to initialize eBPF Monitor: {}", e);
+                    None
+                }
+            };
 
         Ok(Self {
             ebpf_monitor,
             watched_ebpf_configs: HashMap::new(),
+            unwatched_ebpf: HashMap::new(),
             running_monitors: HashMap::new(),
             // running_network_monitors: HashMap::new(),
             event_sender,
+            unit_state_source: None,
+            health: HealthRegistry::new(),
         })
     }
 
@@ -54,13 +148,51 @@ impl MonitorManager {
     /// This is primarily intended for tests or environments where the eBPF artifacts are
     /// unavailable (for example, non-root CI runtimes).
     #[allow(dead_code)]
-    pub fn new_without_ebpf(event_sender: broadcast::Sender<ProcessEvent>) -> Self {
+    pub fn new_without_ebpf(event_sender: EventSender) -> Self {
         Self {
             ebpf_monitor: None,
             watched_ebpf_configs: HashMap::new(),
+            unwatched_ebpf: HashMap::new(),
             running_monitors: HashMap::new(),
             event_sender,
+            unit_state_source: None,
+            health: HealthRegistry::new(),
+        }
+    }
+
+    /// Use the given watcher for eBPF-monitored processes instead of the global eBPF monitor.
+    #[allow(dead_code)]
+    pub fn with_ebpf_watcher(mut self, watcher: impl EbpfWatcher + 'static) -> Self {
+        self.ebpf_monitor = Some(Box::new(watcher));
+        self
+    }
+
+    /// Share the healer's health registry with the monitors, so failures reported during a
+    /// process's post-restart grace period are ignored.
+    pub fn with_health(mut self, health: HealthRegistry) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Use the given source for systemd unit states instead of connecting to D-Bus.
+    #[allow(dead_code)]
+    pub fn with_unit_state_source(mut self, source: Arc<dyn UnitStateSource>) -> Self {
+        self.unit_state_source = Some(source);
+        self
+    }
+
+    // 获取 systemd unit 状态来源，未初始化时尝试连接
+    async fn unit_state_source(&mut self) -> Option<Arc<dyn UnitStateSource>> {
+        if self.unit_state_source.is_none() {
+            match systemd_monitor::default_unit_state_source().await {
+                Ok(source) => self.unit_state_source = Some(source),
+                Err(e) => {
+                    error!("MonitorManager: systemd monitor unavailable: {}", e);
+                    return None;
+                }
+            }
         }
+        self.unit_state_source.clone()
     }
 
     /// Returns the names of non-eBPF monitors that are currently running.
@@ -75,9 +207,35 @@ impl MonitorManager {
         self.watched_ebpf_configs.keys().cloned().collect()
     }
 
-    // 根据新的配置更新所有监控器
+    /// Returns the processes that the last reconcile failed to add to the eBPF watch list,
+    /// sorted by name.
+    #[allow(dead_code)]
+    pub fn unwatched_ebpf_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.unwatched_ebpf.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    // 根据新的配置更新所有监控器；无法启动的监控器记录错误后跳过。
+    // 配置使用了未编译进来的监控类型时返回 MonitorNotCompiled，不做任何改动
     pub async fn reconcile(&mut self, processes: &[ProcessConfig]) -> Result<()> {
+        let plan = self.prepare(processes, false).await?;
+        self.apply(plan).await
+    }
+
+    /// 为新配置构造所有需要启动的监控器，不改动当前运行的任何监控器；
+    /// 任一监控器无法启动时返回错误，供配置重载在替换配置之前放弃
+    pub async fn plan(&mut self, processes: &[ProcessConfig]) -> Result<ReconcilePlan> {
+        self.prepare(processes, true).await
+    }
+
+    /// 执行计划：更新 eBPF 监控列表，停止不再需要的监控器并启动计划中的监控器
+    pub async fn apply(&mut self, plan: ReconcilePlan) -> Result<()> {
         info!("MonitorManager: Starting reconciliation...");
+        let ReconcilePlan {
+            processes,
+            to_start,
+        } = plan;
         debug!(
             total_processes = processes.len(),
             "Reconcile invoked with processes"
@@ -103,22 +261,124 @@ impl MonitorManager {
         self.reconcile_ebpf_monitors(ebpf_configs).await?;
 
         // 更新 PID 监控器
-        self.reconcile_monitors(not_ebpf_configs).await?;
+        self.reconcile_monitors(not_ebpf_configs, to_start).await?;
+
+        // 公布正在运行的监控器，供状态导出查询
+        let running = processes
+            .iter()
+            .filter(|p| {
+                self.running_monitors.contains_key(&p.name)
+                    || self.watched_ebpf_configs.contains_key(&p.name)
+            })
+            .map(|p| (p.name.clone(), p.monitor.kind()))
+            .collect();
+        self.health.set_monitors(running);
 
         info!("MonitorManager: Reconciliation completed.");
         Ok(())
     }
 
+    // 构造需要新启动或重启的非 eBPF 监控器；strict 时任一监控器无法构造即返回错误
+    async fn prepare(
+        &mut self,
+        processes: &[ProcessConfig],
+        strict: bool,
+    ) -> Result<ReconcilePlan> {
+        for process_config in processes.iter().filter(|p| p.enabled) {
+            monitor::check_compiled(process_config)?;
+        }
+        let mut to_start = Vec::new();
+        for process_config in processes
+            .iter()
+            .filter(|p| p.enabled && p.get_ebpf_monitor_config().is_none())
+        {
+            let name = &process_config.name;
+            let should_start = match self.running_monitors.get(name) {
+                Some(monitor) => {
+                    let finished = monitor.handle.is_finished();
+                    if finished {
+                        debug!(process = %name, "Existing monitor task finished - will restart");
+                    } else {
+                        debug!(process = %name, "Monitor already running - no restart needed");
+                    }
+                    finished
+                }
+                None => {
+                    debug!(process = %name, "No existing monitor - will start");
+                    true
+                }
+            };
+            if !should_start {
+                continue;
+            }
+
+            let prepared = if let Some(pid_config) = process_config.get_pid_monitor_config() {
+                PreparedMonitor::Pid(
+                    PidMonitor::new(pid_config, self.event_sender.clone())
+                        .with_health(self.health.clone()),
+                )
+            } else if let Some(network_config) = process_config.get_network_monitor_config() {
+                PreparedMonitor::Network(
+                    NetworkMonitor::new(network_config, self.event_sender.clone())
+                        .with_health(self.health.clone()),
+                )
+            } else if let Some(socket_config) = process_config.get_unix_socket_monitor_config() {
+                PreparedMonitor::UnixSocket(
+                    UnixSocketMonitor::new(socket_config, self.event_sender.clone())
+                        .with_health(self.health.clone()),
+                )
+            } else if let Some(systemd_config) = process_config.get_systemd_monitor_config() {
+                match self.unit_state_source().await {
+                    Some(source) => PreparedMonitor::Systemd(
+                        SystemdMonitor::new(systemd_config, self.event_sender.clone(), source)
+                            .with_health(self.health.clone()),
+                    ),
+                    None if strict => {
+                        anyhow::bail!(
+                            "cannot start systemd monitor for process '{}': systemd monitor unavailable",
+                            name
+                        );
+                    }
+                    None => continue,
+                }
+            } else {
+                debug!(process = %name, "Process has no recognized monitor config after filtering (unexpected)");
+                continue;
+            };
+            to_start.push((name.clone(), prepared));
+        }
+
+        Ok(ReconcilePlan {
+            processes: processes.to_vec(),
+            to_start,
+        })
+    }
+
     // 更新 eBPF 监控器的监控列表
     async fn reconcile_ebpf_monitors(
         &mut self,
         desired_configs: Vec<&ProcessConfig>,
     ) -> Result<()> {
+        self.unwatched_ebpf.clear();
         let Some(ref mut ebpf_monitor) = self.ebpf_monitor else {
-            warn!("MonitorManager: eBPF monitor not available, skipping eBPF reconciliation.");
+            if !desired_configs.is_empty() {
+                let names: Vec<&str> = desired_configs.iter().map(|c| c.name.as_str()).collect();
+                warn!(
+                    "MonitorManager: eBPF monitor not available, {} process(es) are NOT monitored: {}",
+                    names.len(),
+                    names.join(", ")
+                );
+                for config in desired_configs {
+                    self.unwatched_ebpf
+                        .insert(config.name.clone(), "eBPF monitor unavailable".to_string());
+                }
+            } else {
+                warn!("MonitorManager: eBPF monitor not available, skipping eBPF reconciliation.");
+            }
             return Ok(());
         };
         debug!(current_watched = %self.watched_ebpf_configs.keys().cloned().collect::<Vec<_>>().join(","), desired = %desired_configs.iter().map(|c| c.name.clone()).collect::<Vec<_>>().join(","), "Reconciling eBPF monitors");
+        ebpf_monitor.expect_watched(desired_configs.iter().map(|c| c.name.clone()).collect());
         // 构建期望的配置映射
         let desired_configs_map: HashMap<String, ProcessConfig> = desired_configs
             .into_iter()
@@ -165,6 +425,11 @@ impl MonitorManager {
                                 "MonitorManager: Failed to watch config for '{}': {}",
                                 name, e
                             );
+                            let reason = match e.downcast_ref::<EbpfMapFull>() {
+                                Some(full) => format!("map full ({} entries)", full.capacity),
+                                None => e.to_string(),
+                            };
+                            self.unwatched_ebpf.insert(name, reason);
                         }
                     }
                 }
@@ -173,11 +438,41 @@ impl MonitorManager {
             }
         }
 
+        if !self.unwatched_ebpf.is_empty() {
+            let mut unwatched: Vec<String> = self
+                .unwatched_ebpf
+                .iter()
+                .map(|(name, reason)| format!("{} ({})", name, reason))
+                .collect();
+            unwatched.sort();
+            warn!(
+                "MonitorManager: {} process(es) are NOT monitored by eBPF: {}",
+                unwatched.len(),
+                unwatched.join(", ")
+            );
+        }
+
+        // 屏障：期望的进程名全部写入 eBPF map 后才算协调完成，避免启动时漏掉退出事件
+        match tokio::time::timeout(EBPF_READY_TIMEOUT, ebpf_monitor.ready()).await {
+            Ok(()) => info!(
+                "MonitorManager: eBPF monitor ready, watching {} process(es).",
+                self.watched_ebpf_configs.len()
+            ),
+            Err(_) => warn!(
+                "MonitorManager: eBPF monitor not ready after {}s, continuing without it.",
+                EBPF_READY_TIMEOUT.as_secs()
+            ),
+        }
+
         Ok(())
     }
 
     // 更新 PID 监控器的启停状态
-    async fn reconcile_monitors(&mut self, desired_configs: Vec<&ProcessConfig>) -> Result<()> {
+    async fn reconcile_monitors(
+        &mut self,
+        desired_configs: Vec<&ProcessConfig>,
+        to_start: Vec<(String, PreparedMonitor)>,
+    ) -> Result<()> {
         // 构建期望的配置映射
         let desired_configs_map: HashMap<String, &ProcessConfig> = desired_configs
             .into_iter()
@@ -201,53 +496,19 @@ impl MonitorManager {
                 "MonitorManager: Stopping not-ebpf monitor for process '{}'",
                 name
             );
-            if let Some(handle) = self.running_monitors.remove(&name) {
-                // 取消任务并等待一小段时间
-                handle.abort();
-                // 给任务一些时间来清理
-                tokio::time::sleep(Duration::from_millis(100)).await;
+            if let Some(monitor) = self.running_monitors.remove(&name) {
+                monitor.stop(&name).await;
             }
         }
 
         // 启动新的监控器或重启已结束的监控器
-        for (name, process_config) in desired_configs_map {
-            let should_start = match self.running_monitors.get(&name) {
-                Some(handle) => {
-                    let finished = handle.is_finished();
-                    if finished {
-                        debug!(process = %name, "Existing monitor task finished - will restart");
-                    } else {
-                        debug!(process = %name, "Monitor already running - no restart needed");
-                    }
-                    finished
-                }
-                None => {
-                    debug!(process = %name, "No existing monitor - will start");
-                    true
-                }
-            };
-
-            if should_start {
-                if let Some(pid_config) = process_config.get_pid_monitor_config() {
-                    info!(
-                        "MonitorManager: Starting PID monitor for process '{}'",
-                        name
-                    );
-                    let monitor = PidMonitor::new(pid_config, self.event_sender.clone());
-                    let handle = tokio::spawn(monitor.run());
-                    self.running_monitors.insert(name.clone(), handle);
-                } else if let Some(network_config) = process_config.get_network_monitor_config() {
-                    info!(
-                        "MonitorManager: Starting Network monitor for process '{}'",
-                        name
-                    );
-                    let monitor = NetworkMonitor::new(network_config, self.event_sender.clone());
-                    let handle = tokio::spawn(monitor.run());
-                    self.running_monitors.insert(name.clone(), handle);
-                } else {
-                    debug!(process = %name, "Process has no recognized monitor config after filtering (unexpected)");
-                }
-            }
+        for (name, prepared) in to_start {
+            info!(
+                "MonitorManager: Starting {} monitor for process '{}'",
+                prepared.kind(),
+                name
+            );
+            self.running_monitors.insert(name, prepared.spawn());
         }
 
         Ok(())
@@ -257,22 +518,15 @@ impl MonitorManager {
     pub async fn shutdown(&mut self) {
         info!("MonitorManager: Shutting down all monitors...");
 
-        // 停止所有 非ebpf 监控器
-        let mut handles_to_wait = Vec::new();
-        for (name, handle) in self.running_monitors.drain() {
+        // 先通知所有 非ebpf 监控器退出，再逐个等待（设置超时）
+        let monitors: Vec<(String, RunningMonitor)> = self.running_monitors.drain().collect();
+        self.health.set_monitors(HashMap::new());
+        for (name, monitor) in &monitors {
             info!("MonitorManager: Stopping monitor for '{}'", name);
-            handle.abort();
-            handles_to_wait.push(handle);
+            monitor.shutdown.cancel();
         }
-
-        // 等待所有任务完成，但设置超时
-        for handle in handles_to_wait {
-            if let Err(e) = tokio::time::timeout(Duration::from_secs(2), handle).await {
-                warn!(
-                    "MonitorManager: not-ebpf monitor task did not complete within timeout: {:?}",
-                    e
-                );
-            }
+        for (name, monitor) in monitors {
+            monitor.stop(&name).await;
         }
 
         // 关闭 eBPF 监控器，否则守护进程杀不死
@@ -0,0 +1,86 @@
+//! 单次检查模式：对每个已启用的进程执行一次监控检查，必要时立即恢复，然后返回结果。
+//! 适用于 cron / CI 等外部调度场景，不常驻、不进入监控循环。
+
+use crate::{
+    config::AppConfig,
+    event_bus,
+    monitor::{
+        self,
+        network_monitor::NetworkMonitor,
+        pid_monitor::PidMonitor,
+        systemd_monitor::{self, SystemdMonitor},
+        unix_socket_monitor::UnixSocketMonitor,
+        Monitor,
+    },
+    subscriber::{process_healer::ProcessHealer, Subscriber},
+};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// 一次检查的结果
+#[derive(Debug, Default)]
+pub struct OneshotReport {
+    /// 执行了检查的进程
+    pub checked: Vec<String>,
+    /// 检查时处于异常状态（并已尝试恢复）的进程
+    pub down: Vec<String>,
+}
+
+/// 依次检查所有已启用的进程，对异常的进程调用 ProcessHealer 执行恢复。
+pub async fn run_once(config: Arc<RwLock<AppConfig>>) -> OneshotReport {
+    let processes = config.read().await.processes.clone();
+    let event_sender = event_bus::create_event_sender();
+    let mut healer = ProcessHealer::new(event_sender.subscribe(), Arc::clone(&config)).await;
+    let mut report = OneshotReport::default();
+
+    for process in processes.iter().filter(|p| p.enabled) {
+        if let Err(e) = monitor::check_compiled(process) {
+            warn!("[Oneshot] Skipping check: {}", e);
+            continue;
+        }
+        let event = if let Some(pid_config) = process.get_pid_monitor_config() {
+            PidMonitor::new(pid_config, event_sender.clone())
+                .check_once()
+                .await
+        } else if let Some(network_config) = process.get_network_monitor_config() {
+            NetworkMonitor::new(network_config, event_sender.clone())
+                .check_once()
+                .await
+        } else if let Some(socket_config) = process.get_unix_socket_monitor_config() {
+            UnixSocketMonitor::new(socket_config, event_sender.clone())
+                .check_once()
+                .await
+        } else if let Some(systemd_config) = process.get_systemd_monitor_config() {
+            match systemd_monitor::default_unit_state_source().await {
+                Ok(source) => {
+                    SystemdMonitor::new(systemd_config, event_sender.clone(), source)
+                        .check_once()
+                        .await
+                }
+                Err(e) => {
+                    warn!("[Oneshot] Cannot check process '{}': {}", process.name, e);
+                    continue;
+                }
+            }
+        } else {
+            warn!(
+                "[Oneshot] Process '{}' has no pollable monitor (eBPF is event driven), skipping.",
+                process.name
+            );
+            continue;
+        };
+        report.checked.push(process.name.clone());
+
+        match event {
+            Some(event) => {
+                info!("[Oneshot] Process '{}' is down, recovering.", process.name);
+                // 与常驻模式一致，按事件类型选择恢复命令
+                healer.handle_event(event).await;
+                report.down.push(process.name.clone());
+            }
+            None => info!("[Oneshot] Process '{}' is healthy.", process.name),
+        }
+    }
+    report
+}
@@ -1,8 +1,10 @@
 use crate::event_bus::ProcessEvent;
+use async_trait::async_trait;
 use tokio::sync::broadcast;
 
+#[async_trait]
 pub trait Publisher {
-    fn publish(
+    async fn publish(
         &self,
         event: ProcessEvent,
     ) -> Result<usize, broadcast::error::SendError<ProcessEvent>>;
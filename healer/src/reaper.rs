@@ -0,0 +1,42 @@
+//! healer 自己等待的子进程与僵尸进程回收服务（`ServiceManager::reap_zombies`）之间的协调。
+//!
+//! 回收服务用 `waitid(P_ALL)` 回收已退出的子进程；由 healer 自己等待退出状态的子进程（恢复命令、
+//! recovery_guard、pid_source 命令等）若先被它回收，等待方只会得到 ECHILD。这类子进程经 [`spawn`]
+//! 拉起：fork 与登记在同一把锁内完成，回收服务持有同一把锁检查，不会在两者之间回收它们。
+
+use std::collections::BTreeSet;
+use std::io;
+use std::sync::{Mutex, MutexGuard};
+use tokio::process::Child;
+
+static EXEMPT: Mutex<BTreeSet<u32>> = Mutex::new(BTreeSet::new());
+
+/// 回收服务跳过的子进程，drop 时撤销；应在等待方取得退出状态（或交给 `ChildRegistry` 跟踪）之后再 drop
+#[derive(Debug)]
+pub struct Exemption {
+    pid: Option<u32>,
+}
+
+impl Drop for Exemption {
+    fn drop(&mut self) {
+        if let Some(pid) = self.pid {
+            exempt_pids().remove(&pid);
+        }
+    }
+}
+
+/// 拉起子进程并登记为回收服务跳过的子进程，直到返回的 [`Exemption`] 被 drop
+pub fn spawn(spawn: impl FnOnce() -> io::Result<Child>) -> io::Result<(Child, Exemption)> {
+    let mut exempt = exempt_pids();
+    let child = spawn()?;
+    let pid = child.id();
+    if let Some(pid) = pid {
+        exempt.insert(pid);
+    }
+    Ok((child, Exemption { pid }))
+}
+
+/// 回收服务在检查与回收期间持有该锁，其中的 PID 不得回收
+pub fn exempt_pids() -> MutexGuard<'static, BTreeSet<u32>> {
+    EXEMPT.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,173 @@
+//! 定时主动重启：按进程的 `scheduled_restart.cron` 在到点时发布 `ProcessDown`，
+//! 与监控器发现的故障一样经过依赖协调、熔断器与恢复命令。
+//! 仍在运行的实例会先被终止，避免重启后出现两个实例：优先终止自愈器拉起的子进程（`ChildRegistry`），
+//! 否则按 PID 监控的来源或可执行文件查找；终止失败时放弃本次重启。
+
+use crate::config::AppConfig;
+use crate::event_bus::{EventSender, ProcessEvent};
+use crate::subscriber::child_registry::ChildRegistry;
+use crate::utils;
+use chrono::{DateTime, Local};
+use cron::Schedule;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+// 最长的休眠时间，到期后重新读取配置（重载后新增或修改的计划在此间隔内生效）
+const RESCAN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 定时重启任务
+pub struct RestartScheduler;
+
+impl RestartScheduler {
+    /// 在后台按配置的计划发布 ProcessDown；`event_tx` 为监控器一侧的事件总线
+    pub fn spawn(
+        event_tx: EventSender,
+        config: Arc<RwLock<AppConfig>>,
+        children: ChildRegistry,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!("RestartScheduler: Started.");
+            // 进程名 -> (cron 表达式, 下次触发时间)
+            let mut next: HashMap<String, (String, DateTime<Local>)> = HashMap::new();
+            loop {
+                let now = Local::now();
+                Self::refresh(&config, &mut next, now).await;
+
+                let due: Vec<String> = next
+                    .iter()
+                    .filter(|(_, (_, at))| *at <= now)
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                for name in due {
+                    Self::restart(&name, &event_tx, &config, &children).await;
+                    // 从当前时间重新计算，停止实例耗时较长时跳过错过的触发点
+                    let fire = next
+                        .get(&name)
+                        .and_then(|(cron, _)| Self::next_fire(cron, Local::now()));
+                    match (fire, next.get_mut(&name)) {
+                        (Some(fire), Some((_, at))) => *at = fire,
+                        _ => {
+                            next.remove(&name);
+                        }
+                    }
+                }
+
+                let wait = next
+                    .values()
+                    .map(|(_, at)| (*at - Local::now()).to_std().unwrap_or_default())
+                    .min()
+                    .unwrap_or(RESCAN_INTERVAL)
+                    .min(RESCAN_INTERVAL);
+                tokio::time::sleep(wait).await;
+            }
+        })
+    }
+
+    /// `after` 之后的下一次触发时间；表达式无效或不再触发时返回 None
+    pub fn next_fire(cron: &str, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let schedule: Schedule = cron.parse().ok()?;
+        schedule.after(&after).next()
+    }
+
+    // 按当前配置增删计划；表达式未变的进程保留原定的触发时间
+    async fn refresh(
+        config: &Arc<RwLock<AppConfig>>,
+        next: &mut HashMap<String, (String, DateTime<Local>)>,
+        now: DateTime<Local>,
+    ) {
+        let config = config.read().await;
+        let scheduled: HashMap<&str, &str> = config
+            .processes
+            .iter()
+            .filter(|process| process.enabled)
+            .filter_map(|process| {
+                let schedule = process.scheduled_restart.as_ref()?;
+                Some((process.name.as_str(), schedule.cron.as_str()))
+            })
+            .collect();
+        next.retain(|name, (cron, _)| scheduled.get(name.as_str()) == Some(&cron.as_str()));
+        for (name, cron) in scheduled {
+            if next.contains_key(name) {
+                continue;
+            }
+            match Self::next_fire(cron, now) {
+                Some(at) => {
+                    debug!(
+                        "RestartScheduler: Next restart of '{}' at {}.",
+                        name,
+                        at.to_rfc3339()
+                    );
+                    next.insert(name.to_string(), (cron.to_string(), at));
+                }
+                None => warn!(
+                    "RestartScheduler: Schedule '{}' of process '{}' never fires, ignoring.",
+                    cron, name
+                ),
+            }
+        }
+    }
+
+    async fn restart(
+        name: &str,
+        event_tx: &EventSender,
+        config: &Arc<RwLock<AppConfig>>,
+        children: &ChildRegistry,
+    ) {
+        let Some(process_config) = config.read().await.get_process_config_for(name).cloned() else {
+            return;
+        };
+        let stop_timeout = process_config
+            .scheduled_restart
+            .as_ref()
+            .map_or(Duration::ZERO, |schedule| {
+                Duration::from_secs(schedule.stop_timeout_secs)
+            });
+        info!(
+            target: "healer_event",
+            process_name = %name,
+            "Scheduled restart is due."
+        );
+
+        let pid = match children.stop(name, stop_timeout).await {
+            Some(pid) => pid,
+            // 不是由自愈器拉起的实例：与控制命令 stop 一样按 PID 监控的来源或可执行文件查找
+            None => {
+                let found =
+                    tokio::task::spawn_blocking(move || utils::find_running_pid(&process_config))
+                        .await
+                        .ok()
+                        .flatten();
+                match found {
+                    Some(pid) => {
+                        if !utils::terminate(name, pid, stop_timeout).await {
+                            // 旧实例仍在运行时拉起新实例会出现两个实例，放弃本次重启
+                            warn!(
+                                "RestartScheduler: Could not stop '{}' (pid {}), skipping the scheduled restart.",
+                                name, pid
+                            );
+                            return;
+                        }
+                        pid
+                    }
+                    None => 0,
+                }
+            }
+        };
+
+        let event = ProcessEvent::ProcessDown {
+            name: name.to_string(),
+            pid,
+            at: SystemTime::now(),
+        };
+        if event_tx.send(event).await.is_err() {
+            warn!(
+                "RestartScheduler: No subscriber received the scheduled restart of '{}'.",
+                name
+            );
+        }
+    }
+}
@@ -1,48 +1,232 @@
 use crate::{
-    config::AppConfig,
+    config::{AppConfig, ClosedPolicy, EventDelivery, EventForwarderConfig, SubscriberPolicies},
     coordinator::dependency_coordinator::DependencyCoordinator,
-    event_bus::ProcessEvent,
-    subscriber::{process_healer::ProcessHealer, Subscriber},
+    event_bus::{EventReceiver, EventSender},
+    health::HealthRegistry,
+    log_janitor::{LogJanitor, CHILD_LOG_DIR},
+    metrics::Metrics,
+    reaper,
+    restart_scheduler::RestartScheduler,
+    signal_handler::SignalEvent,
+    status_file::StatusFile,
+    subscriber::{
+        child_registry::ChildRegistry,
+        event_filter::{EventFilter, FilteredSubscriber},
+        event_history::EventHistory,
+        forwarder::EventForwarder,
+        process_healer::ProcessHealer,
+        Subscriber,
+    },
 };
 use nix::errno::Errno;
-use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::sys::wait::{waitid, waitpid, Id, WaitPidFlag, WaitStatus};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::signal::unix::{self, SignalKind};
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
 /// 服务管理器，负责管理持久性后台任务
 pub struct ServiceManager;
 
+/// 关键订阅者的监管设置：按 `policies.delivery` 选择投递方式；
+/// 通道关闭时按策略重新订阅，或通过 `runtime_tx` 通知运行时退出
+#[derive(Debug, Clone)]
+pub struct CriticalSupervision {
+    pub policies: SubscriberPolicies,
+    pub runtime_tx: mpsc::Sender<SignalEvent>,
+}
+
+impl CriticalSupervision {
+    /// 以关键订阅者 `name` 的身份订阅；broadcast 投递时通道只会随发送端一起关闭，不需要关闭策略
+    pub fn subscribe(
+        &self,
+        sender: &EventSender,
+        name: &'static str,
+        policy: ClosedPolicy,
+    ) -> EventReceiver {
+        if self.policies.delivery == EventDelivery::Broadcast {
+            return sender.subscribe().into();
+        }
+        match policy {
+            ClosedPolicy::Resubscribe => sender.subscribe_critical_resubscribing(name),
+            ClosedPolicy::Fatal => sender.subscribe_critical().into(),
+        }
+    }
+
+    async fn escalate(&self, name: &'static str) {
+        error!(
+            "ServiceManager: Critical subscriber '{}' lost its event channel, stopping the runtime.",
+            name
+        );
+        let _ = self
+            .runtime_tx
+            .send(SignalEvent::SubscriberLost(name))
+            .await;
+    }
+}
+
 impl ServiceManager {
-    /// 启动所有持久性后台服务
+    /// 启动所有持久性后台服务，返回各服务的任务句柄
     pub fn spawn_persistent_services(
-        monitor_event_sender: &broadcast::Sender<ProcessEvent>,
-        coordinator_event_sender: &broadcast::Sender<ProcessEvent>,
+        monitor_event_sender: &EventSender,
+        coordinator_event_sender: &EventSender,
+        config: &Arc<RwLock<AppConfig>>,
+        foreground: bool,
+        drop_privileges: bool,
+        health: &HealthRegistry,
+        history: &EventHistory,
+        children: &ChildRegistry,
+        metrics: &Metrics,
+        supervision: &CriticalSupervision,
+    ) -> Vec<JoinHandle<()>> {
+        // 自愈器拉起的子进程由各自的任务等待退出，僵尸回收服务需要跳过它们
+        let handles = vec![
+            // 先启动协调器（监听 monitor_event_sender，输出到 coordinator_event_sender）
+            Self::spawn_dependency_coordinator(
+                monitor_event_sender,
+                coordinator_event_sender,
+                config,
+                health,
+                metrics,
+                supervision,
+            ),
+            // Healer 监听协调器输出通道
+            Self::spawn_process_healer(
+                coordinator_event_sender,
+                config,
+                foreground,
+                drop_privileges,
+                health,
+                children,
+                metrics,
+                supervision,
+            ),
+            Self::spawn_event_history(monitor_event_sender, config, history),
+            Self::spawn_zombie_reaper(children.clone()),
+            LogJanitor::spawn(CHILD_LOG_DIR.into(), Arc::clone(config)),
+            StatusFile::spawn(Arc::clone(config), health.clone(), history.clone()),
+            // 定时重启与监控器一样发布到 monitor_event_sender，经协调器处理依赖
+            RestartScheduler::spawn(
+                monitor_event_sender.clone(),
+                Arc::clone(config),
+                children.clone(),
+            ),
+        ];
+        // 协调器与自愈器已在上面同步完成订阅，此后发布的事件都有接收方
+        monitor_event_sender.mark_ready();
+        coordinator_event_sender.mark_ready();
+        handles
+    }
+
+    /// 启动事件历史记录服务
+    fn spawn_event_history(
+        monitor_event_sender: &EventSender,
         config: &Arc<RwLock<AppConfig>>,
-    ) {
-        // 先启动协调器（监听 monitor_event_sender，输出到 coordinator_event_sender）
-        Self::spawn_dependency_coordinator(monitor_event_sender, coordinator_event_sender, config);
-        // Healer 监听协调器输出通道
-        Self::spawn_process_healer(coordinator_event_sender, config);
-        Self::spawn_zombie_reaper();
+        history: &EventHistory,
+    ) -> JoinHandle<()> {
+        // 记录监控器发出的原始事件；作为非关键观察者，落后时丢弃而不是阻塞监控器
+        let mut receiver = monitor_event_sender.subscribe();
+        let config = Arc::clone(config);
+        let history = history.clone();
+        tokio::spawn(async move {
+            let filter = EventFilter::new(&config.read().await.control.event_history_filter);
+            if !filter.is_empty() {
+                info!("ServiceManager: EventHistory only records events matching control.event_history_filter.");
+            }
+            let mut history = FilteredSubscriber::new(history, filter);
+            info!("ServiceManager: EventHistory service started.");
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => history.handle_event(event).await,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("ServiceManager: EventHistory lagged, missed {} messages", n);
+                    }
+                    Err(_) => {
+                        debug!("ServiceManager: EventHistory event channel closed, exiting.");
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+    /// 启动事件转发服务，返回订阅任务与发布任务的句柄
+    pub fn spawn_event_forwarder(
+        monitor_event_sender: &EventSender,
+        config: EventForwarderConfig,
+        labels: HashMap<String, String>,
+    ) -> Vec<JoinHandle<()>> {
+        // 与事件历史一样作为非关键观察者，总线不可用时不影响监控与恢复
+        let mut receiver = monitor_event_sender.subscribe();
+        let filter = EventFilter::new(&config.filter);
+        if !filter.is_empty() {
+            info!("ServiceManager: EventForwarder only forwards events matching event_forwarder.filter.");
+        }
+        let (forwarder, publisher) = EventForwarder::spawn_with_labels(config, labels);
+        let mut forwarder = FilteredSubscriber::new(forwarder, filter);
+        let subscriber = tokio::spawn(async move {
+            info!("ServiceManager: EventForwarder service started.");
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => forwarder.handle_event(event).await,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(
+                            "ServiceManager: EventForwarder lagged, missed {} messages",
+                            n
+                        );
+                    }
+                    Err(_) => {
+                        debug!("ServiceManager: EventForwarder event channel closed, exiting.");
+                        break;
+                    }
+                }
+            }
+        });
+        vec![subscriber, publisher]
     }
 
     /// 启动进程自愈服务
     fn spawn_process_healer(
-        coordinator_event_sender: &broadcast::Sender<ProcessEvent>,
+        coordinator_event_sender: &EventSender,
         config: &Arc<RwLock<AppConfig>>,
-    ) {
-        let healer_receiver = coordinator_event_sender.subscribe();
+        foreground: bool,
+        drop_privileges: bool,
+        health: &HealthRegistry,
+        children: &ChildRegistry,
+        metrics: &Metrics,
+        supervision: &CriticalSupervision,
+    ) -> JoinHandle<()> {
+        // 自愈器是关键订阅者：默认走有界队列，处理慢时让上游等待而不是丢事件
+        let healer_receiver = supervision.subscribe(
+            coordinator_event_sender,
+            "healer",
+            supervision.policies.healer,
+        );
         let healer_config = Arc::clone(config);
+        let health = health.clone();
+        let children = children.clone();
+        let metrics = metrics.clone();
+        let supervision = supervision.clone();
 
         tokio::spawn(async move {
-            let mut healer = ProcessHealer::new(healer_receiver, healer_config).await;
+            let mut healer = ProcessHealer::new(healer_receiver, healer_config)
+                .await
+                .with_foreground(foreground)
+                .with_drop_privileges(drop_privileges)
+                .with_health(health)
+                .with_children(children)
+                .with_metrics(metrics);
+            healer.adopt_running_children().await;
             info!("ServiceManager: ProcessHealer service started.");
             loop {
-                match healer.event_rx.recv().await {
-                    Ok(event) => {
-                        healer.handle_event(event).await;
+                // 同时到达的事件按进程优先级排序后依次处理
+                match healer.next_batch().await {
+                    Ok(events) => {
+                        for event in events {
+                            healer.handle_event(event).await;
+                        }
                     }
                     Err(broadcast::error::RecvError::Lagged(n)) => {
                         warn!(
@@ -51,39 +235,54 @@ impl ServiceManager {
                         );
                     }
                     Err(_) => {
-                        error!("ServiceManager: ProcessHealer event channel closed, exiting.");
+                        supervision.escalate("healer").await;
                         break;
                     }
                 }
             }
-        });
+        })
     }
 
     /// 启动依赖协调器
     fn spawn_dependency_coordinator(
-        monitor_event_sender: &broadcast::Sender<ProcessEvent>,
-        coordinator_event_sender: &broadcast::Sender<ProcessEvent>,
+        monitor_event_sender: &EventSender,
+        coordinator_event_sender: &EventSender,
         config: &Arc<RwLock<AppConfig>>,
-    ) {
-        let in_rx = monitor_event_sender.subscribe();
+        health: &HealthRegistry,
+        metrics: &Metrics,
+        supervision: &CriticalSupervision,
+    ) -> JoinHandle<()> {
+        // 协调器同样是关键订阅者
+        let in_rx = supervision.subscribe(
+            monitor_event_sender,
+            "coordinator",
+            supervision.policies.coordinator,
+        );
         let out_tx = coordinator_event_sender.clone();
         let cfg = Arc::clone(config);
+        let health = health.clone();
+        let metrics = metrics.clone();
+        let supervision = supervision.clone();
         tokio::spawn(async move {
-            let coordinator = DependencyCoordinator::new(in_rx, out_tx, cfg);
+            let coordinator = DependencyCoordinator::new(in_rx, out_tx, cfg)
+                .with_metrics(metrics)
+                .with_health(health);
             tracing::info!("ServiceManager: DependencyCoordinator service started.");
+            // run_loop 只在上游通道关闭时返回（重新订阅模式下不会发生）
             coordinator.run_loop().await;
-        });
+            supervision.escalate("coordinator").await;
+        })
     }
 
     /// 启动僵尸进程清理服务
-    fn spawn_zombie_reaper() {
-        tokio::spawn(async {
+    fn spawn_zombie_reaper(children: ChildRegistry) -> JoinHandle<()> {
+        tokio::spawn(async move {
             info!("ServiceManager: Zombie reaper service started, listening for SIGCHLD.");
 
             match unix::signal(SignalKind::child()) {
                 Ok(mut stream) => loop {
                     stream.recv().await;
-                    Self::reap_zombies();
+                    Self::reap_zombies(&children);
                 },
                 Err(e) => {
                     error!(
@@ -92,13 +291,37 @@ impl ServiceManager {
                     );
                 }
             }
-        });
+        })
     }
 
-    /// 清理僵尸进程
-    fn reap_zombies() {
+    /// 清理僵尸进程；已登记的子进程与经 [`reaper::spawn`] 拉起、仍在等待的子进程留给各自的等待方回收，
+    /// 以便取得退出状态
+    pub fn reap_zombies(children: &ChildRegistry) {
+        // 持锁期间不会有新的子进程在 fork 与登记之间
+        let exempt = reaper::exempt_pids();
         loop {
-            match waitpid(None, Some(WaitPidFlag::WNOHANG)) {
+            // 先用 WNOWAIT 查看下一个已退出的子进程而不回收它
+            let peeked = waitid(
+                Id::All,
+                WaitPidFlag::WEXITED | WaitPidFlag::WNOHANG | WaitPidFlag::WNOWAIT,
+            );
+            let pid = match peeked {
+                Ok(status) => match status.pid() {
+                    Some(pid) => pid,
+                    None => break, // 没有已终止的子进程
+                },
+                Err(Errno::ECHILD) => break,
+                Err(e) => {
+                    error!("ServiceManager: waitid failed: {}", e);
+                    break;
+                }
+            };
+            let child_pid = pid.as_raw() as u32;
+            if exempt.contains(&child_pid) || children.is_tracked_pid(child_pid) {
+                // 下一个已退出的子进程由其等待任务回收；之后的 SIGCHLD 会再次触发清理
+                break;
+            }
+            match waitpid(pid, Some(WaitPidFlag::WNOHANG)) {
                 Ok(WaitStatus::Exited(pid, status)) => {
                     info!(
                         "ServiceManager: Reaped child {} which exited with status {}",
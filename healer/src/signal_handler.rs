@@ -1,38 +1,111 @@
+use crate::config::{SignalConfig, SignalName};
 use anyhow::Result;
+use futures::future::select_all;
 use tokio::signal::unix::{self, SignalKind};
-use tracing::info;
+use tokio::sync::mpsc;
+use tracing::{error, info};
 
-/// 信号处理器，负责处理系统信号
-pub struct SignalHandler;
+/// 信号处理器，负责处理系统信号。
+/// 按 `SignalConfig` 注册重载与关闭信号，注册在处理器的生命周期内保持有效。
+pub struct SignalHandler {
+    signals: Vec<(SignalName, SignalAction, unix::Signal)>,
+}
 
 #[derive(Debug)]
 pub enum SignalEvent {
-    /// 配置重载信号 (SIGHUP)
+    /// 配置重载信号（默认 SIGHUP，见 `signals.reload`）
     ConfigReload,
-    /// 关闭信号 (SIGTERM, SIGINT)
+    /// 关闭信号（默认 SIGTERM、SIGINT，见 `signals.shutdown`）
+    Shutdown,
+    /// 按内存中的配置重新协调监控器，不重新读取配置文件（如控制命令启用/停用进程后）
+    Reconcile,
+    /// 关键订阅者的事件通道已关闭且策略为 fatal，运行时以错误退出
+    SubscriberLost(&'static str),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SignalAction {
+    Reload,
     Shutdown,
 }
 
 impl SignalHandler {
-    /// 等待下一个信号事件
-    pub async fn wait_for_signal() -> Result<SignalEvent> {
-        let mut hup_signal = unix::signal(SignalKind::hangup())?;
-        let mut term_signal = unix::signal(SignalKind::terminate())?;
-        let mut int_signal = unix::signal(SignalKind::interrupt())?;
+    /// 注册配置中的重载与关闭信号
+    pub fn register(config: &SignalConfig) -> Result<Self> {
+        let actions = config
+            .reload
+            .iter()
+            .map(|name| (*name, SignalAction::Reload))
+            .chain(
+                config
+                    .shutdown
+                    .iter()
+                    .map(|name| (*name, SignalAction::Shutdown)),
+            );
+        let mut signals = Vec::new();
+        for (name, action) in actions {
+            let stream = unix::signal(SignalKind::from_raw(name.signal() as i32))?;
+            signals.push((name, action, stream));
+        }
+        Ok(Self { signals })
+    }
 
-        tokio::select! {
-            _ = hup_signal.recv() => {
-                info!("SignalHandler: Received SIGHUP, triggering configuration reload.");
+    /// 等待下一个信号事件
+    pub async fn wait_for_signal(&mut self) -> Result<SignalEvent> {
+        if self.signals.is_empty() {
+            return std::future::pending().await;
+        }
+        let index = {
+            let pending = self
+                .signals
+                .iter_mut()
+                .map(|(_, _, stream)| Box::pin(stream.recv()));
+            select_all(pending).await.1
+        };
+        let (name, action, _) = &self.signals[index];
+        match action {
+            SignalAction::Reload => {
+                info!(
+                    "SignalHandler: Received {}, triggering configuration reload.",
+                    name
+                );
                 Ok(SignalEvent::ConfigReload)
             }
-            _ = term_signal.recv() => {
-                info!("SignalHandler: Received SIGTERM, initiating graceful shutdown.");
-                Ok(SignalEvent::Shutdown)
-            }
-            _ = int_signal.recv() => {
-                info!("SignalHandler: Received SIGINT (Ctrl+C), initiating graceful shutdown.");
+            SignalAction::Shutdown => {
+                info!(
+                    "SignalHandler: Received {}, initiating graceful shutdown.",
+                    name
+                );
                 Ok(SignalEvent::Shutdown)
             }
         }
     }
+
+    /// 持续监听系统信号并转发到核心运行时的控制通道。
+    /// 控制通道关闭或信号注册失败时退出（核心运行时会将通道关闭视为关闭请求）。
+    pub fn spawn_forwarder(control_tx: mpsc::Sender<SignalEvent>, config: &SignalConfig) {
+        let handler = Self::register(config);
+        tokio::spawn(async move {
+            let mut handler = match handler {
+                Ok(handler) => handler,
+                Err(e) => {
+                    error!("SignalHandler: Failed to register signals: {}", e);
+                    return;
+                }
+            };
+            loop {
+                match handler.wait_for_signal().await {
+                    Ok(event) => {
+                        if control_tx.send(event).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        error!("SignalHandler: Failed to wait for signals: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+    }
 }
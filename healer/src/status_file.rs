@@ -0,0 +1,102 @@
+//! 状态文件：定期把运行时状态的 JSON 快照写入 `status_file`，供只能抓取文件的监控系统读取。
+//! 先写入同目录下的临时文件再 rename，读取方不会看到写了一半的内容。
+//!
+//! ```text
+//! $ cat /run/healer/status.json
+//! {"at":"...","pid":812,"uptime_secs":3600,"processes":{"web":{"enabled":true,"state":"healthy","last_event":{...}}}}
+//! ```
+
+use crate::config::AppConfig;
+use crate::health::HealthRegistry;
+use crate::subscriber::event_history::{EventHistory, EventRecord};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 状态文件的写入任务
+pub struct StatusFile;
+
+impl StatusFile {
+    /// 在后台按 `status_file_interval_secs` 改写状态文件；每次都重新读取配置，未设置 `status_file` 时跳过
+    pub fn spawn(
+        config: Arc<RwLock<AppConfig>>,
+        health: HealthRegistry,
+        history: EventHistory,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let mut last_path: Option<PathBuf> = None;
+            loop {
+                let (path, interval) = {
+                    let config = config.read().await;
+                    (config.status_file.clone(), config.status_file_interval_secs)
+                };
+                if let Some(path) = &path {
+                    if last_path.as_ref() != Some(path) {
+                        info!("StatusFile: Writing status snapshots to {:?}.", path);
+                    }
+                    let snapshot = Self::snapshot(&config, &health, &history, started).await;
+                    match Self::write(path, &snapshot) {
+                        Ok(()) => debug!("StatusFile: Updated {:?}.", path),
+                        Err(e) => warn!("StatusFile: Failed to write {:?}: {}", path, e),
+                    }
+                }
+                last_path = path;
+                tokio::time::sleep(Duration::from_secs(interval.max(1))).await;
+            }
+        })
+    }
+
+    /// 当前的状态快照：各进程的启用状态、健康状态与最近一次事件，以及守护进程的运行时长
+    pub async fn snapshot(
+        config: &RwLock<AppConfig>,
+        health: &HealthRegistry,
+        history: &EventHistory,
+        started: Instant,
+    ) -> Value {
+        let mut last_events: HashMap<String, EventRecord> = HashMap::new();
+        for event in history.recent(history.capacity()) {
+            if let Some(name) = event.process_name() {
+                last_events.insert(name.to_string(), EventRecord::from(&event));
+            }
+        }
+        let processes: serde_json::Map<String, Value> = config
+            .read()
+            .await
+            .processes
+            .iter()
+            .map(|process| {
+                let entry = json!({
+                    "enabled": process.enabled,
+                    "state": health.get(&process.name).to_string(),
+                    "last_event": last_events.remove(&process.name),
+                });
+                (process.name.clone(), entry)
+            })
+            .collect();
+        json!({
+            "at": chrono::DateTime::<chrono::Utc>::from(SystemTime::now()).to_rfc3339(),
+            "pid": std::process::id(),
+            "uptime_secs": started.elapsed().as_secs(),
+            "processes": processes,
+        })
+    }
+
+    /// 原子地写入：先写同目录下的临时文件并落盘，再 rename 覆盖目标文件
+    pub fn write(path: &Path, snapshot: &Value) -> std::io::Result<()> {
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = path.with_file_name(tmp_name);
+        let mut file = std::fs::File::create(&tmp_path)?;
+        serde_json::to_writer(&mut file, snapshot)?;
+        file.write_all(b"\n")?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)
+    }
+}
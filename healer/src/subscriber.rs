@@ -1,5 +1,12 @@
 use crate::event_bus;
 use async_trait::async_trait;
+pub mod child_registry;
+pub mod core_capture;
+pub mod event_filter;
+pub mod event_history;
+pub mod forwarder;
+pub mod load_guard;
+pub mod output_tail;
 pub mod process_healer;
 #[async_trait]
 pub trait Subscriber: Send + Sync {
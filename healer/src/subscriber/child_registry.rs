@@ -0,0 +1,233 @@
+//! 恢复时拉起的子进程登记表：保留每个进程最近一次拉起的子进程，由后台任务等待其退出。
+//! 配置了 output_tail_lines 的进程，其子进程输出的尾部也保存在这里。
+
+use super::core_capture::{self, CoreCapture, CoreRecords};
+use super::output_tail::{self, OutputSink, OutputTail};
+use crate::config::CoreCaptureConfig;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::process::Child;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+// 启动后这么快就退出的子进程多半是启动失败（配置错误、端口占用等）
+const FAST_EXIT: Duration = Duration::from_secs(1);
+
+// 检查被接管进程是否仍在运行的间隔
+const ADOPTED_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 子进程的退出信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChildExit {
+    /// 退出状态；被其他地方回收而无法取得时为 None
+    pub status: Option<ExitStatus>,
+    /// 从拉起到退出的时长
+    pub uptime: Duration,
+}
+
+/// 登记中的子进程，克隆后共享同一个退出通知
+#[derive(Debug, Clone)]
+pub struct TrackedChild {
+    pub pid: u32,
+    #[allow(dead_code)]
+    pub started_at: Instant,
+    exit: watch::Receiver<Option<ChildExit>>,
+}
+
+impl TrackedChild {
+    /// 已退出时返回退出信息
+    pub fn exit(&self) -> Option<ChildExit> {
+        *self.exit.borrow()
+    }
+
+    /// 等待子进程退出；等待任务已被取消时返回 None
+    pub async fn wait(&self) -> Option<ChildExit> {
+        let mut exit = self.exit.clone();
+        exit.wait_for(Option::is_some).await.ok().and_then(|e| *e)
+    }
+}
+
+fn report_exit(name: &str, pid: u32, exit: ChildExit) {
+    if exit.uptime < FAST_EXIT {
+        warn!(target: "healer_event", process_name = %name, process_pid = pid, status = ?exit.status, uptime_ms = exit.uptime.as_millis() as u64, "Restarted process exited right after start.");
+    } else {
+        info!(target: "healer_event", process_name = %name, process_pid = pid, status = ?exit.status, uptime_secs = exit.uptime.as_secs(), "Restarted process exited.");
+    }
+}
+
+/// 按进程名索引的子进程表，克隆后指向同一份数据
+#[derive(Debug, Clone, Default)]
+pub struct ChildRegistry {
+    children: Arc<RwLock<HashMap<String, TrackedChild>>>,
+    tails: Arc<RwLock<HashMap<String, Arc<Mutex<OutputTail>>>>>,
+    cores: CoreRecords,
+}
+
+impl ChildRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记新拉起的子进程（替换该进程之前的记录），并在后台等待其退出
+    /// `child` 应经 `reaper::spawn` 拉起，登记之后再撤销豁免，启动即退出的子进程才不会先被回收服务取走退出状态
+    pub fn track(&self, name: &str, mut child: Child) -> Option<TrackedChild> {
+        let pid = child.id()?;
+        let started_at = Instant::now();
+        let (exit_tx, exit_rx) = watch::channel(None);
+        let tracked = TrackedChild {
+            pid,
+            started_at,
+            exit: exit_rx,
+        };
+        self.children
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), tracked.clone());
+
+        let name = name.to_string();
+        tokio::spawn(async move {
+            let status = match child.wait().await {
+                Ok(status) => Some(status),
+                Err(e) => {
+                    warn!(target: "healer_action", process_name = %name, process_pid = pid, error = %e, "Failed to wait for restarted process.");
+                    None
+                }
+            };
+            let exit = ChildExit {
+                status,
+                uptime: started_at.elapsed(),
+            };
+            report_exit(&name, pid, exit);
+            let _ = exit_tx.send(Some(exit));
+        });
+        Some(tracked)
+    }
+
+    /// 登记一个不是 healer 子进程的进程（reparent_children 两次 fork 出的进程，或 healer 重启前拉起、仍在运行的进程）。
+    /// 无法等待其退出，改为定期检查 /proc/<pid> 是否存在，退出状态始终为 None
+    pub fn adopt(&self, name: &str, pid: u32) -> TrackedChild {
+        let started_at = Instant::now();
+        let (exit_tx, exit_rx) = watch::channel(None);
+        let tracked = TrackedChild {
+            pid,
+            started_at,
+            exit: exit_rx,
+        };
+        self.children
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), tracked.clone());
+
+        let name = name.to_string();
+        let proc_dir = PathBuf::from(format!("/proc/{}", pid));
+        tokio::spawn(async move {
+            while proc_dir.exists() {
+                tokio::time::sleep(ADOPTED_POLL_INTERVAL).await;
+            }
+            let exit = ChildExit {
+                status: None,
+                uptime: started_at.elapsed(),
+            };
+            report_exit(&name, pid, exit);
+            let _ = exit_tx.send(Some(exit));
+        });
+        tracked
+    }
+
+    /// 子进程因信号崩溃时按 core_pattern 记录并保存其 core 文件；`comm` 与 `cwd` 用于展开 core_pattern
+    pub fn capture_core_on_crash(
+        &self,
+        name: &str,
+        child: TrackedChild,
+        comm: String,
+        cwd: PathBuf,
+        config: CoreCaptureConfig,
+    ) {
+        let name = name.to_string();
+        let cores = self.cores.clone();
+        tokio::spawn(async move {
+            let Some(status) = child.wait().await.and_then(|exit| exit.status) else {
+                return;
+            };
+            core_capture::capture(&name, child.pid, status, &comm, &cwd, &config, &cores).await;
+        });
+    }
+
+    /// 进程最近一次崩溃的 core 记录
+    #[allow(dead_code)]
+    pub fn last_core(&self, name: &str) -> Option<CoreCapture> {
+        self.cores.last(name)
+    }
+
+    /// 进程最近一次拉起的子进程
+    pub fn get(&self, name: &str) -> Option<TrackedChild> {
+        self.children
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+    }
+
+    /// 终止进程最近一次拉起、仍在运行的子进程：先发送 SIGTERM，`timeout` 内未退出时发送 SIGKILL。
+    /// 返回被终止的子进程 PID，没有运行中的子进程时返回 None
+    pub async fn stop(&self, name: &str, timeout: Duration) -> Option<u32> {
+        let child = self.get(name).filter(|child| child.exit().is_none())?;
+        let target = Pid::from_raw(child.pid as i32);
+        if let Err(e) = kill(target, Signal::SIGTERM) {
+            warn!(target: "healer_action", process_name = %name, process_pid = child.pid, error = %e, "Failed to stop restarted process.");
+        } else if tokio::time::timeout(timeout, child.wait()).await.is_err() {
+            warn!(target: "healer_action", process_name = %name, process_pid = child.pid, timeout_secs = timeout.as_secs(), "Restarted process did not exit in time, killing it.");
+            let _ = kill(target, Signal::SIGKILL);
+        }
+        Some(child.pid)
+    }
+
+    /// 该 PID 是否是仍在等待退出的已登记子进程
+    pub fn is_tracked_pid(&self, pid: u32) -> bool {
+        self.children
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .any(|child| child.pid == pid && child.exit().is_none())
+    }
+
+    /// 接管子进程的输出管道，保留最后 lines 行并转发到 sink；同一进程多次恢复时追加到同一个缓冲区
+    pub fn capture_output(&self, name: &str, child: &mut Child, lines: usize, sink: OutputSink) {
+        let tail = {
+            let mut tails = self.tails.write().unwrap_or_else(|e| e.into_inner());
+            let tail = tails
+                .entry(name.to_string())
+                .or_insert_with(|| Arc::new(Mutex::new(OutputTail::new(lines))));
+            tail.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .set_capacity(lines);
+            Arc::clone(tail)
+        };
+        output_tail::capture(child, sink, tail);
+    }
+
+    /// 进程最近的输出行（按时间顺序）；从未捕获过输出时返回 None
+    pub fn tail(&self, name: &str) -> Option<Vec<String>> {
+        let tails = self.tails.read().unwrap_or_else(|e| e.into_inner());
+        let tail = tails.get(name)?;
+        let lines = tail.lock().unwrap_or_else(|e| e.into_inner()).lines();
+        Some(lines)
+    }
+
+    /// 进程已不在配置中时移除其记录（不影响仍在运行的子进程）
+    pub fn remove(&self, name: &str) {
+        self.children
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name);
+        self.tails
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name);
+    }
+}
@@ -0,0 +1,202 @@
+//! 崩溃现场保留：恢复时拉起的子进程因信号（SIGSEGV、SIGABRT 等）退出时，
+//! 按 `/proc/sys/kernel/core_pattern` 推算 core 文件的位置，并在下一次恢复覆盖现场之前
+//! 把它复制到按进程名划分的目录中。core_pattern 为管道（如 systemd-coredump）时只记录交给了哪个处理程序。
+
+use crate::config::CoreCaptureConfig;
+use nix::sys::signal::Signal;
+use std::collections::HashMap;
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+const CORE_PATTERN_PATH: &str = "/proc/sys/kernel/core_pattern";
+const CORE_USES_PID_PATH: &str = "/proc/sys/kernel/core_uses_pid";
+
+/// 内核按 core_pattern 处理 core 的方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreLocation {
+    /// 写入文件，路径已按 core_pattern 展开
+    File(PathBuf),
+    /// 通过管道交给处理程序（core_pattern 以 `|` 开头），需要用对应工具（如 coredumpctl）取回
+    Pipe(String),
+    /// core_pattern 中含有无法在事后确定的占位符（如 `%t`），保留原样
+    Unresolved(String),
+}
+
+/// 一次崩溃的记录
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreCapture {
+    pub pid: u32,
+    pub signal: Signal,
+    /// 内核是否报告已生成 core（WCOREDUMP）
+    pub core_dumped: bool,
+    pub location: CoreLocation,
+    /// core 文件已复制到的位置；未找到文件或复制失败时为 None
+    pub saved: Option<PathBuf>,
+}
+
+/// 按进程名保存最近一次崩溃记录的共享表，克隆后指向同一份数据
+#[derive(Debug, Clone, Default)]
+pub struct CoreRecords {
+    records: Arc<RwLock<HashMap<String, CoreCapture>>>,
+}
+
+impl CoreRecords {
+    /// 进程最近一次崩溃的记录
+    #[allow(dead_code)]
+    pub fn last(&self, name: &str) -> Option<CoreCapture> {
+        self.records
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+    }
+
+    fn insert(&self, name: &str, capture: CoreCapture) {
+        self.records
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), capture);
+    }
+}
+
+// 会产生 core 的信号（signal(7) 中默认动作为 Core 的信号）
+fn dumps_core(signal: Signal) -> bool {
+    matches!(
+        signal,
+        Signal::SIGQUIT
+            | Signal::SIGILL
+            | Signal::SIGTRAP
+            | Signal::SIGABRT
+            | Signal::SIGBUS
+            | Signal::SIGFPE
+            | Signal::SIGSEGV
+            | Signal::SIGXCPU
+            | Signal::SIGXFSZ
+            | Signal::SIGSYS
+    )
+}
+
+/// 按 core_pattern 推算 core 的位置。`comm` 为 `%e` 的取值，相对路径按进程的工作目录 `cwd` 解析；
+/// `uses_pid` 对应 core_uses_pid，为 true 且模式中没有 `%p` 时在文件名后追加 `.<pid>`
+pub fn expected_core_location(
+    pattern: &str,
+    pid: u32,
+    signal: Signal,
+    comm: &str,
+    cwd: &Path,
+    uses_pid: bool,
+) -> CoreLocation {
+    let pattern = pattern.trim_end_matches('\n');
+    if let Some(handler) = pattern.strip_prefix('|') {
+        return CoreLocation::Pipe(handler.trim().to_string());
+    }
+    let mut expanded = String::new();
+    let mut has_pid = false;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            expanded.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => expanded.push('%'),
+            Some('p' | 'P' | 'i' | 'I') => {
+                has_pid = true;
+                expanded.push_str(&pid.to_string());
+            }
+            Some('s') => expanded.push_str(&(signal as i32).to_string()),
+            Some('e') => expanded.push_str(comm),
+            Some('h') => expanded.push_str(&hostname()),
+            // 其余占位符（时间、uid、可执行文件路径等）无法在事后准确还原
+            _ => return CoreLocation::Unresolved(pattern.to_string()),
+        }
+    }
+    if uses_pid && !has_pid {
+        expanded.push_str(&format!(".{}", pid));
+    }
+    CoreLocation::File(cwd.join(expanded))
+}
+
+fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|name| name.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// 子进程因信号退出时记录（并尽量复制）其 core 文件；正常退出或不产生 core 的信号返回 None
+pub async fn capture(
+    name: &str,
+    pid: u32,
+    status: ExitStatus,
+    comm: &str,
+    cwd: &Path,
+    config: &CoreCaptureConfig,
+    records: &CoreRecords,
+) -> Option<CoreCapture> {
+    let signal = Signal::try_from(status.signal()?).ok()?;
+    if !dumps_core(signal) {
+        return None;
+    }
+    let pattern = tokio::fs::read_to_string(CORE_PATTERN_PATH)
+        .await
+        .unwrap_or_else(|_| "core".to_string());
+    let uses_pid = tokio::fs::read_to_string(CORE_USES_PID_PATH)
+        .await
+        .is_ok_and(|value| value.trim() == "1");
+    let location = expected_core_location(&pattern, pid, signal, comm, cwd, uses_pid);
+
+    let saved = match &location {
+        CoreLocation::File(path) => save_core(name, pid, path, &config.directory).await,
+        CoreLocation::Pipe(handler) => {
+            info!(target: "healer_event", process_name = %name, process_pid = pid, signal = signal.as_str(), handler = %handler, "Process crashed; core was piped to the core_pattern handler (e.g. retrieve it with coredumpctl).");
+            None
+        }
+        CoreLocation::Unresolved(pattern) => {
+            warn!(target: "healer_event", process_name = %name, process_pid = pid, signal = signal.as_str(), core_pattern = %pattern, "Process crashed; core path cannot be resolved from core_pattern.");
+            None
+        }
+    };
+    let capture = CoreCapture {
+        pid,
+        signal,
+        core_dumped: status.core_dumped(),
+        location,
+        saved,
+    };
+    records.insert(name, capture.clone());
+    Some(capture)
+}
+
+// 复制 core 到 <directory>/<进程名>/core.<pid>.<时间戳>
+async fn save_core(name: &str, pid: u32, core: &Path, directory: &Path) -> Option<PathBuf> {
+    if !core.exists() {
+        warn!(target: "healer_event", process_name = %name, process_pid = pid, core_path = %core.display(), "Process crashed but no core file was found (check RLIMIT_CORE).");
+        return None;
+    }
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let target_dir = directory.join(name);
+    let target = target_dir.join(format!("core.{}.{}", pid, timestamp));
+    let result = async {
+        tokio::fs::create_dir_all(&target_dir).await?;
+        tokio::fs::copy(core, &target).await
+    }
+    .await;
+    match result {
+        Ok(_) => {
+            info!(target: "healer_event", process_name = %name, process_pid = pid, core_path = %core.display(), saved_path = %target.display(), "Process crashed; saved its core file.");
+            Some(target)
+        }
+        Err(e) => {
+            warn!(target: "healer_event", process_name = %name, process_pid = pid, core_path = %core.display(), error = %e, "Process crashed; failed to save its core file.");
+            None
+        }
+    }
+}
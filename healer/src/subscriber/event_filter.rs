@@ -0,0 +1,58 @@
+//! 观察者的事件过滤：按进程名与事件类型只放行关心的事件，减少大规模部署下转发与记录的开销。
+//! 只用于观察者（事件转发、事件历史），协调器与自愈器始终接收全部事件。
+
+use super::Subscriber;
+use crate::config::EventFilterConfig;
+use crate::event_bus::ProcessEvent;
+use async_trait::async_trait;
+use std::collections::HashSet;
+
+/// 按进程名集合与事件类型集合过滤事件，集合为空时不限制
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    processes: HashSet<String>,
+    kinds: HashSet<String>,
+}
+
+impl EventFilter {
+    pub fn new(config: &EventFilterConfig) -> Self {
+        Self {
+            processes: config.processes.iter().cloned().collect(),
+            kinds: config.kinds.iter().cloned().collect(),
+        }
+    }
+
+    /// 是否放行所有事件
+    pub fn is_empty(&self) -> bool {
+        self.processes.is_empty() && self.kinds.is_empty()
+    }
+
+    pub fn matches(&self, event: &ProcessEvent) -> bool {
+        let process_matches = self.processes.is_empty()
+            || event
+                .process_name()
+                .is_some_and(|name| self.processes.contains(name));
+        process_matches && (self.kinds.is_empty() || self.kinds.contains(event.kind()))
+    }
+}
+
+/// 只把匹配过滤条件的事件交给内部订阅者
+pub struct FilteredSubscriber<S> {
+    inner: S,
+    filter: EventFilter,
+}
+
+impl<S: Subscriber> FilteredSubscriber<S> {
+    pub fn new(inner: S, filter: EventFilter) -> Self {
+        Self { inner, filter }
+    }
+}
+
+#[async_trait]
+impl<S: Subscriber> Subscriber for FilteredSubscriber<S> {
+    async fn handle_event(&mut self, event: ProcessEvent) {
+        if self.filter.matches(&event) {
+            self.inner.handle_event(event).await;
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! 事件历史：在内存中保留最近 N 条事件，便于排查间歇性的重启。
+
+use super::Subscriber;
+use crate::event_bus::ProcessEvent;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// 有界的事件环形缓冲区，克隆后指向同一份数据；超出容量时丢弃最旧的事件。
+#[derive(Debug, Clone)]
+pub struct EventHistory {
+    events: Arc<Mutex<VecDeque<ProcessEvent>>>,
+    capacity: usize,
+}
+
+/// 事件的 JSON 表示，供控制套接字输出
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct EventRecord {
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// 检测时间（RFC 3339，UTC）
+    pub at: String,
+}
+
+impl From<&ProcessEvent> for EventRecord {
+    fn from(event: &ProcessEvent) -> Self {
+        let (pid, url) = match event {
+            ProcessEvent::ProcessDown { pid, .. }
+            | ProcessEvent::ProcessResourceExceeded { pid, .. } => (Some(*pid), None),
+            ProcessEvent::ProcessDisconnected { url, .. } => (None, Some(url.clone())),
+            _ => (None, None),
+        };
+        Self {
+            kind: event.kind(),
+            name: event.process_name().map(str::to_string),
+            pid,
+            url,
+            at: DateTime::<Utc>::from(event.at()).to_rfc3339(),
+        }
+    }
+}
+
+impl EventHistory {
+    /// 容量为 0 时按 1 处理
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            events: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn push(&self, event: ProcessEvent) {
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// 最近的 `limit` 条事件（不超过容量），按发生顺序从旧到新排列
+    pub fn recent(&self, limit: usize) -> Vec<ProcessEvent> {
+        let events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        let skip = events.len().saturating_sub(limit);
+        events.iter().skip(skip).cloned().collect()
+    }
+}
+
+#[async_trait]
+impl Subscriber for EventHistory {
+    async fn handle_event(&mut self, event: ProcessEvent) {
+        self.push(event);
+    }
+}
@@ -0,0 +1,229 @@
+//! 事件转发：把监控器发出的事件以 JSON（与控制套接字的 `EventRecord` 相同，另加 `host` 与实例标签 `labels`）
+//! 发布到外部消息总线，便于集中查看整个集群的故障。目前支持 Redis pub/sub。
+//!
+//! 发布在后台任务中进行：总线不可用时按指数退避重连，期间的事件缓存在有界队列中，
+//! 队列满时丢弃新事件，不会阻塞监控器。
+
+use super::event_history::EventRecord;
+use super::Subscriber;
+use crate::config::{EventForwarderConfig, ForwarderBackend};
+use crate::event_bus::ProcessEvent;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+// 重连退避的初始值与上限
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+// 建立连接与等待应答的超时时间
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 转发到总线的消息体
+#[derive(Debug, Serialize)]
+struct ForwardedEvent<'a> {
+    host: &'a str,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    labels: &'a HashMap<String, String>,
+    #[serde(flatten)]
+    event: &'a EventRecord,
+}
+
+/// 事件转发订阅者：只负责把事件放入本地队列，由 [`EventForwarder::spawn`] 启动的任务发布
+pub struct EventForwarder {
+    tx: mpsc::Sender<ProcessEvent>,
+    dropped: u64,
+}
+
+impl EventForwarder {
+    /// 启动后台发布任务，返回订阅者与任务句柄
+    pub fn spawn(config: EventForwarderConfig) -> (Self, JoinHandle<()>) {
+        Self::spawn_with_labels(config, HashMap::new())
+    }
+
+    /// 同 [`EventForwarder::spawn`]，并在每条消息中附加实例标签
+    pub fn spawn_with_labels(
+        config: EventForwarderConfig,
+        labels: HashMap<String, String>,
+    ) -> (Self, JoinHandle<()>) {
+        let (tx, rx) = mpsc::channel(config.buffer_capacity.max(1));
+        let host = hostname();
+        let handle = tokio::spawn(run(rx, config, host, labels));
+        (Self { tx, dropped: 0 }, handle)
+    }
+
+    /// 因本地队列已满而丢弃的事件数
+    #[allow(dead_code)]
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+#[async_trait]
+impl Subscriber for EventForwarder {
+    async fn handle_event(&mut self, event: ProcessEvent) {
+        match self.tx.try_send(event) {
+            Ok(()) => {}
+            Err(TrySendError::Full(event)) => {
+                self.dropped += 1;
+                warn!(
+                    "EventForwarder: Buffer is full, dropped {:?} ({} dropped so far).",
+                    event, self.dropped
+                );
+            }
+            Err(TrySendError::Closed(_)) => {
+                warn!("EventForwarder: Publisher task has stopped, event not forwarded.");
+            }
+        }
+    }
+}
+
+/// 按模板生成 channel 名：`{host}` 为主机名，`{event}` 为事件类型，`{process}` 为进程名（无关进程的事件为空）
+pub fn render_channel(template: &str, event: &ProcessEvent, host: &str) -> String {
+    let record = EventRecord::from(event);
+    template
+        .replace("{host}", host)
+        .replace("{event}", record.kind)
+        .replace("{process}", record.name.as_deref().unwrap_or(""))
+}
+
+async fn run(
+    mut rx: mpsc::Receiver<ProcessEvent>,
+    config: EventForwarderConfig,
+    host: String,
+    labels: HashMap<String, String>,
+) {
+    info!(
+        "EventForwarder: Forwarding events to {:?} at {}.",
+        config.backend, config.address
+    );
+    let mut publisher = match config.backend {
+        ForwarderBackend::Redis => RedisPublisher::new(config.address.clone()),
+    };
+    while let Some(event) = rx.recv().await {
+        let channel = render_channel(&config.channel, &event, &host);
+        let record = EventRecord::from(&event);
+        let payload = match serde_json::to_string(&ForwardedEvent {
+            host: &host,
+            labels: &labels,
+            event: &record,
+        }) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("EventForwarder: Failed to serialize {:?}: {}", event, e);
+                continue;
+            }
+        };
+
+        // 连接问题时保留当前事件重试，新事件在队列中等待
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            match publisher.publish(&channel, &payload).await {
+                Ok(()) => {
+                    debug!(
+                        "EventForwarder: Published {} to '{}'.",
+                        record.kind, channel
+                    );
+                    break;
+                }
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    warn!(
+                        "EventForwarder: Bus rejected the event for '{}': {}",
+                        channel, e
+                    );
+                    break;
+                }
+                Err(e) => {
+                    warn!(
+                        "EventForwarder: Failed to publish to {}: {}, retrying in {:?}.",
+                        config.address, e, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    }
+    debug!("EventForwarder: Event queue closed, exiting.");
+}
+
+// Redis 连接：每次 PUBLISH 后读取一行应答，连接出错时丢弃，下次发布时重新连接
+struct RedisPublisher {
+    address: String,
+    conn: Option<BufReader<TcpStream>>,
+}
+
+impl RedisPublisher {
+    fn new(address: String) -> Self {
+        Self {
+            address,
+            conn: None,
+        }
+    }
+
+    // 服务端返回错误应答时以 InvalidData 表示，调用方据此放弃该事件而不是重试
+    async fn publish(&mut self, channel: &str, payload: &str) -> io::Result<()> {
+        let mut conn = match self.conn.take() {
+            Some(conn) => conn,
+            None => {
+                let stream =
+                    tokio::time::timeout(IO_TIMEOUT, TcpStream::connect(self.address.as_str()))
+                        .await
+                        .map_err(|_| {
+                            io::Error::new(io::ErrorKind::TimedOut, "connect timed out")
+                        })??;
+                info!("EventForwarder: Connected to {}.", self.address);
+                BufReader::new(stream)
+            }
+        };
+
+        let result = tokio::time::timeout(IO_TIMEOUT, async {
+            conn.get_mut()
+                .write_all(&encode_command(&["PUBLISH", channel, payload]))
+                .await?;
+            let mut reply = String::new();
+            if conn.read_line(&mut reply).await? == 0 {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+            }
+            match reply.strip_prefix('-') {
+                Some(error) => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    error.trim_end().to_string(),
+                )),
+                None => Ok(()),
+            }
+        })
+        .await
+        .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::TimedOut, "reply timed out")));
+
+        // 错误应答不影响连接本身，其余错误断开连接
+        match &result {
+            Err(e) if e.kind() != io::ErrorKind::InvalidData => {}
+            _ => self.conn = Some(conn),
+        }
+        result
+    }
+}
+
+// RESP 数组：*<n>\r\n 后跟 n 个 $<len>\r\n<bytes>\r\n
+fn encode_command(parts: &[&str]) -> Vec<u8> {
+    let mut buf = format!("*{}\r\n", parts.len()).into_bytes();
+    for part in parts {
+        buf.extend_from_slice(format!("${}\r\n", part.len()).as_bytes());
+        buf.extend_from_slice(part.as_bytes());
+        buf.extend_from_slice(b"\r\n");
+    }
+    buf
+}
+
+fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
@@ -0,0 +1,35 @@
+//! 负载前置检查：配置了 `max_load1` 的进程在 1 分钟平均负载超过阈值时推迟恢复，
+//! 由 ProcessHealer 按退避重新排队，负载回落后再执行，避免在已经过载的机器上加剧重启风暴。
+
+use std::time::Duration;
+
+// 推迟恢复的最长退避
+const MAX_DEFER_BACKOFF_SECS: u64 = 30;
+
+/// 系统负载的来源（默认读取 /proc/loadavg，测试中可替换）
+pub trait LoadReader: Send + Sync {
+    /// 1 分钟平均负载，读取失败时为 None
+    fn load1(&self) -> Option<f64>;
+}
+
+/// 读取 /proc/loadavg
+pub struct ProcLoadAvg;
+
+impl LoadReader for ProcLoadAvg {
+    fn load1(&self) -> Option<f64> {
+        parse_load1(&std::fs::read_to_string("/proc/loadavg").ok()?)
+    }
+}
+
+/// 解析 /proc/loadavg 的第一个字段
+pub fn parse_load1(content: &str) -> Option<f64> {
+    content.split_whitespace().next()?.parse().ok()
+}
+
+/// 第 `attempts` 次推迟后等待的时间：1 秒起倍增，最长 30 秒
+pub fn defer_backoff(attempts: u32) -> Duration {
+    let secs = 1u64
+        .checked_shl(attempts.saturating_sub(1))
+        .unwrap_or(u64::MAX);
+    Duration::from_secs(secs.min(MAX_DEFER_BACKOFF_SECS))
+}
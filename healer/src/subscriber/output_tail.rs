@@ -0,0 +1,115 @@
+//! 被恢复子进程输出的内存尾部：从管道读取 stdout/stderr，原样写往原来的去向（日志文件、终端或丢弃），
+//! 同时把最后若干行保存在每个进程的环形缓冲区中，供控制套接字的 `tail` 命令查看。
+
+use std::collections::VecDeque;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::process::Child;
+
+/// 单行保留的最大字节数，更长的行被拆成多行
+pub const MAX_LINE_BYTES: usize = 1024;
+
+/// 子进程输出原本的去向
+#[derive(Debug)]
+pub enum OutputSink {
+    /// stdout 与 stderr 写入同一个文件
+    File(fs::File),
+    /// 写到 healer 自身的 stdout / stderr
+    Inherit,
+    /// 只保留在内存中
+    Null,
+}
+
+/// 一个进程的输出尾部，最多保留 capacity 行；进程多次恢复时沿用同一个缓冲区
+#[derive(Debug)]
+pub struct OutputTail {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl OutputTail {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// 调整容量（配置重载后），超出时丢弃最旧的行
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.lines.len() > self.capacity {
+            self.lines.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, line: &[u8]) {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines
+            .push_back(String::from_utf8_lossy(line).into_owned());
+    }
+
+    /// 按时间顺序返回保留的行
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+}
+
+/// 接管子进程的 stdout / stderr 管道（需以 `Stdio::piped()` 启动），在后台任务中转发并记录
+pub fn capture(child: &mut Child, sink: OutputSink, tail: Arc<Mutex<OutputTail>>) {
+    let (stdout_sink, stderr_sink): (Option<Writer>, Option<Writer>) = match sink {
+        OutputSink::File(file) => {
+            let stderr_file = file.try_clone().ok();
+            (
+                Some(Box::new(tokio::fs::File::from_std(file))),
+                stderr_file.map(|file| Box::new(tokio::fs::File::from_std(file)) as Writer),
+            )
+        }
+        OutputSink::Inherit => (
+            Some(Box::new(tokio::io::stdout())),
+            Some(Box::new(tokio::io::stderr())),
+        ),
+        OutputSink::Null => (None, None),
+    };
+    if let Some(stdout) = child.stdout.take() {
+        tokio::spawn(forward(stdout, stdout_sink, Arc::clone(&tail)));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(forward(stderr, stderr_sink, tail));
+    }
+}
+
+type Writer = Box<dyn AsyncWrite + Unpin + Send>;
+
+// 逐行读取直到管道关闭（子进程及其后代都已退出）；写往原去向失败时不再写出，但继续记录
+async fn forward<R: AsyncRead + Unpin>(
+    reader: R,
+    mut sink: Option<Writer>,
+    tail: Arc<Mutex<OutputTail>>,
+) {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::with_capacity(MAX_LINE_BYTES);
+    loop {
+        line.clear();
+        // 限制单次读取的长度，没有换行的超长输出不会无限占用内存
+        match (&mut reader)
+            .take(MAX_LINE_BYTES as u64)
+            .read_until(b'\n', &mut line)
+            .await
+        {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if let Some(writer) = sink.as_mut() {
+            if writer.write_all(&line).await.is_err() || writer.flush().await.is_err() {
+                sink = None;
+            }
+        }
+        tail.lock().unwrap_or_else(|e| e.into_inner()).push(&line);
+    }
+}
@@ -1,50 +1,370 @@
-use super::Subscriber;
-use crate::config::{AppConfig, RecoveryConfig};
-use crate::event_bus::ProcessEvent;
+use super::load_guard::{self, LoadReader, ProcLoadAvg};
+use super::{child_registry::ChildRegistry, output_tail::OutputSink, Subscriber};
+use crate::config::{
+    command_allowed, AppConfig, ChildOutput, CommandGlobPolicy, FailureKind, HookConfig,
+    MonitorConfig, NamespaceKind, NsenterConfig, PidSource, ProcessConfig, RecoveryAction,
+    RecoveryConfig, RecoveryDefaults, SignalName, SignalPidSource,
+};
+use crate::config_graph;
+use crate::event_bus::{EventReceiver, ProcessEvent};
+use crate::health::{CircuitRecord, CircuitState, HealthRegistry, HealthState};
+use crate::log_janitor::CHILD_LOG_DIR;
+use crate::metrics::{LatencyKind, Metrics};
+use crate::reaper;
+use crate::utils;
 use async_trait::async_trait;
-use std::collections::{HashMap, VecDeque};
+use nix::sched::{setns, CloneFlags};
+use nix::sys::signal::{kill, killpg, Signal};
+use nix::unistd::{setgid, setgroups, setuid, Gid, Pid, Uid};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::io::Read;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::fs::PermissionsExt;
 use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::process::{Command, Stdio};
-use std::{fs, sync::Arc, time::Instant};
+use std::{
+    fmt, fs, io,
+    sync::Arc,
+    time::{Instant, SystemTime},
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tokio::sync::RwLock;
 use tokio::sync::{broadcast, Mutex};
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 use users::get_user_by_name;
 
-#[derive(PartialEq)]
-enum State {
-    Closed,
-    Open,
-    HalfOpen,
-}
 struct ProcessRecoveryStats {
     recovery_session_starts: VecDeque<Instant>,
-    recovery_state: State,
+    recovery_state: CircuitState,
+    // 进入当前状态的时间
+    state_since: Instant,
     in_cooldown_until: Option<Instant>,
     half_open_safe_until: Option<Instant>,
+    // 连续进入 Open 的次数，半开观察期平稳度过后清零
+    trips: u32,
+    // 监控确认健康（ProcessUp）后持续健康的起点，之后的任何故障都会清除
+    healthy_since: Option<Instant>,
     // half_open_retry_flag: Option<bool>,
 }
 impl Default for ProcessRecoveryStats {
     fn default() -> Self {
         Self {
             recovery_session_starts: VecDeque::new(),
-            recovery_state: State::Closed,
+            recovery_state: CircuitState::Closed,
+            state_since: Instant::now(),
             in_cooldown_until: None,
             half_open_safe_until: None,
+            trips: 0,
+            healthy_since: None,
+        }
+    }
+}
+impl ProcessRecoveryStats {
+    fn enter(&mut self, state: CircuitState) {
+        if state == CircuitState::Open {
+            self.trips += 1;
+        }
+        self.recovery_state = state;
+        self.state_since = Instant::now();
+    }
+
+    // 连续健康达到 healthy_reset_secs 时清空全部熔断历史，返回是否清空。隔离只能手动解除，不受影响
+    fn forgive_if_healthy(&mut self, healthy_reset_secs: u64) -> bool {
+        if healthy_reset_secs == 0 || self.recovery_state == CircuitState::Quarantined {
+            return false;
+        }
+        match self.healthy_since {
+            Some(since) if since.elapsed().as_secs() >= healthy_reset_secs => {
+                *self = Self::default();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // 供状态查询使用的记录，尝试时间换算为墙钟时间
+    fn record(&self) -> CircuitRecord {
+        let now = SystemTime::now();
+        CircuitRecord {
+            state: self.recovery_state,
+            since: self.state_since,
+            cooldown_until: self.in_cooldown_until,
+            attempts: self
+                .recovery_session_starts
+                .iter()
+                .map(|start| now - start.elapsed())
+                .collect(),
+        }
+    }
+}
+
+/// 一次恢复被跳过的原因，由 [`ProcessHealer::heal_process_for`] 返回并统一记录日志
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecoverySkipReason {
+    /// 配置中没有该进程（可能刚被移除）
+    MissingConfig,
+    /// 进程已停用
+    Disabled,
+    /// 去重窗口内的重复故障事件，恢复已经执行过
+    Duplicate,
+    /// 共享同一合并键的进程刚刚被重启
+    Coalesced { key: String },
+    /// recovery_guard 拒绝了本次恢复，不计入熔断器
+    GuardRefused,
+    /// recovery_guard 超过 recovery_timeout_secs，按一次失败的恢复计入熔断器
+    GuardTimedOut,
+    /// 1 分钟平均负载超过 max_load1，收到的故障事件按退避重新排队，不计入熔断器
+    LoadTooHigh { load1: f64, max_load1: f64 },
+    /// 熔断器打开，冷却期到 `until` 结束
+    CircuitOpen { until: Instant },
+    /// 反复熔断后进程被隔离，等待手动 resume 或重新加载配置
+    Quarantined,
+    /// 进程被控制命令 stop 停止，等待 start
+    Paused,
+    /// 无法确定安全的运行身份，且禁止回退为 root
+    RootRefused,
+    /// 无法准备恢复命令（日志文件无法打开、命名空间无法进入等）
+    CommandUnavailable,
+    /// 恢复命令不在 allowed_commands 中，未拉起，按一次失败的恢复计入熔断器
+    CommandNotAllowed { program: PathBuf },
+    /// exec 恢复命令时权限不足（EACCES），在冷却期内不再尝试；`missing_exec_bit` 表示文件没有任何执行位
+    PermissionDenied {
+        program: PathBuf,
+        missing_exec_bit: bool,
+    },
+    /// exec 之前切换 run_as_user 身份或进入目标命名空间时被拒绝（EPERM），在冷却期内不再尝试
+    PrivilegeDropFailed { program: PathBuf },
+    /// 恢复命令不存在（ENOENT），在冷却期内不再尝试
+    CommandNotFound { program: PathBuf },
+    /// 上一次拉起因权限不足或命令不存在而失败，退避到 `until` 结束
+    SpawnBackoff { until: Instant },
+    /// 开启 wait_for_exit 的恢复命令以非零状态退出或超时，已按一次失败的恢复计入熔断器
+    RecoveryFailed { detail: String },
+    /// 上一次开启 wait_for_exit 的恢复命令仍在运行
+    RecoveryInProgress,
+}
+
+impl RecoverySkipReason {
+    /// 日志字段 `skip_reason` 使用的短名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecoverySkipReason::MissingConfig => "missing_config",
+            RecoverySkipReason::Disabled => "disabled",
+            RecoverySkipReason::Duplicate => "duplicate",
+            RecoverySkipReason::Coalesced { .. } => "coalesced",
+            RecoverySkipReason::GuardRefused => "guard_refused",
+            RecoverySkipReason::GuardTimedOut => "guard_timed_out",
+            RecoverySkipReason::LoadTooHigh { .. } => "load_too_high",
+            RecoverySkipReason::CircuitOpen { .. } => "circuit_open",
+            RecoverySkipReason::Quarantined => "quarantined",
+            RecoverySkipReason::Paused => "paused",
+            RecoverySkipReason::RootRefused => "root_refused",
+            RecoverySkipReason::CommandUnavailable => "command_unavailable",
+            RecoverySkipReason::CommandNotAllowed { .. } => "command_not_allowed",
+            RecoverySkipReason::PermissionDenied { .. } => "permission_denied",
+            RecoverySkipReason::PrivilegeDropFailed { .. } => "privilege_drop_failed",
+            RecoverySkipReason::CommandNotFound { .. } => "command_not_found",
+            RecoverySkipReason::SpawnBackoff { .. } => "spawn_backoff",
+            RecoverySkipReason::RecoveryFailed { .. } => "recovery_failed",
+            RecoverySkipReason::RecoveryInProgress => "recovery_in_progress",
+        }
+    }
+
+    /// 是否表示恢复失败，而不是预期内的跳过
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            RecoverySkipReason::GuardTimedOut
+                | RecoverySkipReason::CircuitOpen { .. }
+                | RecoverySkipReason::RootRefused
+                | RecoverySkipReason::CommandUnavailable
+                | RecoverySkipReason::CommandNotAllowed { .. }
+                | RecoverySkipReason::PermissionDenied { .. }
+                | RecoverySkipReason::PrivilegeDropFailed { .. }
+                | RecoverySkipReason::CommandNotFound { .. }
+                | RecoverySkipReason::SpawnBackoff { .. }
+                | RecoverySkipReason::RecoveryFailed { .. }
+        )
+    }
+}
+
+impl fmt::Display for RecoverySkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecoverySkipReason::MissingConfig => write!(f, "no configuration found for process"),
+            RecoverySkipReason::Disabled => write!(f, "process is disabled"),
+            RecoverySkipReason::Duplicate => {
+                write!(f, "duplicate failure event within the dedup window")
+            }
+            RecoverySkipReason::Coalesced { key } => write!(
+                f,
+                "another process sharing coalesce key '{}' was just restarted",
+                key
+            ),
+            RecoverySkipReason::GuardRefused => write!(f, "recovery guard refused"),
+            RecoverySkipReason::GuardTimedOut => write!(
+                f,
+                "recovery guard hung, counted as a failed recovery attempt"
+            ),
+            RecoverySkipReason::LoadTooHigh { load1, max_load1 } => write!(
+                f,
+                "load average {:.2} exceeds max_load1 {:.2}, recovery deferred",
+                load1, max_load1
+            ),
+            RecoverySkipReason::CircuitOpen { until } => write!(
+                f,
+                "circuit breaker is open for another {}s",
+                until.saturating_duration_since(Instant::now()).as_secs()
+            ),
+            RecoverySkipReason::Quarantined => write!(
+                f,
+                "process is quarantined after repeated circuit breaker trips, waiting for a manual resume"
+            ),
+            RecoverySkipReason::Paused => {
+                write!(f, "process was stopped by an operator, waiting for start")
+            }
+            RecoverySkipReason::RootRefused => write!(
+                f,
+                "run_as_user is missing or invalid and forbid_root_fallback is set, refusing to run as root"
+            ),
+            RecoverySkipReason::CommandUnavailable => {
+                write!(f, "the recovery command could not be prepared")
+            }
+            RecoverySkipReason::CommandNotAllowed { program } => write!(
+                f,
+                "recovery command {} is not in allowed_commands, counted as a failed recovery attempt",
+                program.display()
+            ),
+            RecoverySkipReason::PermissionDenied {
+                program,
+                missing_exec_bit: true,
+            } => write!(
+                f,
+                "permission denied executing {}, it has no execute permission (chmod +x); backing off for the cooldown period",
+                program.display()
+            ),
+            RecoverySkipReason::PermissionDenied {
+                program,
+                missing_exec_bit: false,
+            } => write!(
+                f,
+                "permission denied executing {}, check that its ownership and directory permissions allow the configured user to run it and that it is not on a noexec mount; backing off for the cooldown period",
+                program.display()
+            ),
+            RecoverySkipReason::PrivilegeDropFailed { program } => write!(
+                f,
+                "operation not permitted while switching to run_as_user or entering the target namespaces before executing {}, check that healer runs as root; backing off for the cooldown period",
+                program.display()
+            ),
+            RecoverySkipReason::CommandNotFound { program } => write!(
+                f,
+                "recovery command {} does not exist, check the command path or install it; backing off for the cooldown period",
+                program.display()
+            ),
+            RecoverySkipReason::SpawnBackoff { until } => write!(
+                f,
+                "the last spawn failed with a permission or missing-command error, retrying in {}s",
+                until.saturating_duration_since(Instant::now()).as_secs()
+            ),
+            RecoverySkipReason::RecoveryFailed { detail } => write!(
+                f,
+                "recovery command {}, counted as a failed recovery attempt",
+                detail
+            ),
+            RecoverySkipReason::RecoveryInProgress => {
+                write!(f, "the previous recovery command is still running")
+            }
         }
     }
 }
+
 pub struct ProcessHealer {
-    pub event_rx: broadcast::Receiver<ProcessEvent>,
+    pub event_rx: EventReceiver,
     pub app_config: Arc<RwLock<AppConfig>>,
     process_recovery_windows: Mutex<HashMap<String, ProcessRecoveryStats>>,
+    // 按重启组名记录的共享熔断状态
+    group_recovery_windows: Mutex<HashMap<String, ProcessRecoveryStats>>,
+    // 是否以前台模式运行（决定 child_output: inherit 是否可用）
+    foreground: bool,
+    // 为 false 时（--no-drop-privileges）不切换 uid/gid，所有恢复命令以守护进程自身的身份运行
+    drop_privileges: bool,
+    // 各进程的健康状态，由收到的事件与恢复结果更新
+    health: HealthRegistry,
+    // 恢复时拉起的子进程
+    children: ChildRegistry,
+    // 合并键 -> 合并窗口的截止时间，窗口内共享该键的恢复不再重复执行
+    coalesce_until: HashMap<String, Instant>,
+    // 进程名 -> 最近一次执行恢复的时间，用于去重窗口
+    last_recovery: HashMap<String, Instant>,
+    // 进程名 -> 最近一次恢复被跳过的原因
+    last_skip: HashMap<String, RecoverySkipReason>,
+    // 按进程统计的恢复延迟
+    metrics: Metrics,
+    // 正在处理的故障事件的检测时间，用于计算检测到恢复的延迟
+    detected_at: Option<SystemTime>,
+    // 正在处理的故障事件上报的、仍在运行的实例（进程名, PID），恢复时先终止它再拉起
+    running_instance: Option<(String, u32)>,
+    // 读取系统负载，供 max_load1 检查使用
+    load_reader: Arc<dyn LoadReader>,
+    // 进程名 -> 因负载过高推迟恢复的状态
+    load_deferrals: HashMap<String, LoadDeferral>,
+    // 进程名 -> 拉起因权限不足或命令不存在而失败后的退避截止时间
+    spawn_backoff: HashMap<String, Instant>,
+    // 推迟的故障事件在退避结束后经由该通道重新进入 next_batch
+    load_retry_tx: UnboundedSender<ProcessEvent>,
+    load_retry_rx: UnboundedReceiver<ProcessEvent>,
+    // 恢复命令仍在后台等待（wait_for_exit）的进程，期间同一进程的故障事件被跳过
+    waiting: HashSet<String>,
+    // 进程名 -> 该进程的恢复命令结束后继续的子树重启
+    subtree_after: HashMap<String, SubtreeRestart>,
+    // 后台等待的恢复命令结束后经由该通道回到 next_batch
+    wait_done_tx: UnboundedSender<WaitedRecovery>,
+    wait_done_rx: UnboundedReceiver<WaitedRecovery>,
+}
+
+// 后台等待结束的 wait_for_exit 恢复
+struct WaitedRecovery {
+    process_config: ProcessConfig,
+    coalesce: Option<(String, std::time::Duration)>,
+    cascade: bool,
+    result: Result<(), RecoverySkipReason>,
+}
+
+// restart_subtree 中尚未重启的依赖方
+struct SubtreeRestart {
+    root: String,
+    remaining: VecDeque<String>,
+    stop_timeout: std::time::Duration,
+}
+
+#[derive(Default)]
+struct LoadDeferral {
+    // 连续推迟的次数，决定下一次退避
+    attempts: u32,
+    // 是否已有一个重新排队的事件在等待，避免同一进程的重复事件各自排队
+    pending: bool,
+}
+
+// 通过所有检查、可以执行的恢复
+struct RecoveryPlan {
+    process_config: ProcessConfig,
+    run_as: RunAs,
+    coalesce: Option<(String, std::time::Duration)>,
+    // 写入子进程日志前要求的最小可用空间
+    min_free_bytes: Option<u64>,
+    // 全局 reparent_children：经两次 fork 让拉起的进程脱离 healer
+    reparent: bool,
+    // 全局 allowed_commands，None 表示不限制
+    allowed_commands: Option<Vec<PathBuf>>,
+    // 拉起因权限不足或命令不存在而失败时的退避时长（熔断冷却期）
+    spawn_backoff: std::time::Duration,
 }
 
 impl ProcessHealer {
-    pub async fn new(
-        rx: broadcast::Receiver<ProcessEvent>,
-        config: Arc<RwLock<AppConfig>>,
-    ) -> Self {
+    pub async fn new(rx: impl Into<EventReceiver>, config: Arc<RwLock<AppConfig>>) -> Self {
         let recover_map = {
             let config_guard = config.read().await;
             config_guard
@@ -53,154 +373,975 @@ impl ProcessHealer {
                 .map(|p| (p.name.clone(), ProcessRecoveryStats::default()))
                 .collect::<HashMap<String, ProcessRecoveryStats>>()
         }; // 读锁在这个作用域结束时自动释放
+        let (load_retry_tx, load_retry_rx) = unbounded_channel();
+        let (wait_done_tx, wait_done_rx) = unbounded_channel();
 
         Self {
-            event_rx: rx,
+            event_rx: rx.into(),
             app_config: config,
             process_recovery_windows: Mutex::new(recover_map),
+            group_recovery_windows: Mutex::new(HashMap::new()),
+            foreground: false,
+            drop_privileges: true,
+            health: HealthRegistry::new(),
+            children: ChildRegistry::new(),
+            coalesce_until: HashMap::new(),
+            last_recovery: HashMap::new(),
+            last_skip: HashMap::new(),
+            metrics: Metrics::new(),
+            detected_at: None,
+            running_instance: None,
+            load_reader: Arc::new(ProcLoadAvg),
+            load_deferrals: HashMap::new(),
+            spawn_backoff: HashMap::new(),
+            load_retry_tx,
+            load_retry_rx,
+            waiting: HashSet::new(),
+            subtree_after: HashMap::new(),
+            wait_done_tx,
+            wait_done_rx,
         }
     }
 
-    pub async fn heal_process(&mut self, name: &String) {
-        // 使用超时机制获取配置锁，避免无限期阻塞
-        //breaker 返回true，说明仍在熔断；返回false说明可以执行
-        if self.check_circuit_breaker(&name).await {
-            warn!(
-                target = "healer_action",
-                process_name = %name,
-                "Circuit breaker is open, skipping recovery for process {}.",
-                name
-            );
+    /// 使用共享的健康状态表（例如供状态查询使用）
+    pub fn with_health(mut self, health: HealthRegistry) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// 使用共享的延迟指标表
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 替换系统负载的来源（默认读取 /proc/loadavg）
+    #[allow(dead_code)]
+    pub fn with_load_reader(mut self, load_reader: Arc<dyn LoadReader>) -> Self {
+        self.load_reader = load_reader;
+        self
+    }
+
+    /// 使用共享的子进程登记表（例如供僵尸进程回收服务跳过已登记的子进程）
+    pub fn with_children(mut self, children: ChildRegistry) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// reparent_children 开启时，重新登记上一次运行中恢复、仍在运行的进程：
+    /// 按 PID 文件查找，PID 文件过期且开启了 reconcile_pid_file 时按可执行文件路径查找
+    pub async fn adopt_running_children(&self) {
+        let processes: Vec<ProcessConfig> = {
+            let config = self.app_config.read().await;
+            if !config.reparent_children {
+                return;
+            }
+            config
+                .processes
+                .iter()
+                .filter(|p| p.enabled)
+                .cloned()
+                .collect()
+        };
+        for process_config in processes {
+            let name = process_config.name.clone();
+            let found = tokio::task::spawn_blocking(move || running_pid(&process_config))
+                .await
+                .ok()
+                .flatten();
+            if let Some(pid) = found {
+                info!(target: "healer_event", process_name = %name, process_pid = pid, "Re-adopted a process left running by a previous healer.");
+                self.children.adopt(&name, pid);
+            }
+        }
+    }
+
+    /// 等待下一个事件，并一并取出此时已经排队的事件，按进程的 priority 从高到低排序。
+    /// 同一进程的事件优先级相同，排序稳定，因此它们的相对顺序不变。
+    /// 因负载过高推迟、退避已结束的事件同样从这里返回；等待期间顺带完成在后台等待的 wait_for_exit 恢复
+    pub async fn next_batch(&mut self) -> Result<Vec<ProcessEvent>, broadcast::error::RecvError> {
+        let first = loop {
+            tokio::select! {
+                event = self.event_rx.recv() => break event?,
+                Some(event) = self.load_retry_rx.recv() => break self.load_retry_due(event),
+                // 后台等待的恢复命令已结束，完成恢复后继续等待事件
+                Some(waited) = self.wait_done_rx.recv() => self.finish_waited(waited).await,
+            }
+        };
+        let mut batch = vec![first];
+        while let Some(event) = self.event_rx.try_recv() {
+            batch.push(event);
+        }
+        while let Ok(event) = self.load_retry_rx.try_recv() {
+            let event = self.load_retry_due(event);
+            batch.push(event);
+        }
+        if batch.len() > 1 {
+            let config = self.app_config.read().await;
+            let priority_of = |event: &ProcessEvent| {
+                event
+                    .process_name()
+                    .and_then(|name| config.get_process_config_for(name))
+                    .map_or(0, |p| p.priority)
+            };
+            batch.sort_by_key(|event| std::cmp::Reverse(priority_of(event)));
+        }
+        Ok(batch)
+    }
+
+    // 重新排队的事件已取出，之后再次推迟时可以重新安排
+    fn load_retry_due(&mut self, event: ProcessEvent) -> ProcessEvent {
+        if let Some(deferral) = event
+            .process_name()
+            .and_then(|name| self.load_deferrals.get_mut(name))
+        {
+            deferral.pending = false;
+        }
+        event
+    }
+
+    // 负载过高时推迟恢复：退避结束后把原事件重新交给 next_batch（保留检测时间，延迟指标包含推迟的时间）。
+    // 已有排队中的事件时只累计次数，不重复排队
+    fn defer_for_load(&mut self, event: ProcessEvent) {
+        let Some(name) = event.process_name().map(str::to_string) else {
+            return;
+        };
+        let deferral = self.load_deferrals.entry(name.clone()).or_default();
+        deferral.attempts += 1;
+        if deferral.pending {
+            return;
+        }
+        deferral.pending = true;
+        let delay = load_guard::defer_backoff(deferral.attempts);
+        info!(target: "healer_action", process_name = %name, attempts = deferral.attempts, retry_in_secs = delay.as_secs(), "Recovery deferred until the load average drops.");
+        let tx = self.load_retry_tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _ = tx.send(event);
+        });
+    }
+
+    /// 标记是否运行在前台（有终端），仅前台模式下允许子进程继承 stdio
+    pub fn with_foreground(mut self, foreground: bool) -> Self {
+        self.foreground = foreground;
+        self
+    }
+
+    /// 为 false 时不切换 uid/gid（调试用的安全模式，用于区分命令本身的问题与权限问题），生产环境不要关闭
+    pub fn with_drop_privileges(mut self, drop_privileges: bool) -> Self {
+        self.drop_privileges = drop_privileges;
+        self
+    }
+
+    /// 按进程退出处理，等价于 `heal_process_for(name, FailureKind::Down)`
+    #[allow(dead_code)]
+    pub async fn heal_process(&mut self, name: &String) -> Option<RecoverySkipReason> {
+        self.heal_process_for(name, FailureKind::Down).await
+    }
+
+    /// 恢复进程，按故障类型选择 recovery_commands 中的命令；恢复被跳过时返回原因
+    pub async fn heal_process_for(
+        &mut self,
+        name: &String,
+        kind: FailureKind,
+    ) -> Option<RecoverySkipReason> {
+        self.heal(name, kind, true).await
+    }
+
+    // cascade 为 false 时不展开 restart_subtree，用于重启子树中的进程（子树已包含其传递依赖方）
+    async fn heal(
+        &mut self,
+        name: &String,
+        kind: FailureKind,
+        cascade: bool,
+    ) -> Option<RecoverySkipReason> {
+        // 不计入熔断器：上一次恢复的结果尚未确定
+        if self.waiting.contains(name) {
+            return Some(self.report_skip(name, RecoverySkipReason::RecoveryInProgress));
+        }
+        let plan = match self.decide_recovery(name).await {
+            Ok(plan) => plan,
+            Err(reason) => return Some(self.report_skip(name, reason)),
+        };
+        let RecoveryPlan {
+            process_config,
+            run_as,
+            coalesce,
+            min_free_bytes,
+            reparent,
+            allowed_commands,
+            spawn_backoff,
+        } = plan;
+
+        if let RecoveryAction::Signal { signal, pid_source } = &process_config.recovery_action {
+            self.recover_by_signal(&process_config, *signal, pid_source, coalesce)
+                .await;
+            return None;
+        }
+
+        info!(target: "healer_event", process_name = %name, failure = %kind, "Parsed the restart command. Conducting recovery.");
+
+        // 先检查 allowed_commands，被拒绝的命令不截断上一次的日志、不准备 pre_exec
+        let Some(program) = resolve_recovery_program(&process_config, kind) else {
+            return Some(self.report_skip(name, RecoverySkipReason::CommandUnavailable));
+        };
+        if let Some(allowed) = &allowed_commands {
+            if !command_allowed(allowed, &program) {
+                return Some(
+                    self.report_skip(name, RecoverySkipReason::CommandNotAllowed { program }),
+                );
+            }
+        }
+        let Some(mut command) = self.build_command(
+            &process_config,
+            &program,
+            run_as,
+            kind,
+            min_free_bytes,
+            reparent,
+        ) else {
+            return Some(self.report_skip(name, RecoverySkipReason::CommandUnavailable));
+        };
+        let output_tail = command.output_tail.take();
+        let reparent_pipe = command.reparent_pipe.take();
+
+        // 上报故障的实例仍在运行（如内存持续增长）：已决定恢复，先终止它，避免拉起后出现两个实例
+        if let Some((_, pid)) = self
+            .running_instance
+            .take()
+            .filter(|(instance, _)| instance == name)
+        {
+            info!(target: "healer_action", process_name = %name, process_pid = pid, "Terminating the running instance before restarting it.");
+            let stop_timeout = std::time::Duration::from_secs(RUNNING_INSTANCE_STOP_SECS);
+            utils::terminate(name, pid, stop_timeout).await;
+        }
+
+        // fork 时即登记为回收服务跳过的子进程，直到交给 ChildRegistry 跟踪或等待结束，
+        // 启动即退出的子进程不会在此之前被回收服务取走退出状态
+        match reaper::spawn(|| command.spawn()) {
+            Ok((mut child, exemption)) => {
+                if let Some(pipe) = reparent_pipe {
+                    self.adopt_reparented(name, child, pipe);
+                } else {
+                    info!(target: "healer_event", process_name = %name, process_pid = child.id().unwrap_or_default(), "Successfully restarted process.");
+                    self.record_recovery_latency(name);
+                    if let Some((lines, sink)) = output_tail {
+                        self.children.capture_output(name, &mut child, lines, sink);
+                    }
+                    if process_config.wait_for_exit {
+                        // 同步恢复：命令退出成功才算恢复。在后台等待，不占用事件循环，结束后由 next_batch 完成恢复
+                        self.wait_in_background(
+                            process_config,
+                            coalesce,
+                            cascade,
+                            child,
+                            exemption,
+                        );
+                        return None;
+                    } else {
+                        // 由后台任务等待子进程退出，以便观察启动即退出等情况
+                        let tracked = self.children.track(name, child);
+                        if let (Some(tracked), Some(core_config)) =
+                            (tracked, process_config.capture_core.clone())
+                        {
+                            let comm = utils::truncate_process_name(
+                                &utils::extract_executable_name(&process_config.command),
+                            );
+                            let cwd =
+                                std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+                            self.children.capture_core_on_crash(
+                                name,
+                                tracked,
+                                comm,
+                                cwd,
+                                core_config,
+                            );
+                        }
+                    }
+                }
+                drop(exemption);
+                self.complete_recovery(&process_config, coalesce, cascade)
+                    .await;
+            }
+            // 权限不足与命令不存在在人工修复前不会自愈，立即重试没有意义，退避一个冷却期
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                self.spawn_backoff
+                    .insert(name.clone(), Instant::now() + spawn_backoff);
+                // EPERM 来自 exec 之前的 setuid/setgid/setgroups 或 setns；EACCES 来自 exec 本身
+                let reason = if e.raw_os_error() == Some(libc::EPERM) {
+                    RecoverySkipReason::PrivilegeDropFailed { program }
+                } else {
+                    let missing_exec_bit = fs::metadata(&program)
+                        .is_ok_and(|metadata| metadata.permissions().mode() & 0o111 == 0);
+                    RecoverySkipReason::PermissionDenied {
+                        program,
+                        missing_exec_bit,
+                    }
+                };
+                return Some(self.report_skip(name, reason));
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.spawn_backoff
+                    .insert(name.clone(), Instant::now() + spawn_backoff);
+                return Some(
+                    self.report_skip(name, RecoverySkipReason::CommandNotFound { program }),
+                );
+            }
+            Err(e) => {
+                tracing::error!(target: "healer_action",
+                    process_name = %name,
+                    error = %e,
+                    "Failed to restart process.");
+            }
+        }
+        None
+    }
+
+    // 根进程已拉起：按拓扑顺序逐个停止并恢复传递依赖它的进程。
+    // 单个进程的恢复被跳过时记录原因并继续，依赖成环或范围过大时不重启任何依赖方
+    async fn restart_subtree(
+        &mut self,
+        root: &String,
+        max_dependents: usize,
+        stop_timeout: std::time::Duration,
+    ) {
+        let order = {
+            let config = self.app_config.read().await;
+            config_graph::subtree_restart_order(&config, root, max_dependents)
+        };
+        let order = match order {
+            Ok(order) => order,
+            Err(e) => {
+                warn!(target: "healer_action", process_name = %root, error = %e, "Not restarting the dependency subtree.");
+                return;
+            }
+        };
+        if order.is_empty() {
             return;
         }
+        info!(target: "healer_action", process_name = %root, dependents = %order.join(", "), "Restarting the dependency subtree.");
+        self.continue_subtree(SubtreeRestart {
+            root: root.clone(),
+            remaining: order.into(),
+            stop_timeout,
+        })
+        .await;
+    }
+
+    // 依次停止并恢复子树中剩下的进程。依赖方的恢复命令在后台等待（wait_for_exit）时暂停，
+    // 等它结束后由 finish_waited 继续，保证依赖方按顺序完成恢复
+    async fn continue_subtree(&mut self, mut subtree: SubtreeRestart) {
+        while let Some(dependent) = subtree.remaining.pop_front() {
+            if let Some(pid) = self.children.stop(&dependent, subtree.stop_timeout).await {
+                debug!(target: "healer_action", process_name = %dependent, process_pid = pid, "Stopped dependent before restarting it.");
+            }
+            // 显式装箱为 dyn Future，打断 heal -> restart_subtree -> heal 的递归类型
+            let recovery: Pin<Box<dyn Future<Output = Option<RecoverySkipReason>> + Send + '_>> =
+                Box::pin(self.heal(&dependent, FailureKind::Down, false));
+            match recovery.await {
+                Some(reason) => {
+                    warn!(target: "healer_action", process_name = %dependent, root = %subtree.root, skip_reason = reason.as_str(), "Dependent was not restarted with its subtree.");
+                }
+                None if self.waiting.contains(&dependent) => {
+                    self.subtree_after.insert(dependent, subtree);
+                    return;
+                }
+                None => {}
+            }
+        }
+    }
+
+    // 恢复已执行：记录恢复；直接处理的故障按 restart_subtree 继续重启依赖它的进程
+    async fn complete_recovery(
+        &mut self,
+        process_config: &ProcessConfig,
+        coalesce: Option<(String, std::time::Duration)>,
+        cascade: bool,
+    ) {
+        self.mark_recovered(process_config, coalesce);
+        if let RecoveryAction::RestartSubtree {
+            max_dependents,
+            stop_timeout_secs,
+        } = process_config.recovery_action
+        {
+            if cascade {
+                let stop_timeout = std::time::Duration::from_secs(stop_timeout_secs);
+                self.restart_subtree(&process_config.name, max_dependents, stop_timeout)
+                    .await;
+            }
+        }
+    }
+
+    // wait_for_exit：在后台等待恢复命令退出，期间保持回收服务的豁免，结果经由通道交回 next_batch
+    fn wait_in_background(
+        &mut self,
+        process_config: ProcessConfig,
+        coalesce: Option<(String, std::time::Duration)>,
+        cascade: bool,
+        child: tokio::process::Child,
+        exemption: reaper::Exemption,
+    ) {
+        self.waiting.insert(process_config.name.clone());
+        let tx = self.wait_done_tx.clone();
+        tokio::spawn(async move {
+            let result = wait_recovery_command(&process_config, child).await;
+            drop(exemption);
+            let _ = tx.send(WaitedRecovery {
+                process_config,
+                coalesce,
+                cascade,
+                result,
+            });
+        });
+    }
+
+    // 后台等待的恢复命令已结束：成功才算恢复，失败时不进入去重窗口，下一次故障事件立即重试；
+    // 该进程属于正在重启的子树时，之后继续重启子树中剩下的进程
+    async fn finish_waited(&mut self, waited: WaitedRecovery) {
+        let WaitedRecovery {
+            process_config,
+            coalesce,
+            cascade,
+            result,
+        } = waited;
+        let name = process_config.name.clone();
+        self.waiting.remove(&name);
+        let subtree = self.subtree_after.remove(&name);
+        match result {
+            Ok(()) => {
+                self.complete_recovery(&process_config, coalesce, cascade)
+                    .await
+            }
+            Err(reason) => {
+                let reason = self.report_skip(&name, reason);
+                if let Some(subtree) = &subtree {
+                    warn!(target: "healer_action", process_name = %name, root = %subtree.root, skip_reason = reason.as_str(), "Dependent was not restarted with its subtree.");
+                }
+            }
+        }
+        if let Some(subtree) = subtree {
+            self.continue_subtree(subtree).await;
+        }
+    }
+
+    /// 等待所有在后台等待的 wait_for_exit 恢复命令结束并完成恢复（包括由此继续的子树重启）。
+    /// 服务中由 next_batch 处理；直接调用 heal_process 时用它等待结果，失败原因见 `last_skip_reason`
+    #[allow(dead_code)]
+    pub async fn wait_for_recoveries(&mut self) {
+        while !self.waiting.is_empty() {
+            let Some(waited) = self.wait_done_rx.recv().await else {
+                return;
+            };
+            self.finish_waited(waited).await;
+        }
+    }
+
+    // 向仍在运行的进程发送信号代替重新拉起。熔断器已记录本次尝试，
+    // 找不到目标进程或发送失败即为一次失败的恢复
+    async fn recover_by_signal(
+        &mut self,
+        process_config: &ProcessConfig,
+        signal: SignalName,
+        pid_source: &SignalPidSource,
+        coalesce: Option<(String, std::time::Duration)>,
+    ) {
+        let name = &process_config.name;
+        // 读取 PID 文件或扫描 /proc 都是阻塞操作，不能占用订阅者所在的异步任务
+        let target = {
+            let process_config = process_config.clone();
+            let pid_source = pid_source.clone();
+            tokio::task::spawn_blocking(move || resolve_signal_target(&process_config, &pid_source))
+        };
+        let Some(pid) = target.await.ok().flatten() else {
+            tracing::error!(target: "healer_action", process_name = %name, pid_source = ?pid_source, "No running process found to signal, counting as a failed recovery attempt.");
+            return;
+        };
+        match kill(Pid::from_raw(pid as i32), signal.signal()) {
+            Ok(()) => {
+                info!(target: "healer_event", process_name = %name, process_pid = pid, signal = signal.signal().as_str(), "Recovered process by signal.");
+                self.record_recovery_latency(name);
+                self.mark_recovered(process_config, coalesce);
+            }
+            Err(e) => {
+                tracing::error!(target: "healer_action", process_name = %name, process_pid = pid, signal = signal.signal().as_str(), error = %e, "Failed to signal process.");
+            }
+        }
+    }
+
+    // 恢复已执行：记录去重与合并窗口，开启监控宽限期，等待监控再次确认健康（ProcessUp）后才回到 Healthy
+    fn mark_recovered(
+        &mut self,
+        process_config: &ProcessConfig,
+        coalesce: Option<(String, std::time::Duration)>,
+    ) {
+        let name = &process_config.name;
+        let now = Instant::now();
+        self.last_recovery.insert(name.clone(), now);
+        self.last_skip.remove(name.as_str());
+        if let Some((key, window)) = coalesce {
+            self.coalesce_until.insert(key, now + window);
+        }
+        if process_config.post_restart_grace_secs > 0 {
+            let grace = std::time::Duration::from_secs(process_config.post_restart_grace_secs);
+            self.health.suppress_monitor_until(name, now + grace);
+        }
+        self.health.set(name, HealthState::Recovering);
+    }
+
+    // 监控确认健康：开始计算持续健康的时间（已在计时的不重新开始）
+    async fn mark_healthy(&mut self, name: &str) {
+        // 进程已经在运行（例如被手动拉起），不必再等待拉起失败的退避结束
+        self.spawn_backoff.remove(name);
+        let mut windows = self.process_recovery_windows.lock().await;
+        let stats = windows.entry(name.to_string()).or_default();
+        stats.healthy_since.get_or_insert_with(Instant::now);
+    }
+
+    // 开启 memory_growth 的 PID 监控上报 ProcessDown 时进程仍在运行；上报的 PID 已退出或被复用时按普通的退出处理，
+    // 不终止无关的进程
+    async fn is_leaking_instance(&self, name: &str, pid: u32, at: SystemTime) -> bool {
+        let watches_memory = self
+            .app_config
+            .read()
+            .await
+            .get_process_config_for(name)
+            .is_some_and(|process| {
+                matches!(&process.monitor, MonitorConfig::Pid(fields) if fields.memory_growth.is_some())
+            });
+        watches_memory && pid != 0 && utils::running_since(pid, at)
+    }
+
+    // 记录从故障事件的检测时间到执行恢复的延迟；直接调用 heal_process 时没有检测时间，不记录
+    fn record_recovery_latency(&mut self, name: &str) {
+        if let Some(at) = self.detected_at.take() {
+            let latency = self
+                .metrics
+                .observe_since(LatencyKind::DetectionToRecovery, name, at);
+            debug!(target: "healer_event", process_name = %name, latency_ms = latency.as_millis() as u64, "Recorded detection-to-recovery latency.");
+        }
+    }
+
+    // reparent_children：中间进程退出后由 tokio 在后台回收，转而按 /proc 跟踪被收养的孙进程
+    fn adopt_reparented(&mut self, name: &str, child: tokio::process::Child, pipe: ReparentPipe) {
+        drop(child);
+        self.record_recovery_latency(name);
+        match pipe.grandchild_pid() {
+            Some(pid) => {
+                info!(target: "healer_event", process_name = %name, process_pid = pid, "Successfully restarted process, detached from healer.");
+                self.children.adopt(name, pid);
+            }
+            None => {
+                warn!(target: "healer_event", process_name = %name, "Restarted process detached from healer, but its pid is unknown.");
+            }
+        }
+    }
+
+    /// 最近一次恢复被跳过的原因；之后成功执行过恢复时为 None
+    #[allow(dead_code)]
+    pub fn last_skip_reason(&self, name: &str) -> Option<&RecoverySkipReason> {
+        self.last_skip.get(name)
+    }
+
+    // 统一记录跳过原因：预期内的跳过为 info，表示恢复失败的为 warn
+    fn report_skip(&mut self, name: &str, reason: RecoverySkipReason) -> RecoverySkipReason {
+        if reason.is_failure() {
+            warn!(target: "healer_action", process_name = %name, skip_reason = reason.as_str(), "Recovery skipped: {}.", reason);
+        } else {
+            info!(target: "healer_action", process_name = %name, skip_reason = reason.as_str(), "Recovery skipped: {}.", reason);
+        }
+        self.last_skip.insert(name.to_string(), reason.clone());
+        reason
+    }
+
+    // 决定是否执行恢复：依次检查配置、去重、合并、guard、熔断器与运行身份，
+    // 跳过时返回原因；各检查自身的状态更新（健康状态、熔断计数）在这里完成
+    async fn decide_recovery(&mut self, name: &String) -> Result<RecoveryPlan, RecoverySkipReason> {
         // 限定 read 锁作用域：只在获取并克隆需要的配置期间持有，避免后续阻塞操作（文件IO、spawn）长期占用读锁
+        // 使用超时机制获取配置锁，避免无限期阻塞
         let process_config_opt = {
             match tokio::time::timeout(std::time::Duration::from_secs(5), self.app_config.read())
                 .await
             {
-                Ok(guard) => guard.get_process_config_for(&name).cloned(),
+                Ok(guard) => guard.get_process_config_for(&name).cloned().map(|p| {
+                    (
+                        p,
+                        guard.forbid_root_fallback,
+                        guard.recovery_defaults,
+                        guard.min_free_bytes,
+                        guard.reparent_children,
+                        guard.allowed_commands.clone(),
+                    )
+                }),
                 Err(_) => None,
             }
         }; // 读锁在这里释放
 
-        if process_config_opt.is_none() {
-            warn!(
-                target = "healer_action",
-                process_name = %name,
-                "No configuration found for process."
-            );
-            return;
+        let Some((
+            process_config,
+            global_forbid_root_fallback,
+            recovery_defaults,
+            min_free_bytes,
+            reparent_children,
+            allowed_commands,
+        )) = process_config_opt
+        else {
+            self.process_recovery_windows.lock().await.remove(name);
+            self.health.remove(name);
+            self.children.remove(name);
+            self.last_recovery.remove(name);
+            return Err(RecoverySkipReason::MissingConfig);
+        };
+        if !process_config.enabled {
+            return Err(RecoverySkipReason::Disabled);
+        }
+        if self.health.is_paused(name) {
+            return Err(RecoverySkipReason::Paused);
+        }
+        if self.health.is_quarantined(name) {
+            return Err(RecoverySkipReason::Quarantined);
         }
-        let process_config = process_config_opt.unwrap();
+        let anti_flap = match &process_config.recovery {
+            RecoveryConfig::Regular(fields) => fields.anti_flap(&recovery_defaults),
+            RecoveryConfig::NotRegular(_) => recovery_defaults,
+        };
+        if let Some(last) = self.last_recovery.get(name.as_str()) {
+            if last.elapsed() < std::time::Duration::from_secs(anti_flap.dedup_window_secs) {
+                // 同一次故障被多个监控器或重复事件报告，恢复已经执行过
+                return Err(RecoverySkipReason::Duplicate);
+            }
+        }
+        match self.spawn_backoff.get(name.as_str()) {
+            Some(&until) if until > Instant::now() => {
+                return Err(RecoverySkipReason::SpawnBackoff { until });
+            }
+            Some(_) => {
+                self.spawn_backoff.remove(name.as_str());
+            }
+            None => {}
+        }
+        self.health.set(name, HealthState::Down);
 
-        let command_to_run = &process_config.command;
-        let args = &process_config.args;
-        info!(target = "healer_event", process_name = %name, "Parsed the restart command. Conducting recovery.");
+        let run_as = if self.drop_privileges {
+            resolve_run_as(
+                &process_config,
+                process_config.forbid_root_fallback || global_forbid_root_fallback,
+            )
+        } else {
+            warn!(target: "healer_action", process_name = %name, "Privilege dropping is disabled (--no-drop-privileges), running the recovery command as the daemon user.");
+            Some(RunAs::Root)
+        };
 
-        // 创建日志目录（如果不存在）
-        if let Err(e) = std::fs::create_dir_all("/var/log/healer") {
-            warn!(target = "healer_action", process_name = %name, error = %e, "Failed to create log directory, using /tmp");
+        let coalesce = match &process_config.recovery {
+            RecoveryConfig::Regular(fields) => fields.coalesce_key.clone().map(|key| {
+                (
+                    key,
+                    std::time::Duration::from_secs(fields.coalesce_window_secs),
+                )
+            }),
+            RecoveryConfig::NotRegular(_) => None,
+        };
+        if let Some((key, _)) = &coalesce {
+            let now = Instant::now();
+            self.coalesce_until.retain(|_, until| *until > now);
+            if self.coalesce_until.contains_key(key) {
+                // 共享同一合并键的恢复刚刚执行过，不计入熔断器，等待监控确认健康
+                self.health.set(name, HealthState::Recovering);
+                return Err(RecoverySkipReason::Coalesced { key: key.clone() });
+            }
         }
 
-        let child_log_path = format!("/var/log/healer/{}.restarted.log", name);
-        let child_output_file = match fs::File::create(&child_log_path) {
-            Ok(file) => file,
-            Err(e) => {
-                warn!(
-                    target = "healer_action",
-                    process_name = %name,
-                    error = %e,
-                    "Failed to create log file, trying /tmp"
-                );
-                // 尝试在/tmp创建日志文件
-                let fallback_path = format!("/tmp/healer_{}.restarted.log", name);
-                match fs::File::create(&fallback_path) {
-                    Ok(file) => file,
-                    Err(e2) => {
-                        tracing::error!(
-                            target = "healer_action",
-                            process_name = %name,
-                            error = %e2,
-                            "Failed to create fallback log file, aborting recovery."
-                        );
-                        return;
+        // 前置检查放在熔断器之前：被 guard 或负载检查跳过的恢复不计入重试次数
+        if let Some(max_load1) = process_config.max_load1 {
+            match self.load_reader.load1() {
+                Some(load1) if load1 > max_load1 => {
+                    return Err(RecoverySkipReason::LoadTooHigh { load1, max_load1 });
+                }
+                Some(_) => {
+                    if self
+                        .load_deferrals
+                        .get(name.as_str())
+                        .is_some_and(|deferral| !deferral.pending)
+                    {
+                        self.load_deferrals.remove(name.as_str());
                     }
                 }
+                None => {
+                    warn!(target: "healer_action", process_name = %name, "Cannot read the load average, ignoring max_load1.");
+                }
             }
-        };
+        }
+        // 无法确定安全的运行身份时不执行 guard，交由下方按失败的恢复处理
+        if let (Some(guard), Some(run_as)) = (&process_config.recovery_guard, run_as) {
+            match run_recovery_guard(&process_config, guard, run_as).await {
+                GuardOutcome::Passed => {}
+                GuardOutcome::TimedOut if process_config.recovery_timeout_secs.is_some() => {
+                    // 超过 recovery_timeout_secs 的挂起命令按一次失败的恢复计入熔断器
+                    if let Err(RecoverySkipReason::CircuitOpen { until }) =
+                        self.check_circuit_breaker(name).await
+                    {
+                        self.health.set_circuit_open(name, until);
+                    }
+                    return Err(RecoverySkipReason::GuardTimedOut);
+                }
+                GuardOutcome::Refused | GuardOutcome::TimedOut => {
+                    return Err(RecoverySkipReason::GuardRefused);
+                }
+            }
+        }
 
-        let mut command = Command::new(command_to_run);
+        if let Err(reason) = self.check_circuit_breaker(name).await {
+            if let RecoverySkipReason::CircuitOpen { until } = reason {
+                self.health.set_circuit_open(name, until);
+            }
+            return Err(reason);
+        }
+
+        // 熔断器已记录本次尝试，拒绝启动即视为一次失败的恢复
+        let Some(run_as) = run_as else {
+            return Err(RecoverySkipReason::RootRefused);
+        };
+        Ok(RecoveryPlan {
+            process_config,
+            run_as,
+            coalesce,
+            min_free_bytes,
+            reparent: reparent_children,
+            allowed_commands,
+            spawn_backoff: std::time::Duration::from_secs(anti_flap.cooldown_secs),
+        })
+    }
+    // 根据进程配置构造恢复命令：参数、运行用户以及子进程的输出去向
+    fn build_command(
+        &self,
+        process_config: &ProcessConfig,
+        program: &Path,
+        run_as: RunAs,
+        kind: FailureKind,
+        min_free_bytes: Option<u64>,
+        reparent: bool,
+    ) -> Option<RecoveryCommand> {
+        let name = &process_config.name;
+        let (_, args) = process_config.recovery_command_for(kind);
+        let mut command = Command::new(program);
         command.args(args);
+        apply_priority(&mut command, process_config, run_as);
+        if let Some(umask) = process_config.umask {
+            debug!(target: "healer_action", process_name = %name, umask = %umask, "Setting the umask of the recovered process.");
+            let bits = umask.bits() as libc::mode_t;
+            // SAFETY: 闭包在 fork 之后、exec 之前运行，只调用 umask 系统调用
+            unsafe {
+                command.pre_exec(move || {
+                    libc::umask(bits);
+                    Ok(())
+                });
+            }
+        }
 
-        // 改进的权限处理
-        if !process_config.run_as_root {
-            if let Some(username) = &process_config.run_as_user {
-                match get_user_by_name(username) {
-                    Some(user) => {
-                        command.uid(user.uid());
-                        command.gid(user.primary_group_id());
-                        info!(target: "healer_action", process_name = %name, user = %username, uid = %user.uid(), "Dropping privileges to run as specified user.");
-                    }
-                    None => {
-                        warn!(target: "healer_action", process_name = %name, user = %username, "Specified user not found. Process will run as root. This is a security risk.");
-                    }
-                }
-            } else {
-                warn!(target: "healer_action", process_name = %name, "run_as_root is false but no run_as_user specified. Process will run as root.");
+        // 进入命名空间时由 pre_exec 在 setns 之后再切换身份，否则 setns 会因权限不足失败
+        let mut pid_namespace = None;
+        match &process_config.nsenter {
+            Some(nsenter) => {
+                pid_namespace = enter_namespaces(&mut command, name, nsenter, run_as)?;
             }
+            None => run_as.apply(&mut command),
         }
 
         // 被恢复的进程重定向io
-        command.stdout(Stdio::from(child_output_file.try_clone().unwrap()));
-        command.stderr(Stdio::from(child_output_file));
-
-        match command.spawn() {
-            Ok(child) => {
-                info!(target = "healer_event", process_name = %name, process_pid = %child.id(), "Successfully restarted process.");
+        let mut child_output = process_config.child_output;
+        if child_output == ChildOutput::Inherit && !self.foreground {
+            warn!(target: "healer_action", process_name = %name, "child_output 'inherit' is only supported in foreground mode (the daemon has no terminal), falling back to 'file'.");
+            child_output = ChildOutput::File;
+        }
+        if child_output == ChildOutput::File {
+            if let Some(available) = low_log_space(min_free_bytes) {
+                warn!(target: "healer_action", process_name = %name, available_bytes = available, min_free_bytes = ?min_free_bytes, "Free space for child logs is below min_free_bytes, discarding the output of the restarted process.");
+                child_output = ChildOutput::Null;
             }
-            Err(e) => {
-                tracing::error!(target = "healer_action",
-                    process_name = %name,
-                    error = %e,
-                    "Failed to restart process. This might be due to permission issues or invalid command path.");
+        }
+        // 脱离 healer 时无法同步等待退出，也无法进入 pid 命名空间后再分叉
+        let reparent = reparent
+            && if process_config.wait_for_exit || process_config.nsenter.is_some() {
+                warn!(target: "healer_action", process_name = %name, "reparent_children does not apply to processes with wait_for_exit or nsenter, keeping the recovered process as a child.");
+                false
+            } else {
+                true
+            };
+        // 保留输出尾部时经由管道转发，由 ChildRegistry 写往原来的去向
+        let mut output_tail = None;
+        let output_tail_lines = process_config.output_tail_lines.filter(|_| {
+            if reparent {
+                warn!(target: "healer_action", process_name = %name, "output_tail_lines is ignored with reparent_children, the output goes directly to its destination.");
+            }
+            !reparent
+        });
+        if let Some(lines) = output_tail_lines {
+            let sink = match child_output {
+                ChildOutput::File => OutputSink::File(open_child_log(name)?),
+                ChildOutput::Null => OutputSink::Null,
+                ChildOutput::Inherit => OutputSink::Inherit,
+            };
+            command.stdout(Stdio::piped());
+            command.stderr(Stdio::piped());
+            output_tail = Some((lines, sink));
+        } else {
+            match child_output {
+                ChildOutput::File => {
+                    let child_output_file = open_child_log(name)?;
+                    command.stdout(Stdio::from(child_output_file.try_clone().ok()?));
+                    command.stderr(Stdio::from(child_output_file));
+                }
+                ChildOutput::Null => {
+                    command.stdout(Stdio::null());
+                    command.stderr(Stdio::null());
+                }
+                ChildOutput::Inherit => {
+                    command.stdout(Stdio::inherit());
+                    command.stderr(Stdio::inherit());
+                }
             }
         }
+
+        // 必须最后注册：分叉出的孙进程继续执行之后的 pre_exec 并 exec
+        let reparent_pipe = if reparent {
+            match ReparentPipe::register(&mut command) {
+                Ok(pipe) => Some(pipe),
+                Err(e) => {
+                    warn!(target: "healer_action", process_name = %name, error = %e, "Cannot create the reparent pipe.");
+                    return None;
+                }
+            }
+        } else {
+            None
+        };
+
+        Some(RecoveryCommand {
+            command,
+            pid_namespace,
+            output_tail,
+            reparent_pipe,
+        })
     }
-    async fn check_circuit_breaker(&mut self, name: &String) -> bool {
-        let process_config = {
+
+    // 仍在熔断时返回 CircuitOpen（附冷却期结束时间）
+    async fn check_circuit_breaker(&mut self, name: &String) -> Result<(), RecoverySkipReason> {
+        let (process_config, group, defaults) = {
             let cfg = self.app_config.read().await;
-            cfg.get_process_config_for(name).cloned()
+            let process_config = cfg.get_process_config_for(name).cloned();
+            let group = process_config
+                .as_ref()
+                .and_then(|p| p.restart_group.as_deref())
+                .and_then(|group_name| cfg.get_restart_group(group_name).cloned());
+            (process_config, group, cfg.recovery_defaults)
         };
 
         let Some(process_config) = process_config else {
             warn!("No configuration found for process {}", name);
             self.process_recovery_windows.lock().await.remove(name);
-            return false;
+            return Ok(());
         };
 
+        // 同一重启组的成员共享重试预算与冷却期：组预算耗尽后所有成员一起熔断
+        if let Some(group) = group {
+            let budget = group.budget();
+            let anti_flap = budget.anti_flap(&defaults);
+            let recovery = RecoveryConfig::Regular(budget);
+            let mut windows = self.group_recovery_windows.lock().await;
+            let stats = windows
+                .entry(group.name.clone())
+                .or_insert_with(ProcessRecoveryStats::default);
+            debug!(
+                "[{}] Checking circuit breaker state of restart group '{}'.",
+                name, group.name
+            );
+            let open = Self::evaluate_circuit_breaker(stats, &recovery, anti_flap, name);
+            self.health.set_circuit(name, stats.record());
+            return Self::circuit_result(open, stats);
+        }
+
         let mut windows = self.process_recovery_windows.lock().await;
         let stats = windows
             .entry(name.clone())
             .or_insert_with(ProcessRecoveryStats::default);
+        if stats.recovery_state == CircuitState::Quarantined && !self.health.is_quarantined(name) {
+            // 隔离已被手动解除，熔断器从头开始
+            info!(
+                "[{}] Quarantine lifted, resetting the circuit breaker.",
+                name
+            );
+            *stats = ProcessRecoveryStats::default();
+        }
+        if let RecoveryConfig::Regular(fields) = &process_config.recovery {
+            if stats.forgive_if_healthy(fields.healthy_reset_secs) {
+                info!(
+                    target: "healer_action",
+                    process_name = %name,
+                    healthy_reset_secs = fields.healthy_reset_secs,
+                    "Process stayed healthy long enough, cleared its failure history."
+                );
+            }
+        }
+        // 再次故障，持续健康从下一次 ProcessUp 重新计时
+        stats.healthy_since = None;
 
+        let anti_flap = match &process_config.recovery {
+            RecoveryConfig::Regular(fields) => fields.anti_flap(&defaults),
+            RecoveryConfig::NotRegular(_) => defaults,
+        };
         debug!("[{}] Checking circuit breaker state.", name);
+        let open = Self::evaluate_circuit_breaker(stats, &process_config.recovery, anti_flap, name);
+        if let RecoveryConfig::Regular(fields) = &process_config.recovery {
+            if open
+                && stats.recovery_state == CircuitState::Open
+                && fields.quarantine_after_trips > 0
+                && stats.trips >= fields.quarantine_after_trips
+            {
+                stats.enter(CircuitState::Quarantined);
+                stats.in_cooldown_until = None;
+                self.health.quarantine(name);
+                error!(
+                    target: "healer_action",
+                    process_name = %name,
+                    trips = stats.trips,
+                    "Circuit breaker tripped {} times in a row, process quarantined. Automatic recovery is suspended until a manual resume or config reload.",
+                    stats.trips
+                );
+            }
+        }
+        self.health.set_circuit(name, stats.record());
+        Self::circuit_result(open, stats)
+    }
 
+    fn circuit_result(open: bool, stats: &ProcessRecoveryStats) -> Result<(), RecoverySkipReason> {
+        if !open {
+            return Ok(());
+        }
+        if stats.recovery_state == CircuitState::Quarantined {
+            return Err(RecoverySkipReason::Quarantined);
+        }
+        Err(RecoverySkipReason::CircuitOpen {
+            until: stats.in_cooldown_until.unwrap_or_else(Instant::now),
+        })
+    }
+
+    // 熔断状态机：返回 true 表示仍在熔断，本次不应恢复
+    fn evaluate_circuit_breaker(
+        stats: &mut ProcessRecoveryStats,
+        recovery: &RecoveryConfig,
+        anti_flap: RecoveryDefaults,
+        name: &str,
+    ) -> bool {
+        let cooldown = std::time::Duration::from_secs(anti_flap.cooldown_secs);
         match stats.recovery_state {
-            State::Closed => {
-                if let RecoveryConfig::Regular(regular_healer_fields) = &process_config.recovery {
+            CircuitState::Closed => {
+                if let RecoveryConfig::Regular(regular_healer_fields) = recovery {
                     stats.recovery_session_starts.retain(|start_time| {
                         start_time.elapsed().as_secs() < regular_healer_fields.retry_window_secs
                     });
 
                     if stats.recovery_session_starts.len() == regular_healer_fields.retries as usize
                     {
-                        stats.recovery_state = State::Open;
-                        stats.in_cooldown_until = Some(
-                            Instant::now()
-                                + std::time::Duration::from_secs(
-                                    regular_healer_fields.cooldown_secs,
-                                ),
-                        );
+                        stats.enter(CircuitState::Open);
+                        stats.in_cooldown_until = Some(Instant::now() + cooldown);
                         stats.recovery_session_starts.clear();
                         return true;
                     } else {
@@ -212,7 +1353,7 @@ impl ProcessHealer {
                         );
                         return false;
                     }
-                } else if let RecoveryConfig::NotRegular(_) = &process_config.recovery {
+                } else if let RecoveryConfig::NotRegular(_) = recovery {
                     warn!("Shouldn't be here, NotRegular is not implemented yet");
                     return false;
                 } else {
@@ -220,16 +1361,8 @@ impl ProcessHealer {
                     return false;
                 }
             }
-            State::Open => {
+            CircuitState::Open => {
                 let now = Instant::now();
-                let cooldown_secs = match &process_config.recovery {
-                    RecoveryConfig::Regular(fields) => fields.cooldown_secs,
-                    _ => {
-                        warn!("Shouldn't be here, NotRegular is not implemented yet");
-                        5
-                    }
-                };
-
                 if let Some(cooldown_until) = stats.in_cooldown_until {
                     if now < cooldown_until {
                         return true;
@@ -239,51 +1372,474 @@ impl ProcessHealer {
                         "Open state without cooldown for process {}. Reinstating cooldown.",
                         name
                     );
-                    stats.in_cooldown_until =
-                        Some(now + std::time::Duration::from_secs(cooldown_secs));
+                    stats.in_cooldown_until = Some(now + cooldown);
                     return true;
                 }
 
-                stats.recovery_state = State::HalfOpen;
+                stats.enter(CircuitState::HalfOpen);
                 stats.recovery_session_starts.clear();
-                stats.half_open_safe_until = Some(now + std::time::Duration::from_secs(2));
+                stats.half_open_safe_until =
+                    Some(now + std::time::Duration::from_secs(anti_flap.half_open_window_secs));
                 false
             }
-            State::HalfOpen => {
+            CircuitState::HalfOpen => {
                 if let Some(safe_until) = stats.half_open_safe_until {
                     let now = Instant::now();
                     if now < safe_until {
-                        let cooldown_secs = match &process_config.recovery {
-                            RecoveryConfig::Regular(fields) => fields.cooldown_secs,
-                            _ => {
-                                warn!("Shouldn't be here, NotRegular is not implemented yet");
-                                5
-                            }
-                        };
                         warn!(
                             "Process {} is in half-open; attempt failed within safe window. Back to open (cooldown).",
                             name
                         );
-                        stats.recovery_state = State::Open;
-                        stats.in_cooldown_until =
-                            Some(now + std::time::Duration::from_secs(cooldown_secs));
+                        stats.enter(CircuitState::Open);
+                        stats.in_cooldown_until = Some(now + cooldown);
                         stats.half_open_safe_until = None;
                         stats.recovery_session_starts.clear();
                         return true;
                     } else {
-                        stats.recovery_state = State::Closed;
+                        stats.enter(CircuitState::Closed);
                         stats.half_open_safe_until = None;
                         stats.recovery_session_starts.clear();
+                        stats.trips = 0;
                         return false;
                     }
                 } else {
                     warn!("Half-open state without safe time set for process {}", name);
-                    stats.recovery_state = State::Closed;
+                    stats.enter(CircuitState::Closed);
                     stats.half_open_safe_until = None;
                     stats.recovery_session_starts.clear();
                     return false;
                 }
             }
+            // 隔离期间不再恢复，解除隔离时在 check_circuit_breaker 中重置
+            CircuitState::Quarantined => true,
+        }
+    }
+}
+
+// 子进程的运行身份
+#[derive(Clone, Copy)]
+enum RunAs {
+    Root,
+    User { uid: u32, gid: u32 },
+}
+
+impl RunAs {
+    fn apply(self, command: &mut Command) {
+        if let RunAs::User { uid, gid } = self {
+            command.uid(uid);
+            command.gid(gid);
+        }
+    }
+}
+
+// 开启 wait_for_exit 且未设置 recovery_timeout_secs 时等待恢复命令的最长时间（秒）
+const DEFAULT_WAIT_FOR_EXIT_SECS: u64 = 60;
+
+// 恢复前终止仍在运行的实例时，SIGTERM 之后等待其退出的最长时间（秒），超时后发送 SIGKILL
+const RUNNING_INSTANCE_STOP_SECS: u64 = 10;
+
+// ioprio_set 的 which 参数：按线程/进程 ID 设置
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+// ioprio 值的高位为调度类别
+const IOPRIO_CLASS_SHIFT: u32 = 13;
+// 实时与尽力而为类别的默认级别（0-7，数字越小优先级越高）
+const IOPRIO_DEFAULT_LEVEL: u32 = 4;
+
+// 在 exec 前设置子进程的 nice 值与 IO 调度类别。子进程中无法记录日志，
+// 因此在父进程中预先判断并告警；设置失败时不阻止启动
+fn apply_priority(command: &mut Command, process_config: &ProcessConfig, run_as: RunAs) {
+    let name = &process_config.name;
+    // 未进入命名空间时，std 在执行 pre_exec 之前就已切换到 run_as_user
+    let privileged = Uid::effective().is_root()
+        && (matches!(run_as, RunAs::Root) || process_config.nsenter.is_some());
+
+    let nice = process_config.nice.map(|requested| {
+        let nice = requested.clamp(-20, 19);
+        if nice != requested {
+            warn!(target: "healer_action", process_name = %name, requested, nice, "nice is out of range, clamping.");
+        }
+        if nice < 0 && !privileged {
+            warn!(target: "healer_action", process_name = %name, nice, "Only root can lower the nice value, the recovered process keeps its default priority.");
+        }
+        nice
+    });
+    let ioprio = process_config.ionice_class.and_then(|class| match class {
+        1 | 2 => {
+            if class == 1 && !privileged {
+                warn!(target: "healer_action", process_name = %name, "Only root can use the realtime IO class, the recovered process keeps its default IO priority.");
+            }
+            Some(((class as u32) << IOPRIO_CLASS_SHIFT) | IOPRIO_DEFAULT_LEVEL)
+        }
+        3 => Some(3 << IOPRIO_CLASS_SHIFT),
+        // 其他取值已被配置校验拒绝
+        _ => None,
+    });
+    if nice.is_none() && ioprio.is_none() {
+        return;
+    }
+
+    // SAFETY: 闭包在 fork 之后、exec 之前运行，只调用 setpriority/ioprio_set 系统调用，不分配内存
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(nice) = nice {
+                libc::setpriority(libc::PRIO_PROCESS, 0, nice);
+            }
+            if let Some(ioprio) = ioprio {
+                libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio);
+            }
+            Ok(())
+        });
+    }
+}
+
+// 构造好的恢复命令；进入 pid 命名空间时需要由单独的线程发起 spawn
+struct RecoveryCommand {
+    command: Command,
+    pid_namespace: Option<fs::File>,
+    // 保留输出尾部时的行数与输出原本的去向
+    output_tail: Option<(usize, OutputSink)>,
+    // reparent_children 时用于取回孙进程 pid 的管道
+    reparent_pipe: Option<ReparentPipe>,
+}
+
+impl RecoveryCommand {
+    fn spawn(self) -> io::Result<tokio::process::Child> {
+        let mut command = tokio::process::Command::from(self.command);
+        let Some(pid_namespace) = self.pid_namespace else {
+            return command.spawn();
+        };
+        // setns(CLONE_NEWPID) 只影响调用线程之后创建的子进程，因此在一次性线程中进入后再 fork，
+        // 不污染 healer 自身及 tokio 工作线程；该线程需进入当前 runtime 才能登记子进程
+        let runtime = tokio::runtime::Handle::current();
+        std::thread::spawn(move || {
+            let _runtime = runtime.enter();
+            setns(&pid_namespace, CloneFlags::CLONE_NEWPID)?;
+            command.spawn()
+        })
+        .join()
+        .map_err(|_| io::Error::new(io::ErrorKind::Other, "namespace spawn thread panicked"))?
+    }
+}
+
+// reparent_children：子进程在 exec 前 setsid 并再 fork 一次后立即退出，由孙进程 exec 目标命令，
+// 孙进程被 init（或 subreaper）收养，healer 退出或被 systemd 停止时不随之结束。
+// 孙进程的 pid 由中间进程经管道写回
+struct ReparentPipe {
+    rx: OwnedFd,
+    tx: OwnedFd,
+}
+
+impl ReparentPipe {
+    fn register(command: &mut Command) -> io::Result<Self> {
+        let mut fds = [0; 2];
+        // SAFETY: pipe2 写入两个新打开的描述符，随后立即交给 OwnedFd 管理
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let (rx, tx) = unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+        let tx_fd = tx.as_raw_fd();
+        // SAFETY: 闭包在 fork 之后、exec 之前运行，只调用 setsid/fork/write/_exit，不分配内存
+        unsafe {
+            command.pre_exec(move || {
+                libc::setsid();
+                match libc::fork() {
+                    -1 => Err(io::Error::last_os_error()),
+                    0 => Ok(()),
+                    pid => {
+                        let bytes = pid.to_ne_bytes();
+                        libc::write(tx_fd, bytes.as_ptr().cast(), bytes.len());
+                        libc::_exit(0)
+                    }
+                }
+            });
+        }
+        Ok(Self { rx, tx })
+    }
+
+    // spawn 返回时孙进程已 exec（或 exec 失败），中间进程已写入 pid
+    fn grandchild_pid(self) -> Option<u32> {
+        drop(self.tx);
+        let mut bytes = [0u8; 4];
+        fs::File::from(self.rx).read_exact(&mut bytes).ok()?;
+        u32::try_from(i32::from_ne_bytes(bytes)).ok()
+    }
+}
+
+fn namespace_flag(kind: NamespaceKind) -> CloneFlags {
+    match kind {
+        NamespaceKind::Ipc => CloneFlags::CLONE_NEWIPC,
+        NamespaceKind::Uts => CloneFlags::CLONE_NEWUTS,
+        NamespaceKind::Net => CloneFlags::CLONE_NEWNET,
+        NamespaceKind::Pid => CloneFlags::CLONE_NEWPID,
+        NamespaceKind::Mnt => CloneFlags::CLONE_NEWNS,
+    }
+}
+
+// 在父进程中打开命名空间文件，子进程 exec 前依次 setns 并切换运行身份；
+// 返回需要由发起 spawn 的线程进入的 pid 命名空间。打开失败时返回 None，放弃本次恢复
+fn enter_namespaces(
+    command: &mut Command,
+    name: &str,
+    nsenter: &NsenterConfig,
+    run_as: RunAs,
+) -> Option<Option<fs::File>> {
+    let paths = nsenter.resolved_paths();
+    if paths.is_empty() {
+        warn!(target: "healer_action", process_name = %name, "nsenter is configured without target_pid or paths, running in the current namespaces.");
+    }
+    let mut pid_namespace = None;
+    let mut namespaces = Vec::new();
+    for (kind, path) in paths {
+        let file = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::error!(target: "healer_action", process_name = %name, namespace = kind.proc_name(), path = %path.display(), error = %e, "Failed to open namespace.");
+                return None;
+            }
+        };
+        if kind == NamespaceKind::Pid {
+            pid_namespace = Some(file);
+        } else {
+            namespaces.push((namespace_flag(kind), file));
+        }
+    }
+
+    // SAFETY: 闭包在 fork 之后、exec 之前运行，只调用 setns/setgid/setuid 等系统调用，不分配内存
+    unsafe {
+        command.pre_exec(move || {
+            for (flag, file) in &namespaces {
+                setns(file, *flag)?;
+            }
+            if let RunAs::User { uid, gid } = run_as {
+                let _ = setgroups(&[]);
+                setgid(Gid::from_raw(gid))?;
+                setuid(Uid::from_raw(uid))?;
+            }
+            Ok(())
+        });
+    }
+    Some(pid_namespace)
+}
+
+// 按进程配置确定运行身份：run_as_root 为 false 时切换到 run_as_user；
+// 无法切换且禁止回退为 root 时返回 None
+fn resolve_run_as(process_config: &ProcessConfig, forbid_root_fallback: bool) -> Option<RunAs> {
+    let name = &process_config.name;
+    if process_config.run_as_root {
+        return Some(RunAs::Root);
+    }
+    // 改进的权限处理
+    if let Some(username) = &process_config.run_as_user {
+        match get_user_by_name(username) {
+            Some(user) => {
+                info!(target: "healer_action", process_name = %name, user = %username, uid = %user.uid(), "Dropping privileges to run as specified user.");
+                return Some(RunAs::User {
+                    uid: user.uid(),
+                    gid: user.primary_group_id(),
+                });
+            }
+            None if forbid_root_fallback => {
+                warn!(target: "healer_action", process_name = %name, user = %username, "Specified user not found and root fallback is forbidden.");
+                return None;
+            }
+            None => {
+                warn!(target: "healer_action", process_name = %name, user = %username, "Specified user not found. Process will run as root. This is a security risk.");
+            }
+        }
+    } else if forbid_root_fallback {
+        warn!(target: "healer_action", process_name = %name, "run_as_root is false but no run_as_user specified, and root fallback is forbidden.");
+        return None;
+    } else {
+        warn!(target: "healer_action", process_name = %name, "run_as_root is false but no run_as_user specified. Process will run as root.");
+    }
+    Some(RunAs::Root)
+}
+
+// 恢复前置检查的结果
+enum GuardOutcome {
+    // 在超时内以 0 退出
+    Passed,
+    // 非 0 退出或无法执行
+    Refused,
+    // 超时，进程组已被杀死
+    TimedOut,
+}
+
+// 执行恢复前置检查；命令在独立的进程组中运行，超时后连同其派生的子进程一起杀死
+async fn run_recovery_guard(
+    process_config: &ProcessConfig,
+    guard: &HookConfig,
+    run_as: RunAs,
+) -> GuardOutcome {
+    let name = &process_config.name;
+    let mut command = Command::new(&guard.command);
+    command.args(&guard.args);
+    command.stdin(Stdio::null());
+    command.stdout(Stdio::null());
+    command.stderr(Stdio::null());
+    command.process_group(0);
+    // guard 与被恢复进程使用相同的身份运行
+    run_as.apply(&mut command);
+
+    let mut command = tokio::process::Command::from(command);
+    command.kill_on_drop(true);
+    let timeout_secs = process_config
+        .recovery_timeout_secs
+        .unwrap_or(guard.timeout_secs)
+        .max(1);
+    // 等待结束前僵尸进程回收服务跳过 guard，否则 wait 失败（ECHILD）会被当作拒绝
+    let (mut child, _exemption) = match reaper::spawn(|| command.spawn()) {
+        Ok(spawned) => spawned,
+        Err(e) => {
+            warn!(target: "healer_action", process_name = %name, error = %e, "Failed to run recovery guard.");
+            return GuardOutcome::Refused;
+        }
+    };
+    let pgid = child.id();
+    match tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), child.wait()).await {
+        Ok(Ok(status)) if status.success() => GuardOutcome::Passed,
+        Ok(Ok(status)) => {
+            debug!(target: "healer_action", process_name = %name, status = %status, "Recovery guard returned non-zero.");
+            GuardOutcome::Refused
+        }
+        Ok(Err(e)) => {
+            warn!(target: "healer_action", process_name = %name, error = %e, "Failed to wait for recovery guard.");
+            GuardOutcome::Refused
+        }
+        Err(_) => {
+            warn!(target: "healer_action", process_name = %name, timeout_secs, "Recovery guard timed out, killing its process group.");
+            if let Some(pgid) = pgid {
+                let _ = killpg(Pid::from_raw(pgid as i32), Signal::SIGKILL);
+            }
+            let _ = child.kill().await;
+            GuardOutcome::TimedOut
+        }
+    }
+}
+
+// 等待开启 wait_for_exit 的恢复命令退出，超时后杀死该命令
+async fn wait_recovery_command(
+    process_config: &ProcessConfig,
+    mut child: tokio::process::Child,
+) -> Result<(), RecoverySkipReason> {
+    let name = &process_config.name;
+    let timeout_secs = process_config
+        .recovery_timeout_secs
+        .unwrap_or(DEFAULT_WAIT_FOR_EXIT_SECS)
+        .max(1);
+    let detail = match tokio::time::timeout(
+        std::time::Duration::from_secs(timeout_secs),
+        child.wait(),
+    )
+    .await
+    {
+        Ok(Ok(status)) if status.success() => {
+            debug!(target: "healer_action", process_name = %name, "Recovery command exited successfully.");
+            return Ok(());
+        }
+        Ok(Ok(status)) => format!("exited with {}", status),
+        Ok(Err(e)) => format!("could not be waited for: {}", e),
+        Err(_) => {
+            let _ = child.kill().await;
+            format!("timed out after {}s", timeout_secs)
+        }
+    };
+    Err(RecoverySkipReason::RecoveryFailed { detail })
+}
+
+// 信号恢复的目标进程：PID 文件中仍存在的进程，或按可执行文件路径扫描到的进程
+fn resolve_signal_target(process_config: &ProcessConfig, source: &SignalPidSource) -> Option<u32> {
+    match source {
+        SignalPidSource::PidFile { path } => fs::read_to_string(path)
+            .ok()?
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|pid| *pid > 0 && Path::new(&format!("/proc/{}", pid)).exists()),
+        SignalPidSource::ExeScan { exe } => {
+            let exe = match exe {
+                Some(exe) => exe.clone(),
+                None => {
+                    utils::resolve_command(&process_config.command, process_config.command_glob)
+                        .ok()?
+                }
+            };
+            utils::find_pid_by_exe(&exe, false)
+        }
+    }
+}
+
+// 由 pid 监控的 PID 文件或 reconcile_pid_file 的可执行文件路径确定仍在运行的进程
+fn running_pid(process_config: &ProcessConfig) -> Option<u32> {
+    let monitor = process_config.get_pid_monitor_config()?;
+    let PidSource::File { path } = &monitor.source else {
+        return None;
+    };
+    let from_file = fs::read_to_string(path)
+        .ok()
+        .and_then(|pid| pid.trim().parse::<u32>().ok())
+        .filter(|pid| *pid > 0 && Path::new(&format!("/proc/{}", pid)).exists());
+    from_file.or_else(|| {
+        let exe = monitor.reconcile_exe?;
+        let exe = utils::resolve_command(&exe.to_string_lossy(), CommandGlobPolicy::Newest).ok()?;
+        utils::find_pid_by_exe(&exe, false)
+    })
+}
+
+// 按故障类型选出恢复命令并展开 command_glob
+fn resolve_recovery_program(process_config: &ProcessConfig, kind: FailureKind) -> Option<PathBuf> {
+    let name = &process_config.name;
+    let (program, _) = process_config.recovery_command_for(kind);
+    match utils::resolve_command(program, process_config.command_glob) {
+        Ok(program) => {
+            debug!(target: "healer_action", process_name = %name, program = %program.display(), "Resolved the recovery command.");
+            Some(program)
+        }
+        Err(e) => {
+            warn!(target: "healer_action", process_name = %name, error = %e, "Cannot resolve the recovery command.");
+            None
+        }
+    }
+}
+
+// 日志目录所在文件系统的可用空间低于 min_free_bytes 时返回当前可用空间；无法统计时不拦截
+fn low_log_space(min_free_bytes: Option<u64>) -> Option<u64> {
+    let min_free_bytes = min_free_bytes?;
+    let available = utils::available_bytes(Path::new(CHILD_LOG_DIR))?;
+    (available < min_free_bytes).then_some(available)
+}
+
+// 打开被恢复进程的日志文件，优先 /var/log/healer，失败时回退到 /tmp
+fn open_child_log(name: &str) -> Option<fs::File> {
+    // 创建日志目录（如果不存在）
+    if let Err(e) = std::fs::create_dir_all(CHILD_LOG_DIR) {
+        warn!(target: "healer_action", process_name = %name, error = %e, "Failed to create log directory, using /tmp");
+    }
+
+    let child_log_path = format!("{}/{}.restarted.log", CHILD_LOG_DIR, name);
+    match fs::File::create(&child_log_path) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            warn!(
+                target: "healer_action",
+                process_name = %name,
+                error = %e,
+                "Failed to create log file, trying /tmp"
+            );
+            // 尝试在/tmp创建日志文件
+            let fallback_path = format!("/tmp/healer_{}.restarted.log", name);
+            match fs::File::create(&fallback_path) {
+                Ok(file) => Some(file),
+                Err(e2) => {
+                    tracing::error!(
+                        target: "healer_action",
+                        process_name = %name,
+                        error = %e2,
+                        "Failed to create fallback log file, aborting recovery."
+                    );
+                    None
+                }
+            }
         }
     }
 }
@@ -291,13 +1847,42 @@ impl ProcessHealer {
 #[async_trait]
 impl Subscriber for ProcessHealer {
     async fn handle_event(&mut self, event: ProcessEvent) {
-        //heal_process
-        if let ProcessEvent::ProcessDown { name, pid } = &event {
-            info!(target = "healer_event", process_name = %name, process_pid = %pid, "Received ProcessDown event. Initiating recovery process.");
-            self.heal_process(name).await
-        } else if let ProcessEvent::ProcessDisconnected { name, url } = &event {
-            info!(target = "healer_event", process_name = %name, url = %url, "Received ProcessDisconnected event. Initiating recovery process.");
-            self.heal_process(name).await;
+        //heal_process：按事件类型选择恢复命令
+        self.detected_at = Some(event.at());
+        let skipped = match &event {
+            ProcessEvent::ProcessDown { name, pid, at } => {
+                info!(target: "healer_event", process_name = %name, process_pid = %pid, "Received ProcessDown event. Initiating recovery process.");
+                // 上报的实例仍在运行（内存持续增长）：按资源超限选择恢复命令，恢复时先终止它
+                if self.is_leaking_instance(name, *pid, *at).await {
+                    self.running_instance = Some((name.clone(), *pid));
+                    self.heal_process_for(name, FailureKind::Resource).await
+                } else {
+                    self.heal_process_for(name, FailureKind::Down).await
+                }
+            }
+            ProcessEvent::ProcessDisconnected { name, url, .. } => {
+                info!(target: "healer_event", process_name = %name, url = %url, "Received ProcessDisconnected event. Initiating recovery process.");
+                self.heal_process_for(name, FailureKind::Disconnected).await
+            }
+            ProcessEvent::ProcessResourceExceeded { name, pid, .. } => {
+                info!(target: "healer_event", process_name = %name, process_pid = %pid, "Received ProcessResourceExceeded event. Initiating recovery process.");
+                self.heal_process_for(name, FailureKind::Resource).await
+            }
+            ProcessEvent::ProcessUp { name, .. } => {
+                debug!(target: "healer_event", process_name = %name, "Process confirmed healthy.");
+                self.health.set(name, HealthState::Healthy);
+                // 已确认健康，之后的故障不再属于慢启动，立即恢复监控
+                self.health.clear_monitor_grace(name);
+                self.mark_healthy(name).await;
+                None
+            }
+            _ => None,
+        };
+        if let Some(RecoverySkipReason::LoadTooHigh { .. }) = skipped {
+            self.defer_for_load(event);
         }
+        // 恢复被跳过时检测时间与仍在运行的实例没有被使用，不能留给之后直接发起的恢复
+        self.detected_at = None;
+        self.running_instance = None;
     }
 }
@@ -1,23 +1,54 @@
+use crate::config::{CommandGlobPolicy, GroupScope, PidSource, ProcessConfig};
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
 use std::collections::HashMap;
 use std::default::Default;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use sysinfo::{ProcessRefreshKind, RefreshKind, System, UpdateKind};
-use tracing::debug;
+use tracing::{debug, warn};
 
 #[allow(dead_code)] // Utility function for future use
 pub fn find_pid_by_exe_path(path: &str) -> Option<u32> {
+    find_pid_by_exe(Path::new(path), false)
+}
+
+/// 按可执行文件路径查找进程：两侧都规范化（解析符号链接、去掉 ` (deleted)` 标记）后比较；
+/// `basename_fallback` 为 true 且没有路径完全一致的进程时，退而按文件名匹配。
+#[allow(dead_code)]
+pub fn find_pid_by_exe(path: &Path, basename_fallback: bool) -> Option<u32> {
     let process_kind = ProcessRefreshKind::default().with_exe(UpdateKind::Always);
     let rk = RefreshKind::nothing().with_processes(process_kind);
     let sys = System::new_with_specifics(rk);
+    let target = normalize_exe_path(path);
+    let mut by_name = None;
     for process in sys.processes().values() {
         debug!("the exe for pid {} is {:?}", process.pid(), process.exe());
-        if let Some(exe_path) = process.exe() {
-            if exe_path.to_str() == Some(path) {
-                let pid_as_usize = usize::from(process.pid());
-                return Some(pid_as_usize as u32);
-            }
+        let Some(exe_path) = process.exe() else {
+            continue;
+        };
+        let pid = usize::from(process.pid()) as u32;
+        let exe_path = normalize_exe_path(exe_path);
+        if exe_path == target {
+            return Some(pid);
+        }
+        if basename_fallback && by_name.is_none() && exe_path.file_name() == target.file_name() {
+            by_name = Some(pid);
         }
     }
-    None
+    by_name
+}
+
+/// 规范化可执行文件路径：去掉升级后 `/proc/<pid>/exe` 带的 ` (deleted)` 后缀，
+/// 能解析时再解析符号链接；文件已不存在时保留原路径。
+pub fn normalize_exe_path(path: &Path) -> PathBuf {
+    let stripped = path
+        .to_str()
+        .and_then(|s| s.strip_suffix(" (deleted)"))
+        .map(Path::new)
+        .unwrap_or(path);
+    std::fs::canonicalize(stripped).unwrap_or_else(|_| stripped.to_path_buf())
 }
 
 /// 截断进程名到内核限制的16个字符（包括null终止符，所以实际是15个字符）
@@ -30,6 +61,27 @@ pub fn truncate_process_name(name: &str) -> String {
     }
 }
 
+/// 获取互斥锁；若持有者 panic 导致锁被毒化，记录警告、清除毒化标记并继续使用其中的数据
+pub fn lock_or_recover<'a, T>(mutex: &'a Mutex<T>, what: &str) -> MutexGuard<'a, T> {
+    mutex.lock().unwrap_or_else(|poisoned| {
+        warn!(
+            "Mutex guarding {} was poisoned by a panicked task, recovering.",
+            what
+        );
+        mutex.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+/// 将进程名编码为 `PROCESS_NAMES_TO_MONITOR` 的键：最多15字节，末尾保留null终止符
+pub fn process_name_key(name: &str) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    let bytes = name.as_bytes();
+    let copy_len = bytes.len().min(15);
+    key[..copy_len].copy_from_slice(&bytes[..copy_len]);
+    key
+}
+
 /// 根据截断的进程名查找完整的进程配置名
 /// 返回可能匹配的进程配置名列表
 #[allow(dead_code)] // Utility function for future use
@@ -76,6 +128,43 @@ pub fn extract_executable_name(command: &str) -> String {
         .to_string()
 }
 
+// 按脚本或 jar 包区分实例的常见解释器
+const KNOWN_INTERPRETERS: &[&str] = &[
+    "python", "python2", "python3", "perl", "ruby", "node", "php", "lua", "bash", "sh", "java",
+];
+
+// python3.11、perl5.36 这类带版本号的可执行文件名按去掉版本号后的名称判断
+fn is_interpreter(executable: &str) -> bool {
+    let base = executable.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    KNOWN_INTERPRETERS.contains(&executable) || KNOWN_INTERPRETERS.contains(&base)
+}
+
+/// command 为已知解释器时，从 args 中取出被执行的脚本（java 为 `-jar` 之后的 jar 包，
+/// python 的 `-m` 为模块名）的文件名，作为匹配用的进程名；不是解释器或找不到脚本时返回 None。
+/// 例如：`python3 /opt/app/foo.py` -> "foo.py"，`java -Xmx1g -jar app.jar` -> "app.jar"
+pub fn interpreter_script_name(command: &str, args: &[String]) -> Option<String> {
+    let executable = extract_executable_name(command);
+    if !is_interpreter(&executable) {
+        return None;
+    }
+    let mut args = args.iter();
+    let script = if executable == "java" {
+        args.by_ref().find(|arg| *arg == "-jar")?;
+        args.next()?
+    } else {
+        loop {
+            let arg = args.next()?;
+            if arg == "-m" {
+                break args.next()?;
+            }
+            if !arg.starts_with('-') {
+                break arg;
+            }
+        }
+    };
+    Some(extract_executable_name(script))
+}
+
 /// 匹配进程名：处理截断名可能对应多个配置的情况
 /// 优先返回精确匹配，如果有多个匹配则返回第一个
 #[allow(dead_code)] // Utility function for future use
@@ -101,3 +190,226 @@ pub fn smart_match_process_name(
         None
     }
 }
+
+// 解析 /proc/<pid>/stat 中的状态、进程组与会话 ID；comm 可能包含空格与括号，从最后一个 ')' 之后开始解析
+fn parse_proc_stat(stat: &str) -> Option<(char, i32, i32)> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    let mut fields = rest.split_whitespace();
+    let state = fields.next()?.chars().next()?;
+    let _ppid = fields.next()?;
+    let pgrp = fields.next()?.parse().ok()?;
+    let session = fields.next()?.parse().ok()?;
+    Some((state, pgrp, session))
+}
+
+/// 按 glob 解析命令失败的原因
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CommandGlobError {
+    #[error("invalid glob pattern '{pattern}': {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+    #[error("glob '{0}' matches no file")]
+    NoMatch(String),
+    #[error("glob '{pattern}' matches {count} files")]
+    Ambiguous { pattern: String, count: usize },
+}
+
+/// 命令中是否含有 glob 通配符
+pub fn is_glob_pattern(command: &str) -> bool {
+    command.contains(['*', '?', '['])
+}
+
+/// 解析命令路径：不含通配符时原样返回；否则在匹配的普通文件中按 `policy` 选择修改时间最新的一个，
+/// 或在匹配到多个时报错
+pub fn resolve_command(
+    command: &str,
+    policy: CommandGlobPolicy,
+) -> Result<PathBuf, CommandGlobError> {
+    if !is_glob_pattern(command) {
+        return Ok(PathBuf::from(command));
+    }
+    let paths = glob::glob(command).map_err(|e| CommandGlobError::InvalidPattern {
+        pattern: command.to_string(),
+        reason: e.msg.to_string(),
+    })?;
+    let mut matches: Vec<(PathBuf, std::time::SystemTime)> = paths
+        .filter_map(|path| path.ok())
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(&path).ok()?;
+            let modified = metadata.modified().ok()?;
+            metadata.is_file().then_some((path, modified))
+        })
+        .collect();
+    if matches.len() > 1 && policy == CommandGlobPolicy::Fail {
+        return Err(CommandGlobError::Ambiguous {
+            pattern: command.to_string(),
+            count: matches.len(),
+        });
+    }
+    // 修改时间相同时取路径排序靠后的（通常是较高的版本号）
+    matches.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    matches
+        .pop()
+        .map(|(path, _)| path)
+        .ok_or_else(|| CommandGlobError::NoMatch(command.to_string()))
+}
+
+/// `path` 所在文件系统对非特权用户可用的空间（字节）；`path` 尚不存在时按最近的已存在上级目录统计
+pub fn available_bytes(path: &Path) -> Option<u64> {
+    let stat = path
+        .ancestors()
+        .find_map(|dir| nix::sys::statvfs::statvfs(dir).ok())?;
+    Some(stat.blocks_available() as u64 * stat.fragment_size() as u64)
+}
+
+/// 进程所在的进程组或会话 ID，进程不存在时返回 None
+pub fn group_id_of(pid: i32, scope: GroupScope) -> Option<i32> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let (_, pgrp, session) = parse_proc_stat(&stat)?;
+    Some(match scope {
+        GroupScope::ProcessGroup => pgrp,
+        GroupScope::Session => session,
+    })
+}
+
+/// 扫描 /proc，统计仍属于给定进程组或会话的进程数（不含僵尸进程）
+pub fn count_group_members(scope: GroupScope, id: i32) -> usize {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("stat")).ok())
+        .filter_map(|stat| parse_proc_stat(&stat))
+        .filter(|(state, pgrp, session)| {
+            let member_id = match scope {
+                GroupScope::ProcessGroup => *pgrp,
+                GroupScope::Session => *session,
+            };
+            *state != 'Z' && member_id == id
+        })
+        .count()
+}
+
+/// PID 对应的进程是否仍在运行（不含僵尸进程）且启动于 `at` 之前，用于确认上报故障的实例没有被复用该 PID 的进程取代；
+/// 启动时间按秒级的 btime 换算，留 1 秒余量
+pub fn running_since(pid: u32, at: SystemTime) -> bool {
+    let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) else {
+        return false;
+    };
+    let Some((_, rest)) = stat.rsplit_once(')') else {
+        return false;
+    };
+    // ')' 之后第一个字段是状态（第 3 个字段），启动时间是第 22 个字段，单位为时钟滴答
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    if matches!(fields.first(), None | Some(&"Z")) {
+        return false;
+    }
+    let Some(start_ticks) = fields.get(19).and_then(|ticks| ticks.parse::<u64>().ok()) else {
+        return false;
+    };
+    let Some(boot_secs) = std::fs::read_to_string("/proc/stat").ok().and_then(|stat| {
+        stat.lines()
+            .find_map(|line| line.strip_prefix("btime ")?.trim().parse::<u64>().ok())
+    }) else {
+        return false;
+    };
+    // SAFETY: sysconf 只读取系统配置
+    let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks_per_sec <= 0 {
+        return false;
+    }
+    let started = UNIX_EPOCH
+        + Duration::from_secs(boot_secs)
+        + Duration::from_millis(start_ticks * 1000 / ticks_per_sec as u64);
+    started <= at + Duration::from_secs(1)
+}
+
+/// 查找不是由自愈器拉起、仍在运行的进程：按 PID 监控的 PID 文件 / cgroup.procs 查找，找不到时按 command 的可执行文件扫描
+pub fn find_running_pid(process_config: &ProcessConfig) -> Option<u32> {
+    let from_monitor = process_config
+        .get_pid_monitor_config()
+        .and_then(|monitor| match monitor.source {
+            PidSource::File { path } | PidSource::CgroupProcs { path } => {
+                std::fs::read_to_string(path)
+                    .ok()?
+                    .lines()
+                    .find_map(|line| line.trim().parse::<u32>().ok().filter(|pid| *pid > 0))
+            }
+            PidSource::Command { .. } => None,
+        })
+        .filter(|pid| Path::new(&format!("/proc/{}", pid)).exists());
+    from_monitor.or_else(|| {
+        let exe = resolve_command(&process_config.command, process_config.command_glob).ok()?;
+        find_pid_by_exe(&exe, false)
+    })
+}
+
+/// 先发送 SIGTERM，timeout 内进程仍未退出时发送 SIGKILL；进程已退出或被杀死时返回 true
+pub async fn terminate(process: &str, pid: u32, timeout: Duration) -> bool {
+    let target = Pid::from_raw(pid as i32);
+    if let Err(e) = kill(target, Signal::SIGTERM) {
+        warn!("Failed to stop process '{}' (pid {}): {}", process, pid, e);
+        return false;
+    }
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tokio::time::Instant::now() < deadline {
+        if kill(target, None).is_err() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    warn!(
+        "Process '{}' (pid {}) did not exit in time, killing it.",
+        process, pid
+    );
+    kill(target, Signal::SIGKILL).is_ok()
+}
+
+/// 展开 URL 模板失败的原因
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum UrlTemplateError {
+    #[error("environment variable '{0}' is not set")]
+    MissingEnv(String),
+    #[error("'{{port}}' is used but no port is configured")]
+    MissingPort,
+    #[error("unterminated '${{' in '{0}'")]
+    Unterminated(String),
+    #[error("'{url}' is not a valid URL: {reason}")]
+    InvalidUrl { url: String, reason: String },
+}
+
+/// 展开 URL 模板：`${NAME}` 取环境变量，`{port}` 取 `port`，展开后必须是合法的 URL。
+/// 例如：`http://127.0.0.1:${APP_PORT}/health`、`http://127.0.0.1:{port}/health`
+pub fn expand_url_template(template: &str, port: Option<u16>) -> Result<String, UrlTemplateError> {
+    let mut url = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        url.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| UrlTemplateError::Unterminated(template.to_string()))?;
+        let name = &after[..end];
+        let value =
+            std::env::var(name).map_err(|_| UrlTemplateError::MissingEnv(name.to_string()))?;
+        url.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    url.push_str(rest);
+
+    if url.contains("{port}") {
+        let port = port.ok_or(UrlTemplateError::MissingPort)?;
+        url = url.replace("{port}", &port.to_string());
+    }
+    reqwest::Url::parse(&url).map_err(|e| UrlTemplateError::InvalidUrl {
+        url: url.clone(),
+        reason: e.to_string(),
+    })?;
+    Ok(url)
+}
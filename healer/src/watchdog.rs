@@ -0,0 +1,103 @@
+//! 自我看门狗：主循环定期递增心跳计数器，独立线程检查计数器是否前进；
+//! 超过 `watchdog_timeout_secs` 未前进时认为事件循环已卡死，由回调（守护进程中为退出进程）处理。
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+/// 主循环发送心跳的间隔
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+// 计数器取该值表示主循环已正常退出，看门狗随之停止
+const DISARMED: u64 = u64::MAX;
+
+/// 主循环心跳计数器，克隆后指向同一个计数器
+#[derive(Debug, Clone, Default)]
+pub struct Heartbeat(Arc<AtomicU64>);
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn beat(&self) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                (count != DISARMED).then(|| count.wrapping_add(1) % DISARMED)
+            });
+    }
+
+    /// 在主循环中等待耗时较长的操作（重载、协调监控器时等待监控器停止与 eBPF 就绪）时继续发送心跳。
+    /// 只在 `future` 挂起时发送，执行阻塞调用卡住 runtime 的情况仍会被看门狗发现
+    pub async fn beat_while<F: Future>(&self, future: F) -> F::Output {
+        let mut ticker = tokio::time::interval(HEARTBEAT_INTERVAL);
+        tokio::pin!(future);
+        loop {
+            tokio::select! {
+                output = &mut future => return output,
+                _ = ticker.tick() => self.beat(),
+            }
+        }
+    }
+
+    /// 主循环正常退出（如关闭过程较慢）时停止看门狗
+    pub fn disarm(&self) {
+        self.0.store(DISARMED, Ordering::Relaxed);
+    }
+
+    fn count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 看门狗线程
+pub struct Watchdog;
+
+impl Watchdog {
+    /// 在独立的系统线程中检查心跳，不依赖可能已被阻塞的 tokio runtime；
+    /// 心跳超过 `timeout` 未前进时调用一次 `on_stall` 并结束
+    pub fn spawn(
+        heartbeat: Heartbeat,
+        timeout: Duration,
+        on_stall: impl FnOnce() + Send + 'static,
+    ) -> std::io::Result<JoinHandle<()>> {
+        std::thread::Builder::new()
+            .name("healer-watchdog".into())
+            .spawn(move || {
+                info!("Watchdog: Started with a {:?} timeout.", timeout);
+                let poll = HEARTBEAT_INTERVAL.min(timeout);
+                let mut last_count = heartbeat.count();
+                let mut last_change = Instant::now();
+                loop {
+                    std::thread::sleep(poll);
+                    let count = heartbeat.count();
+                    if count == DISARMED {
+                        return;
+                    }
+                    if count != last_count {
+                        last_count = count;
+                        last_change = Instant::now();
+                    } else if last_change.elapsed() >= timeout {
+                        error!(
+                            "Watchdog: Main loop has not sent a heartbeat for {:?}, it appears to be stalled.",
+                            last_change.elapsed()
+                        );
+                        on_stall();
+                        return;
+                    }
+                }
+            })
+    }
+
+    /// 守护进程的处理方式：等待日志写出后以非零退出码退出，由 systemd 等外部监管者重启
+    pub fn exit_process() {
+        eprintln!("healer: event loop stalled, exiting so the supervisor can restart it");
+        // 非阻塞日志写入线程仍在运行，稍等片刻让已记录的日志落盘
+        std::thread::sleep(Duration::from_millis(200));
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,165 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+// 恢复命令只创建 marker 文件；retries 为 1，第二次恢复会被熔断器拦下
+fn config(command: &str, allowed_commands: &str, marker: &Path) -> AppConfig {
+    let yaml = format!(
+        r#"
+allowed_commands: {allowed_commands}
+processes:
+  - name: "svc"
+    enabled: true
+    command: "{command}"
+    args: ["{marker}"]
+    run_as_root: true
+    child_output: "null"
+    wait_for_exit: true
+    monitor: {{ type: "pid", pid_file_path: "/nonexistent/svc.pid", interval_secs: 60 }}
+    recovery: {{ type: "regular", retries: 1, retry_window_secs: 60, cooldown_secs: 60 }}
+"#,
+        marker = marker.display()
+    );
+    AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap()
+}
+
+async fn heal(config: AppConfig) -> (ProcessHealer, Option<RecoverySkipReason>) {
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await;
+    let reason = healer.heal_process(&"svc".to_string()).await;
+    healer.wait_for_recoveries().await;
+    (healer, reason)
+}
+
+#[tokio::test]
+async fn command_outside_the_allowlist_is_not_spawned() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("spawned");
+    let (mut healer, reason) = heal(config("/bin/touch", r#"["/usr/local/bin"]"#, &marker)).await;
+
+    let reason = reason.expect("recovery should be refused");
+    assert_eq!(
+        reason,
+        RecoverySkipReason::CommandNotAllowed {
+            program: PathBuf::from("/bin/touch")
+        }
+    );
+    assert!(reason.is_failure());
+    assert!(!marker.exists());
+    // 被拒绝的恢复已计入重试预算
+    assert!(matches!(
+        healer.heal_process(&"svc".to_string()).await,
+        Some(RecoverySkipReason::CircuitOpen { .. })
+    ));
+}
+
+#[tokio::test]
+async fn refused_command_keeps_the_previous_child_log() {
+    let name = format!("allowed-commands-{}", std::process::id());
+    let yaml = format!(
+        r#"
+allowed_commands: ["/usr/local/bin"]
+processes:
+  - name: "{name}"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    child_output: "file"
+    monitor: {{ type: "pid", pid_file_path: "/nonexistent/svc.pid", interval_secs: 60 }}
+    recovery: {{ type: "regular", retries: 1, retry_window_secs: 60 }}
+"#
+    );
+    let config = AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap();
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await;
+    let reason = healer.heal_process(&name).await;
+    assert!(matches!(
+        reason,
+        Some(RecoverySkipReason::CommandNotAllowed { .. })
+    ));
+    // 未打开（截断或创建）子进程日志
+    assert!(!Path::new(&format!("/var/log/healer/{name}.restarted.log")).exists());
+    assert!(!Path::new(&format!("/tmp/healer_{name}.restarted.log")).exists());
+}
+
+#[tokio::test]
+async fn exact_paths_and_directory_prefixes_are_allowed() {
+    let dir = TempDir::new().unwrap();
+    for allowed in [r#"["/bin/touch"]"#, r#"["/usr/local/bin", "/bin"]"#] {
+        let marker = dir.path().join("spawned");
+        let _ = std::fs::remove_file(&marker);
+        let (_, reason) = heal(config("/bin/touch", allowed, &marker)).await;
+        assert_eq!(reason, None, "allowed_commands: {allowed}");
+        assert!(marker.exists(), "allowed_commands: {allowed}");
+    }
+}
+
+#[tokio::test]
+async fn parent_dir_components_cannot_escape_a_prefix() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("spawned");
+    let (_, reason) = heal(config("/usr/../bin/touch", r#"["/usr"]"#, &marker)).await;
+    assert!(matches!(
+        reason,
+        Some(RecoverySkipReason::CommandNotAllowed { .. })
+    ));
+    assert!(!marker.exists());
+}
+
+#[tokio::test]
+async fn unset_allowlist_does_not_restrict() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("spawned");
+    let (_, reason) = heal(config("/bin/touch", "null", &marker)).await;
+    assert_eq!(reason, None);
+    assert!(marker.exists());
+}
+
+#[test]
+fn relative_entries_are_rejected() {
+    let yaml = "allowed_commands: [\"bin\"]\nprocesses: []\n";
+    let err = AppConfig::parse(yaml, ConfigFormat::Yaml).unwrap_err();
+    assert!(err.to_string().contains("allowed_commands"), "{err}");
+}
+
+#[test]
+fn guard_and_pid_source_commands_must_be_allowed() {
+    let yaml = |allowed: &str| {
+        format!(
+            r#"
+allowed_commands: {allowed}
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/usr/sbin/svc"
+    args: []
+    run_as_root: true
+    recovery_guard: {{ command: "/usr/local/bin/guard" }}
+    monitor:
+      type: "pid"
+      interval_secs: 60
+      pid_source: {{ type: "command", command: "/usr/bin/systemctl", args: ["show", "-p", "MainPID", "--value", "svc"] }}
+    recovery: {{ type: "regular", retries: 1, retry_window_secs: 60 }}
+"#
+        )
+    };
+    let err =
+        AppConfig::parse(&yaml(r#"["/usr/sbin", "/usr/bin"]"#), ConfigFormat::Yaml).unwrap_err();
+    assert!(err.to_string().contains("recovery_guard"), "{err}");
+    let err = AppConfig::parse(
+        &yaml(r#"["/usr/sbin", "/usr/local/bin"]"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("pid_source"), "{err}");
+    AppConfig::parse(
+        &yaml(r#"["/usr/sbin", "/usr/local/bin", "/usr/bin"]"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap();
+}
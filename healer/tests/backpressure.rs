@@ -0,0 +1,82 @@
+use healer::event_bus::{create_event_sender, EventReceiver, ProcessEvent};
+use std::time::SystemTime;
+use tokio::time::{sleep, timeout, Duration};
+
+const EVENTS: u32 = 500;
+
+#[tokio::test]
+async fn slow_critical_subscriber_receives_every_process_down() {
+    let sender = create_event_sender();
+    let mut slow: EventReceiver = sender.subscribe_critical().into();
+
+    // 发布数量远超队列容量，发布方应被阻塞而不是丢弃事件
+    let publisher = {
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            for pid in 1..=EVENTS {
+                sender
+                    .send(ProcessEvent::ProcessDown {
+                        name: "slow".into(),
+                        pid,
+                        at: SystemTime::now(),
+                    })
+                    .await
+                    .expect("critical subscriber should be registered");
+            }
+        })
+    };
+
+    let mut received = Vec::new();
+    while received.len() < EVENTS as usize {
+        let event = timeout(Duration::from_secs(10), slow.recv())
+            .await
+            .expect("timed out waiting for events")
+            .expect("critical channel should not lag or close");
+        if let ProcessEvent::ProcessDown { pid, .. } = event {
+            received.push(pid);
+        }
+        // 模拟处理缓慢的自愈器
+        if received.len() % 50 == 0 {
+            sleep(Duration::from_millis(20)).await;
+        }
+    }
+    publisher.await.expect("publisher task panicked");
+
+    assert_eq!(received, (1..=EVENTS).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn broadcast_observer_lags_under_the_same_load() {
+    let sender = create_event_sender();
+    let mut observer: EventReceiver = sender.subscribe().into();
+
+    for pid in 1..=EVENTS {
+        sender
+            .send(ProcessEvent::ProcessDown {
+                name: "observer".into(),
+                pid,
+                at: SystemTime::now(),
+            })
+            .await
+            .expect("observer should be registered");
+    }
+
+    // 非关键观察者仍是 broadcast 语义：落后时丢弃最旧的事件
+    assert!(matches!(
+        observer.recv().await,
+        Err(tokio::sync::broadcast::error::RecvError::Lagged(_))
+    ));
+}
+
+#[tokio::test]
+async fn publishing_without_subscribers_fails() {
+    let sender = create_event_sender();
+    let result = sender
+        .send(ProcessEvent::ProcessDown {
+            name: "nobody".into(),
+            pid: 1,
+            at: SystemTime::now(),
+        })
+        .await;
+    assert!(result.is_err(), "no subscribers should be reported");
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// 子进程把自己 stdout 指向的目标写入 marker 文件
+fn probe_process(name: &str, marker: &Path, child_output: ChildOutput) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            format!("readlink /proc/$$/fd/1 > {}", marker.display()),
+        ],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retry_window_secs: 30,
+            cooldown_secs: Some(10),
+            ..Default::default()
+        }),
+        child_output,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+async fn stdout_target_of(child_output: ChildOutput, foreground: bool) -> String {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let name = format!("child_output_{:?}_{}", child_output, std::process::id()).to_lowercase();
+    let marker = temp_dir.path().join("stdout.target");
+    let config = AppConfig {
+        processes: vec![probe_process(&name, &marker, child_output)],
+        ..Default::default()
+    };
+
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_foreground(foreground);
+    healer.heal_process(&name).await;
+
+    for _ in 0..50 {
+        if let Ok(target) = std::fs::read_to_string(&marker) {
+            if !target.trim().is_empty() {
+                return target.trim().to_string();
+            }
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    panic!("restarted child never wrote its stdout target for {name}");
+}
+
+#[tokio::test]
+async fn file_mode_writes_to_restarted_log() {
+    let target = stdout_target_of(ChildOutput::File, false).await;
+    assert!(
+        target.ends_with(".restarted.log"),
+        "expected a restarted.log file, got {target}"
+    );
+}
+
+#[tokio::test]
+async fn null_mode_discards_output() {
+    let target = stdout_target_of(ChildOutput::Null, false).await;
+    assert_eq!(target, "/dev/null");
+}
+
+#[tokio::test]
+async fn inherit_mode_shares_healer_stdout_in_foreground() {
+    let own = std::fs::read_link("/proc/self/fd/1").expect("failed to read own stdout");
+    let target = stdout_target_of(ChildOutput::Inherit, true).await;
+    assert_eq!(target, own.to_string_lossy());
+}
+
+#[tokio::test]
+async fn inherit_mode_falls_back_to_file_when_daemonized() {
+    let target = stdout_target_of(ChildOutput::Inherit, false).await;
+    assert!(
+        target.ends_with(".restarted.log"),
+        "inherit without a terminal should fall back to the log file, got {target}"
+    );
+}
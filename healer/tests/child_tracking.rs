@@ -0,0 +1,91 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::child_registry::ChildRegistry;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::os::unix::process::ExitStatusExt;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::time::{timeout, Duration};
+
+fn shell_process(name: &str, script: &str) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec!["-c".into(), script.into()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retry_window_secs: 30,
+            cooldown_secs: Some(10),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(format!("/tmp/{name}.pid"))),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig, children: &ChildRegistry) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_children(children.clone())
+}
+
+#[tokio::test]
+async fn fast_exiting_child_is_observed() {
+    let children = ChildRegistry::new();
+    let name = "fast_exit".to_string();
+    let mut healer = healer_for(shell_process(&name, "exit 3"), &children).await;
+
+    healer.heal_process(&name).await;
+
+    let child = children
+        .get(&name)
+        .expect("restarted child should be registered");
+    let exit = timeout(Duration::from_secs(5), child.wait())
+        .await
+        .expect("child exit was never observed")
+        .expect("wait task should report the exit");
+    assert_eq!(exit.status.and_then(|s| s.code()), Some(3));
+    assert!(
+        exit.uptime < Duration::from_secs(1),
+        "uptime should reflect an immediate exit, got {:?}",
+        exit.uptime
+    );
+    assert_eq!(child.exit(), Some(exit));
+}
+
+#[tokio::test]
+async fn running_child_is_tracked_until_it_exits() {
+    let children = ChildRegistry::new();
+    let name = "long_running".to_string();
+    let mut healer = healer_for(shell_process(&name, "sleep 30"), &children).await;
+
+    healer.heal_process(&name).await;
+
+    let child = children
+        .get(&name)
+        .expect("restarted child should be registered");
+    assert!(child.exit().is_none());
+    assert!(children.is_tracked_pid(child.pid));
+
+    nix::sys::signal::kill(
+        nix::unistd::Pid::from_raw(child.pid as i32),
+        nix::sys::signal::Signal::SIGKILL,
+    )
+    .unwrap();
+    let exit = timeout(Duration::from_secs(5), child.wait())
+        .await
+        .expect("child exit was never observed")
+        .expect("wait task should report the exit");
+    assert_eq!(exit.status.and_then(|s| s.signal()), Some(9));
+    assert!(!children.is_tracked_pid(child.pid));
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, CommandGlobPolicy, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use healer::utils::{resolve_command, CommandGlobError};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+// 安装一个版本：脚本把版本号写入第一个参数指定的文件，修改时间设为 `age` 之前
+fn install(root: &Path, version: &str, age: Duration) -> PathBuf {
+    let bin = root.join(format!("app-{}", version)).join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let exe = bin.join("app");
+    std::fs::write(&exe, format!("#!/bin/sh\necho {} > \"$1\"\n", version)).unwrap();
+    std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&exe)
+        .unwrap()
+        .set_modified(SystemTime::now() - age)
+        .unwrap();
+    exe
+}
+
+// 每次恢复执行一次命令，等待其退出
+fn process(command: &str, marker: &Path, policy: CommandGlobPolicy) -> ProcessConfig {
+    ProcessConfig {
+        command: command.into(),
+        args: vec![marker.display().to_string()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 2,
+            cooldown_secs: Some(60),
+            dedup_window_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        wait_for_exit: true,
+        command_glob: policy,
+        ..common::process(
+            "versioned",
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+fn pattern(root: &Path) -> String {
+    format!("{}/app-*/bin/app", root.display())
+}
+
+#[test]
+fn newest_match_wins_regardless_of_name() {
+    let dir = TempDir::new().unwrap();
+    // 版本号较大但安装得更早
+    install(dir.path(), "2.0", Duration::from_secs(3600));
+    let newest = install(dir.path(), "1.5", Duration::from_secs(60));
+
+    assert_eq!(
+        resolve_command(&pattern(dir.path()), CommandGlobPolicy::Newest),
+        Ok(newest)
+    );
+    assert_eq!(
+        resolve_command(&pattern(dir.path()), CommandGlobPolicy::Fail),
+        Err(CommandGlobError::Ambiguous {
+            pattern: pattern(dir.path()),
+            count: 2
+        })
+    );
+}
+
+#[test]
+fn plain_commands_and_missing_matches() {
+    assert_eq!(
+        resolve_command("/bin/sh", CommandGlobPolicy::Fail),
+        Ok(PathBuf::from("/bin/sh"))
+    );
+    let dir = TempDir::new().unwrap();
+    assert_eq!(
+        resolve_command(&pattern(dir.path()), CommandGlobPolicy::Newest),
+        Err(CommandGlobError::NoMatch(pattern(dir.path())))
+    );
+}
+
+#[tokio::test]
+async fn recovery_runs_the_newest_installed_version() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("version");
+    install(dir.path(), "1.0", Duration::from_secs(3600));
+    install(dir.path(), "1.1", Duration::from_secs(60));
+    let mut healer = healer_for(process(
+        &pattern(dir.path()),
+        &marker,
+        CommandGlobPolicy::Newest,
+    ))
+    .await;
+
+    assert_eq!(healer.heal_process(&"versioned".to_string()).await, None);
+    healer.wait_for_recoveries().await;
+    assert_eq!(std::fs::read_to_string(&marker).unwrap(), "1.1\n");
+}
+
+#[tokio::test]
+async fn ambiguous_match_fails_the_attempt_when_configured() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("version");
+    install(dir.path(), "1.0", Duration::from_secs(3600));
+    install(dir.path(), "1.1", Duration::from_secs(60));
+    let mut healer = healer_for(process(
+        &pattern(dir.path()),
+        &marker,
+        CommandGlobPolicy::Fail,
+    ))
+    .await;
+
+    assert_eq!(
+        healer.heal_process(&"versioned".to_string()).await,
+        Some(RecoverySkipReason::CommandUnavailable)
+    );
+    assert!(!marker.exists());
+}
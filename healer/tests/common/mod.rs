@@ -0,0 +1,45 @@
+// 各集成测试共用的配置构造函数：只写测试关心的字段，其余取默认值。ProcessConfig 新增字段时
+// 不需要改动测试；监控字段在这里集中构造，新增时只需改动本文件
+#![allow(dead_code)]
+
+use healer::config::{MonitorConfig, NetworkMonitorFields, PidMonitorFields, ProcessConfig};
+use std::path::PathBuf;
+
+/// 启用、以 root 运行 `/bin/true` 的进程
+pub fn process(name: impl Into<String>, monitor: MonitorConfig) -> ProcessConfig {
+    ProcessConfig {
+        name: name.into(),
+        enabled: true,
+        command: "/bin/true".into(),
+        run_as_root: true,
+        monitor,
+        ..Default::default()
+    }
+}
+
+/// 每秒读取一次 PID 文件的 pid 监控
+pub fn pid_fields(pid_file_path: impl Into<PathBuf>) -> PidMonitorFields {
+    PidMonitorFields {
+        pid_file_path: Some(pid_file_path.into()),
+        pid_source: None,
+        interval_secs: 1,
+        check_timeout_secs: None,
+        memory_growth: None,
+        startup_grace_secs: 0,
+        reconcile_pid_file: false,
+        watch_group: None,
+    }
+}
+
+/// 每分钟检查一次的网络监控
+pub fn network_fields(target_url: impl Into<String>) -> NetworkMonitorFields {
+    NetworkMonitorFields {
+        target_url: target_url.into(),
+        interval_secs: 60,
+        check_timeout_secs: None,
+        max_backoff_secs: None,
+        health_json_path: None,
+        health_json_expect: None,
+        port: None,
+    }
+}
@@ -0,0 +1,144 @@
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn process_yaml(name: &str, command: &str, extra: &str) -> String {
+    format!(
+        r#"
+  - name: "{name}"
+    enabled: true
+    command: "{command}"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "pid", pid_file_path: "/run/{name}.pid", interval_secs: 3 }}
+{extra}"#
+    )
+}
+
+// 运行 `healer --config <file> check`，返回退出码与解析后的 JSON 报告
+fn run_check(dir: &Path, processes: &[String]) -> (i32, Value) {
+    let path = dir.join("config.yaml");
+    std::fs::write(&path, format!("processes:{}", processes.concat())).unwrap();
+    run_check_path(&path)
+}
+
+fn run_check_path(path: &Path) -> (i32, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_healer"))
+        .arg("--config")
+        .arg(path)
+        .arg("check")
+        .output()
+        .expect("failed to run healer check");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let report: Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("stdout should be a JSON report ({e}):\n{stdout}"));
+    (output.status.code().expect("exit code"), report)
+}
+
+fn categories(report: &Value) -> Vec<String> {
+    report["problems"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["category"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn clean_config_exits_zero() {
+    let temp_dir = TempDir::new().unwrap();
+    let (code, report) = run_check(
+        temp_dir.path(),
+        &[
+            process_yaml("db", "/bin/true", ""),
+            process_yaml("web", "true", "    dependencies: [\"db\"]\n"),
+        ],
+    );
+    assert_eq!(code, 0, "{report}");
+    assert_eq!(report["ok"], true);
+    assert_eq!(report["processes"], 2);
+}
+
+#[test]
+fn missing_binary_and_cycle_have_distinct_exit_codes() {
+    let temp_dir = TempDir::new().unwrap();
+    let (missing_code, report) = run_check(
+        temp_dir.path(),
+        &[process_yaml("web", "/nonexistent/bin/web", "")],
+    );
+    assert_eq!(categories(&report), vec!["missing_binary"]);
+    assert_eq!(report["problems"][0]["process"], "web");
+
+    let (cycle_code, report) = run_check(
+        temp_dir.path(),
+        &[
+            process_yaml("a", "/bin/true", "    dependencies: [\"b\"]\n"),
+            process_yaml("b", "/bin/true", "    dependencies: [\"a\"]\n"),
+        ],
+    );
+    assert_eq!(categories(&report), vec!["dependency_cycle"]);
+    assert_eq!(
+        report["problems"][0]["message"],
+        "dependency cycle: a -> b -> a"
+    );
+
+    assert_ne!(missing_code, 0);
+    assert_ne!(cycle_code, 0);
+    assert_ne!(missing_code, cycle_code);
+}
+
+#[test]
+fn non_executable_command_is_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    let script = temp_dir.path().join("script.sh");
+    std::fs::write(&script, "#!/bin/sh\n").unwrap();
+    let (code, report) = run_check(
+        temp_dir.path(),
+        &[process_yaml("svc", script.to_str().unwrap(), "")],
+    );
+    assert_eq!(code, 15);
+    let message = report["problems"][0]["message"].as_str().unwrap();
+    assert!(message.contains("is not executable"), "{message}");
+}
+
+#[test]
+fn unknown_dependency_and_user_are_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    let (code, report) = run_check(
+        temp_dir.path(),
+        &[process_yaml(
+            "web",
+            "/bin/true",
+            "    run_as_user: \"no-such-user-healer\"\n    dependencies: [\"ghost\"]\n",
+        )],
+    );
+    assert_eq!(
+        categories(&report),
+        vec!["unknown_dependency", "unknown_user"]
+    );
+    // 多类问题时取最靠前的类别
+    assert_eq!(code, 13);
+}
+
+#[test]
+fn load_failures_have_their_own_exit_codes() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let (code, report) = run_check_path(&temp_dir.path().join("missing.yaml"));
+    assert_eq!(categories(&report), vec!["config_read"]);
+    assert_eq!(code, 10);
+
+    let path = temp_dir.path().join("broken.yaml");
+    std::fs::write(&path, "processes: [").unwrap();
+    let (code, report) = run_check_path(&path);
+    assert_eq!(categories(&report), vec!["config_parse"]);
+    assert_eq!(code, 11);
+
+    let (code, report) = run_check(
+        temp_dir.path(),
+        &[process_yaml("web", "/bin/true", "").replace("interval_secs: 3", "interval_secs: 0")],
+    );
+    assert_eq!(categories(&report), vec!["config_invalid"]);
+    assert_eq!(code, 12);
+}
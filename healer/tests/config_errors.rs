@@ -0,0 +1,37 @@
+use healer::config::{AppConfig, ConfigError, ConfigFormat};
+use std::error::Error;
+use tempfile::TempDir;
+
+#[test]
+fn missing_file_is_an_io_error() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let err = AppConfig::load_from_file(&temp_dir.path().join("missing.yaml"))
+        .expect_err("a missing file should fail to load");
+    match err {
+        ConfigError::Io(e) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+        other => panic!("expected ConfigError::Io, got {other:?}"),
+    }
+}
+
+#[test]
+fn malformed_file_is_a_parse_error() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let path = temp_dir.path().join("broken.json");
+    std::fs::write(&path, "{ \"processes\": [").unwrap();
+    let err = AppConfig::load_from_file(&path).expect_err("malformed JSON should fail to load");
+    assert!(err.source().is_some(), "parse errors keep the parser error");
+    match err {
+        ConfigError::Parse { format, .. } => assert_eq!(format, ConfigFormat::Json),
+        other => panic!("expected ConfigError::Parse, got {other:?}"),
+    }
+}
+
+#[test]
+fn invalid_value_is_a_validation_error() {
+    let err = AppConfig::parse(
+        "processes: []\nruntime_worker_threads: 0\n",
+        ConfigFormat::Yaml,
+    )
+    .expect_err("0 worker threads should be rejected");
+    assert!(matches!(err, ConfigError::Validation(_)), "got {err:?}");
+}
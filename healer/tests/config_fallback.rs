@@ -0,0 +1,93 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::config_cache;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+// 监控测试进程自身（始终存活），oneshot 检查结果为 0 个异常
+fn config_yaml(dir: &Path, allow_fallback: bool) -> String {
+    let pid_file = dir.join("self.pid");
+    std::fs::write(&pid_file, std::process::id().to_string()).unwrap();
+    format!(
+        r#"
+allow_config_fallback: {allow_fallback}
+processes:
+  - name: "cached"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "pid", pid_file_path: "{}", interval_secs: 1 }}
+"#,
+        pid_file.display()
+    )
+}
+
+fn oneshot(config_path: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_healer"))
+        .arg("--config")
+        .arg(config_path)
+        .arg("oneshot")
+        .output()
+        .expect("failed to run healer oneshot")
+}
+
+const CORRUPT: &str = "processes:\n  - name: \"cached\n    enabled: tr";
+
+#[test]
+fn startup_falls_back_to_the_last_known_good_config() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("config.yaml");
+    std::fs::write(&config_path, config_yaml(dir.path(), true)).unwrap();
+
+    let output = oneshot(&config_path);
+    assert!(output.status.success(), "{output:?}");
+    assert!(config_cache::cache_path(&config_path).exists());
+
+    // 主配置在两次启动之间被截断
+    std::fs::write(&config_path, CORRUPT).unwrap();
+    let output = oneshot(&config_path);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr:\n{stderr}");
+    assert!(stderr.contains("last-known-good"), "stderr:\n{stderr}");
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("checked 1 process(es), 0 down"),
+        "the cached config should have been used: {output:?}"
+    );
+}
+
+#[test]
+fn corrupt_config_without_fallback_still_fails() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("config.yaml");
+    std::fs::write(&config_path, config_yaml(dir.path(), false)).unwrap();
+    assert!(oneshot(&config_path).status.success());
+    assert!(!config_cache::cache_path(&config_path).exists());
+
+    std::fs::write(&config_path, CORRUPT).unwrap();
+    assert!(!oneshot(&config_path).status.success());
+}
+
+#[test]
+fn cache_follows_the_allow_config_fallback_setting() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("healer.yaml");
+    assert_eq!(
+        config_cache::cache_path(&config_path),
+        dir.path().join("healer.yaml.last-good")
+    );
+
+    let source = config_yaml(dir.path(), true);
+    let config = AppConfig::parse(&source, ConfigFormat::Yaml).unwrap();
+    config_cache::update(&config_path, &config, &source);
+    let cached = config_cache::load_fallback(&config_path, ConfigFormat::Yaml)
+        .expect("cache should be usable");
+    assert_eq!(cached.processes[0].name, "cached");
+
+    // 关闭选项后旧缓存被删除，不会再回退
+    let source = config_yaml(dir.path(), false);
+    let config = AppConfig::parse(&source, ConfigFormat::Yaml).unwrap();
+    config_cache::update(&config_path, &config, &source);
+    assert!(!config_cache::cache_path(&config_path).exists());
+    assert!(config_cache::load_fallback(&config_path, ConfigFormat::Yaml).is_none());
+}
@@ -0,0 +1,100 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::config_graph::{DependencyGraph, GraphFormat};
+
+fn config(processes: &str) -> AppConfig {
+    AppConfig::parse(&format!("processes:\n{processes}"), ConfigFormat::Yaml)
+        .expect("config should parse")
+}
+
+fn process(name: &str, dependencies: &str) -> String {
+    format!(
+        r#"  - name: "{name}"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "pid", pid_file_path: "/run/{name}.pid", interval_secs: 3 }}
+    dependencies: {dependencies}
+"#
+    )
+}
+
+#[test]
+fn dot_output_contains_labeled_edges() {
+    let config = config(
+        &[
+            process(
+                "web",
+                r#"[ "db", { target: "cache", kind: "after", hard: false, on_failure: "skip" } ]"#,
+            ),
+            process("db", "[]"),
+            process("cache", "[]"),
+        ]
+        .concat(),
+    );
+    let graph = DependencyGraph::from_config(&config);
+    assert!(graph.cycles.is_empty());
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph healer {"), "{dot}");
+    assert!(dot.trim_end().ends_with('}'), "{dot}");
+    assert!(
+        dot.contains(r#""web" -> "db" [label="requires, hard, on_failure=abort"];"#),
+        "{dot}"
+    );
+    assert!(
+        dot.contains(r#""web" -> "cache" [label="after, soft, on_failure=skip", style=dashed];"#),
+        "{dot}"
+    );
+    assert!(!dot.contains("color=red"), "{dot}");
+}
+
+#[test]
+fn cycles_and_missing_targets_are_highlighted() {
+    let config = config(
+        &[
+            process("a", r#"[ "b" ]"#),
+            process("b", r#"[ "a", "ghost" ]"#),
+        ]
+        .concat(),
+    );
+    let graph = DependencyGraph::from_config(&config);
+    assert_eq!(graph.cycles, vec![vec!["a", "b", "a"]]);
+
+    let in_cycle: Vec<_> = graph
+        .edges
+        .iter()
+        .map(|edge| (edge.from.as_str(), edge.to.as_str(), edge.in_cycle))
+        .collect();
+    assert_eq!(
+        in_cycle,
+        vec![("a", "b", true), ("b", "a", true), ("b", "ghost", false)]
+    );
+    assert!(graph
+        .nodes
+        .iter()
+        .any(|node| node.name == "ghost" && node.missing));
+
+    let dot = graph.to_dot();
+    assert!(
+        dot.contains(
+            r#""a" -> "b" [label="requires, hard, on_failure=abort", color=red, penwidth=2];"#
+        ),
+        "{dot}"
+    );
+    assert!(dot.contains(r#""ghost" [style=dashed"#), "{dot}");
+}
+
+#[test]
+fn json_output_lists_nodes_and_edges() {
+    let config = config(&[process("web", r#"[ "db" ]"#), process("db", "[]")].concat());
+    let json = serde_json::to_value(DependencyGraph::from_config(&config)).unwrap();
+    assert_eq!(json["nodes"].as_array().unwrap().len(), 2);
+    assert_eq!(json["edges"][0]["from"], "web");
+    assert_eq!(json["edges"][0]["to"], "db");
+    assert_eq!(json["edges"][0]["kind"], "requires");
+    assert_eq!(json["edges"][0]["in_cycle"], false);
+
+    assert_eq!("JSON".parse::<GraphFormat>(), Ok(GraphFormat::Json));
+    assert!("svg".parse::<GraphFormat>().is_err());
+}
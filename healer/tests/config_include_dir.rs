@@ -0,0 +1,109 @@
+use healer::config::{AppConfig, ConfigFormat};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn process_yaml(name: &str, command: &str) -> String {
+    format!(
+        r#"
+  - name: "{name}"
+    enabled: true
+    command: "{command}"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "pid", pid_file_path: "/run/{name}.pid", interval_secs: 5 }}
+"#
+    )
+}
+
+fn write(path: &Path, content: &str) {
+    std::fs::write(path, content).unwrap();
+}
+
+fn main_config(dir: &Path, include_dir: &str) -> std::path::PathBuf {
+    let path = dir.join("healer.yaml");
+    write(
+        &path,
+        &format!(
+            "include_dir: \"{include_dir}\"\nprocesses:{}{}",
+            process_yaml("web", "/usr/bin/web"),
+            process_yaml("db", "/usr/bin/db")
+        ),
+    );
+    path
+}
+
+#[test]
+fn later_files_override_processes_by_name_and_append_new_ones() {
+    let dir = TempDir::new().unwrap();
+    let conf_d = dir.path().join("conf.d");
+    std::fs::create_dir(&conf_d).unwrap();
+    // 文件名顺序决定优先级：20-local 覆盖 10-vendor
+    write(
+        &conf_d.join("20-local.yaml"),
+        &format!("processes:{}", process_yaml("web", "/opt/local/web")),
+    );
+    write(
+        &conf_d.join("10-vendor.yaml"),
+        &format!(
+            "processes:{}{}",
+            process_yaml("web", "/opt/vendor/web"),
+            process_yaml("cache", "/usr/bin/cache")
+        ),
+    );
+    // 非 YAML 文件被忽略
+    write(&conf_d.join("README"), "not a config");
+
+    let path = main_config(dir.path(), "conf.d");
+    let config = AppConfig::load_from_file(&path).unwrap();
+
+    let processes: Vec<(&str, &str)> = config
+        .processes
+        .iter()
+        .map(|p| (p.name.as_str(), p.command.as_str()))
+        .collect();
+    assert_eq!(
+        processes,
+        [
+            ("web", "/opt/local/web"),
+            ("db", "/usr/bin/db"),
+            ("cache", "/usr/bin/cache")
+        ]
+    );
+}
+
+#[test]
+fn missing_include_dir_is_not_an_error() {
+    let dir = TempDir::new().unwrap();
+    let path = main_config(dir.path(), "absent.d");
+    let config = AppConfig::load_from_file(&path).unwrap();
+    assert_eq!(config.processes.len(), 2);
+}
+
+#[test]
+fn merged_config_is_validated_and_overlay_errors_name_the_file() {
+    let dir = TempDir::new().unwrap();
+    let conf_d = dir.path().join("conf.d");
+    std::fs::create_dir(&conf_d).unwrap();
+    write(&conf_d.join("broken.yaml"), "processes: [ { name: 1 ");
+    let path = main_config(dir.path(), "conf.d");
+    let err = AppConfig::load_from_file(&path).unwrap_err();
+    assert!(err.to_string().contains("broken.yaml"), "{err}");
+
+    // 附加配置中的进程同样经过校验
+    write(
+        &conf_d.join("broken.yaml"),
+        "processes:\n  - { name: \"bad\", enabled: true, command: \"/bin/true\", args: [], run_as_root: true, monitor: { type: \"network\", target_url: \"not a url\", interval_secs: 5 } }\n",
+    );
+    let err = AppConfig::load_from_file(&path).unwrap_err();
+    assert!(err.to_string().contains("'bad'"), "{err}");
+}
+
+#[test]
+fn parse_without_a_path_ignores_include_dir() {
+    let yaml = format!(
+        "include_dir: \"/nonexistent/conf.d\"\nprocesses:{}",
+        process_yaml("web", "/usr/bin/web")
+    );
+    let config = AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap();
+    assert_eq!(config.processes.len(), 1);
+}
@@ -0,0 +1,144 @@
+use healer::config::{AppConfig, ConfigFormat};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use tempfile::TempDir;
+
+const YAML: &str = r#"
+processes:
+  - name: "web"
+    enabled: true
+    command: "/usr/bin/web"
+    args: []
+    run_as_root: true
+    monitor: { type: "pid", pid_file_path: "/run/web.pid", interval_secs: 3 }
+    recovery: { type: "regular", retries: 3, retry_window_secs: 60, cooldown_secs: 180 }
+"#;
+
+const TOML: &str = r#"
+[[processes]]
+name = "web"
+enabled = true
+command = "/usr/bin/web"
+args = []
+run_as_root = true
+
+[processes.monitor]
+type = "pid"
+pid_file_path = "/run/web.pid"
+interval_secs = 3
+
+[processes.recovery]
+type = "regular"
+retries = 3
+retry_window_secs = 60
+cooldown_secs = 180
+"#;
+
+const JSON: &str = r#"{
+  "processes": [{
+    "name": "web", "enabled": true, "command": "/usr/bin/web", "args": [], "run_as_root": true,
+    "monitor": { "type": "pid", "pid_file_path": "/run/web.pid", "interval_secs": 3 },
+    "recovery": { "type": "regular", "retries": 3, "retry_window_secs": 60, "cooldown_secs": 180 }
+  }]
+}"#;
+
+fn list_from_stdin(config: &str, format: Option<&str>) -> Output {
+    list_from_stdin_in(config, format, Path::new("."))
+}
+
+fn list_from_stdin_in(config: &str, format: Option<&str>, current_dir: &Path) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_healer"));
+    command.current_dir(current_dir);
+    command.arg("--config").arg("-");
+    if let Some(format) = format {
+        command.arg("--config-format").arg(format);
+    }
+    let mut child = command
+        .arg("list")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run healer list");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(config.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn assert_lists_web(output: Output) {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "healer list should succeed, stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.trim_end() == "web [enabled]"),
+        "missing process in output:\n{stdout}"
+    );
+}
+
+#[test]
+fn yaml_config_is_read_from_stdin_by_default() {
+    assert_lists_web(list_from_stdin(YAML, None));
+}
+
+#[test]
+fn toml_config_is_read_from_stdin() {
+    assert_lists_web(list_from_stdin(TOML, Some("toml")));
+}
+
+#[test]
+fn json_config_is_read_from_stdin() {
+    assert_lists_web(list_from_stdin(JSON, Some("json")));
+}
+
+#[test]
+fn relative_include_dir_is_resolved_against_the_current_directory() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir(dir.path().join("conf.d")).unwrap();
+    std::fs::write(
+        dir.path().join("conf.d/worker.yaml"),
+        YAML.replace("web", "worker"),
+    )
+    .unwrap();
+    let config = format!("include_dir: \"conf.d\"\n{YAML}");
+
+    let output = list_from_stdin_in(&config, None, dir.path());
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert_lists_web(output);
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.trim_end() == "worker [enabled]"),
+        "missing drop-in process in output:\n{stdout}"
+    );
+}
+
+#[test]
+fn mismatched_format_is_rejected() {
+    let output = list_from_stdin(TOML, Some("json"));
+    assert!(!output.status.success());
+}
+
+#[test]
+fn formats_agree_on_the_parsed_config() {
+    for (content, format) in [
+        (YAML, ConfigFormat::Yaml),
+        (TOML, ConfigFormat::Toml),
+        (JSON, ConfigFormat::Json),
+    ] {
+        let config = AppConfig::parse(content, format).expect("config should parse");
+        assert_eq!(config.processes.len(), 1);
+        assert_eq!(config.processes[0].name, "web");
+    }
+    assert_eq!("TOML".parse::<ConfigFormat>(), Ok(ConfigFormat::Toml));
+    assert!("ini".parse::<ConfigFormat>().is_err());
+}
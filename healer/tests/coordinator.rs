@@ -1,32 +1,29 @@
+mod common;
+
 use healer::config::{
-    AppConfig, DependencyConfig, DependencyKind, MonitorConfig, NetworkMonitorFields, OnFailure,
-    ProcessConfig, RawDependency, RecoveryConfig, RegularHealerFields,
+    AppConfig, DependencyConfig, DependencyKind, MonitorConfig, OnFailure, ProcessConfig,
+    RawDependency, RecoveryConfig, RegularHealerFields,
 };
 use healer::coordinator::dependency_coordinator::DependencyCoordinator;
 use healer::event_bus::{create_event_sender, ProcessEvent};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::SystemTime;
 use tokio::sync::RwLock;
 
 fn mk_process(name: &str, deps: Vec<RawDependency>) -> ProcessConfig {
     ProcessConfig {
-        name: name.to_string(),
-        enabled: true,
-        command: "/bin/true".to_string(),
-        args: vec![],
-        run_as_user: None,
-        run_as_root: true,
-        working_dir: None,
-        monitor: MonitorConfig::Network(NetworkMonitorFields {
-            target_url: "http://127.0.0.1:1/health".to_string(),
-            interval_secs: 60,
-        }),
         recovery: RecoveryConfig::Regular(RegularHealerFields {
             retries: 1,
             retry_window_secs: 5,
-            cooldown_secs: 5,
+            cooldown_secs: Some(5),
+            ..Default::default()
         }),
         dependencies: deps,
+        ..common::process(
+            name,
+            MonitorConfig::Network(common::network_fields("http://127.0.0.1:1/health")),
+        )
     }
 }
 
@@ -40,11 +37,9 @@ async fn defers_then_releases_on_timeout_skip() {
         on_failure: OnFailure::Skip,
     });
     let cfg = AppConfig {
-        log_level: None,
-        log_directory: None,
-        pid_file_directory: None,
         working_directory: Some(PathBuf::from("/")),
         processes: vec![mk_process("A", vec![dep]), mk_process("B", vec![])],
+        ..Default::default()
     };
     let shared = Arc::new(RwLock::new(cfg));
 
@@ -60,18 +55,24 @@ async fn defers_then_releases_on_timeout_skip() {
     });
 
     // 1) B 先下线，标记为恢复中
-    let _ = in_tx.send(ProcessEvent::ProcessDown {
-        name: "B".to_string(),
-        pid: 123,
-    });
+    let _ = in_tx
+        .send(ProcessEvent::ProcessDown {
+            name: "B".to_string(),
+            pid: 123,
+            at: SystemTime::now(),
+        })
+        .await;
     // 留出处理时间
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
     // 2) A 下线，应被延后（不应立刻转发）
-    let _ = in_tx.send(ProcessEvent::ProcessDown {
-        name: "A".to_string(),
-        pid: 456,
-    });
+    let _ = in_tx
+        .send(ProcessEvent::ProcessDown {
+            name: "A".to_string(),
+            pid: 456,
+            at: SystemTime::now(),
+        })
+        .await;
 
     // 短超时内不应收到 A 的转发
     let mut got_immediate = false;
@@ -0,0 +1,118 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, CoreCaptureConfig, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::child_registry::ChildRegistry;
+use healer::subscriber::core_capture::{expected_core_location, CoreLocation};
+use healer::subscriber::process_healer::ProcessHealer;
+use nix::sys::signal::Signal;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// 恢复命令启动后立即以 SIGSEGV 崩溃
+fn crashing_process(name: &str, cores: &Path) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec!["-c".into(), "ulimit -c unlimited; kill -SEGV $$".into()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        capture_core: Some(CoreCaptureConfig {
+            directory: cores.to_path_buf(),
+        }),
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(cores.join("crash.pid"))),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig, children: &ChildRegistry) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_children(children.clone())
+}
+
+#[test]
+fn core_pattern_is_expanded() {
+    let cwd = Path::new("/srv/app");
+    let expect = |pattern: &str, uses_pid: bool| {
+        expected_core_location(pattern, 42, Signal::SIGSEGV, "server", cwd, uses_pid)
+    };
+    assert_eq!(
+        expect("core", false),
+        CoreLocation::File(PathBuf::from("/srv/app/core"))
+    );
+    assert_eq!(
+        expect("core\n", true),
+        CoreLocation::File(PathBuf::from("/srv/app/core.42"))
+    );
+    assert_eq!(
+        expect("/var/crash/core.%e.%p.%s.%%", true),
+        CoreLocation::File(PathBuf::from("/var/crash/core.server.42.11.%"))
+    );
+    assert_eq!(
+        expect(
+            "|/usr/lib/systemd/systemd-coredump %P %u %g %s %t %c %h",
+            false
+        ),
+        CoreLocation::Pipe("/usr/lib/systemd/systemd-coredump %P %u %g %s %t %c %h".into())
+    );
+    assert_eq!(
+        expect("/var/crash/core.%t", false),
+        CoreLocation::Unresolved("/var/crash/core.%t".into())
+    );
+}
+
+#[tokio::test]
+async fn crash_of_restarted_process_records_core_path() {
+    // 需要 root：core_pattern 指向的目录通常只有 root 可写
+    if !nix::unistd::geteuid().is_root() {
+        eprintln!("skipped: requires root");
+        return;
+    }
+    let dir = TempDir::new().unwrap();
+    let children = ChildRegistry::new();
+    let name = "crasher".to_string();
+    let mut healer = healer_for(crashing_process(&name, dir.path()), &children).await;
+    assert_eq!(healer.heal_process(&name).await, None);
+
+    let mut capture = None;
+    for _ in 0..50 {
+        capture = children.last_core(&name);
+        if capture.is_some() {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    let capture = capture.expect("crash should have been recorded");
+    assert_eq!(capture.signal, Signal::SIGSEGV);
+    assert_eq!(
+        Some(capture.pid),
+        children.get(&name).map(|child| child.pid)
+    );
+    match &capture.location {
+        CoreLocation::File(path) => {
+            assert!(path.is_absolute(), "core path should be resolved: {path:?}");
+            if let Some(saved) = &capture.saved {
+                assert!(saved.starts_with(dir.path().join(&name)));
+                assert!(saved.exists());
+            }
+        }
+        CoreLocation::Pipe(handler) => assert!(!handler.is_empty()),
+        CoreLocation::Unresolved(pattern) => assert!(pattern.contains('%')),
+    }
+}
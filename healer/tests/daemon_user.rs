@@ -0,0 +1,73 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::daemon_handler::{DaemonError, DaemonIdentity};
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{fork, geteuid, getgid, getuid, ForkResult, Group, User};
+
+#[test]
+fn identity_is_optional() {
+    assert_eq!(DaemonIdentity::resolve(None, None).unwrap(), None);
+    let config = AppConfig::parse("processes: []\n", ConfigFormat::Yaml).unwrap();
+    assert_eq!(config.daemon_user, None);
+    assert_eq!(config.daemon_group, None);
+}
+
+#[test]
+fn unknown_user_or_group_is_an_error() {
+    assert!(matches!(
+        DaemonIdentity::resolve(Some("no-such-healer-user"), None),
+        Err(DaemonError::UnknownUser(name)) if name == "no-such-healer-user"
+    ));
+    assert!(matches!(
+        DaemonIdentity::resolve(None, Some("no-such-healer-group")),
+        Err(DaemonError::UnknownGroup(name)) if name == "no-such-healer-group"
+    ));
+}
+
+#[test]
+fn group_defaults_to_the_users_primary_group() {
+    let nobody = User::from_name("nobody").unwrap().expect("user nobody");
+    let identity = DaemonIdentity::resolve(Some("nobody"), None)
+        .unwrap()
+        .unwrap();
+    assert_eq!(identity.uid, nobody.uid.as_raw());
+    assert_eq!(identity.gid, nobody.gid.as_raw());
+
+    let root_group = Group::from_gid(0.into()).unwrap().expect("group 0");
+    let identity = DaemonIdentity::resolve(Some("nobody"), Some(&root_group.name))
+        .unwrap()
+        .unwrap();
+    assert_eq!(identity.gid, 0);
+}
+
+#[test]
+fn daemon_uid_changes_after_dropping() {
+    if !geteuid().is_root() {
+        eprintln!("skipping: not running as root");
+        return;
+    }
+    let nobody = User::from_name("nobody").unwrap().expect("user nobody");
+    // 在测试进程里切换身份会影响其他测试，因此在 fork 出的子进程中切换，通过退出码报告结果
+    let identity = DaemonIdentity::resolve(Some("nobody"), None)
+        .unwrap()
+        .unwrap();
+    // SAFETY: 子进程只调用系统调用后立即 _exit
+    match unsafe { fork() }.expect("fork") {
+        ForkResult::Child => {
+            let code = match identity.apply() {
+                Ok(()) if getuid() == nobody.uid && geteuid() == nobody.uid => {
+                    if getgid() == nobody.gid {
+                        0
+                    } else {
+                        3
+                    }
+                }
+                Ok(()) => 2,
+                Err(_) => 1,
+            };
+            unsafe { libc::_exit(code) }
+        }
+        ForkResult::Parent { child } => {
+            assert_eq!(waitpid(child, None).unwrap(), WaitStatus::Exited(child, 0));
+        }
+    }
+}
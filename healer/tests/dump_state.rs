@@ -0,0 +1,110 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, ControlConfig, MonitorConfig, PidMonitorFields, ProcessConfig,
+    RecoveryConfig, RegularHealerFields,
+};
+use healer::control::ControlState;
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::health::HealthRegistry;
+use healer::monitor_manager::MonitorManager;
+use healer::subscriber::{event_history::EventHistory, process_healer::ProcessHealer, Subscriber};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+
+fn pid_process(name: &str, dir: &Path) -> ProcessConfig {
+    ProcessConfig {
+        args: vec!["--db-password".into(), "hunter2".into()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(PidMonitorFields {
+                interval_secs: 60,
+                ..common::pid_fields(dir.join(format!("{name}.pid")))
+            }),
+        )
+    }
+}
+
+#[tokio::test]
+async fn dump_includes_monitor_type_and_breaker_state() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let dir = temp_dir.path();
+    let mut paused = pid_process("batch", dir);
+    paused.enabled = false;
+    let config = Arc::new(RwLock::new(AppConfig {
+        processes: vec![pid_process("web", dir), paused],
+        control: ControlConfig {
+            http_token: Some("s3cr3t-token".into()),
+            ..Default::default()
+        },
+        ..Default::default()
+    }));
+    let health = HealthRegistry::new();
+    let history = EventHistory::new(8);
+
+    let mut manager =
+        MonitorManager::new_without_ebpf(create_event_sender()).with_health(health.clone());
+    let processes = config.read().await.processes.clone();
+    manager.reconcile(&processes).await.expect("reconcile");
+
+    // 一次恢复尝试让熔断器留下记录
+    let down = ProcessEvent::ProcessDown {
+        name: "web".into(),
+        pid: 4242,
+        at: SystemTime::now(),
+    };
+    history.push(down.clone());
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::clone(&config))
+        .await
+        .with_health(health.clone());
+    healer.handle_event(down).await;
+
+    let (runtime_tx, _runtime_rx) = mpsc::channel(8);
+    let state = ControlState {
+        history,
+        config: Arc::clone(&config),
+        runtime_tx,
+        children: Default::default(),
+        event_tx: create_event_sender(),
+        health,
+    };
+    let dump = state.handle_line(r#"{"cmd":"dump-state"}"#).await;
+    assert_eq!(dump["ok"], true, "{dump}");
+
+    assert_eq!(dump["monitors"]["web"], "pid", "{dump}");
+    assert!(dump["monitors"].get("batch").is_none());
+    let web = &dump["config"]["processes"][0];
+    assert_eq!(web["name"], "web");
+    assert_eq!(web["monitor"]["type"], "pid");
+
+    assert_eq!(dump["circuits"]["web"]["state"], "closed", "{dump}");
+    assert_eq!(
+        dump["circuits"]["web"]["recent_attempts"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(dump["health"]["web"], "recovering");
+    assert_eq!(dump["events"][0]["name"], "web");
+    assert_eq!(dump["overrides"]["disabled"], serde_json::json!(["batch"]));
+    assert!(dump["deferred"].as_object().unwrap().is_empty());
+
+    // 令牌与敏感参数不会出现在导出中
+    let text = dump.to_string();
+    assert!(!text.contains("s3cr3t-token"), "{text}");
+    assert!(!text.contains("hunter2"), "{text}");
+    assert_eq!(dump["config"]["control"]["http_token"], "<redacted>");
+
+    manager.shutdown().await;
+    assert!(health.monitors().is_empty());
+}
@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use healer::config::{EbpfMonitorConfig, ProcessConfig};
+use healer::event_bus::create_event_sender;
+use healer::monitor::ebpf_monitor::{EbpfMapFull, EbpfReadiness, EbpfWatcher};
+use healer::monitor_manager::MonitorManager;
+use std::collections::HashSet;
+
+/// 容量受限的 eBPF 监控列表，达到容量后返回 EbpfMapFull
+struct BoundedWatcher {
+    capacity: usize,
+    comms: HashSet<String>,
+    readiness: EbpfReadiness,
+}
+
+impl BoundedWatcher {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            comms: HashSet::new(),
+            readiness: EbpfReadiness::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl EbpfWatcher for BoundedWatcher {
+    fn expect_watched(&self, names: Vec<String>) {
+        self.readiness.expect(names);
+    }
+
+    fn ready(&self) -> BoxFuture<'static, ()> {
+        Box::pin(self.readiness.wait_ready())
+    }
+
+    async fn watch_config(&mut self, ebpf_config: EbpfMonitorConfig) -> anyhow::Result<()> {
+        let comm = ebpf_config.monitored_comm();
+        if !self.comms.contains(&comm) && self.comms.len() >= self.capacity {
+            self.readiness.mark_removed(&ebpf_config.name);
+            return Err(EbpfMapFull {
+                comm,
+                capacity: self.capacity as u32,
+            }
+            .into());
+        }
+        self.comms.insert(comm);
+        self.readiness.mark_populated(&ebpf_config.name);
+        Ok(())
+    }
+
+    async fn unwatch_config(&mut self, ebpf_config: EbpfMonitorConfig) -> anyhow::Result<()> {
+        self.comms.remove(&ebpf_config.monitored_comm());
+        self.readiness.mark_removed(&ebpf_config.name);
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) {}
+}
+
+fn ebpf_process(name: &str) -> ProcessConfig {
+    let yaml = format!(
+        r#"
+name: "{name}"
+enabled: true
+run_as_root: true
+command: "/usr/bin/{name}"
+monitor:
+  type: "ebpf"
+"#
+    );
+    serde_yaml::from_str(&yaml).expect("valid process config")
+}
+
+#[tokio::test]
+async fn reconcile_reports_processes_that_do_not_fit_in_the_map() {
+    let mut manager = MonitorManager::new_without_ebpf(create_event_sender())
+        .with_ebpf_watcher(BoundedWatcher::new(2));
+
+    let processes = vec![
+        ebpf_process("svc_a"),
+        ebpf_process("svc_b"),
+        ebpf_process("svc_c"),
+    ];
+    manager
+        .reconcile(&processes)
+        .await
+        .expect("reconcile should not fail when the map is full");
+
+    let mut watched = manager.watched_ebpf_names();
+    watched.sort();
+    let unwatched = manager.unwatched_ebpf_names();
+    assert_eq!(watched.len(), 2, "only two processes fit: {watched:?}");
+    assert_eq!(unwatched.len(), 1, "one process should be reported");
+    assert!(
+        !watched.contains(&unwatched[0]),
+        "a process is either watched or reported as unwatched"
+    );
+
+    // 移除一个已监控的进程后，下一次协调会重新尝试之前放不下的进程
+    let remaining: Vec<ProcessConfig> = processes
+        .into_iter()
+        .filter(|p| p.name != watched[0])
+        .collect();
+    manager
+        .reconcile(&remaining)
+        .await
+        .expect("second reconcile should succeed");
+    assert!(manager.unwatched_ebpf_names().is_empty());
+    assert_eq!(manager.watched_ebpf_names().len(), 2);
+
+    manager.shutdown().await;
+}
+
+#[tokio::test]
+async fn reconcile_reports_all_ebpf_processes_when_ebpf_is_unavailable() {
+    let mut manager = MonitorManager::new_without_ebpf(create_event_sender());
+    manager
+        .reconcile(&[ebpf_process("svc_b"), ebpf_process("svc_a")])
+        .await
+        .expect("reconcile should succeed");
+
+    assert_eq!(
+        manager.unwatched_ebpf_names(),
+        vec!["svc_a".to_string(), "svc_b".to_string()]
+    );
+    assert!(manager.watched_ebpf_names().is_empty());
+}
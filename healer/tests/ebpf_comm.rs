@@ -0,0 +1,58 @@
+use healer::config::ProcessConfig;
+use healer::utils::process_name_key;
+
+fn ebpf_process(extra: &str) -> ProcessConfig {
+    let yaml = format!(
+        r#"
+name: "py_service"
+enabled: true
+run_as_root: true
+command: "/usr/bin/python3.11"
+args: ["/opt/app/server.py"]
+monitor:
+  type: "ebpf"
+{extra}
+"#
+    );
+    serde_yaml::from_str(&yaml).expect("valid process config")
+}
+
+#[test]
+fn process_comm_override_is_used_as_map_key() {
+    let process = ebpf_process("  process_comm: \"python3\"");
+    let ebpf = process
+        .get_ebpf_monitor_config()
+        .expect("ebpf monitor config");
+
+    assert_eq!(ebpf.monitored_comm(), "python3");
+
+    let mut expected = [0u8; 16];
+    expected[..7].copy_from_slice(b"python3");
+    assert_eq!(process_name_key(&ebpf.monitored_comm()), expected);
+}
+
+#[test]
+fn comm_defaults_to_executable_name() {
+    let process = ebpf_process("");
+    let ebpf = process
+        .get_ebpf_monitor_config()
+        .expect("ebpf monitor config");
+
+    assert_eq!(ebpf.monitored_comm(), "python3.11");
+}
+
+#[test]
+fn long_comm_override_is_truncated_to_kernel_limit() {
+    let process = ebpf_process("  process_comm: \"a-very-long-thread-name\"");
+    let ebpf = process
+        .get_ebpf_monitor_config()
+        .expect("ebpf monitor config");
+
+    let comm = ebpf.monitored_comm();
+    assert_eq!(comm, "a-very-long-thr");
+    assert_eq!(
+        process_name_key(&comm)[15],
+        0,
+        "key keeps a null terminator"
+    );
+}
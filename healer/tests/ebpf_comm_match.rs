@@ -0,0 +1,240 @@
+use healer::config::{AppConfig, CommMatch, ConfigFormat, EbpfMonitorConfig, MonitorConfig};
+use healer::monitor::ebpf_monitor::CommMatcher;
+use healer::utils::{interpreter_script_name, process_name_key};
+
+fn ebpf_config(name: &str, process_comm: Option<&str>, comm_match: CommMatch) -> EbpfMonitorConfig {
+    EbpfMonitorConfig {
+        name: name.into(),
+        command: format!("/usr/bin/{name}"),
+        process_comm: process_comm.map(String::from),
+        comm_match,
+    }
+}
+
+#[test]
+fn exact_resolves_only_the_registered_comm() {
+    let mut matcher = CommMatcher::default();
+    let config = ebpf_config("web", None, CommMatch::Exact);
+    matcher.insert_exact(config.monitored_comm(), config.name.clone());
+
+    assert_eq!(matcher.resolve("web"), vec!["web".to_string()]);
+    assert!(matcher.resolve("web-worker").is_empty());
+    assert!(!matcher.reports_all_exits());
+}
+
+#[test]
+fn prefix_matches_renamed_comms() {
+    let mut matcher = CommMatcher::default();
+    matcher
+        .insert_pattern(&ebpf_config("workers", Some("worker"), CommMatch::Prefix))
+        .expect("prefix should register");
+
+    assert!(matcher.reports_all_exits());
+    assert_eq!(matcher.resolve("worker-3"), vec!["workers".to_string()]);
+    assert_eq!(matcher.resolve("worker"), vec!["workers".to_string()]);
+    assert!(matcher.resolve("sshd").is_empty());
+
+    assert!(matcher.remove_pattern("workers"));
+    assert!(!matcher.reports_all_exits());
+    assert!(matcher.resolve("worker-3").is_empty());
+}
+
+#[test]
+fn prefix_is_truncated_like_the_kernel_comm() {
+    let mut matcher = CommMatcher::default();
+    matcher
+        .insert_pattern(&ebpf_config(
+            "long",
+            Some("very-long-process-name"),
+            CommMatch::Prefix,
+        ))
+        .expect("prefix should register");
+
+    // 内核中的 comm 最多 15 字节
+    assert_eq!(matcher.resolve("very-long-proce"), vec!["long".to_string()]);
+}
+
+#[test]
+fn regex_matches_against_the_comm() {
+    let mut matcher = CommMatcher::default();
+    matcher
+        .insert_pattern(&ebpf_config(
+            "pool",
+            Some(r"^php-fpm\d*$"),
+            CommMatch::Regex,
+        ))
+        .expect("regex should register");
+
+    assert_eq!(matcher.resolve("php-fpm7"), vec!["pool".to_string()]);
+    assert!(matcher.resolve("php").is_empty());
+}
+
+#[test]
+fn exact_and_pattern_matches_are_both_reported() {
+    let mut matcher = CommMatcher::default();
+    matcher.insert_exact("nginx".into(), "nginx".into());
+    matcher
+        .insert_pattern(&ebpf_config("web-tier", Some("ng"), CommMatch::Prefix))
+        .expect("prefix should register");
+
+    assert_eq!(
+        matcher.resolve("nginx"),
+        vec!["nginx".to_string(), "web-tier".to_string()]
+    );
+}
+
+#[test]
+fn reregistering_a_pattern_replaces_it() {
+    let mut matcher = CommMatcher::default();
+    matcher
+        .insert_pattern(&ebpf_config("svc", Some("old"), CommMatch::Prefix))
+        .unwrap();
+    matcher
+        .insert_pattern(&ebpf_config("svc", Some("new"), CommMatch::Prefix))
+        .unwrap();
+
+    assert!(matcher.resolve("old-1").is_empty());
+    assert_eq!(matcher.resolve("new-1"), vec!["svc".to_string()]);
+}
+
+#[test]
+fn invalid_or_missing_patterns_are_rejected() {
+    let mut matcher = CommMatcher::default();
+    assert!(matcher
+        .insert_pattern(&ebpf_config("bad", Some("("), CommMatch::Regex))
+        .is_err());
+    assert!(matcher
+        .insert_pattern(&ebpf_config("none", None, CommMatch::Regex))
+        .is_err());
+    assert!(matcher
+        .insert_pattern(&ebpf_config("exact", None, CommMatch::Exact))
+        .is_err());
+    assert!(!matcher.reports_all_exits());
+}
+
+#[test]
+fn non_utf8_comms_match_on_raw_bytes() {
+    // 内核把 comm 按字节截断为 15 字节，"ab" 之后的第 5 个汉字只剩首字节，不是合法的 UTF-8
+    let config = ebpf_config("db", Some("ab数据库服务"), CommMatch::Exact);
+    let kernel_comm = process_name_key(&config.monitored_comm());
+    assert!(std::str::from_utf8(&kernel_comm[..15]).is_err());
+
+    let mut matcher = CommMatcher::default();
+    matcher.insert_exact(config.monitored_comm(), config.name.clone());
+    assert_eq!(matcher.resolve_raw(&kernel_comm), vec!["db".to_string()]);
+
+    // NUL 之后的残留字节不影响匹配
+    let mut comm = *b"svc\0\xff\xff\0\0\0\0\0\0\0\0\0\0";
+    matcher.insert_exact("svc".into(), "svc".into());
+    assert_eq!(matcher.resolve_raw(&comm), vec!["svc".to_string()]);
+
+    // prefix 同样按字节比较，非 UTF-8 的后缀不影响
+    matcher
+        .insert_pattern(&ebpf_config("workers", Some("wk-"), CommMatch::Prefix))
+        .unwrap();
+    comm = *b"wk-\xfe\xff\0\0\0\0\0\0\0\0\0\0\0";
+    assert_eq!(matcher.resolve_raw(&comm), vec!["workers".to_string()]);
+}
+
+fn ebpf_yaml(monitor: &str) -> String {
+    format!(
+        r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/usr/bin/svc"
+    args: []
+    run_as_root: true
+    monitor: {monitor}
+    recovery: {{ type: "regular", retries: 3, retry_window_secs: 60, cooldown_secs: 180 }}
+"#
+    )
+}
+
+#[test]
+fn comm_match_is_parsed_and_validated() {
+    let config = AppConfig::parse(&ebpf_yaml(r#"{ type: "ebpf" }"#), ConfigFormat::Yaml)
+        .expect("exact is the default");
+    let MonitorConfig::Ebpf(fields) = &config.processes[0].monitor else {
+        panic!("expected an ebpf monitor");
+    };
+    assert_eq!(fields.comm_match, CommMatch::Exact);
+
+    let config = AppConfig::parse(
+        &ebpf_yaml(r#"{ type: "ebpf", process_comm: "svc", comm_match: "prefix" }"#),
+        ConfigFormat::Yaml,
+    )
+    .expect("prefix should parse");
+    assert_eq!(
+        config.processes[0].monitor.to_string(),
+        "ebpf (comm=svc, match=prefix)"
+    );
+
+    let err = AppConfig::parse(
+        &ebpf_yaml(r#"{ type: "ebpf", comm_match: "regex" }"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("requires process_comm"), "{err}");
+
+    let err = AppConfig::parse(
+        &ebpf_yaml(r#"{ type: "ebpf", process_comm: "(", comm_match: "regex" }"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("invalid process_comm regex"),
+        "{err}"
+    );
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn interpreter_commands_match_on_the_script() {
+    let name = |command: &str, list: &[&str]| interpreter_script_name(command, &args(list));
+    assert_eq!(
+        name("/usr/bin/python3", &["/opt/app/foo.py"]).as_deref(),
+        Some("foo.py")
+    );
+    assert_eq!(
+        name("python3.11", &["-u", "foo.py", "--port", "80"]).as_deref(),
+        Some("foo.py")
+    );
+    assert_eq!(
+        name("python3", &["-m", "http.server"]).as_deref(),
+        Some("http.server")
+    );
+    assert_eq!(
+        name("java", &["-Xmx1g", "-jar", "/srv/app.jar", "serve"]).as_deref(),
+        Some("app.jar")
+    );
+    // 不是解释器，或解释器没有脚本参数时沿用可执行文件名
+    assert_eq!(name("/usr/sbin/nginx", &["-g", "daemon off;"]), None);
+    assert_eq!(name("python3", &["-c"]), None);
+    assert_eq!(name("java", &["-cp", "app.jar", "Main"]), None);
+}
+
+#[test]
+fn ebpf_config_derives_comm_from_interpreter_script() {
+    let yaml = |monitor: &str| {
+        ebpf_yaml(monitor).replace(
+            "command: \"/usr/bin/svc\"\n    args: []",
+            "command: \"/usr/bin/python3\"\n    args: [\"/opt/app/foo.py\"]",
+        )
+    };
+    let config = AppConfig::parse(&yaml(r#"{ type: "ebpf" }"#), ConfigFormat::Yaml).unwrap();
+    let ebpf = config.processes[0].get_ebpf_monitor_config().unwrap();
+    assert_eq!(ebpf.monitored_comm(), "foo.py");
+
+    // 显式的 process_comm 优先于推导结果
+    let config = AppConfig::parse(
+        &yaml(r#"{ type: "ebpf", process_comm: "worker" }"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap();
+    let ebpf = config.processes[0].get_ebpf_monitor_config().unwrap();
+    assert_eq!(ebpf.monitored_comm(), "worker");
+}
@@ -2,8 +2,13 @@ use std::fs;
 // no extra std::io imports needed
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
 use std::thread;
 use std::time::Duration;
+
+// 各用例共用 pid 目录并会清理残留进程，需串行执行
+static E2E_LOCK: Mutex<()> = Mutex::new(());
+
 fn workspace_root() -> PathBuf {
     // CARGO_MANIFEST_DIR 指向 healer 子 crate；集成测试期望使用工作区根目录（其父目录）
     let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -15,7 +20,7 @@ fn workspace_root() -> PathBuf {
 fn cleanup_stray_processes() {
     let base = workspace_root();
     let healer_bin = base.join("target/debug/healer");
-    let test_helper = base.join("target/debug/test_process");
+    let test_helper = test_process_bin();
 
     let patterns = vec![
         healer_bin.to_string_lossy().to_string(),
@@ -86,10 +91,21 @@ fn kill_child(mut child: Child) {
     let _ = child.wait();
 }
 
-fn build_ebpf_config(base: &str) -> String {
+// 测试辅助进程由 cargo 与 healer 一同构建，启动时通过 --comm 把自己的 comm 设为 HELPER_COMM
+fn test_process_bin() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_simple_test_process"))
+}
+
+// 辅助进程的 comm，与配置中的 process_comm 一致，不依赖可执行文件名的截断规则
+const HELPER_COMM: &str = "counter_ebpf";
+
+fn build_ebpf_config(base: &str, attach: &str) -> String {
+    let helper = test_process_bin();
+    let helper = helper.display();
     format!(
         r#"
 log_level: "info"
+ebpf_attach: "{attach}"
 log_directory: "{base}/target/debug/healer-tests/logs"
 pid_file_directory: "{base}/target/debug/healer-tests/pids"
 working_directory: "/"
@@ -97,12 +113,13 @@ working_directory: "/"
 processes:
   - name: "counter_ebpf"
     enabled: true
-    command: "{base}/target/debug/test_process_ebpf"
-    args: []
+    command: "{helper}"
+    args: ["--pid-file", "{base}/target/debug/healer-tests/pids/counter.pid", "--comm", "{HELPER_COMM}"]
     run_as_root: true
     run_as_user: null
     monitor:
       type: "ebpf"
+      process_comm: "{HELPER_COMM}"
     recovery:
       type: "regular"
       retries: 3
@@ -112,102 +129,20 @@ processes:
     )
 }
 
-fn ensure_test_binaries() {
-    // 创建共享的测试二进制，使用统一的逻辑
-    let base = workspace_root();
-    let pids_dir = base.join("target/debug/healer-tests/pids");
-    let helper_src_dir = base.join("target/debug/healer-tests");
-    if let Err(err) = fs::create_dir_all(&helper_src_dir) {
-        panic!(
-            "failed to create helper source directory {}: {}",
-            helper_src_dir.display(),
-            err
-        );
-    }
-
-    // 使用与 process_e2e.rs 相同的测试二进制代码
-    let helper_src = format!(
-        r#"fn main(){{
-        use std::{{fs,thread,time,process,io::{{self,Write}}}};
-        let pid = process::id();
-        fs::create_dir_all("{}").expect("failed to create pid directory");
-        fs::write("{}/counter.pid", pid.to_string()).expect("failed to write pid file");
-        let mut n=0u64; loop{{ print!("\r[PID {{}}] alive {{}}", pid,n); io::stdout().flush().expect("flush failed"); thread::sleep(time::Duration::from_secs(1)); n+=1; }}
-    }}"#,
-        pids_dir.display(),
-        pids_dir.display()
-    );
-
-    let bin_dir = base.join("target").join("debug");
-    let test_bin_src = helper_src_dir.join("test_process_ebpf.rs");
-    write_file(test_bin_src.to_str().unwrap(), &helper_src);
-
-    // 为 eBPF 测试使用独立的二进制名称
-    let out_bin = bin_dir.join("test_process_ebpf");
-
-    // 如果已存在可执行文件，跳过编译，避免在 sudo 环境下找不到 rustc
-    if out_bin.exists() {
-        return;
-    }
-
-    // 尝试编译，如果失败则提供有用的错误信息
-    let result = try_compile_test_binary(&test_bin_src, &out_bin);
-    match result {
-        Ok(()) => println!("Successfully compiled eBPF test binary"),
-        Err(e) => {
-            eprintln!("Warning: Failed to compile eBPF test binary: {}", e);
-            eprintln!("Hint: If running with sudo, try pre-compiling the binary:");
-            eprintln!(
-                "  rustc -O {} -o {}",
-                test_bin_src.display(),
-                out_bin.display()
-            );
-            eprintln!("Or set RUSTC environment variable to point to rustc executable.");
-            panic!("Cannot proceed without test binary");
-        }
-    }
-}
-
-fn try_compile_test_binary(src: &std::path::Path, out: &std::path::Path) -> Result<(), String> {
-    // 尝试多种方式找到 rustc
-    let rustc_candidates = [
-        // 首先尝试环境变量
-        std::env::var("RUSTC").ok(),
-        // 尝试使用 which 命令
-        Command::new("which")
-            .arg("rustc")
-            .output()
-            .ok()
-            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
-            .filter(|s| !s.is_empty()),
-        // 常见路径
-        Some("/usr/bin/rustc".to_string()),
-        Some("/usr/local/bin/rustc".to_string()),
-        Some("/home/lxq/.cargo/bin/rustc".to_string()),
-        Some("/root/.cargo/bin/rustc".to_string()),
-        // 最后尝试直接调用
-        Some("rustc".to_string()),
-    ];
-
-    for rustc_opt in rustc_candidates.into_iter().flatten() {
-        if let Ok(status) = Command::new(&rustc_opt)
-            .args(["-O", src.to_str().unwrap(), "-o", out.to_str().unwrap()])
-            .status()
-        {
-            if status.success() {
-                return Ok(());
-            } else {
-                return Err(format!("rustc compilation failed with status: {}", status));
-            }
-        }
-    }
-
-    Err("Could not find rustc executable".to_string())
+#[test]
+#[ignore]
+fn ebpf_detects_exit_and_recovers() {
+    detects_exit_and_recovers("tracepoint");
 }
 
+// 备选挂载点 kprobe do_exit 同样应能检测到退出
 #[test]
 #[ignore]
-fn ebpf_detects_exit_and_recovers() {
+fn ebpf_kprobe_attach_detects_exit_and_recovers() {
+    detects_exit_and_recovers("kprobe");
+}
+
+fn detects_exit_and_recovers(attach: &str) {
     // 仅在显式开启时运行（需要 root/capabilities）
     if std::env::var("HEALER_EBPF_E2E")
         .ok()
@@ -218,26 +153,27 @@ fn ebpf_detects_exit_and_recovers() {
         eprintln!("skipped: set HEALER_EBPF_E2E=1 to run");
         return;
     }
+    let _guard = E2E_LOCK.lock().unwrap_or_else(|e| e.into_inner());
 
     cleanup_stray_processes();
-    ensure_test_binaries();
     let base = workspace_root();
-    let cfg_text = build_ebpf_config(base.to_str().unwrap());
-    let cfg_path = base.join("target/debug/ebpf_config.yaml");
+    let cfg_text = build_ebpf_config(base.to_str().unwrap(), attach);
+    let cfg_path = base.join(format!("target/debug/ebpf_config_{attach}.yaml"));
     write_file(cfg_path.to_str().unwrap(), &cfg_text);
 
     // 先启动被监控进程，便于观察 eBPF 事件
-    let helper_bin = base.join("target/debug/test_process_ebpf");
-    let mut child = Command::new(&helper_bin)
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .expect("failed to spawn test_process_ebpf");
-
     let pid_path = base.join("target/debug/healer-tests/pids/counter.pid");
     if let Some(parent) = pid_path.parent() {
         fs::create_dir_all(parent).expect("failed to ensure pid directory exists");
     }
+    let mut child = Command::new(test_process_bin())
+        .arg("--pid-file")
+        .arg(&pid_path)
+        .args(["--comm", HELPER_COMM])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to spawn simple_test_process");
 
     let helper_pid = child.id() as i32;
     fs::write(&pid_path, helper_pid.to_string()).expect("failed to prime helper pid file");
@@ -282,7 +218,8 @@ fn ebpf_detects_exit_and_recovers() {
     }
     assert!(
         new_pid > 0 && new_pid != first_pid,
-        "ebpf did not trigger restart"
+        "ebpf ({}) did not trigger restart",
+        attach
     );
 
     // 清理
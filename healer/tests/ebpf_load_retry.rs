@@ -0,0 +1,74 @@
+use anyhow::anyhow;
+use healer::config::EbpfAttach;
+use healer::monitor::ebpf_monitor::{load_and_attach, retry_with_backoff, EbpfLoadRetry};
+use std::time::Duration;
+use tokio::time::Instant;
+
+fn quick_retry(attempts: u32) -> EbpfLoadRetry {
+    EbpfLoadRetry {
+        attempts,
+        initial_backoff: Duration::from_millis(20),
+        max_backoff: Duration::from_millis(40),
+    }
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried_with_backoff() {
+    let mut calls = 0;
+    let started = Instant::now();
+    let value = retry_with_backoff("load", quick_retry(5), || {
+        calls += 1;
+        if calls < 3 {
+            Err(anyhow!("tracefs not ready"))
+        } else {
+            Ok(calls)
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(value, 3);
+    // 两次退避：20ms + 40ms
+    assert!(started.elapsed() >= Duration::from_millis(60));
+}
+
+#[tokio::test]
+async fn gives_up_after_the_last_attempt() {
+    let mut calls = 0;
+    let err = retry_with_backoff("load", quick_retry(3), || -> anyhow::Result<()> {
+        calls += 1;
+        Err(anyhow!("failure {calls}"))
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(calls, 3);
+    assert_eq!(err.to_string(), "failure 3");
+
+    // attempts 为 0 时仍至少尝试一次
+    let mut calls = 0;
+    let _ = retry_with_backoff("load", quick_retry(0), || -> anyhow::Result<()> {
+        calls += 1;
+        Err(anyhow!("failure"))
+    })
+    .await;
+    assert_eq!(calls, 1);
+}
+
+#[tokio::test]
+#[ignore = "requires root to load eBPF programs"]
+async fn real_load_succeeds_after_an_injected_failure() {
+    if !nix::unistd::geteuid().is_root() {
+        eprintln!("skipping: not running as root");
+        return;
+    }
+    let mut calls = 0;
+    let bpf = retry_with_backoff("load and attach the eBPF program", quick_retry(3), || {
+        calls += 1;
+        if calls == 1 {
+            return Err(anyhow!("injected failure"));
+        }
+        load_and_attach(EbpfAttach::Tracepoint)
+    })
+    .await;
+    assert!(bpf.is_ok(), "{:?}", bpf.err());
+    assert_eq!(calls, 2);
+}
@@ -0,0 +1,45 @@
+use healer::utils::lock_or_recover;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+fn poisoned_mapping() -> Arc<Mutex<HashMap<String, String>>> {
+    let mapping = Arc::new(Mutex::new(HashMap::new()));
+    let clone = Arc::clone(&mapping);
+    let _ = std::thread::spawn(move || {
+        let mut guard = clone.lock().unwrap();
+        guard.insert("python3.11".to_string(), "py_service".to_string());
+        panic!("simulated panic while holding the mapping lock");
+    })
+    .join();
+    assert!(
+        mapping.is_poisoned(),
+        "mutex should be poisoned by the panic"
+    );
+    mapping
+}
+
+#[test]
+fn poisoned_mapping_is_recovered_with_its_data() {
+    let mapping = poisoned_mapping();
+
+    let guard = lock_or_recover(&mapping, "eBPF process name mapping");
+    assert_eq!(
+        guard.get("python3.11").map(String::as_str),
+        Some("py_service"),
+        "data written before the panic should still be visible"
+    );
+    drop(guard);
+
+    assert!(
+        !mapping.is_poisoned(),
+        "poison flag should be cleared after recovery"
+    );
+    assert!(mapping.lock().is_ok());
+}
+
+#[test]
+fn healthy_mutex_is_locked_normally() {
+    let mapping = Mutex::new(vec![1, 2, 3]);
+    lock_or_recover(&mapping, "test vector").push(4);
+    assert_eq!(*mapping.lock().unwrap(), vec![1, 2, 3, 4]);
+}
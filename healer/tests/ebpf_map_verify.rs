@@ -0,0 +1,111 @@
+use healer::monitor::ebpf_monitor::{
+    insert_verified, remove_verified, EbpfMapFull, ProcessNameMap,
+};
+use healer::utils::process_name_key;
+use std::collections::HashSet;
+use std::io;
+
+/// 模拟内核哈希表：达到容量后插入新 key 返回 E2BIG
+struct BoundedMap {
+    capacity: usize,
+    entries: HashSet<[u8; 16]>,
+    drop_writes: bool,
+}
+
+impl BoundedMap {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashSet::new(),
+            drop_writes: false,
+        }
+    }
+}
+
+impl ProcessNameMap for BoundedMap {
+    fn contains(&self, key: &[u8; 16]) -> io::Result<bool> {
+        Ok(self.entries.contains(key))
+    }
+
+    fn insert(&mut self, key: [u8; 16]) -> io::Result<()> {
+        if !self.entries.contains(&key) && self.entries.len() >= self.capacity {
+            return Err(io::Error::from_raw_os_error(
+                nix::errno::Errno::E2BIG as i32,
+            ));
+        }
+        if !self.drop_writes {
+            self.entries.insert(key);
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &[u8; 16]) -> io::Result<()> {
+        if self.drop_writes {
+            return Ok(());
+        }
+        self.entries
+            .remove(key)
+            .then_some(())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+}
+
+#[test]
+fn insert_past_capacity_reports_map_full() {
+    let capacity = 4;
+    let mut map = BoundedMap::new(capacity);
+    for i in 0..capacity {
+        let comm = format!("proc_{i}");
+        insert_verified(&mut map, process_name_key(&comm), &comm, capacity as u32)
+            .expect("insert within capacity should succeed");
+    }
+
+    let err = insert_verified(
+        &mut map,
+        process_name_key("overflow"),
+        "overflow",
+        capacity as u32,
+    )
+    .expect_err("insert past capacity should fail");
+    let full = err
+        .downcast_ref::<EbpfMapFull>()
+        .expect("error should be EbpfMapFull");
+    assert_eq!(full.comm, "overflow");
+    assert_eq!(full.capacity, capacity as u32);
+    assert!(err.to_string().contains("is full"), "got: {err}");
+
+    // 已存在的 key 重复写入只是更新，不受容量限制
+    insert_verified(
+        &mut map,
+        process_name_key("proc_0"),
+        "proc_0",
+        capacity as u32,
+    )
+    .expect("re-inserting an existing key should succeed");
+}
+
+#[test]
+fn silently_dropped_insert_is_detected_by_read_back() {
+    let mut map = BoundedMap::new(8);
+    map.drop_writes = true;
+
+    let err = insert_verified(&mut map, process_name_key("ghost"), "ghost", 8)
+        .expect_err("missing entry after insert should be an error");
+    assert!(err.downcast_ref::<EbpfMapFull>().is_none());
+    assert!(err.to_string().contains("missing"), "got: {err}");
+}
+
+#[test]
+fn remove_is_verified() {
+    let mut map = BoundedMap::new(8);
+    let key = process_name_key("worker");
+    insert_verified(&mut map, key, "worker", 8).unwrap();
+    remove_verified(&mut map, key, "worker").expect("remove should succeed");
+    assert!(!map.contains(&key).unwrap());
+
+    map.entries.insert(key);
+    map.drop_writes = true;
+    let err = remove_verified(&mut map, key, "worker")
+        .expect_err("entry still present after removal should be an error");
+    assert!(err.to_string().contains("still present"), "got: {err}");
+}
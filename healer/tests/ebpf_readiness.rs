@@ -0,0 +1,50 @@
+use healer::monitor::ebpf_monitor::EbpfReadiness;
+use tokio::time::{timeout, Duration};
+
+#[tokio::test]
+async fn ready_waits_until_all_expected_names_are_populated() {
+    let readiness = EbpfReadiness::new();
+    readiness.expect(["alpha".to_string(), "beta".to_string()]);
+
+    let ready = tokio::spawn(readiness.wait_ready());
+    readiness.mark_populated("alpha");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(
+        !ready.is_finished(),
+        "readiness must not resolve while 'beta' is not in the map yet"
+    );
+
+    readiness.mark_populated("beta");
+    timeout(Duration::from_secs(1), ready)
+        .await
+        .expect("readiness should resolve once every name is populated")
+        .expect("readiness task panicked");
+}
+
+#[tokio::test]
+async fn failed_watch_does_not_block_readiness() {
+    let readiness = EbpfReadiness::new();
+    readiness.expect(["alpha".to_string(), "broken".to_string()]);
+    readiness.mark_populated("alpha");
+    readiness.mark_removed("broken");
+
+    timeout(Duration::from_secs(1), readiness.wait_ready())
+        .await
+        .expect("names that failed to be watched are dropped from the barrier");
+}
+
+#[tokio::test]
+async fn new_expectation_resets_readiness() {
+    let readiness = EbpfReadiness::new();
+    timeout(Duration::from_secs(1), readiness.wait_ready())
+        .await
+        .expect("nothing expected means ready");
+
+    readiness.expect(["gamma".to_string()]);
+    assert!(
+        timeout(Duration::from_millis(200), readiness.wait_ready())
+            .await
+            .is_err(),
+        "a newly expected name must be populated before reconcile completes"
+    );
+}
@@ -0,0 +1,83 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::monitor::ebpf_monitor::{wait_for_work, EbpfWorkerOptions, WorkerWake};
+use std::future::pending;
+use tokio::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+// 模拟 worker 循环：没有可读的 buffer，只在定期读取或关闭时醒来，返回定期读取的次数
+async fn idle_worker(shutdown: CancellationToken, poll_interval: Duration) -> usize {
+    let mut polls = 0;
+    loop {
+        match wait_for_work(&shutdown, pending::<()>(), poll_interval).await {
+            WorkerWake::Shutdown => return polls,
+            WorkerWake::Ready(()) => unreachable!("nothing becomes readable"),
+            WorkerWake::Poll => polls += 1,
+        }
+    }
+}
+
+#[tokio::test]
+async fn cancellation_wakes_an_idle_worker_before_the_poll_interval() {
+    let shutdown = CancellationToken::new();
+    let worker = tokio::spawn(idle_worker(shutdown.clone(), Duration::from_secs(1)));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let started = Instant::now();
+    shutdown.cancel();
+    let polls = worker.await.unwrap();
+    let elapsed = started.elapsed();
+
+    // 旧的 1 秒超时轮询最坏要等满一个间隔才能发现关闭标志
+    assert_eq!(polls, 0);
+    assert!(
+        elapsed < Duration::from_millis(200),
+        "shutdown should not wait for the 1s poll, took {elapsed:?}"
+    );
+}
+
+#[tokio::test]
+async fn idle_worker_polls_at_the_configured_interval() {
+    let shutdown = CancellationToken::new();
+    let worker = tokio::spawn(idle_worker(shutdown.clone(), Duration::from_millis(20)));
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    shutdown.cancel();
+    let polls = worker.await.unwrap();
+    assert!((3..=8).contains(&polls), "polled {polls} times");
+}
+
+#[tokio::test]
+async fn shutdown_takes_priority_over_ready_work() {
+    let shutdown = CancellationToken::new();
+    shutdown.cancel();
+    let wake = wait_for_work(&shutdown, async { 1 }, Duration::from_secs(1)).await;
+    assert!(matches!(wake, WorkerWake::Shutdown));
+}
+
+#[test]
+fn poll_interval_is_configurable_and_positive() {
+    let config = AppConfig::parse("processes: []\n", ConfigFormat::Yaml).unwrap();
+    assert_eq!(
+        EbpfWorkerOptions::from_config(&config).poll_interval,
+        Duration::from_secs(1)
+    );
+
+    let config = AppConfig::parse(
+        "ebpf_max_workers: 2\nebpf_poll_interval_ms: 250\nprocesses: []\n",
+        ConfigFormat::Yaml,
+    )
+    .unwrap();
+    assert_eq!(
+        EbpfWorkerOptions::from_config(&config),
+        EbpfWorkerOptions {
+            max_workers: Some(2),
+            poll_interval: Duration::from_millis(250),
+        }
+    );
+
+    let err = AppConfig::parse(
+        "ebpf_poll_interval_ms: 0\nprocesses: []\n",
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("ebpf_poll_interval_ms"), "{err}");
+}
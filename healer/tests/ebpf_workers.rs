@@ -0,0 +1,40 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::monitor::ebpf_monitor::assign_cpus;
+
+#[test]
+fn worker_count_respects_the_cap_and_covers_every_cpu() {
+    let cpus: Vec<u32> = (0..128).collect();
+    let groups = assign_cpus(&cpus, Some(8));
+    assert_eq!(groups.len(), 8);
+    assert!(groups.iter().all(|group| group.len() == 16));
+
+    let mut covered: Vec<u32> = groups.into_iter().flatten().collect();
+    covered.sort_unstable();
+    assert_eq!(
+        covered, cpus,
+        "every CPU buffer is read by exactly one worker"
+    );
+}
+
+#[test]
+fn uneven_and_oversized_caps() {
+    // 各组数量最多相差 1
+    let groups = assign_cpus(&[0, 1, 2, 3, 4], Some(2));
+    assert_eq!(groups, vec![vec![0, 2, 4], vec![1, 3]]);
+
+    // 上限大于 CPU 数时每个 CPU 一个 worker，与未设置相同
+    let cpus = [0, 1, 2, 5];
+    assert_eq!(assign_cpus(&cpus, Some(64)), assign_cpus(&cpus, None));
+    assert_eq!(assign_cpus(&cpus, None).len(), 4);
+    assert!(assign_cpus(&[], Some(4)).is_empty());
+}
+
+#[test]
+fn ebpf_max_workers_must_be_positive() {
+    let yaml = |workers: usize| format!("ebpf_max_workers: {workers}\nprocesses: []\n");
+    let config = AppConfig::parse(&yaml(4), ConfigFormat::Yaml).unwrap();
+    assert_eq!(config.ebpf_max_workers, Some(4));
+
+    let err = AppConfig::parse(&yaml(0), ConfigFormat::Yaml).unwrap_err();
+    assert!(err.to_string().contains("ebpf_max_workers"), "{err}");
+}
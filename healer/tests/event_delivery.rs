@@ -0,0 +1,109 @@
+use healer::config::{AppConfig, ClosedPolicy, EventDelivery, SubscriberPolicies};
+use healer::event_bus::{create_event_sender, EventReceiver, ProcessEvent};
+use healer::service_manager::CriticalSupervision;
+use std::time::SystemTime;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout, Duration};
+
+// 超过关键队列（64）与 broadcast（128）的容量
+const EVENTS: u32 = 200;
+
+fn supervision(delivery: EventDelivery) -> CriticalSupervision {
+    CriticalSupervision {
+        policies: SubscriberPolicies {
+            delivery,
+            ..Default::default()
+        },
+        runtime_tx: mpsc::channel(1).0,
+    }
+}
+
+fn down(pid: u32) -> ProcessEvent {
+    ProcessEvent::ProcessDown {
+        name: "svc".into(),
+        pid,
+        at: SystemTime::now(),
+    }
+}
+
+fn pid_of(event: ProcessEvent) -> u32 {
+    match event {
+        ProcessEvent::ProcessDown { pid, .. } => pid,
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+// 慢消费者：每个事件之间稍作停顿，收到最后一个事件或超时为止
+async fn drain_slowly(rx: &mut EventReceiver) -> (Vec<u32>, u64) {
+    let mut received = Vec::new();
+    let mut missed = 0;
+    while received.last() != Some(&(EVENTS - 1)) {
+        match timeout(Duration::from_secs(5), rx.recv()).await {
+            Ok(Ok(event)) => received.push(pid_of(event)),
+            Ok(Err(RecvError::Lagged(n))) => missed += n,
+            Ok(Err(RecvError::Closed)) => panic!("event channel closed"),
+            Err(_) => panic!("timed out after receiving {:?}", received),
+        }
+        sleep(Duration::from_millis(1)).await;
+    }
+    (received, missed)
+}
+
+#[tokio::test]
+async fn queued_delivery_blocks_the_publisher_and_loses_nothing() {
+    let sender = create_event_sender();
+    let mut rx =
+        supervision(EventDelivery::Queued).subscribe(&sender, "healer", ClosedPolicy::Fatal);
+
+    let publisher_tx = sender.clone();
+    let publisher = tokio::spawn(async move {
+        for pid in 0..EVENTS {
+            publisher_tx.send(down(pid)).await.unwrap();
+        }
+    });
+    sleep(Duration::from_millis(200)).await;
+    assert!(
+        !publisher.is_finished(),
+        "the publisher should wait while the queue is full"
+    );
+
+    let (received, missed) = drain_slowly(&mut rx).await;
+    assert_eq!(missed, 0);
+    assert_eq!(received, (0..EVENTS).collect::<Vec<_>>());
+    publisher.await.unwrap();
+}
+
+#[tokio::test]
+async fn broadcast_delivery_never_blocks_and_drops_the_oldest() {
+    let sender = create_event_sender();
+    let mut rx =
+        supervision(EventDelivery::Broadcast).subscribe(&sender, "healer", ClosedPolicy::Fatal);
+
+    // 消费者尚未读取，发布也不会等待
+    timeout(Duration::from_secs(1), async {
+        for pid in 0..EVENTS {
+            sender.send(down(pid)).await.unwrap();
+        }
+    })
+    .await
+    .expect("broadcast delivery should not apply backpressure");
+
+    let (received, missed) = drain_slowly(&mut rx).await;
+    assert!(missed > 0, "a slow consumer should lag behind");
+    assert_eq!(received.len() as u64 + missed, EVENTS as u64);
+    // 丢失的是最旧的事件，保留下来的仍然连续有序
+    let first = received[0];
+    assert_eq!(received, (first..EVENTS).collect::<Vec<_>>());
+}
+
+#[test]
+fn delivery_defaults_to_queued() {
+    let config: AppConfig = serde_yaml::from_str("processes: []\n").unwrap();
+    assert_eq!(config.subscribers.delivery, EventDelivery::Queued);
+
+    let config: AppConfig =
+        serde_yaml::from_str("processes: []\nsubscribers:\n  delivery: broadcast\n").unwrap();
+    assert_eq!(config.subscribers.delivery, EventDelivery::Broadcast);
+    assert_eq!(config.subscribers.healer, ClosedPolicy::Resubscribe);
+}
@@ -0,0 +1,138 @@
+use healer::config::{AppConfig, ConfigFormat, EventFilterConfig};
+use healer::event_bus::ProcessEvent;
+use healer::subscriber::event_filter::{EventFilter, FilteredSubscriber};
+use healer::subscriber::event_history::EventHistory;
+use healer::subscriber::Subscriber;
+use std::time::SystemTime;
+
+fn down(name: &str) -> ProcessEvent {
+    ProcessEvent::ProcessDown {
+        name: name.into(),
+        pid: 1,
+        at: SystemTime::now(),
+    }
+}
+
+fn up(name: &str) -> ProcessEvent {
+    ProcessEvent::ProcessUp {
+        name: name.into(),
+        at: SystemTime::now(),
+    }
+}
+
+fn filter(processes: &[&str], kinds: &[&str]) -> EventFilter {
+    EventFilter::new(&EventFilterConfig {
+        processes: processes.iter().map(|p| p.to_string()).collect(),
+        kinds: kinds.iter().map(|k| k.to_string()).collect(),
+    })
+}
+
+// 依次投递事件，返回被记录下来的 (类型, 进程名)
+async fn recorded(filter: EventFilter, events: Vec<ProcessEvent>) -> Vec<(String, String)> {
+    let history = EventHistory::new(16);
+    let mut subscriber = FilteredSubscriber::new(history.clone(), filter);
+    for event in events {
+        subscriber.handle_event(event).await;
+    }
+    history
+        .recent(16)
+        .iter()
+        .map(|event| {
+            (
+                event.kind().to_string(),
+                event.process_name().unwrap_or_default().to_string(),
+            )
+        })
+        .collect()
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected
+        .iter()
+        .map(|(kind, name)| (kind.to_string(), name.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn filtered_subscriber_only_sees_configured_processes() {
+    let events = vec![down("web"), down("db"), up("web"), down("cache")];
+    assert_eq!(
+        recorded(filter(&["web", "cache"], &[]), events).await,
+        pairs(&[
+            ("process_down", "web"),
+            ("process_up", "web"),
+            ("process_down", "cache")
+        ])
+    );
+}
+
+#[tokio::test]
+async fn process_and_kind_sets_must_both_match() {
+    let events = vec![
+        down("web"),
+        up("web"),
+        down("db"),
+        ProcessEvent::ProcessRestartSuccess {
+            at: SystemTime::now(),
+        },
+    ];
+    assert_eq!(
+        recorded(filter(&["web"], &["process_down"]), events.clone()).await,
+        pairs(&[("process_down", "web")])
+    );
+    // 只按类型过滤时，与具体进程无关的事件照常匹配
+    assert_eq!(
+        recorded(
+            filter(&[], &["process_down", "process_restart_success"]),
+            events
+        )
+        .await,
+        pairs(&[
+            ("process_down", "web"),
+            ("process_down", "db"),
+            ("process_restart_success", "")
+        ])
+    );
+}
+
+#[tokio::test]
+async fn empty_filter_passes_everything() {
+    let filter = EventFilter::new(&EventFilterConfig::default());
+    assert!(filter.is_empty());
+    assert_eq!(
+        recorded(filter, vec![down("web"), up("db")]).await,
+        pairs(&[("process_down", "web"), ("process_up", "db")])
+    );
+}
+
+#[test]
+fn filters_are_parsed_per_observer() {
+    let yaml = r#"
+processes: []
+control:
+  event_history_filter: { processes: ["web"] }
+event_forwarder:
+  backend: "redis"
+  address: "127.0.0.1:6379"
+  filter: { kinds: ["process_down", "process_disconnected"] }
+"#;
+    let config = AppConfig::parse(yaml, ConfigFormat::Yaml).unwrap();
+    assert_eq!(config.control.event_history_filter.processes, ["web"]);
+    assert!(config.control.event_history_filter.kinds.is_empty());
+    let forwarder = config.event_forwarder.unwrap();
+    assert_eq!(
+        forwarder.filter.kinds,
+        ["process_down", "process_disconnected"]
+    );
+}
+
+#[test]
+fn unknown_event_kind_is_rejected() {
+    let yaml = r#"
+processes: []
+control:
+  event_history_filter: { kinds: ["process_dwon"] }
+"#;
+    let err = AppConfig::parse(yaml, ConfigFormat::Yaml).unwrap_err();
+    assert!(err.to_string().contains("process_dwon"), "{err}");
+}
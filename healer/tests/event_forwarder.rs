@@ -0,0 +1,208 @@
+use healer::config::{AppConfig, ConfigFormat, EventForwarderConfig, ForwarderBackend};
+use healer::event_bus::ProcessEvent;
+use healer::subscriber::forwarder::{render_channel, EventForwarder};
+use healer::subscriber::Subscriber;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::SystemTime;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::{timeout, Duration};
+
+fn down(name: &str, pid: u32) -> ProcessEvent {
+    ProcessEvent::ProcessDown {
+        name: name.into(),
+        pid,
+        at: SystemTime::now(),
+    }
+}
+
+fn forwarder_config(address: SocketAddr, buffer_capacity: usize) -> EventForwarderConfig {
+    EventForwarderConfig {
+        backend: ForwarderBackend::Redis,
+        address: address.to_string(),
+        channel: "healer.{event}.{process}".into(),
+        buffer_capacity,
+        filter: Default::default(),
+    }
+}
+
+// 读取一条 RESP 数组命令
+async fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await.ok()? == 0 {
+        return None;
+    }
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut parts = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut buf = vec![0u8; len + 2];
+        reader.read_exact(&mut buf).await.ok()?;
+        buf.truncate(len);
+        parts.push(String::from_utf8(buf).ok()?);
+    }
+    Some(parts)
+}
+
+// 只支持 PUBLISH 的 Redis 替身，把收到的命令转交给测试
+async fn mock_redis(listener: TcpListener) -> mpsc::UnboundedReceiver<Vec<String>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut reader = BufReader::new(stream);
+                while let Some(command) = read_command(&mut reader).await {
+                    if reader.get_mut().write_all(b":1\r\n").await.is_err() {
+                        break;
+                    }
+                    let _ = tx.send(command);
+                }
+            });
+        }
+    });
+    rx
+}
+
+#[tokio::test]
+async fn process_down_is_published_as_json() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let mut commands = mock_redis(listener).await;
+
+    let (mut forwarder, _task) = EventForwarder::spawn(forwarder_config(address, 16));
+    forwarder.handle_event(down("web", 42)).await;
+
+    let command = timeout(Duration::from_secs(5), commands.recv())
+        .await
+        .expect("event should be forwarded")
+        .unwrap();
+    assert_eq!(command[0], "PUBLISH");
+    assert_eq!(command[1], "healer.process_down.web");
+    let payload: Value = serde_json::from_str(&command[2]).expect("payload should be JSON");
+    assert_eq!(payload["kind"], "process_down");
+    assert_eq!(payload["name"], "web");
+    assert_eq!(payload["pid"], 42);
+    assert!(payload["host"].is_string());
+    assert!(payload["at"].is_string());
+    // 未配置标签时不输出 labels 字段
+    assert!(payload.get("labels").is_none());
+}
+
+#[tokio::test]
+async fn instance_labels_are_included_in_the_payload() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let mut commands = mock_redis(listener).await;
+
+    let labels = HashMap::from([
+        ("datacenter".to_string(), "sh1".to_string()),
+        ("env".to_string(), "prod".to_string()),
+    ]);
+    let (mut forwarder, _task) =
+        EventForwarder::spawn_with_labels(forwarder_config(address, 16), labels);
+    forwarder.handle_event(down("web", 42)).await;
+
+    let command = timeout(Duration::from_secs(5), commands.recv())
+        .await
+        .expect("event should be forwarded")
+        .unwrap();
+    let payload: Value = serde_json::from_str(&command[2]).expect("payload should be JSON");
+    assert_eq!(payload["labels"]["datacenter"], "sh1");
+    assert_eq!(payload["labels"]["env"], "prod");
+    assert_eq!(payload["name"], "web");
+}
+
+#[tokio::test]
+async fn events_are_delivered_after_the_bus_comes_back() {
+    // 先占用一个端口再释放，使第一次连接失败
+    let address = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap()
+    };
+    let (mut forwarder, _task) = EventForwarder::spawn(forwarder_config(address, 16));
+    forwarder.handle_event(down("db", 7)).await;
+    forwarder.handle_event(down("cache", 8)).await;
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let listener = TcpListener::bind(address).await.unwrap();
+    let mut commands = mock_redis(listener).await;
+
+    let mut channels = Vec::new();
+    for _ in 0..2 {
+        let command = timeout(Duration::from_secs(5), commands.recv())
+            .await
+            .expect("buffered event should be forwarded after reconnect")
+            .unwrap();
+        channels.push(command[1].clone());
+    }
+    assert_eq!(
+        channels,
+        vec!["healer.process_down.db", "healer.process_down.cache"]
+    );
+}
+
+#[tokio::test]
+async fn full_buffer_drops_new_events() {
+    let address = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap()
+    };
+    let (mut forwarder, _task) = EventForwarder::spawn(forwarder_config(address, 1));
+    for pid in 0..3 {
+        forwarder.handle_event(down("svc", pid)).await;
+    }
+    assert!(forwarder.dropped() >= 1);
+}
+
+#[test]
+fn channel_template_placeholders() {
+    let up = ProcessEvent::ProcessUp {
+        name: "api".into(),
+        at: SystemTime::now(),
+    };
+    assert_eq!(
+        render_channel("healer.{host}.{event}.{process}", &up, "node1"),
+        "healer.node1.process_up.api"
+    );
+    let restarted = ProcessEvent::ProcessRestartSuccess {
+        at: SystemTime::now(),
+    };
+    assert_eq!(
+        render_channel("{event}/{process}", &restarted, "node1"),
+        "process_restart_success/"
+    );
+}
+
+#[test]
+fn forwarder_config_defaults_and_validation() {
+    let yaml = |forwarder: &str| {
+        format!(
+            r#"
+processes: []
+event_forwarder: {forwarder}
+"#
+        )
+    };
+    let config = AppConfig::parse(
+        &yaml(r#"{ backend: "redis", address: "127.0.0.1:6379" }"#),
+        ConfigFormat::Yaml,
+    )
+    .expect("forwarder config should parse");
+    let forwarder = config.event_forwarder.expect("forwarder should be set");
+    assert_eq!(forwarder.backend, ForwarderBackend::Redis);
+    assert_eq!(forwarder.channel, "healer.{host}.{event}");
+    assert_eq!(forwarder.buffer_capacity, 1024);
+
+    let err = AppConfig::parse(
+        &yaml(r#"{ backend: "redis", address: "127.0.0.1:6379", buffer_capacity: 0 }"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("buffer_capacity"), "{err}");
+}
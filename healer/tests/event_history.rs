@@ -0,0 +1,113 @@
+use healer::config::AppConfig;
+use healer::control::{ControlServer, ControlState};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::subscriber::{event_history::EventHistory, Subscriber};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::sync::{mpsc, RwLock};
+
+fn control_state(history: EventHistory) -> ControlState {
+    let config: AppConfig = serde_yaml::from_str("processes: []").expect("valid config");
+    let (runtime_tx, _) = mpsc::channel(1);
+    ControlState {
+        history,
+        config: Arc::new(RwLock::new(config)),
+        runtime_tx,
+        children: Default::default(),
+        event_tx: create_event_sender(),
+        health: Default::default(),
+    }
+}
+
+fn down(pid: u32) -> ProcessEvent {
+    ProcessEvent::ProcessDown {
+        name: "flaky".into(),
+        pid,
+        at: UNIX_EPOCH + Duration::from_secs(1_700_000_000 + pid as u64),
+    }
+}
+
+fn pids(events: &[ProcessEvent]) -> Vec<u32> {
+    events
+        .iter()
+        .map(|event| match event {
+            ProcessEvent::ProcessDown { pid, .. } => *pid,
+            other => panic!("unexpected event {:?}", other),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn only_most_recent_events_are_retained() {
+    let mut history = EventHistory::new(3);
+    for pid in 1..=5 {
+        history.handle_event(down(pid)).await;
+    }
+
+    assert_eq!(pids(&history.recent(10)), vec![3, 4, 5]);
+    assert_eq!(pids(&history.recent(2)), vec![4, 5]);
+    assert!(history.recent(0).is_empty());
+}
+
+#[tokio::test]
+async fn events_command_returns_json_records() {
+    let history = EventHistory::new(8);
+    history.push(down(1));
+    history.push(ProcessEvent::ProcessUp {
+        name: "flaky".into(),
+        at: SystemTime::now(),
+    });
+    let state = control_state(history);
+
+    let response = state.handle_line(r#"{"cmd":"events","limit":50}"#).await;
+    assert_eq!(response["ok"], true);
+    let events = response["events"].as_array().expect("events array");
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["kind"], "process_down");
+    assert_eq!(events[0]["name"], "flaky");
+    assert_eq!(events[0]["pid"], 1);
+    assert!(events[0]["at"]
+        .as_str()
+        .is_some_and(|at| at.starts_with("2023-11-14T22:13:21")));
+    assert_eq!(events[1]["kind"], "process_up");
+    assert!(events[1].get("pid").is_none());
+
+    let limited = state.handle_line(r#"{"cmd":"events","limit":1}"#).await;
+    assert_eq!(limited["events"][0]["kind"], "process_up");
+
+    let invalid = state.handle_line(r#"{"cmd":"nope"}"#).await;
+    assert_eq!(invalid["ok"], false);
+}
+
+#[tokio::test]
+async fn control_socket_serves_events() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let socket_path = temp_dir.path().join("control.sock");
+    let history = EventHistory::new(4);
+    history.push(down(7));
+
+    let server = ControlServer::spawn(&socket_path, control_state(history))
+        .expect("control socket should bind");
+
+    let stream = UnixStream::connect(&socket_path)
+        .await
+        .expect("connect to control socket");
+    let (reader, mut writer) = stream.into_split();
+    writer
+        .write_all(b"{\"cmd\":\"events\"}\n")
+        .await
+        .expect("write request");
+    let mut lines = BufReader::new(reader).lines();
+    let line = tokio::time::timeout(Duration::from_secs(2), lines.next_line())
+        .await
+        .expect("response in time")
+        .expect("read response")
+        .expect("one response line");
+    let response: serde_json::Value = serde_json::from_str(&line).expect("valid json");
+    assert_eq!(response["events"][0]["pid"], 7);
+
+    server.abort();
+}
@@ -0,0 +1,95 @@
+use healer::config::{AppConfig, ClosedPolicy};
+use healer::coordinator::dependency_coordinator::DependencyCoordinator;
+use healer::event_bus::{create_event_sender, EventReceiver, EventSender, ProcessEvent};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, timeout, Duration};
+
+fn down(name: &str) -> ProcessEvent {
+    ProcessEvent::ProcessDown {
+        name: name.to_string(),
+        pid: 1,
+        at: SystemTime::now(),
+    }
+}
+
+// 重新订阅发生在订阅端观察到关闭之后，期间发布的事件没有接收者；反复发送直到送达
+async fn send_until_delivered(tx: &EventSender, name: &str) {
+    for _ in 0..50 {
+        if matches!(tx.send(down(name)).await, Ok(n) if n > 0) {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("event for '{}' was never delivered", name);
+}
+
+async fn expect_down(rx: &mut EventReceiver, expected: &str) {
+    let event = timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("timed out waiting for event")
+        .expect("receiver should stay open");
+    match event {
+        ProcessEvent::ProcessDown { name, .. } => assert_eq!(name, expected),
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn resubscribing_receiver_survives_channel_closure() {
+    let tx = create_event_sender();
+    let mut rx = tx.subscribe_critical_resubscribing("test");
+
+    send_until_delivered(&tx, "before").await;
+    expect_down(&mut rx, "before").await;
+
+    tx.close_critical();
+    let pending = tokio::spawn(async move {
+        expect_down(&mut rx, "after").await;
+    });
+    send_until_delivered(&tx, "after").await;
+    pending.await.unwrap();
+}
+
+#[tokio::test]
+async fn plain_critical_receiver_reports_closed() {
+    let tx = create_event_sender();
+    let mut rx: EventReceiver = tx.subscribe_critical().into();
+
+    tx.close_critical();
+    assert!(rx.recv().await.is_err());
+}
+
+#[tokio::test]
+async fn coordinator_keeps_forwarding_after_resubscribe() {
+    let config: AppConfig = serde_yaml::from_str("processes: []").unwrap();
+    let in_tx = create_event_sender();
+    let out_tx = create_event_sender();
+    let mut out_rx: EventReceiver = out_tx.subscribe_critical().into();
+
+    let coordinator = DependencyCoordinator::new(
+        in_tx.subscribe_critical_resubscribing("coordinator"),
+        out_tx.clone(),
+        Arc::new(RwLock::new(config)),
+    );
+    let task = tokio::spawn(coordinator.run_loop());
+
+    send_until_delivered(&in_tx, "first").await;
+    expect_down(&mut out_rx, "first").await;
+
+    in_tx.close_critical();
+    send_until_delivered(&in_tx, "second").await;
+    expect_down(&mut out_rx, "second").await;
+
+    assert!(!task.is_finished(), "coordinator loop should keep running");
+    task.abort();
+}
+
+#[test]
+fn subscriber_policies_parse_from_config() {
+    let config: AppConfig =
+        serde_yaml::from_str("processes: []\nsubscribers:\n  healer: fatal\n").unwrap();
+    assert_eq!(config.subscribers.healer, ClosedPolicy::Fatal);
+    assert_eq!(config.subscribers.coordinator, ClosedPolicy::Resubscribe);
+}
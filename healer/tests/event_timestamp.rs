@@ -0,0 +1,69 @@
+use healer::config::{PidMonitorConfig, PidSource};
+use healer::event_bus::{create_event_sender, EventReceiver, ProcessEvent};
+use healer::monitor::{pid_monitor::PidMonitor, Monitor};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+#[tokio::test]
+async fn timestamp_survives_broadcast_and_critical_delivery() {
+    let sender = create_event_sender();
+    let mut observer: EventReceiver = sender.subscribe().into();
+    let mut critical: EventReceiver = sender.subscribe_critical().into();
+
+    // 使用固定的检测时间，确保订阅者看到的是发布方的时间而不是接收时间
+    let detected_at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    sender
+        .send(ProcessEvent::ProcessDown {
+            name: "stamped".into(),
+            pid: 7,
+            at: detected_at,
+        })
+        .await
+        .expect("event should be delivered");
+
+    for rx in [&mut observer, &mut critical] {
+        let event = timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("event should arrive")
+            .expect("channel should be open");
+        assert_eq!(event.at(), detected_at);
+        assert!(matches!(
+            event,
+            ProcessEvent::ProcessDown { ref name, pid: 7, .. } if name == "stamped"
+        ));
+    }
+}
+
+#[tokio::test]
+async fn monitor_stamps_event_at_detection() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let monitor = PidMonitor::new(
+        PidMonitorConfig {
+            name: "missing".into(),
+            source: PidSource::File {
+                path: temp_dir.path().join("missing.pid"),
+            },
+            interval_secs: 1,
+            check_timeout_secs: 1,
+            memory_growth: None,
+            startup_grace_secs: 0,
+            reconcile_exe: None,
+            watch_group: None,
+        },
+        create_event_sender(),
+    );
+
+    let before = SystemTime::now();
+    let event = monitor
+        .check_once()
+        .await
+        .expect("missing pid file should be reported down");
+    let after = SystemTime::now();
+
+    assert!(matches!(event, ProcessEvent::ProcessDown { pid: 0, .. }));
+    assert!(
+        before <= event.at() && event.at() <= after,
+        "detection time should be set by the monitor"
+    );
+}
@@ -0,0 +1,94 @@
+use healer::utils::{find_pid_by_exe, normalize_exe_path};
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
+
+// 复制一份 sleep 到临时目录，使测试进程拥有独一无二的可执行文件路径；
+// 各用例使用不同的文件名，避免并行运行时按文件名匹配到彼此的进程
+fn private_sleep(dir: &Path, name: &str) -> PathBuf {
+    let real_dir = dir.join("lib/foo/bin");
+    std::fs::create_dir_all(&real_dir).expect("create real dir");
+    let real = real_dir.join(name);
+    std::fs::copy("/bin/sleep", &real).expect("copy sleep binary");
+    real
+}
+
+async fn find_eventually(path: &Path, basename_fallback: bool) -> Option<u32> {
+    for _ in 0..20 {
+        if let Some(pid) = find_pid_by_exe(path, basename_fallback) {
+            return Some(pid);
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    None
+}
+
+fn stop(mut child: Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[test]
+fn deleted_marker_is_stripped() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let real = private_sleep(temp_dir.path(), "healer-t-deleted");
+    let canonical = std::fs::canonicalize(&real).unwrap();
+
+    let deleted = PathBuf::from(format!("{} (deleted)", real.display()));
+    assert_eq!(normalize_exe_path(&deleted), canonical);
+
+    // 文件不存在时保留原路径（仍去掉标记）
+    let gone = PathBuf::from("/nonexistent/healer/foo (deleted)");
+    assert_eq!(
+        normalize_exe_path(&gone),
+        PathBuf::from("/nonexistent/healer/foo")
+    );
+}
+
+#[tokio::test]
+async fn process_started_through_symlink_is_found_by_link_path() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let real = private_sleep(temp_dir.path(), "healer-t-link");
+    let link_dir = temp_dir.path().join("usr/bin");
+    std::fs::create_dir_all(&link_dir).unwrap();
+    let link = link_dir.join("healer-t-link");
+    symlink(&real, &link).expect("create symlink");
+
+    let child = Command::new(&link).arg("30").spawn().expect("spawn");
+    let pid = child.id();
+
+    assert_eq!(find_eventually(&link, false).await, Some(pid));
+    assert_eq!(find_eventually(&real, false).await, Some(pid));
+    stop(child);
+}
+
+#[tokio::test]
+async fn upgraded_binary_with_deleted_exe_is_still_found() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let real = private_sleep(temp_dir.path(), "healer-t-upgrade");
+    let child = Command::new(&real).arg("30").spawn().expect("spawn");
+    let pid = child.id();
+    assert_eq!(find_eventually(&real, false).await, Some(pid));
+
+    // 模拟升级：删除正在运行的可执行文件，/proc/<pid>/exe 变为 "<path> (deleted)"
+    let canonical = std::fs::canonicalize(&real).unwrap();
+    std::fs::remove_file(&real).unwrap();
+    assert_eq!(find_eventually(&canonical, false).await, Some(pid));
+    stop(child);
+}
+
+#[tokio::test]
+async fn basename_fallback_is_opt_in() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let real = private_sleep(temp_dir.path(), "healer-t-basename");
+    let child = Command::new(&real).arg("30").spawn().expect("spawn");
+    let pid = child.id();
+    assert_eq!(find_eventually(&real, false).await, Some(pid));
+
+    let elsewhere = Path::new("/opt/not-installed-here/healer-t-basename");
+    assert_eq!(find_pid_by_exe(elsewhere, false), None);
+    assert_eq!(find_eventually(elsewhere, true).await, Some(pid));
+    stop(child);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, HookConfig, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::health::{HealthRegistry, HealthState};
+use healer::subscriber::{process_healer::ProcessHealer, Subscriber};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// guard 运行期间进程停留在 Down，便于观察恢复前的状态
+fn slow_guarded_process(name: &str) -> ProcessConfig {
+    ProcessConfig {
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        recovery_guard: Some(HookConfig {
+            command: "/bin/sleep".into(),
+            args: vec!["1".into()],
+            timeout_secs: 10,
+        }),
+        ..common::process(
+            name,
+            MonitorConfig::Network(common::network_fields("http://127.0.0.1:1/health")),
+        )
+    }
+}
+
+#[tokio::test]
+async fn health_follows_monitor_events_and_recovery() {
+    let name = "tracked".to_string();
+    let config = AppConfig {
+        processes: vec![slow_guarded_process(&name)],
+        ..Default::default()
+    };
+    let health = HealthRegistry::new();
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_health(health.clone());
+
+    assert_eq!(health.get(&name), HealthState::Unknown);
+
+    healer
+        .handle_event(ProcessEvent::ProcessUp {
+            name: name.clone(),
+            at: SystemTime::now(),
+        })
+        .await;
+    assert_eq!(health.get(&name), HealthState::Healthy);
+
+    let down = ProcessEvent::ProcessDown {
+        name: name.clone(),
+        pid: 42,
+        at: SystemTime::now(),
+    };
+    let recovery = tokio::spawn(async move {
+        healer.handle_event(down).await;
+        healer
+    });
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(
+        health.get(&name),
+        HealthState::Down,
+        "process should be down while recovery has not run yet"
+    );
+
+    let mut healer = recovery.await.expect("recovery task panicked");
+    assert_eq!(health.get(&name), HealthState::Recovering);
+
+    healer
+        .handle_event(ProcessEvent::ProcessUp {
+            name: name.clone(),
+            at: SystemTime::now(),
+        })
+        .await;
+    assert_eq!(health.get(&name), HealthState::Healthy);
+    assert_eq!(
+        health.snapshot().into_iter().collect::<Vec<_>>(),
+        vec![(name, HealthState::Healthy)]
+    );
+}
@@ -0,0 +1,114 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::health::{CircuitState, HealthRegistry};
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use healer::subscriber::Subscriber;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+
+// retries: 1 且冷却期为 0：每两次故障熔断一次；连续熔断 2 次即隔离
+fn config(healthy_reset_secs: u64) -> AppConfig {
+    let yaml = format!(
+        r#"
+processes:
+  - name: "flappy"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    child_output: "null"
+    monitor: {{ type: "pid", pid_file_path: "/nonexistent/flappy.pid", interval_secs: 60 }}
+    recovery:
+      type: "regular"
+      retries: 1
+      retry_window_secs: 60
+      cooldown_secs: 0
+      half_open_window_secs: 60
+      quarantine_after_trips: 2
+      healthy_reset_secs: {healthy_reset_secs}
+"#
+    );
+    AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap()
+}
+
+async fn healer(config: AppConfig, health: &HealthRegistry) -> ProcessHealer {
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_health(health.clone())
+}
+
+fn up(name: &str) -> ProcessEvent {
+    ProcessEvent::ProcessUp {
+        name: name.to_string(),
+        at: SystemTime::now(),
+    }
+}
+
+fn is_circuit_open(reason: Option<RecoverySkipReason>) -> bool {
+    matches!(reason, Some(RecoverySkipReason::CircuitOpen { .. }))
+}
+
+#[tokio::test]
+async fn sustained_health_clears_failure_history() {
+    let health = HealthRegistry::new();
+    let mut healer = healer(config(1), &health).await;
+    let name = "flappy".to_string();
+
+    // 第 1 次熔断
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert!(is_circuit_open(healer.heal_process(&name).await));
+
+    // 进程恢复后持续健康超过 healthy_reset_secs
+    healer.handle_event(up(&name)).await;
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    // 历史已清空：从 Closed 重新开始，再熔断一次也只是第 1 次，不会隔离
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert_eq!(health.circuit(&name).unwrap().state, CircuitState::Closed);
+    assert_eq!(health.circuit(&name).unwrap().recent_attempts.len(), 1);
+    assert!(is_circuit_open(healer.heal_process(&name).await));
+    assert!(!health.is_quarantined(&name));
+}
+
+#[tokio::test]
+async fn short_health_does_not_clear_failure_history() {
+    let health = HealthRegistry::new();
+    let mut healer = healer(config(60), &health).await;
+    let name = "flappy".to_string();
+
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert!(is_circuit_open(healer.heal_process(&name).await));
+
+    healer.handle_event(up(&name)).await;
+
+    // 健康时间不够：半开试探后再次故障，连续熔断 2 次进入隔离
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert_eq!(
+        healer.heal_process(&name).await,
+        Some(RecoverySkipReason::Quarantined)
+    );
+    assert!(health.is_quarantined(&name));
+}
+
+#[tokio::test]
+async fn failure_restarts_the_healthy_period() {
+    let health = HealthRegistry::new();
+    let mut healer = healer(config(1), &health).await;
+    let name = "flappy".to_string();
+
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert!(is_circuit_open(healer.heal_process(&name).await));
+
+    // 确认健康后很快又故障（半开试探），持续健康被打断
+    healer.handle_event(up(&name)).await;
+    assert_eq!(healer.heal_process(&name).await, None);
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    // 没有新的 ProcessUp，之前的健康不算数
+    assert_eq!(
+        healer.heal_process(&name).await,
+        Some(RecoverySkipReason::Quarantined)
+    );
+}
@@ -1,5 +1,7 @@
+mod common;
+
 use healer::config::{
-    AppConfig, MonitorConfig, PidMonitorFields, ProcessConfig, RecoveryConfig, RegularHealerFields,
+    AppConfig, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
 };
 use healer::subscriber::process_healer::ProcessHealer;
 use std::path::Path;
@@ -16,33 +18,26 @@ fn make_process(
     pid_dir: &Path,
 ) -> ProcessConfig {
     ProcessConfig {
-        name: name.to_string(),
-        enabled: true,
         command: command.into(),
         args,
-        run_as_user: None,
-        run_as_root: true,
-        working_dir: None,
-        monitor: MonitorConfig::Pid(PidMonitorFields {
-            pid_file_path: pid_dir.join(format!("{name}.pid")),
-            interval_secs: 1,
-        }),
         recovery: RecoveryConfig::Regular(RegularHealerFields {
-            retries: 3,
-            retry_window_secs: 60,
-            cooldown_secs: 30,
+            cooldown_secs: Some(30),
+            ..Default::default()
         }),
-        dependencies: vec![],
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(pid_dir.join(format!("{name}.pid")))),
+        )
     }
 }
 
 fn make_config(base_dir: &Path, processes: Vec<ProcessConfig>) -> AppConfig {
     AppConfig {
-        log_level: None,
         log_directory: Some(base_dir.join("logs")),
         pid_file_directory: Some(base_dir.join("pids")),
         processes,
         working_directory: Some(base_dir.to_path_buf()),
+        ..Default::default()
     }
 }
 
@@ -0,0 +1,236 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::control::ControlState;
+use healer::event_bus::create_event_sender;
+use healer::health::HealthRegistry;
+use healer::http_api::{HttpApiServer, HttpApiState};
+use healer::signal_handler::SignalEvent;
+use healer::subscriber::{event_history::EventHistory, process_healer::ProcessHealer, Subscriber};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{sleep, timeout, Duration};
+
+const TOKEN: &str = "test-token";
+
+fn touch_process(name: &str, marker: &Path) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/touch".into(),
+        args: vec![marker.display().to_string()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 1,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+struct Harness {
+    addr: SocketAddr,
+    health: HealthRegistry,
+    runtime_rx: mpsc::Receiver<SignalEvent>,
+}
+
+// 启动 HTTP 接口，并让自愈器直接消费接口发布的事件
+async fn start(process: ProcessConfig) -> Harness {
+    let config = Arc::new(RwLock::new(AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    }));
+    let event_tx = create_event_sender();
+    let health = HealthRegistry::new();
+    let mut healer = ProcessHealer::new(event_tx.subscribe(), Arc::clone(&config))
+        .await
+        .with_health(health.clone());
+    tokio::spawn(async move {
+        while let Ok(event) = healer.event_rx.recv().await {
+            healer.handle_event(event).await;
+        }
+    });
+
+    let (runtime_tx, runtime_rx) = mpsc::channel(8);
+    let state = HttpApiState {
+        control: ControlState {
+            history: EventHistory::new(8),
+            config,
+            runtime_tx,
+            children: Default::default(),
+            event_tx: event_tx.clone(),
+            health: health.clone(),
+        },
+        health: health.clone(),
+        event_tx,
+        token: TOKEN.into(),
+    };
+    let (addr, _) = HttpApiServer::spawn("127.0.0.1:0".parse().unwrap(), state)
+        .await
+        .expect("failed to start HTTP API");
+    Harness {
+        addr,
+        health,
+        runtime_rx,
+    }
+}
+
+async fn post(addr: SocketAddr, path: &str, token: Option<&str>) -> u16 {
+    let mut request = reqwest::Client::new().post(format!("http://{addr}{path}"));
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    request
+        .send()
+        .await
+        .expect("request failed")
+        .status()
+        .as_u16()
+}
+
+async fn get(addr: SocketAddr, path: &str) -> (u16, serde_json::Value) {
+    let response = reqwest::Client::new()
+        .get(format!("http://{addr}{path}"))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .expect("request failed");
+    let status = response.status().as_u16();
+    let body = response.text().await.expect("failed to read body");
+    (
+        status,
+        serde_json::from_str(&body).expect("body is not JSON"),
+    )
+}
+
+async fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+    for _ in 0..30 {
+        if condition() {
+            return true;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn restart_endpoint_restarts_the_process() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("restarted");
+    let harness = start(touch_process("web", &marker)).await;
+
+    assert_eq!(
+        post(harness.addr, "/processes/web/restart", Some(TOKEN)).await,
+        202
+    );
+    assert!(
+        wait_for(|| marker.exists()).await,
+        "the process should have been restarted"
+    );
+}
+
+#[tokio::test]
+async fn restart_endpoint_rejects_bad_requests() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("restarted");
+    let harness = start(touch_process("web", &marker)).await;
+
+    assert_eq!(
+        post(harness.addr, "/processes/web/restart", None).await,
+        401
+    );
+    assert_eq!(
+        post(harness.addr, "/processes/web/restart", Some("wrong")).await,
+        401
+    );
+    assert_eq!(
+        post(harness.addr, "/processes/missing/restart", Some(TOKEN)).await,
+        404
+    );
+    sleep(Duration::from_millis(300)).await;
+    assert!(
+        !marker.exists(),
+        "rejected requests must not restart anything"
+    );
+}
+
+#[tokio::test]
+async fn restart_endpoint_reports_open_circuit_breaker() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("restarted");
+    let harness = start(touch_process("web", &marker)).await;
+
+    // retries 为 1：第一次恢复执行，第二次打开熔断器
+    assert_eq!(
+        post(harness.addr, "/processes/web/restart", Some(TOKEN)).await,
+        202
+    );
+    assert!(wait_for(|| marker.exists()).await);
+    assert_eq!(
+        post(harness.addr, "/processes/web/restart", Some(TOKEN)).await,
+        202
+    );
+    assert!(
+        wait_for(|| harness.health.is_circuit_open("web")).await,
+        "the second attempt should open the circuit breaker"
+    );
+
+    assert_eq!(
+        post(harness.addr, "/processes/web/restart", Some(TOKEN)).await,
+        409
+    );
+}
+
+#[tokio::test]
+async fn reload_endpoint_requests_a_reload() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let mut harness = start(touch_process("web", &temp_dir.path().join("m"))).await;
+
+    assert_eq!(post(harness.addr, "/reload", Some(TOKEN)).await, 202);
+    let event = timeout(Duration::from_secs(2), harness.runtime_rx.recv())
+        .await
+        .expect("no runtime request received");
+    assert!(matches!(event, Some(SignalEvent::ConfigReload)));
+}
+
+#[tokio::test]
+async fn circuit_endpoint_reports_breaker_state() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("restarted");
+    let harness = start(touch_process("web", &marker)).await;
+
+    // 尚未尝试恢复
+    let (status, body) = get(harness.addr, "/processes/web/circuit").await;
+    assert_eq!(status, 200);
+    assert!(body["circuit"].is_null());
+    assert_eq!(get(harness.addr, "/processes/missing/circuit").await.0, 404);
+
+    assert_eq!(
+        post(harness.addr, "/processes/web/restart", Some(TOKEN)).await,
+        202
+    );
+    assert!(wait_for(|| marker.exists()).await);
+    let (_, body) = get(harness.addr, "/processes/web/circuit").await;
+    assert_eq!(body["circuit"]["state"], "closed");
+    assert_eq!(
+        body["circuit"]["recent_attempts"].as_array().unwrap().len(),
+        1
+    );
+
+    assert_eq!(
+        post(harness.addr, "/processes/web/restart", Some(TOKEN)).await,
+        202
+    );
+    assert!(wait_for(|| harness.health.is_circuit_open("web")).await);
+    let (status, body) = get(harness.addr, "/processes/web/circuit").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["circuit"]["state"], "open");
+    assert!(body["circuit"]["remaining_cooldown_secs"].as_u64().unwrap() > 0);
+}
@@ -0,0 +1,63 @@
+use healer::config::{AppConfig, ConfigFormat};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn write_config(dir: &Path, extra: &str) -> std::path::PathBuf {
+    let config_path = dir.join("config.yaml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "log_directory: {:?}\npid_file_directory: {:?}\nprocesses: []\n{}",
+            dir.join("logs"),
+            dir,
+            extra
+        ),
+    )
+    .unwrap();
+    config_path
+}
+
+#[test]
+fn empty_config_exits_cleanly_after_the_idle_period() {
+    let dir = TempDir::new().unwrap();
+    let config_path = write_config(dir.path(), "exit_when_idle_secs: 1\n");
+
+    let started = Instant::now();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_healer"))
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--foreground")
+        .env("RUST_LOG", "off")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if started.elapsed() > Duration::from_secs(15) {
+            let _ = child.kill();
+            let _ = child.wait();
+            panic!("healer did not exit after the idle period");
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    };
+    assert_eq!(status.code(), Some(0));
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[test]
+fn idle_exit_is_off_by_default_and_must_be_positive() {
+    let config = AppConfig::parse("processes: []\n", ConfigFormat::Yaml).unwrap();
+    assert_eq!(config.exit_when_idle_secs, None);
+    let err = AppConfig::parse(
+        "exit_when_idle_secs: 0\nprocesses: []\n",
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("exit_when_idle_secs"), "{err}");
+}
@@ -0,0 +1,93 @@
+use healer::instance_lock::{InstanceLock, InstanceLockError};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn write_config(dir: &Path) -> std::path::PathBuf {
+    let config_path = dir.join("config.yaml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "log_directory: {:?}\npid_file_directory: {:?}\nprocesses: []\n",
+            dir.join("logs"),
+            dir
+        ),
+    )
+    .unwrap();
+    config_path
+}
+
+fn healer(config_path: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_healer"));
+    command
+        .arg("--config")
+        .arg(config_path)
+        .arg("--foreground")
+        .stdin(Stdio::null());
+    command
+}
+
+// 等待实例把自己的进程号写入 PID 文件，即已持有锁
+fn wait_for_lock(child: &mut Child, pid_file: &Path) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait().unwrap() {
+            panic!("first healer exited early with {status}");
+        }
+        let content = std::fs::read_to_string(pid_file).unwrap_or_default();
+        if content.trim() == child.id().to_string() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    panic!("first healer never locked {pid_file:?}");
+}
+
+#[test]
+fn second_lock_on_the_same_pid_file_is_refused() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("run").join("healer.pid");
+
+    let lock = InstanceLock::acquire(&path).expect("first lock should succeed");
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap().trim(),
+        std::process::id().to_string()
+    );
+    match InstanceLock::acquire(&path) {
+        Err(InstanceLockError::Held { pid, .. }) => assert_eq!(pid, Some(std::process::id())),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("second lock should be refused"),
+    }
+
+    // 释放后可以重新获取
+    drop(lock);
+    InstanceLock::acquire(&path).expect("lock should be free after release");
+}
+
+#[test]
+fn second_instance_refuses_to_start() {
+    let dir = TempDir::new().unwrap();
+    let config_path = write_config(dir.path());
+    let pid_file = dir.path().join("healer.pid");
+
+    let mut first = healer(&config_path)
+        .env("RUST_LOG", "off")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    wait_for_lock(&mut first, &pid_file);
+
+    let second = healer(&config_path).output().unwrap();
+    let _ = first.kill();
+    let _ = first.wait();
+
+    let stderr = String::from_utf8_lossy(&second.stderr);
+    assert_eq!(second.status.code(), Some(1), "stderr:\n{stderr}");
+    assert!(stderr.contains("already running"), "stderr:\n{stderr}");
+    assert!(
+        stderr.contains(&format!("pid {}", first.id())),
+        "stderr:\n{stderr}"
+    );
+}
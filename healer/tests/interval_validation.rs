@@ -0,0 +1,83 @@
+use healer::config::{
+    AppConfig, ConfigError, ConfigFormat, NetworkMonitorConfig, PidMonitorConfig, PidSource,
+};
+use healer::event_bus::create_event_sender;
+use healer::monitor::{network_monitor::NetworkMonitor, pid_monitor::PidMonitor};
+
+fn config_with_monitor(monitor: &str) -> String {
+    format!(
+        r#"
+processes:
+  - name: "web"
+    enabled: true
+    command: "/usr/bin/web"
+    args: []
+    run_as_root: true
+    monitor: {monitor}
+    recovery: {{ type: "regular", retries: 3, retry_window_secs: 60, cooldown_secs: 180 }}
+"#
+    )
+}
+
+#[test]
+fn zero_interval_fails_validation() {
+    for monitor in [
+        r#"{ type: "pid", pid_file_path: "/run/web.pid", interval_secs: 0 }"#,
+        r#"{ type: "network", target_url: "http://127.0.0.1:8080/health", interval_secs: 0 }"#,
+        r#"{ type: "systemd", unit: "web.service", interval_secs: 0 }"#,
+    ] {
+        let err = AppConfig::parse(&config_with_monitor(monitor), ConfigFormat::Yaml)
+            .expect_err("interval_secs of 0 should be rejected");
+        assert!(
+            matches!(&err, ConfigError::Validation(message)
+                if message == "process 'web': monitor interval_secs must be at least 1"),
+            "unexpected error: {err}"
+        );
+    }
+}
+
+#[test]
+fn positive_interval_passes_validation() {
+    let config = AppConfig::parse(
+        &config_with_monitor(r#"{ type: "pid", pid_file_path: "/run/web.pid", interval_secs: 1 }"#),
+        ConfigFormat::Yaml,
+    )
+    .expect("interval_secs of 1 should be accepted");
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn pid_monitor_clamps_zero_interval() {
+    let monitor = PidMonitor::new(
+        PidMonitorConfig {
+            name: "zero".into(),
+            source: PidSource::File {
+                path: "/run/zero.pid".into(),
+            },
+            interval_secs: 0,
+            check_timeout_secs: 1,
+            memory_growth: None,
+            startup_grace_secs: 0,
+            reconcile_exe: None,
+            watch_group: None,
+        },
+        create_event_sender(),
+    );
+    assert_eq!(monitor.check_interval(), 1);
+}
+
+#[test]
+fn network_monitor_clamps_zero_interval() {
+    let monitor = NetworkMonitor::new(
+        NetworkMonitorConfig {
+            name: "zero".into(),
+            target_url: "http://127.0.0.1:1/health".into(),
+            interval_secs: 0,
+            check_timeout_secs: 1,
+            max_backoff_secs: 8,
+            health_json: None,
+        },
+        create_event_sender(),
+    );
+    assert_eq!(monitor.check_interval(), 1);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ConfigFormat, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::Healer;
+use tempfile::TempDir;
+use tokio::time::{timeout, Duration};
+
+fn pid_process(name: &str, base: &std::path::Path) -> ProcessConfig {
+    ProcessConfig {
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retry_window_secs: 30,
+            cooldown_secs: Some(10),
+            ..Default::default()
+        }),
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(base.join(format!("{name}.pid")))),
+        )
+    }
+}
+
+#[tokio::test]
+async fn builds_from_in_memory_config_and_shuts_down() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let config = AppConfig {
+        log_directory: Some(temp_dir.path().join("logs")),
+        pid_file_directory: Some(temp_dir.path().join("pids")),
+        processes: vec![pid_process("embedded", temp_dir.path())],
+        working_directory: Some(temp_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+
+    let handle = Healer::builder()
+        .config(config)
+        .enable_ebpf(false)
+        .build()
+        .run()
+        .await
+        .expect("healer should start from an in-memory config");
+
+    assert_eq!(handle.config().read().await.processes.len(), 1);
+
+    timeout(Duration::from_secs(5), handle.shutdown())
+        .await
+        .expect("shutdown should complete in time")
+        .expect("shutdown should be clean");
+}
+
+#[tokio::test]
+async fn run_without_config_fails() {
+    let result = Healer::builder().enable_ebpf(false).build().run().await;
+    assert!(result.is_err(), "running without a config should fail");
+}
+
+#[test]
+fn default_config_matches_an_empty_config_file() {
+    let parsed = AppConfig::parse("processes: []\n", ConfigFormat::Yaml).unwrap();
+    assert_eq!(
+        format!("{:?}", AppConfig::default()),
+        format!("{:?}", parsed)
+    );
+}
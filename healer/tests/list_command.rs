@@ -0,0 +1,88 @@
+use std::process::Command;
+use tempfile::TempDir;
+
+const CONFIG: &str = r#"
+processes:
+  - name: "api"
+    enabled: true
+    command: "/usr/bin/api-server"
+    args: ["--port", "8080"]
+    run_as_root: true
+    monitor:
+      type: "network"
+      target_url: "http://127.0.0.1:8080/health"
+      interval_secs: 5
+    recovery:
+      type: "regular"
+      retries: 3
+      retry_window_secs: 60
+      cooldown_secs: 180
+    dependencies:
+      - "db"
+  - name: "db"
+    enabled: false
+    command: "/usr/bin/db"
+    args: []
+    run_as_root: true
+    monitor:
+      type: "pid"
+      pid_file_path: "/run/db.pid"
+      interval_secs: 2
+      check_timeout_secs: 1
+    recovery:
+      type: "regular"
+      retries: 5
+      retry_window_secs: 30
+      cooldown_secs: 60
+"#;
+
+#[test]
+fn list_prints_process_summaries() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let config_path = temp_dir.path().join("config.yaml");
+    std::fs::write(&config_path, CONFIG).expect("failed to write config");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_healer"))
+        .arg("--config")
+        .arg(&config_path)
+        .arg("list")
+        .output()
+        .expect("failed to run healer list");
+    assert!(output.status.success(), "healer list should succeed");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().map(str::trim_end).collect();
+    let expected = [
+        "api [enabled]",
+        "  command:    /usr/bin/api-server --port 8080",
+        "  monitor:    network (url=http://127.0.0.1:8080/health, interval=5s, timeout=5s)",
+        "  recovery:   regular (retries=3 within 60s, cooldown=180s)",
+        "  depends on: db (requires, hard, max_wait=30s, on_failure=abort)",
+        "db [disabled]",
+        "  command:    /usr/bin/db",
+        "  monitor:    pid (source=PID file /run/db.pid, interval=2s, timeout=1s)",
+        "  recovery:   regular (retries=5 within 30s, cooldown=60s)",
+        "  depends on: -",
+    ];
+    for line in expected {
+        assert!(
+            lines.contains(&line),
+            "missing line {line:?} in output:\n{stdout}"
+        );
+    }
+}
+
+#[test]
+fn list_rejects_invalid_config() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let config_path = temp_dir.path().join("config.yaml");
+    std::fs::write(&config_path, "processes: not-a-list\n").expect("failed to write config");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_healer"))
+        .arg("--config")
+        .arg(&config_path)
+        .arg("list")
+        .output()
+        .expect("failed to run healer list");
+    assert!(!output.status.success(), "an invalid config should fail");
+}
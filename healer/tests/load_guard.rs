@@ -0,0 +1,157 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, ConfigFormat, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::subscriber::load_guard::{defer_backoff, parse_load1, LoadReader};
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use healer::subscriber::Subscriber;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, timeout, Duration};
+
+// 可在测试中调整的负载
+struct MockLoad(Mutex<f64>);
+
+impl MockLoad {
+    fn set(&self, load1: f64) {
+        *self.0.lock().unwrap() = load1;
+    }
+}
+
+impl LoadReader for MockLoad {
+    fn load1(&self) -> Option<f64> {
+        Some(*self.0.lock().unwrap())
+    }
+}
+
+// 被恢复的进程只负责创建 marker 文件，用于判断是否发生了 spawn
+fn load_limited_process(name: &str, marker: &Path, max_load1: f64) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/touch".into(),
+        args: vec![marker.display().to_string()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        max_load1: Some(max_load1),
+        ..common::process(
+            name,
+            MonitorConfig::Network(common::network_fields("http://127.0.0.1:1/health")),
+        )
+    }
+}
+
+fn config_with(process: ProcessConfig) -> AppConfig {
+    AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    }
+}
+
+fn down(name: &str) -> ProcessEvent {
+    ProcessEvent::ProcessDown {
+        name: name.into(),
+        pid: 4242,
+        at: SystemTime::now(),
+    }
+}
+
+async fn wait_for(path: &Path) -> bool {
+    for _ in 0..50 {
+        if path.exists() {
+            return true;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn high_load_defers_recovery_until_it_drops() {
+    let temp_dir = TempDir::new().unwrap();
+    let marker = temp_dir.path().join("restarted");
+    let config = config_with(load_limited_process("svc", &marker, 2.0));
+    let load = Arc::new(MockLoad(Mutex::new(8.0)));
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_load_reader(load.clone());
+
+    healer.handle_event(down("svc")).await;
+    assert_eq!(
+        healer.last_skip_reason("svc"),
+        Some(&RecoverySkipReason::LoadTooHigh {
+            load1: 8.0,
+            max_load1: 2.0
+        })
+    );
+    // 推迟期间重复的故障事件不会再次排队
+    healer.handle_event(down("svc")).await;
+
+    // 第一次重新排队时负载仍然过高，再次推迟
+    let batch = timeout(Duration::from_secs(5), healer.next_batch())
+        .await
+        .expect("the deferred event should be re-queued")
+        .unwrap();
+    assert_eq!(batch.len(), 1);
+    for event in batch {
+        healer.handle_event(event).await;
+    }
+    assert!(matches!(
+        healer.last_skip_reason("svc"),
+        Some(RecoverySkipReason::LoadTooHigh { .. })
+    ));
+    sleep(Duration::from_millis(300)).await;
+    assert!(!marker.exists(), "recovery must not run while load is high");
+
+    // 负载回落后，下一次重新排队的事件被执行
+    load.set(0.5);
+    let batch = timeout(Duration::from_secs(5), healer.next_batch())
+        .await
+        .expect("the deferred event should be re-queued again")
+        .unwrap();
+    assert_eq!(batch.len(), 1);
+    for event in batch {
+        healer.handle_event(event).await;
+    }
+    assert_eq!(healer.last_skip_reason("svc"), None);
+    assert!(
+        wait_for(&marker).await,
+        "recovery should run once load drops"
+    );
+    drop(sender);
+}
+
+#[test]
+fn load_average_parsing_and_backoff() {
+    assert_eq!(parse_load1("0.52 0.58 0.59 1/467 12345\n"), Some(0.52));
+    assert_eq!(parse_load1(""), None);
+
+    assert_eq!(defer_backoff(1), Duration::from_secs(1));
+    assert_eq!(defer_backoff(3), Duration::from_secs(4));
+    assert_eq!(defer_backoff(10), Duration::from_secs(30));
+    assert_eq!(defer_backoff(100), Duration::from_secs(30));
+}
+
+#[test]
+fn non_positive_max_load1_is_rejected() {
+    let yaml = r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: { type: "pid", pid_file_path: "/run/svc.pid", interval_secs: 3 }
+    max_load1: 0
+"#;
+    let err = AppConfig::parse(yaml, ConfigFormat::Yaml).unwrap_err();
+    assert!(err.to_string().contains("max_load1"), "{err}");
+}
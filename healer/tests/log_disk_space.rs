@@ -0,0 +1,97 @@
+mod common;
+
+use healer::config::{
+    AppConfig, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use healer::utils::available_bytes;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+// 同步恢复：向 stdout 输出一行，并在 marker 中记录已执行
+fn process(name: &str, marker: &Path) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            r#"echo restarted; echo run >> "$1""#.into(),
+            "recover".into(),
+            marker.display().to_string(),
+        ],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 2,
+            cooldown_secs: Some(60),
+            dedup_window_secs: Some(60),
+            ..Default::default()
+        }),
+        wait_for_exit: true,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig, min_free_bytes: Option<u64>) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        min_free_bytes,
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+// 子进程日志可能写入的位置（日志目录不可写时回退到 /tmp）
+fn child_logs(name: &str) -> [PathBuf; 2] {
+    [
+        PathBuf::from(format!("/var/log/healer/{}.restarted.log", name)),
+        PathBuf::from(format!("/tmp/healer_{}.restarted.log", name)),
+    ]
+}
+
+#[test]
+fn available_bytes_uses_nearest_existing_directory() {
+    let dir = TempDir::new().unwrap();
+    let available = available_bytes(dir.path()).expect("statvfs on a temp dir");
+    assert!(available > 0);
+    // 尚未创建的日志目录按其上级目录所在的文件系统统计
+    assert!(available_bytes(&dir.path().join("not/yet/created")).is_some());
+}
+
+#[tokio::test]
+async fn low_disk_space_discards_child_output_but_still_restarts() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("runs");
+    let name = format!("low_disk_{}", std::process::id());
+    let mut healer = healer_for(process(&name, &marker), Some(u64::MAX)).await;
+
+    assert_eq!(healer.heal_process(&name).await, None);
+    healer.wait_for_recoveries().await;
+    assert!(marker.exists(), "the process should still be restarted");
+    for log in child_logs(&name) {
+        assert!(!log.exists(), "{} should not be written", log.display());
+    }
+}
+
+#[tokio::test]
+async fn enough_disk_space_keeps_file_logging() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("runs");
+    let name = format!("enough_disk_{}", std::process::id());
+    let mut healer = healer_for(process(&name, &marker), Some(1)).await;
+
+    assert_eq!(healer.heal_process(&name).await, None);
+    healer.wait_for_recoveries().await;
+    assert!(marker.exists());
+    let logs: Vec<_> = child_logs(&name)
+        .into_iter()
+        .filter(|log| log.exists())
+        .collect();
+    assert_eq!(logs.len(), 1, "the child log should be written");
+    assert_eq!(std::fs::read_to_string(&logs[0]).unwrap(), "restarted\n");
+    let _ = std::fs::remove_file(&logs[0]);
+}
@@ -0,0 +1,67 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::logger::filter_directives;
+use tracing_subscriber::EnvFilter;
+
+fn parse(extra: &str) -> AppConfig {
+    AppConfig::parse(&format!("processes: []\n{extra}"), ConfigFormat::Yaml).unwrap()
+}
+
+// EnvFilter 的 Display 输出各条指令，顺序不保证
+fn directives_of(filter: &EnvFilter) -> Vec<String> {
+    let mut directives: Vec<String> = filter.to_string().split(',').map(String::from).collect();
+    directives.sort();
+    directives
+}
+
+#[test]
+fn config_level_is_used_without_rust_log() {
+    let config = parse(
+        "log_level: \"warn,healer::monitor=debug\"\nlog_targets:\n  dep_coord: trace\n  healer_action: info\n",
+    );
+    let directives = filter_directives(None, &config.log_directives());
+    assert_eq!(
+        directives,
+        "warn,healer::monitor=debug,dep_coord=trace,healer_action=info"
+    );
+
+    let filter = EnvFilter::try_new(&directives).unwrap();
+    assert_eq!(
+        directives_of(&filter),
+        [
+            "dep_coord=trace",
+            "healer::monitor=debug",
+            "healer_action=info",
+            "warn"
+        ]
+    );
+}
+
+#[test]
+fn simple_level_and_default() {
+    assert_eq!(parse("log_level: \"debug\"\n").log_directives(), "debug");
+    assert_eq!(parse("").log_directives(), "info");
+}
+
+#[test]
+fn rust_log_takes_precedence() {
+    let config = parse("log_level: \"debug\"\n");
+    assert_eq!(
+        filter_directives(Some("error".into()), &config.log_directives()),
+        "error"
+    );
+    // 空的 RUST_LOG 视为未设置
+    assert_eq!(
+        filter_directives(Some(" ".into()), &config.log_directives()),
+        "debug"
+    );
+}
+
+#[test]
+fn invalid_levels_are_rejected() {
+    let err = AppConfig::parse(
+        "processes: []\nlog_targets:\n  healer::monitor: loud\n",
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("log_targets"), "{err}");
+}
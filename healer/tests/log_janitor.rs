@@ -0,0 +1,60 @@
+use healer::log_janitor::LogJanitor;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+// 写入指定大小的假日志，并把修改时间设为 age_secs 秒之前
+fn fake_log(dir: &Path, name: &str, bytes: usize, age_secs: u64) -> PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, vec![b'x'; bytes]).unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+        .unwrap();
+    path
+}
+
+fn dir_bytes(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().metadata().unwrap().len())
+        .sum()
+}
+
+#[test]
+fn oversized_directory_is_trimmed_oldest_first() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let dir = temp_dir.path();
+    let oldest = fake_log(dir, "a.restarted.log", 4000, 400);
+    let older = fake_log(dir, "b.restarted.log", 4000, 300);
+    let newer = fake_log(dir, "c.restarted.log", 4000, 200);
+    let newest = fake_log(dir, "d.restarted.log", 4000, 100);
+
+    let removed = LogJanitor::trim(dir, 10_000);
+
+    let removed_paths: Vec<_> = removed.iter().map(|r| r.path.clone()).collect();
+    assert_eq!(removed_paths, vec![oldest.clone(), older.clone()]);
+    assert!(dir_bytes(dir) <= 10_000);
+    assert!(!oldest.exists() && !older.exists());
+    assert!(newer.exists() && newest.exists());
+}
+
+#[test]
+fn directory_under_budget_is_left_alone() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let dir = temp_dir.path();
+    fake_log(dir, "a.restarted.log", 1000, 200);
+    fake_log(dir, "b.restarted.log", 1000, 100);
+
+    assert!(LogJanitor::trim(dir, 2000).is_empty());
+    assert_eq!(dir_bytes(dir), 2000);
+}
+
+#[test]
+fn missing_directory_is_ignored() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    assert!(LogJanitor::trim(&temp_dir.path().join("missing"), 0).is_empty());
+}
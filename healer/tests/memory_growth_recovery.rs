@@ -0,0 +1,90 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MemoryGrowthPolicy, MonitorConfig, PidMonitorFields, ProcessConfig,
+};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::subscriber::{process_healer::ProcessHealer, Subscriber};
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use std::os::unix::process::ExitStatusExt;
+use std::process::ExitStatus;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+
+fn leaking_process(name: &str) -> ProcessConfig {
+    ProcessConfig {
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(PidMonitorFields {
+                memory_growth: Some(MemoryGrowthPolicy {
+                    window_samples: 3,
+                    growth_percent: 10.0,
+                }),
+                ..common::pid_fields("/nonexistent/leaking.pid")
+            }),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+// 模拟内存泄漏的实例；由单独的线程等待，退出后立即被回收，不会以僵尸进程的形式残留
+fn spawn_instance() -> (u32, JoinHandle<ExitStatus>) {
+    let mut child = std::process::Command::new("/bin/sleep")
+        .arg("30")
+        .spawn()
+        .expect("failed to spawn sleep");
+    let pid = child.id();
+    (pid, std::thread::spawn(move || child.wait().unwrap()))
+}
+
+#[tokio::test]
+async fn running_instance_is_terminated_before_restart() {
+    let (pid, exited) = spawn_instance();
+    let mut healer = healer_for(leaking_process("leaking")).await;
+
+    healer
+        .handle_event(ProcessEvent::ProcessDown {
+            name: "leaking".into(),
+            pid,
+            at: SystemTime::now(),
+        })
+        .await;
+
+    let status = exited.join().unwrap();
+    assert_eq!(status.signal(), Some(Signal::SIGTERM as i32));
+}
+
+#[tokio::test]
+async fn process_started_after_the_report_is_left_running() {
+    // 上报之后才启动的进程只是复用了 PID，不是上报的实例
+    let at = SystemTime::now() - Duration::from_secs(10);
+    let (pid, exited) = spawn_instance();
+    let mut healer = healer_for(leaking_process("reused")).await;
+
+    healer
+        .handle_event(ProcessEvent::ProcessDown {
+            name: "reused".into(),
+            pid,
+            at,
+        })
+        .await;
+
+    assert!(
+        !exited.is_finished(),
+        "an unrelated process must not be killed"
+    );
+    kill(Pid::from_raw(pid as i32), Signal::SIGKILL).unwrap();
+    exited.join().unwrap();
+}
@@ -0,0 +1,48 @@
+use healer::config::MemoryGrowthPolicy;
+use healer::monitor::memory_trend::MemoryTrend;
+
+fn trend(window_samples: usize, growth_percent: f64) -> MemoryTrend {
+    MemoryTrend::new(MemoryGrowthPolicy {
+        window_samples,
+        growth_percent,
+    })
+}
+
+#[test]
+fn rising_rss_series_trips_the_detector() {
+    let mut detector = trend(5, 20.0);
+    let series = [100_000, 104_000, 108_000, 113_000, 121_000];
+    let tripped: Vec<bool> = series.iter().map(|&rss| detector.push(rss)).collect();
+    assert_eq!(tripped, vec![false, false, false, false, true]);
+}
+
+#[test]
+fn growth_below_threshold_does_not_trip() {
+    let mut detector = trend(5, 20.0);
+    for rss in [100_000, 102_000, 104_000, 106_000, 108_000, 110_000] {
+        assert!(
+            !detector.push(rss),
+            "10% growth should stay under a 20% limit"
+        );
+    }
+}
+
+#[test]
+fn a_dip_inside_the_window_is_not_a_trend() {
+    let mut detector = trend(4, 10.0);
+    for rss in [100_000, 130_000, 90_000, 140_000] {
+        assert!(!detector.push(rss), "non-monotonic samples should not trip");
+    }
+}
+
+#[test]
+fn baseline_is_rebuilt_after_tripping() {
+    let mut detector = trend(3, 10.0);
+    assert!(!detector.push(100));
+    assert!(!detector.push(110));
+    assert!(detector.push(120));
+    // 触发后窗口清空，需要重新积累完整窗口
+    assert!(!detector.push(130));
+    assert!(!detector.push(140));
+    assert!(detector.push(160));
+}
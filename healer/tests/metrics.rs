@@ -0,0 +1,126 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::metrics::{high_cardinality_labels, latency_between, Histogram, LatencyKind, Metrics};
+use healer::subscriber::{process_healer::ProcessHealer, Subscriber};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+
+fn process(name: &str, enabled: bool) -> ProcessConfig {
+    ProcessConfig {
+        enabled,
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Network(common::network_fields("http://127.0.0.1:1/health")),
+        )
+    }
+}
+
+async fn healer_for(processes: Vec<ProcessConfig>, metrics: &Metrics) -> ProcessHealer {
+    let config = AppConfig {
+        processes,
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_metrics(metrics.clone())
+}
+
+#[test]
+fn latency_is_measured_from_detection_time() {
+    let at = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+    assert_eq!(
+        latency_between(at, at + Duration::from_millis(750)),
+        Duration::from_millis(750)
+    );
+    // 时钟回拨时不产生负延迟
+    assert_eq!(
+        latency_between(at, at - Duration::from_secs(1)),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn histogram_places_samples_in_buckets() {
+    let mut histogram = Histogram::default();
+    histogram.observe(Duration::from_millis(5));
+    histogram.observe(Duration::from_millis(300));
+    histogram.observe(Duration::from_secs(120));
+
+    assert_eq!(histogram.count(), 3);
+    assert_eq!(histogram.sum(), Duration::from_millis(120_305));
+    assert_eq!(histogram.max(), Duration::from_secs(120));
+    let buckets = histogram.buckets();
+    assert!(buckets.contains(&(Some(10), 1)));
+    assert!(buckets.contains(&(Some(500), 1)));
+    assert_eq!(buckets.last(), Some(&(None, 1)));
+}
+
+#[tokio::test]
+async fn recovery_records_latency_since_detection() {
+    let metrics = Metrics::new();
+    let mut healer = healer_for(vec![process("svc", true)], &metrics).await;
+
+    // 模拟监控器在 1.5 秒前检测到故障
+    healer
+        .handle_event(ProcessEvent::ProcessDown {
+            name: "svc".into(),
+            pid: 4242,
+            at: SystemTime::now() - Duration::from_millis(1500),
+        })
+        .await;
+
+    let histogram = metrics
+        .histogram(LatencyKind::DetectionToRecovery, "svc")
+        .expect("a latency sample should be recorded for the recovery");
+    assert_eq!(histogram.count(), 1);
+    assert!(histogram.max() >= Duration::from_millis(1500));
+    assert!(histogram.max() < Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn skipped_recovery_records_nothing() {
+    let metrics = Metrics::new();
+    let mut healer = healer_for(vec![process("off", false)], &metrics).await;
+
+    healer
+        .handle_event(ProcessEvent::ProcessDown {
+            name: "off".into(),
+            pid: 4242,
+            at: SystemTime::now(),
+        })
+        .await;
+
+    assert!(metrics
+        .histogram(LatencyKind::DetectionToRecovery, "off")
+        .is_none());
+    assert!(metrics.snapshot().is_empty());
+}
+
+#[test]
+fn unique_looking_labels_are_flagged_as_high_cardinality() {
+    let labels = HashMap::from([
+        ("env".to_string(), "prod".to_string()),
+        ("role".to_string(), "db-primary".to_string()),
+        (
+            "instance_id".to_string(),
+            "0f8fad5b-d9cb-469f-a165-70867728950e".to_string(),
+        ),
+        ("started".to_string(), "1760572800".to_string()),
+    ]);
+    assert_eq!(
+        high_cardinality_labels(&labels),
+        vec!["instance_id".to_string(), "started".to_string()]
+    );
+}
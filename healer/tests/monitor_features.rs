@@ -0,0 +1,98 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::event_bus::create_event_sender;
+use healer::monitor::{check_compiled, MonitorNotCompiled};
+use healer::monitor_manager::MonitorManager;
+
+fn config() -> AppConfig {
+    AppConfig::parse(
+        r#"
+processes:
+  - name: "api"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: { type: "network", target_url: "http://127.0.0.1:1/", interval_secs: 5 }
+  - name: "worker"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: { type: "pid", pid_file_path: "/run/worker.pid", interval_secs: 5 }
+  - name: "db"
+    enabled: true
+    command: "/usr/bin/db"
+    args: []
+    run_as_root: true
+    monitor: { type: "ebpf" }
+  - name: "unit"
+    enabled: true
+    command: "systemctl"
+    args: ["restart", "unit.service"]
+    run_as_root: true
+    monitor: { type: "systemd", unit: "unit.service", interval_secs: 5 }
+"#,
+        ConfigFormat::Yaml,
+    )
+    .expect("config should parse regardless of enabled features")
+}
+
+#[test]
+fn check_compiled_follows_the_enabled_features() {
+    let config = config();
+    let compiled = |name: &str| check_compiled(config.get_process_config_for(name).unwrap());
+    assert_eq!(compiled("api").is_ok(), cfg!(feature = "monitor-network"));
+    assert_eq!(compiled("worker").is_ok(), cfg!(feature = "monitor-pid"));
+    assert_eq!(compiled("db").is_ok(), cfg!(feature = "monitor-ebpf"));
+    // systemd 的分发始终可用，D-Bus 来源另由 systemd feature 控制
+    assert!(compiled("unit").is_ok());
+}
+
+// 精简构建：cargo test --no-default-features --features monitor-pid --test monitor_features
+#[cfg(not(feature = "monitor-network"))]
+#[tokio::test]
+async fn reconcile_rejects_a_monitor_type_that_was_not_compiled_in() {
+    let config = config();
+    let processes: Vec<_> = config
+        .processes
+        .iter()
+        .filter(|p| p.name == "api" || p.name == "worker")
+        .cloned()
+        .collect();
+    let mut manager = MonitorManager::new_without_ebpf(create_event_sender());
+
+    let err = manager.reconcile(&processes).await.unwrap_err();
+    let not_compiled = err
+        .downcast_ref::<MonitorNotCompiled>()
+        .expect("error should name the missing feature");
+    assert_eq!(
+        not_compiled,
+        &MonitorNotCompiled {
+            process: "api".into(),
+            monitor: "network",
+            feature: "monitor-network",
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "process 'api' uses a network monitor, but healer was built without the 'monitor-network' feature"
+    );
+    // 不做部分协调，编译进来的 pid 监控器也没有启动
+    assert!(manager.running_monitor_names().is_empty());
+}
+
+#[cfg(feature = "monitor-network")]
+#[tokio::test]
+async fn compiled_monitor_types_reconcile_normally() {
+    let config = config();
+    let processes: Vec<_> = config
+        .processes
+        .iter()
+        .filter(|p| p.name == "api")
+        .cloned()
+        .collect();
+    let mut manager = MonitorManager::new_without_ebpf(create_event_sender());
+    manager.reconcile(&processes).await.unwrap();
+    assert_eq!(manager.running_monitor_names(), vec!["api".to_string()]);
+    manager.shutdown().await;
+}
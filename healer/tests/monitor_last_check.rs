@@ -0,0 +1,106 @@
+use healer::config::{AppConfig, UnixSocketMonitorConfig};
+use healer::control::ControlState;
+use healer::event_bus::create_event_sender;
+use healer::health::HealthRegistry;
+use healer::http_api::HttpApiState;
+use healer::monitor::unix_socket_monitor::UnixSocketMonitor;
+use healer::monitor::Monitor;
+use healer::subscriber::event_history::EventHistory;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{sleep, Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+const TOKEN: &str = "test-token";
+
+fn api_state(health: &HealthRegistry) -> HttpApiState {
+    let config: AppConfig = serde_yaml::from_str("processes: []\n").unwrap();
+    let event_tx = create_event_sender();
+    HttpApiState {
+        control: ControlState {
+            history: EventHistory::new(8),
+            config: Arc::new(RwLock::new(config)),
+            runtime_tx: mpsc::channel(1).0,
+            children: Default::default(),
+            event_tx: event_tx.clone(),
+            health: health.clone(),
+        },
+        health: health.clone(),
+        event_tx,
+        token: TOKEN.into(),
+    }
+}
+
+async fn wait_for_check_after(
+    health: &HealthRegistry,
+    name: &str,
+    after: SystemTime,
+) -> SystemTime {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if let Some(at) = health.last_check(name).filter(|at| *at > after) {
+            return at;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    panic!("monitor '{}' did not check in", name);
+}
+
+#[tokio::test]
+async fn last_check_advances_across_ticks_and_is_queryable() {
+    let temp_dir = TempDir::new().unwrap();
+    let health = HealthRegistry::new();
+    // 套接字不存在：监控照常逐轮检查并上报断连，检查时间与结果无关
+    let monitor = UnixSocketMonitor::new(
+        UnixSocketMonitorConfig {
+            name: "sock".into(),
+            path: temp_dir.path().join("absent.sock"),
+            interval_secs: 1,
+            check_timeout_secs: 1,
+        },
+        create_event_sender(),
+    )
+    .with_health(health.clone());
+    assert_eq!(health.last_check("sock"), None);
+
+    let shutdown = CancellationToken::new();
+    tokio::spawn(monitor.run(shutdown.clone()));
+    health.set_monitors(HashMap::from([("sock".to_string(), "unix_socket")]));
+
+    let first = wait_for_check_after(&health, "sock", SystemTime::UNIX_EPOCH).await;
+    let second = wait_for_check_after(&health, "sock", first).await;
+    assert!(second > first);
+
+    let (status, body) = api_state(&health)
+        .handle("GET", "/monitors", Some(&format!("Bearer {TOKEN}")))
+        .await;
+    assert_eq!(status, 200, "{body}");
+    let entry = &body["monitors"]["sock"];
+    assert_eq!(entry["type"], "unix_socket", "{body}");
+    assert!(entry["last_check_at"].is_string(), "{body}");
+    assert!(
+        entry["secs_since_last_check"].as_u64().unwrap() <= 2,
+        "{body}"
+    );
+
+    // 监控器停止后检查时间不再更新，从运行列表中移除后也不再报告
+    shutdown.cancel();
+    sleep(Duration::from_millis(100)).await;
+    let stopped_at = health.last_check("sock").unwrap();
+    sleep(Duration::from_millis(1500)).await;
+    assert_eq!(health.last_check("sock"), Some(stopped_at));
+    health.set_monitors(HashMap::new());
+    assert_eq!(health.last_check("sock"), None);
+}
+
+#[tokio::test]
+async fn monitors_endpoint_requires_get() {
+    let health = HealthRegistry::new();
+    let (status, _) = api_state(&health)
+        .handle("POST", "/monitors", Some(&format!("Bearer {TOKEN}")))
+        .await;
+    assert_eq!(status, 405);
+}
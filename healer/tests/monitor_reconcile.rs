@@ -1,73 +1,51 @@
+mod common;
+
 use healer::config::{
-    MonitorConfig, NetworkMonitorFields, PidMonitorFields, ProcessConfig, RecoveryConfig,
-    RegularHealerFields,
+    MonitorConfig, NetworkMonitorFields, ProcessConfig, RecoveryConfig, RegularHealerFields,
 };
 use healer::event_bus::create_event_sender;
 use healer::monitor_manager::MonitorManager;
 
 fn pid_process(name: &str, pid_path: &str) -> ProcessConfig {
     ProcessConfig {
-        name: name.into(),
-        enabled: true,
-        command: "/bin/true".into(),
-        args: vec![],
-        run_as_user: None,
-        run_as_root: true,
-        working_dir: None,
-        monitor: MonitorConfig::Pid(PidMonitorFields {
-            pid_file_path: pid_path.into(),
-            interval_secs: 1,
-        }),
         recovery: RecoveryConfig::Regular(RegularHealerFields {
-            retries: 3,
             retry_window_secs: 30,
-            cooldown_secs: 10,
+            cooldown_secs: Some(10),
+            ..Default::default()
         }),
-        dependencies: vec![],
+        ..common::process(name, MonitorConfig::Pid(common::pid_fields(pid_path)))
     }
 }
 
 fn network_process(name: &str, url: &str) -> ProcessConfig {
     ProcessConfig {
-        name: name.into(),
-        enabled: true,
-        command: "/bin/true".into(),
-        args: vec![],
-        run_as_user: None,
-        run_as_root: true,
-        working_dir: None,
-        monitor: MonitorConfig::Network(NetworkMonitorFields {
-            target_url: url.into(),
-            interval_secs: 1,
-        }),
         recovery: RecoveryConfig::Regular(RegularHealerFields {
-            retries: 3,
             retry_window_secs: 30,
-            cooldown_secs: 10,
+            cooldown_secs: Some(10),
+            ..Default::default()
         }),
-        dependencies: vec![],
+        ..common::process(
+            name,
+            MonitorConfig::Network(NetworkMonitorFields {
+                interval_secs: 1,
+                ..common::network_fields(url)
+            }),
+        )
     }
 }
 
 fn disabled_process(name: &str) -> ProcessConfig {
     ProcessConfig {
-        name: name.into(),
         enabled: false,
-        command: "/bin/true".into(),
-        args: vec![],
-        run_as_user: None,
-        run_as_root: true,
-        working_dir: None,
-        monitor: MonitorConfig::Pid(PidMonitorFields {
-            pid_file_path: "/tmp/ignore.pid".into(),
-            interval_secs: 1,
-        }),
         recovery: RecoveryConfig::Regular(RegularHealerFields {
-            retries: 3,
             retry_window_secs: 30,
-            cooldown_secs: 10,
+            cooldown_secs: Some(10),
+            ..Default::default()
         }),
-        dependencies: vec![],
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields("/tmp/ignore.pid")),
+        )
     }
 }
 
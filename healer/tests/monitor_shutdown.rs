@@ -0,0 +1,73 @@
+use healer::config::{PidMonitorConfig, PidSource};
+use healer::event_bus::create_event_sender;
+use healer::monitor::{pid_monitor::PidMonitor, Monitor};
+use std::io;
+use std::sync::{Arc, Mutex};
+use tokio::time::{sleep, timeout, Duration};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone, Default)]
+struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl LogBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+#[tokio::test]
+async fn pid_monitor_exits_cleanly_on_cancel() {
+    let logs = LogBuffer::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let monitor = PidMonitor::new(
+        PidMonitorConfig {
+            name: "graceful".into(),
+            source: PidSource::File {
+                path: "/nonexistent/graceful.pid".into(),
+            },
+            interval_secs: 1,
+            check_timeout_secs: 1,
+            memory_growth: None,
+            startup_grace_secs: 0,
+            reconcile_exe: None,
+            watch_group: None,
+        },
+        create_event_sender(),
+    );
+    let shutdown = CancellationToken::new();
+    let handle = tokio::spawn(monitor.run(shutdown.clone()));
+
+    sleep(Duration::from_millis(200)).await;
+    shutdown.cancel();
+
+    let joined = timeout(Duration::from_secs(2), handle)
+        .await
+        .expect("monitor should stop within the timeout");
+    assert!(
+        joined.is_ok(),
+        "monitor task should return normally instead of being aborted"
+    );
+    assert!(
+        logs.contents()
+            .contains("[Monitor] Task for 'graceful' stopped cleanly."),
+        "expected clean-exit log line, got:\n{}",
+        logs.contents()
+    );
+}
@@ -0,0 +1,82 @@
+use healer::config::{PidMonitorConfig, PidSource};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::monitor::{pid_monitor::PidMonitor, Monitor};
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::process::Command;
+use tempfile::TempDir;
+use tokio::time::{timeout, Duration};
+use tokio_util::sync::CancellationToken;
+
+fn dead_pid() -> u32 {
+    let mut child = Command::new("/bin/true")
+        .spawn()
+        .expect("failed to spawn /bin/true");
+    let pid = child.id();
+    child.wait().expect("failed to wait /bin/true");
+    pid
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn slow_check_times_out_and_ticks_continue() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let fifo_path = temp_dir.path().join("hung.fifo");
+    let real_pid_path = temp_dir.path().join("real.pid");
+    let link_path = temp_dir.path().join("svc.pid");
+
+    // 没有写端的 FIFO：读取会一直阻塞，模拟挂起的文件系统
+    let status = Command::new("mkfifo")
+        .arg(&fifo_path)
+        .status()
+        .expect("failed to run mkfifo");
+    assert!(status.success(), "mkfifo failed");
+    symlink(&fifo_path, &link_path).expect("failed to create symlink");
+
+    let event_tx = create_event_sender();
+    let mut event_rx = event_tx.subscribe();
+    let monitor = PidMonitor::new(
+        PidMonitorConfig {
+            name: "slow".into(),
+            source: PidSource::File {
+                path: link_path.clone(),
+            },
+            interval_secs: 1,
+            check_timeout_secs: 1,
+            memory_growth: None,
+            startup_grace_secs: 0,
+            reconcile_exe: None,
+            watch_group: None,
+        },
+        event_tx,
+    );
+    let shutdown = CancellationToken::new();
+    let handle = tokio::spawn(monitor.run(shutdown.clone()));
+
+    // 第一次检查卡在 FIFO 上，期间不应有任何事件
+    assert!(
+        timeout(Duration::from_millis(1500), event_rx.recv())
+            .await
+            .is_err(),
+        "no event expected while the check is hung"
+    );
+
+    // 切换到一个记录已退出进程的普通 PID 文件，后续 tick 应能检测到
+    fs::write(&real_pid_path, dead_pid().to_string()).expect("failed to write pid file");
+    fs::remove_file(&link_path).expect("failed to remove symlink");
+    symlink(&real_pid_path, &link_path).expect("failed to re-create symlink");
+
+    let evt = timeout(Duration::from_secs(5), event_rx.recv())
+        .await
+        .expect("monitor loop stalled after a hung check")
+        .expect("event channel closed");
+    assert!(
+        matches!(evt, ProcessEvent::ProcessDown { ref name, .. } if name == "slow"),
+        "expected ProcessDown for 'slow', got {:?}",
+        evt
+    );
+
+    shutdown.cancel();
+    let _ = timeout(Duration::from_secs(3), handle).await;
+    // 打开写端释放仍阻塞在 FIFO 上的读线程
+    drop(fs::OpenOptions::new().write(true).open(&fifo_path));
+}
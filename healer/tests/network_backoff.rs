@@ -0,0 +1,95 @@
+use healer::config::{AppConfig, ConfigFormat, NetworkMonitorConfig};
+use healer::event_bus::create_event_sender;
+use healer::monitor::{network_monitor::NetworkMonitor, Monitor};
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::time::Duration;
+
+fn monitor_for(address: SocketAddr) -> NetworkMonitor {
+    NetworkMonitor::new(
+        NetworkMonitorConfig {
+            name: "api".into(),
+            target_url: format!("http://{address}/health"),
+            interval_secs: 2,
+            check_timeout_secs: 2,
+            max_backoff_secs: 10,
+            health_json: None,
+        },
+        create_event_sender(),
+    )
+}
+
+// 对每个连接都返回 200 的最小 HTTP 服务
+fn serve_healthy(listener: TcpListener) {
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await;
+            });
+        }
+    });
+}
+
+#[tokio::test]
+async fn interval_backs_off_during_outage_and_resets_on_recovery() {
+    // 先占用一个端口再释放，使检查时连接被拒绝
+    let address = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap()
+    };
+    let monitor = monitor_for(address);
+    assert_eq!(monitor.effective_interval(), Duration::from_secs(2));
+
+    let mut intervals = Vec::new();
+    for _ in 0..4 {
+        assert!(monitor.check_once().await.is_some(), "endpoint is down");
+        intervals.push(monitor.effective_interval().as_secs());
+    }
+    assert_eq!(monitor.consecutive_failures(), 4);
+    // 每次失败翻倍，并被 max_backoff_secs 截断
+    assert_eq!(intervals, vec![4, 8, 10, 10]);
+
+    let listener = TcpListener::bind(address).await.unwrap();
+    serve_healthy(listener);
+    assert!(monitor.check_once().await.is_none(), "endpoint is back");
+    assert_eq!(monitor.consecutive_failures(), 0);
+    assert_eq!(monitor.effective_interval(), Duration::from_secs(2));
+}
+
+#[test]
+fn max_backoff_defaults_and_validation() {
+    let yaml = |monitor: &str| {
+        format!(
+            r#"
+processes:
+  - name: "api"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: {monitor}
+"#
+        )
+    };
+    let config = AppConfig::parse(
+        &yaml(r#"{ type: "network", target_url: "http://127.0.0.1:1/", interval_secs: 5 }"#),
+        ConfigFormat::Yaml,
+    )
+    .expect("network monitor should parse");
+    let network = config.processes[0].get_network_monitor_config().unwrap();
+    assert_eq!(network.max_backoff_secs, 40);
+
+    let err = AppConfig::parse(
+        &yaml(
+            r#"{ type: "network", target_url: "http://127.0.0.1:1/", interval_secs: 5, max_backoff_secs: 3 }"#,
+        ),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("max_backoff_secs"), "{err}");
+}
@@ -0,0 +1,126 @@
+use healer::config::{AppConfig, ConfigFormat, JsonHealthCheck, NetworkMonitorConfig};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::monitor::{network_monitor::NetworkMonitor, Monitor};
+use serde_json::json;
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+// 对每个连接都返回 200 和给定响应体的最小 HTTP 服务
+async fn serve_body(body: &'static str) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    address
+}
+
+fn monitor_for(address: SocketAddr, pointer: &str, expect: serde_json::Value) -> NetworkMonitor {
+    NetworkMonitor::new(
+        NetworkMonitorConfig {
+            name: "api".into(),
+            target_url: format!("http://{address}/health"),
+            interval_secs: 2,
+            check_timeout_secs: 2,
+            max_backoff_secs: 10,
+            health_json: Some(JsonHealthCheck {
+                pointer: pointer.into(),
+                expect,
+            }),
+        },
+        create_event_sender(),
+    )
+}
+
+#[tokio::test]
+async fn ok_status_with_bad_json_state_is_a_disconnect() {
+    let address = serve_body(r#"{"status":"down"}"#).await;
+    let monitor = monitor_for(address, "/status", json!("up"));
+    match monitor.check_once().await {
+        Some(ProcessEvent::ProcessDisconnected { name, url, .. }) => {
+            assert_eq!(name, "api");
+            assert_eq!(url, format!("http://{address}/health"));
+        }
+        other => panic!("expected a disconnect, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn matching_nested_value_is_healthy() {
+    let address = serve_body(r#"{"checks":{"db":{"ok":true}},"status":"up"}"#).await;
+    assert!(monitor_for(address, "/checks/db/ok", json!(true))
+        .check_once()
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn non_json_or_missing_field_is_unhealthy() {
+    let address = serve_body("OK").await;
+    assert!(monitor_for(address, "/status", json!("up"))
+        .check_once()
+        .await
+        .is_some());
+
+    let address = serve_body(r#"{"state":"up"}"#).await;
+    assert!(monitor_for(address, "/status", json!("up"))
+        .check_once()
+        .await
+        .is_some());
+}
+
+#[test]
+fn health_json_fields_are_parsed_and_validated() {
+    let yaml = |extra: &str| {
+        format!(
+            r#"
+processes:
+  - name: "api"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "network", target_url: "http://127.0.0.1:1/", interval_secs: 5{extra} }}
+"#
+        )
+    };
+    let config = AppConfig::parse(
+        &yaml(r#", health_json_path: "/status", health_json_expect: "up""#),
+        ConfigFormat::Yaml,
+    )
+    .expect("health json check should parse");
+    let network = config.processes[0].get_network_monitor_config().unwrap();
+    assert_eq!(
+        network.health_json,
+        Some(JsonHealthCheck {
+            pointer: "/status".into(),
+            expect: json!("up"),
+        })
+    );
+
+    let err = AppConfig::parse(
+        &yaml(r#", health_json_path: "/status""#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("set together"), "{err}");
+
+    let err = AppConfig::parse(
+        &yaml(r#", health_json_path: "status", health_json_expect: "up""#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("JSON pointer"), "{err}");
+}
@@ -0,0 +1,60 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use nix::unistd::{geteuid, User};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+// 恢复命令把自己的 uid 写入 marker；配置要求以 nobody 身份运行
+fn config(marker: &Path) -> AppConfig {
+    let yaml = format!(
+        r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/bin/sh"
+    args: ["-c", "id -u > {marker}"]
+    run_as_root: false
+    run_as_user: "nobody"
+    child_output: "null"
+    wait_for_exit: true
+    monitor: {{ type: "pid", pid_file_path: "/nonexistent/svc.pid", interval_secs: 60 }}
+"#,
+        marker = marker.display()
+    );
+    AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap()
+}
+
+async fn recovered_uid(drop_privileges: bool) -> Option<u32> {
+    let temp_dir = TempDir::new().unwrap();
+    // 切换到 nobody 后仍需能写入 marker
+    std::fs::set_permissions(temp_dir.path(), std::fs::Permissions::from_mode(0o777)).unwrap();
+    let marker = temp_dir.path().join("uid");
+    let config = Arc::new(RwLock::new(config(&marker)));
+    let mut healer = ProcessHealer::new(create_event_sender().subscribe(), config)
+        .await
+        .with_drop_privileges(drop_privileges);
+    healer.heal_process(&"svc".to_string()).await;
+    healer.wait_for_recoveries().await;
+    std::fs::read_to_string(&marker)
+        .ok()
+        .map(|uid| uid.trim().parse().unwrap())
+}
+
+#[tokio::test]
+async fn uid_is_kept_when_privilege_dropping_is_disabled() {
+    assert_eq!(recovered_uid(false).await, Some(geteuid().as_raw()));
+}
+
+#[tokio::test]
+async fn run_as_user_applies_by_default() {
+    if !geteuid().is_root() {
+        eprintln!("skipping: not running as root");
+        return;
+    }
+    let nobody = User::from_name("nobody").unwrap().expect("user nobody");
+    assert_eq!(recovered_uid(true).await, Some(nobody.uid.as_raw()));
+}
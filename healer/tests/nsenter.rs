@@ -0,0 +1,106 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, NamespaceKind, NsenterConfig, ProcessConfig,
+    RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::{Child, Command};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// 在新的 pid 命名空间中启动一个 sleep，返回 unshare 进程及命名空间内 sleep 的 PID
+fn spawn_in_new_pid_namespace() -> (Child, u32) {
+    let unshare = Command::new("unshare")
+        .args(["--pid", "--fork", "sleep", "30"])
+        .spawn()
+        .expect("failed to spawn unshare");
+    let children = format!("/proc/{0}/task/{0}/children", unshare.id());
+    for _ in 0..50 {
+        if let Some(pid) = std::fs::read_to_string(&children)
+            .ok()
+            .and_then(|content| content.split_whitespace().next()?.parse().ok())
+        {
+            return (unshare, pid);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    panic!("unshare did not fork a child in time");
+}
+
+fn nsenter_process(name: &str, marker: &Path, target_pid: u32) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            format!("readlink /proc/self/ns/pid > {}", marker.display()),
+        ],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 1,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        // 只进入 pid 命名空间，marker 仍写在宿主机的文件系统中
+        nsenter: Some(NsenterConfig {
+            target_pid: Some(target_pid),
+            namespaces: vec![NamespaceKind::Pid],
+            paths: BTreeMap::new(),
+        }),
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires root to enter another pid namespace"]
+async fn recovery_command_runs_in_target_pid_namespace() {
+    if !nix::unistd::geteuid().is_root() {
+        eprintln!("skipping: not running as root");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("ns");
+    let (mut unshare, target_pid) = spawn_in_new_pid_namespace();
+
+    let name = "in_container".to_string();
+    let config = AppConfig {
+        processes: vec![nsenter_process(&name, &marker, target_pid)],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await;
+    healer.heal_process(&name).await;
+
+    let mut recorded = String::new();
+    for _ in 0..20 {
+        recorded = std::fs::read_to_string(&marker).unwrap_or_default();
+        if !recorded.trim().is_empty() {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    let target_ns = std::fs::read_link(format!("/proc/{}/ns/pid", target_pid))
+        .expect("failed to read target pid namespace");
+    let own_ns = std::fs::read_link("/proc/self/ns/pid").expect("failed to read own namespace");
+
+    let _ = nix::sys::signal::kill(
+        nix::unistd::Pid::from_raw(target_pid as i32),
+        nix::sys::signal::Signal::SIGKILL,
+    );
+    let _ = unshare.kill();
+    let _ = unshare.wait();
+
+    assert_eq!(recorded.trim(), target_ns.display().to_string());
+    assert_ne!(
+        target_ns, own_ns,
+        "target should live in a separate pid namespace"
+    );
+}
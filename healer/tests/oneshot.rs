@@ -0,0 +1,62 @@
+use std::process::Command;
+use tempfile::TempDir;
+
+fn dead_pid() -> u32 {
+    let mut child = Command::new("/bin/true")
+        .spawn()
+        .expect("failed to spawn /bin/true");
+    let pid = child.id();
+    child.wait().expect("failed to wait /bin/true");
+    pid
+}
+
+#[test]
+fn oneshot_restarts_dead_process_and_reports_it() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let pid_file = temp_dir.path().join("dead.pid");
+    std::fs::write(&pid_file, dead_pid().to_string()).expect("failed to write pid file");
+    let marker = temp_dir.path().join("restarted");
+    let config_path = temp_dir.path().join("config.yaml");
+    std::fs::write(
+        &config_path,
+        format!(
+            r#"
+processes:
+  - name: "dead"
+    enabled: true
+    command: "/bin/touch"
+    args: ["{marker}"]
+    run_as_root: true
+    child_output: "null"
+    monitor:
+      type: "pid"
+      pid_file_path: "{pid_file}"
+      interval_secs: 1
+"#,
+            marker = marker.display(),
+            pid_file = pid_file.display()
+        ),
+    )
+    .expect("failed to write config");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_healer"))
+        .arg("--config")
+        .arg(&config_path)
+        .arg("oneshot")
+        .output()
+        .expect("failed to run healer oneshot");
+
+    assert_eq!(
+        output.status.code(),
+        Some(1),
+        "exit code should be the number of processes found down, stdout:\n{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    for _ in 0..20 {
+        if marker.exists() {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    panic!("the dead process should have been restarted");
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, ConfigFormat, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::control::ControlState;
+use healer::event_bus::create_event_sender;
+use healer::subscriber::child_registry::ChildRegistry;
+use healer::subscriber::event_history::EventHistory;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{sleep, timeout, Duration};
+
+fn shell_process(name: &str, script: &str, output_tail_lines: Option<usize>) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec!["-c".into(), script.into()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retry_window_secs: 30,
+            cooldown_secs: Some(10),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        output_tail_lines,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(format!("/tmp/{name}.pid"))),
+        )
+    }
+}
+
+fn shared_config(processes: Vec<ProcessConfig>) -> Arc<RwLock<AppConfig>> {
+    Arc::new(RwLock::new(AppConfig {
+        processes,
+        ..Default::default()
+    }))
+}
+
+// 子进程退出后管道中的内容可能仍在转发，轮询直到满足条件
+async fn wait_for_tail(children: &ChildRegistry, name: &str, expected_last: &str) -> Vec<String> {
+    timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(lines) = children.tail(name) {
+                if lines.last().map(String::as_str) == Some(expected_last) {
+                    return lines;
+                }
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("child output should be captured")
+}
+
+#[tokio::test]
+async fn tail_keeps_the_last_lines_and_is_served_over_control() {
+    let name = "chatty";
+    let config = shared_config(vec![shell_process(
+        name,
+        "for i in 1 2 3 4 5; do echo line$i; done",
+        Some(3),
+    )]);
+    let children = ChildRegistry::new();
+    let mut healer = ProcessHealer::new(create_event_sender().subscribe(), Arc::clone(&config))
+        .await
+        .with_children(children.clone());
+
+    healer.heal_process(&name.to_string()).await;
+
+    let lines = wait_for_tail(&children, name, "line5").await;
+    assert_eq!(lines, vec!["line3", "line4", "line5"]);
+
+    let (runtime_tx, _runtime_rx) = mpsc::channel(1);
+    let state = ControlState {
+        history: EventHistory::new(8),
+        config,
+        runtime_tx,
+        children,
+        event_tx: create_event_sender(),
+        health: Default::default(),
+    };
+    let response = state
+        .handle_line(r#"{"cmd":"tail","process":"chatty","lines":2}"#)
+        .await;
+    assert_eq!(response["ok"], true, "{response}");
+    assert_eq!(response["lines"], serde_json::json!(["line4", "line5"]));
+
+    let response = state
+        .handle_line(r#"{"cmd":"tail","process":"ghost"}"#)
+        .await;
+    assert_eq!(response["ok"], false);
+}
+
+#[tokio::test]
+async fn stderr_is_captured_and_long_lines_are_bounded() {
+    let name = "noisy";
+    let config = shared_config(vec![shell_process(
+        name,
+        "echo to-stderr >&2; head -c 3000 /dev/zero | tr '\\0' x; echo; sleep 0.2; echo done",
+        Some(10),
+    )]);
+    let children = ChildRegistry::new();
+    let mut healer = ProcessHealer::new(create_event_sender().subscribe(), Arc::clone(&config))
+        .await
+        .with_children(children.clone());
+
+    healer.heal_process(&name.to_string()).await;
+
+    let lines = wait_for_tail(&children, name, "done").await;
+    assert!(lines.contains(&"to-stderr".to_string()), "{lines:?}");
+    assert!(lines.iter().all(|line| line.len() <= 1024), "{lines:?}");
+    let long: usize = lines
+        .iter()
+        .filter(|line| line.starts_with('x'))
+        .map(String::len)
+        .sum();
+    assert_eq!(long, 3000, "long output should be split, not lost");
+}
+
+#[test]
+fn output_tail_lines_is_bounded() {
+    let yaml = |lines: usize| {
+        format!(
+            r#"
+processes:
+  - name: "web"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "pid", pid_file_path: "/run/web.pid", interval_secs: 3 }}
+    output_tail_lines: {lines}
+"#
+        )
+    };
+    let config = AppConfig::parse(&yaml(50), ConfigFormat::Yaml).unwrap();
+    assert_eq!(config.processes[0].output_tail_lines, Some(50));
+    for lines in [0, 100_000] {
+        let err = AppConfig::parse(&yaml(lines), ConfigFormat::Yaml).unwrap_err();
+        assert!(err.to_string().contains("output_tail_lines"), "{err}");
+    }
+}
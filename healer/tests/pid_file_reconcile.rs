@@ -0,0 +1,83 @@
+use healer::config::{PidMonitorConfig, PidSource};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::monitor::{pid_monitor::PidMonitor, Monitor};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
+
+// 复制一份 sleep，使被监控进程拥有独一无二的可执行文件路径
+fn private_sleep(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    std::fs::copy("/bin/sleep", &path).expect("copy sleep binary");
+    path
+}
+
+// 已退出进程的 PID，用来制造过期的 PID 文件
+fn dead_pid() -> u32 {
+    let mut child = Command::new("true").spawn().expect("spawn true");
+    let pid = child.id();
+    child.wait().expect("wait true");
+    pid
+}
+
+fn monitor(pid_path: PathBuf, reconcile_exe: Option<PathBuf>) -> PidMonitor {
+    PidMonitor::new(
+        PidMonitorConfig {
+            name: "stale".into(),
+            source: PidSource::File { path: pid_path },
+            interval_secs: 1,
+            check_timeout_secs: 5,
+            memory_growth: None,
+            startup_grace_secs: 0,
+            reconcile_exe,
+            watch_group: None,
+        },
+        create_event_sender(),
+    )
+}
+
+#[tokio::test]
+async fn stale_pid_file_is_corrected_when_process_still_runs() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let exe = private_sleep(temp_dir.path(), "healer-t-reconcile");
+    let mut child = Command::new(&exe).arg("30").spawn().expect("spawn");
+    // 等待 exec 完成，/proc/<pid>/exe 指向复制出的文件
+    sleep(Duration::from_millis(200)).await;
+
+    let pid_path = temp_dir.path().join("stale.pid");
+    std::fs::write(&pid_path, format!("{}\n", dead_pid())).unwrap();
+
+    let event = monitor(pid_path.clone(), Some(exe)).check_once().await;
+    let content = std::fs::read_to_string(&pid_path).unwrap();
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    assert!(
+        event.is_none(),
+        "a live process should not be reported down, got {:?}",
+        event
+    );
+    assert_eq!(content.trim(), child.id().to_string());
+}
+
+#[tokio::test]
+async fn stale_pid_file_is_reported_down_without_reconcile() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let pid_path = temp_dir.path().join("stale.pid");
+    let stale = dead_pid();
+    std::fs::write(&pid_path, format!("{}\n", stale)).unwrap();
+
+    let event = monitor(pid_path.clone(), None).check_once().await;
+
+    assert!(
+        matches!(event, Some(ProcessEvent::ProcessDown { .. })),
+        "stale pid file should be reported down, got {:?}",
+        event
+    );
+    assert_eq!(
+        std::fs::read_to_string(&pid_path).unwrap().trim(),
+        stale.to_string()
+    );
+}
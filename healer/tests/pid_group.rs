@@ -0,0 +1,114 @@
+use healer::config::{AppConfig, ConfigFormat, GroupScope, PidMonitorConfig, PidSource};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::monitor::{pid_monitor::PidMonitor, Monitor};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Child, Command};
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
+
+// master 在后台 fork 出一个 worker，worker 直到 stop 文件出现才退出
+const MASTER: &str = r#"( while [ ! -e "$2" ]; do sleep 0.1; done ) & echo $$ > "$1"; wait"#;
+
+fn spawn_master(pid_file: &Path, stop_file: &Path) -> Child {
+    Command::new("/bin/sh")
+        .args(["-c", MASTER, "master"])
+        .arg(pid_file)
+        .arg(stop_file)
+        .process_group(0)
+        .spawn()
+        .expect("failed to spawn master")
+}
+
+fn monitor(pid_file: &Path, watch_group: Option<GroupScope>) -> PidMonitor {
+    PidMonitor::new(
+        PidMonitorConfig {
+            name: "forking".into(),
+            source: PidSource::File {
+                path: pid_file.to_path_buf(),
+            },
+            interval_secs: 1,
+            check_timeout_secs: 2,
+            memory_growth: None,
+            startup_grace_secs: 0,
+            reconcile_exe: None,
+            watch_group,
+        },
+        create_event_sender(),
+    )
+}
+
+async fn wait_for_pid_file(pid_file: &Path) {
+    for _ in 0..50 {
+        if std::fs::read_to_string(pid_file).is_ok_and(|content| !content.trim().is_empty()) {
+            return;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    panic!("master did not write its pid file");
+}
+
+#[tokio::test]
+async fn group_is_down_only_after_the_last_worker_exits() {
+    let dir = TempDir::new().unwrap();
+    let pid_file = dir.path().join("master.pid");
+    let stop_file = dir.path().join("stop");
+    let mut master = spawn_master(&pid_file, &stop_file);
+    wait_for_pid_file(&pid_file).await;
+    let monitor = monitor(&pid_file, Some(GroupScope::ProcessGroup));
+    assert!(monitor.check_once().await.is_none());
+
+    // master 退出，worker 仍在同一进程组中
+    master.kill().unwrap();
+    master.wait().unwrap();
+    assert!(monitor.check_once().await.is_none());
+
+    // worker 退出后整个进程组为空，上报 master 的退出
+    std::fs::write(&stop_file, "").unwrap();
+    let mut event = None;
+    for _ in 0..30 {
+        event = monitor.check_once().await;
+        if event.is_some() {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert!(
+        matches!(event, Some(ProcessEvent::ProcessDown { pid, .. }) if pid == master.id()),
+        "expected ProcessDown for the master, got {:?}",
+        event
+    );
+}
+
+#[tokio::test]
+async fn without_watch_group_the_master_alone_decides() {
+    let dir = TempDir::new().unwrap();
+    let pid_file = dir.path().join("master.pid");
+    let stop_file = dir.path().join("stop");
+    let mut master = spawn_master(&pid_file, &stop_file);
+    wait_for_pid_file(&pid_file).await;
+    let monitor = monitor(&pid_file, None);
+    assert!(monitor.check_once().await.is_none());
+
+    master.kill().unwrap();
+    master.wait().unwrap();
+    let event = monitor.check_once().await;
+    std::fs::write(&stop_file, "").unwrap();
+    assert!(matches!(event, Some(ProcessEvent::ProcessDown { .. })));
+}
+
+#[test]
+fn watch_group_is_parsed() {
+    let yaml = r#"
+processes:
+  - name: "nginx"
+    enabled: true
+    command: "/usr/sbin/nginx"
+    args: []
+    run_as_root: true
+    monitor: { type: "pid", pid_file_path: "/run/nginx.pid", interval_secs: 5, watch_group: "session" }
+"#;
+    let config = AppConfig::parse(yaml, ConfigFormat::Yaml).unwrap();
+    let pid_config = config.processes[0].get_pid_monitor_config().unwrap();
+    assert_eq!(pid_config.watch_group, Some(GroupScope::Session));
+}
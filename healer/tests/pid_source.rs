@@ -0,0 +1,136 @@
+use healer::config::{AppConfig, ConfigFormat, PidMonitorConfig, PidSource};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::monitor::{pid_monitor::PidMonitor, Monitor};
+use std::process::Command;
+use tempfile::TempDir;
+use tokio::sync::broadcast;
+use tokio::time::{timeout_at, Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+fn dead_pid() -> u32 {
+    let mut child = Command::new("/bin/true")
+        .spawn()
+        .expect("failed to spawn /bin/true");
+    let pid = child.id();
+    child.wait().expect("failed to wait /bin/true");
+    pid
+}
+
+fn start_monitor(
+    name: &str,
+    source: PidSource,
+) -> (broadcast::Receiver<ProcessEvent>, CancellationToken) {
+    let event_tx = create_event_sender();
+    let event_rx = event_tx.subscribe();
+    let monitor = PidMonitor::new(
+        PidMonitorConfig {
+            name: name.into(),
+            source,
+            interval_secs: 1,
+            check_timeout_secs: 1,
+            memory_growth: None,
+            startup_grace_secs: 0,
+            reconcile_exe: None,
+            watch_group: None,
+        },
+        event_tx,
+    );
+    let shutdown = CancellationToken::new();
+    tokio::spawn(monitor.run(shutdown.clone()));
+    (event_rx, shutdown)
+}
+
+// 等待下一个 ProcessDown，忽略确认健康的 ProcessUp
+async fn next_down(rx: &mut broadcast::Receiver<ProcessEvent>, wait: Duration) -> Option<u32> {
+    let deadline = Instant::now() + wait;
+    loop {
+        match timeout_at(deadline, rx.recv()).await {
+            Ok(Ok(ProcessEvent::ProcessDown { pid, .. })) => return Some(pid),
+            Ok(Ok(ProcessEvent::ProcessUp { .. })) => continue,
+            _ => return None,
+        }
+    }
+}
+
+#[tokio::test]
+async fn empty_cgroup_procs_is_reported_down() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let procs = temp_dir.path().join("cgroup.procs");
+    std::fs::write(&procs, "").expect("failed to write cgroup.procs");
+
+    let (mut rx, shutdown) = start_monitor("cg", PidSource::CgroupProcs { path: procs });
+    assert_eq!(
+        next_down(&mut rx, Duration::from_secs(3)).await,
+        Some(0),
+        "empty cgroup.procs should be treated as down"
+    );
+    shutdown.cancel();
+}
+
+#[tokio::test]
+async fn populated_cgroup_procs_is_alive() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let procs = temp_dir.path().join("cgroup.procs");
+    std::fs::write(&procs, format!("{}\n", std::process::id()))
+        .expect("failed to write cgroup.procs");
+
+    let (mut rx, shutdown) = start_monitor("cg_alive", PidSource::CgroupProcs { path: procs });
+    assert_eq!(
+        next_down(&mut rx, Duration::from_millis(2500)).await,
+        None,
+        "a live pid in cgroup.procs should not be reported down"
+    );
+    shutdown.cancel();
+}
+
+#[tokio::test]
+async fn command_source_reports_dead_pid() {
+    let pid = dead_pid();
+    let (mut rx, shutdown) = start_monitor(
+        "cmd",
+        PidSource::Command {
+            command: "/bin/sh".into(),
+            args: vec!["-c".into(), format!("echo {pid}")],
+        },
+    );
+    assert_eq!(
+        next_down(&mut rx, Duration::from_secs(3)).await,
+        Some(pid),
+        "the pid printed by the command should be checked"
+    );
+    shutdown.cancel();
+}
+
+#[tokio::test]
+async fn command_source_zero_output_is_down() {
+    let (mut rx, shutdown) = start_monitor(
+        "cmd_zero",
+        PidSource::Command {
+            command: "/bin/echo".into(),
+            args: vec!["0".into()],
+        },
+    );
+    assert_eq!(
+        next_down(&mut rx, Duration::from_secs(3)).await,
+        Some(0),
+        "MainPID=0 style output should be treated as down"
+    );
+    shutdown.cancel();
+}
+
+#[test]
+fn pid_monitor_without_a_source_is_rejected() {
+    let yaml = r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: { type: "pid", interval_secs: 5 }
+"#;
+    let err = AppConfig::parse(yaml, ConfigFormat::Yaml).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("'svc'"), "{message}");
+    assert!(message.contains("pid_source"), "{message}");
+}
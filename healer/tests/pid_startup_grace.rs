@@ -0,0 +1,89 @@
+use healer::config::{PidMonitorConfig, PidSource};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::monitor::{pid_monitor::PidMonitor, Monitor};
+use std::path::PathBuf;
+use std::process::Command;
+use tempfile::TempDir;
+use tokio::sync::broadcast;
+use tokio::time::{sleep, timeout, Duration};
+use tokio_util::sync::CancellationToken;
+
+fn start_monitor(
+    path: PathBuf,
+    startup_grace_secs: u64,
+) -> (broadcast::Receiver<ProcessEvent>, CancellationToken) {
+    let event_tx = create_event_sender();
+    let event_rx = event_tx.subscribe();
+    let monitor = PidMonitor::new(
+        PidMonitorConfig {
+            name: "late_writer".into(),
+            source: PidSource::File { path },
+            interval_secs: 1,
+            check_timeout_secs: 1,
+            memory_growth: None,
+            startup_grace_secs,
+            reconcile_exe: None,
+            watch_group: None,
+        },
+        event_tx,
+    );
+    let shutdown = CancellationToken::new();
+    tokio::spawn(monitor.run(shutdown.clone()));
+    (event_rx, shutdown)
+}
+
+#[tokio::test]
+async fn pid_file_written_late_is_not_reported_down() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let pid_path = temp_dir.path().join("late.pid");
+    let mut child = Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .expect("failed to spawn sleep");
+
+    let (mut rx, shutdown) = start_monitor(pid_path.clone(), 5);
+
+    // 服务启动 2 秒后才写入 PID 文件
+    sleep(Duration::from_secs(2)).await;
+    std::fs::write(&pid_path, child.id().to_string()).expect("failed to write pid file");
+
+    // 写入后监控只应确认健康（ProcessUp），不应出现 ProcessDown
+    while let Ok(evt) = timeout(Duration::from_secs(5), rx.recv()).await {
+        let evt = evt.expect("event channel closed");
+        assert!(
+            matches!(evt, ProcessEvent::ProcessUp { .. }),
+            "no ProcessDown expected while the pid file was being written, got {:?}",
+            evt
+        );
+    }
+
+    shutdown.cancel();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[tokio::test]
+async fn missing_pid_file_is_reported_after_grace() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let pid_path = temp_dir.path().join("never.pid");
+
+    let (mut rx, shutdown) = start_monitor(pid_path, 2);
+
+    assert!(
+        timeout(Duration::from_millis(1500), rx.recv())
+            .await
+            .is_err(),
+        "a missing pid file should be tolerated during the grace period"
+    );
+    let evt = timeout(Duration::from_secs(4), rx.recv())
+        .await
+        .expect("missing pid file should be reported once the grace period ends")
+        .expect("event channel closed");
+    assert!(
+        matches!(evt, ProcessEvent::ProcessDown { pid: 0, .. }),
+        "expected ProcessDown with pid 0, got {:?}",
+        evt
+    );
+
+    shutdown.cancel();
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::Healer;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout, Duration};
+
+// 慢启动的服务：记录每次启动，3 秒后才写入 PID 文件
+const SLOW_START: &str = r#"echo $$ >> "$1"; sleep 3; echo $$ > "$2"; exec sleep 30"#;
+
+fn slow_service(base: &Path, post_restart_grace_secs: u64) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            SLOW_START.into(),
+            "slow".into(),
+            base.join("starts").display().to_string(),
+            base.join("slow.pid").display().to_string(),
+        ],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 5,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        post_restart_grace_secs,
+        ..common::process(
+            "slow",
+            MonitorConfig::Pid(common::pid_fields(base.join("slow.pid"))),
+        )
+    }
+}
+
+// 运行约 5 秒，返回服务被启动的次数
+async fn count_starts(post_restart_grace_secs: u64) -> usize {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let base = temp_dir.path();
+    let config = AppConfig {
+        log_directory: Some(base.join("logs")),
+        pid_file_directory: Some(base.join("pids")),
+        processes: vec![slow_service(base, post_restart_grace_secs)],
+        working_directory: Some(base.to_path_buf()),
+        ..Default::default()
+    };
+
+    let handle = Healer::builder()
+        .config(config)
+        .enable_ebpf(false)
+        .build()
+        .run()
+        .await
+        .expect("healer should start");
+    sleep(Duration::from_secs(5)).await;
+    timeout(Duration::from_secs(5), handle.shutdown())
+        .await
+        .expect("shutdown should complete in time")
+        .expect("shutdown should be clean");
+
+    let starts = std::fs::read_to_string(base.join("starts")).unwrap_or_default();
+    let pids: Vec<i32> = starts
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect();
+    for pid in &pids {
+        let _ = kill(Pid::from_raw(*pid), Signal::SIGKILL);
+    }
+    pids.len()
+}
+
+#[tokio::test]
+async fn slow_start_is_not_restarted_during_grace() {
+    assert_eq!(count_starts(6).await, 1);
+}
+
+#[tokio::test]
+async fn slow_start_is_restarted_again_without_grace() {
+    assert!(count_starts(0).await > 1);
+}
@@ -69,15 +69,6 @@ fn find_free_port() -> u16 {
         .port()
 }
 
-fn workspace_root() -> PathBuf {
-    // CARGO_MANIFEST_DIR 指向 healer 子 crate；集成测试期望使用工作区根目录（其父目录）
-    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    crate_dir
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or(crate_dir)
-}
-
 fn write_file(path: &PathBuf, content: &str) {
     if let Some(parent) = path.parent() {
         let _ = fs::create_dir_all(parent);
@@ -141,10 +132,8 @@ fn kill_child(child: &mut Child) {
 }
 
 fn build_pid_only_config(ctx: &TestContext) -> String {
-    let base = workspace_root();
     let logs_dir = ctx.logs_dir();
     let pids_dir = ctx.pids_dir();
-    let test_id = ctx.temp_path().file_name().unwrap().to_string_lossy();
 
     format!(
         r#"
@@ -156,8 +145,8 @@ working_directory: "/"
 processes:
   - name: "counter"
     enabled: true
-    command: "{}/target/debug/test_process_{}"
-    args: []
+    command: "{}"
+    args: ["--pid-file", "{}/counter.pid"]
     run_as_root: true
     run_as_user: null
     monitor:
@@ -172,8 +161,8 @@ processes:
 "#,
         logs_dir.display(),
         pids_dir.display(),
-        base.display(),
-        test_id,
+        test_process_bin().display(),
+        pids_dir.display(),
         pids_dir.display()
     )
 }
@@ -213,64 +202,30 @@ processes:
     )
 }
 
-fn ensure_test_binaries(ctx: &TestContext) {
-    let pids_dir = ctx.pids_dir();
-    let helper_src = format!(
-        r#"fn main(){{
-        use std::{{fs,thread,time,process,io::{{self,Write}}}};
-        let pid = process::id();
-        fs::create_dir_all("{}").expect("failed to create pid directory");
-        fs::write("{}/counter.pid", pid.to_string()).expect("failed to write pid file");
-        let mut n=0u64; loop{{ print!("\r[PID {{}}] alive {{}}", pid,n); io::stdout().flush().expect("flush failed"); thread::sleep(time::Duration::from_secs(1)); n+=1; }}
-    }}"#,
-        pids_dir.display(),
-        pids_dir.display()
-    );
-
-    let base = workspace_root();
-    let bin_dir = base.join("target").join("debug");
-    let test_bin_src = ctx.temp_path().join("test_process.rs");
-    write_file(&test_bin_src, &helper_src);
-
-    // 为每个测试创建唯一的二进制文件名
-    let test_id = ctx.temp_path().file_name().unwrap().to_string_lossy();
-    let out_bin = bin_dir.join(format!("test_process_{}", test_id));
-    let status = Command::new("rustc")
-        .args([
-            "-O",
-            test_bin_src.to_str().unwrap(),
-            "-o",
-            out_bin.to_str().unwrap(),
-        ])
-        .status()
-        .expect("failed to run rustc for test helper");
-    assert!(status.success(), "failed to build test helper bin");
+// 测试辅助进程由 cargo 与 healer 一同构建（见 Cargo.toml 中的 simple_test_process bin）
+fn test_process_bin() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_simple_test_process"))
 }
 
 #[test]
 fn restart_on_pid_exit_and_circuit_breaker() {
     let mut ctx = TestContext::new();
-    ensure_test_binaries(&ctx);
 
     let cfg_text = build_pid_only_config(&ctx);
     let cfg_path = ctx.temp_path().join("it_config.yaml");
     write_file(&cfg_path, &cfg_text);
 
     // 先启动 helper，保证 PID 文件存在
-    let base = workspace_root();
-    let test_id = ctx.temp_path().file_name().unwrap().to_string_lossy();
-    let helper_bin = base
-        .join("target")
-        .join("debug")
-        .join(format!("test_process_{}", test_id));
-    let mut initial = Command::new(helper_bin)
+    let pid_path = ctx.pids_dir().join("counter.pid");
+    let mut initial = Command::new(test_process_bin())
+        .arg("--pid-file")
+        .arg(&pid_path)
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .spawn()
         .expect("failed to spawn initial test_process");
 
     // 直接写入 PID 文件，消除 helper 自行写入的竞态
-    let pid_path = ctx.pids_dir().join("counter.pid");
     let initial_pid = initial.id() as i32;
     fs::write(&pid_path, initial_pid.to_string())
         .expect("failed to prime PID file for initial helper");
@@ -416,7 +371,6 @@ fn restart_on_pid_exit_and_circuit_breaker() {
 #[test]
 fn network_monitor_detects_crash_and_recovers() {
     let mut ctx = TestContext::new();
-    // ensure_test_binaries(&ctx);
 
     let dummy_py = format!(
         r#"import http.server, socketserver, sys
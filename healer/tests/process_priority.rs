@@ -0,0 +1,108 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, ConfigFormat, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::child_registry::{ChildRegistry, TrackedChild};
+use healer::subscriber::process_healer::ProcessHealer;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+fn sleeping_process(name: &str, nice: Option<i32>, ionice_class: Option<u8>) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sleep".into(),
+        args: vec!["30".into()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retry_window_secs: 30,
+            cooldown_secs: Some(10),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        nice,
+        ionice_class,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(format!("/tmp/{name}.pid"))),
+        )
+    }
+}
+
+// 执行一次恢复并返回拉起的子进程
+async fn restart(process: ProcessConfig) -> TrackedChild {
+    let name = process.name.clone();
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let children = ChildRegistry::new();
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_children(children.clone());
+    healer.heal_process(&name).await;
+    children
+        .get(&name)
+        .expect("restarted child should be registered")
+}
+
+// /proc/<pid>/stat 的第 19 个字段是 nice 值
+fn nice_of(pid: u32) -> i32 {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).unwrap();
+    let (_, rest) = stat.rsplit_once(") ").unwrap();
+    rest.split_whitespace().nth(16).unwrap().parse().unwrap()
+}
+
+fn kill(child: &TrackedChild) {
+    let _ = nix::sys::signal::kill(
+        nix::unistd::Pid::from_raw(child.pid as i32),
+        nix::sys::signal::Signal::SIGKILL,
+    );
+}
+
+#[tokio::test]
+async fn child_runs_with_configured_nice() {
+    let child = restart(sleeping_process("nice_child", Some(7), None)).await;
+    let nice = nice_of(child.pid);
+    kill(&child);
+    assert_eq!(nice, 7);
+}
+
+#[tokio::test]
+async fn out_of_range_nice_is_clamped() {
+    let child = restart(sleeping_process("nice_clamped", Some(40), None)).await;
+    let nice = nice_of(child.pid);
+    kill(&child);
+    assert_eq!(nice, 19);
+}
+
+#[tokio::test]
+async fn child_runs_with_configured_io_class() {
+    let child = restart(sleeping_process("idle_io_child", None, Some(3))).await;
+    // ioprio_get(IOPRIO_WHO_PROCESS, pid)，高位为调度类别
+    let ioprio = unsafe { libc::syscall(libc::SYS_ioprio_get, 1, child.pid) };
+    kill(&child);
+    assert_eq!(ioprio >> 13, 3);
+}
+
+#[test]
+fn unknown_io_class_is_rejected_at_load() {
+    let yaml = |ionice_class: u8| {
+        format!(
+            r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    ionice_class: {ionice_class}
+    monitor: {{ type: "pid", pid_file_path: "/run/svc.pid", interval_secs: 5 }}
+"#
+        )
+    };
+    AppConfig::parse(&yaml(2), ConfigFormat::Yaml).unwrap();
+    let err = AppConfig::parse(&yaml(4), ConfigFormat::Yaml).unwrap_err();
+    assert!(err.to_string().contains("ionice_class"), "{err}");
+}
@@ -0,0 +1,131 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::event_bus::create_event_sender;
+use healer::health::{CircuitState, HealthRegistry, HealthState};
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+// retries: 1 且冷却期为 0：每两次故障熔断一次，冷却结束后的半开试探在观察窗口内再次失败即重新熔断
+fn config(quarantine_after_trips: u32) -> AppConfig {
+    let yaml = format!(
+        r#"
+processes:
+  - name: "flappy"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    child_output: "null"
+    monitor: {{ type: "pid", pid_file_path: "/nonexistent/flappy.pid", interval_secs: 60 }}
+    recovery:
+      type: "regular"
+      retries: 1
+      retry_window_secs: 60
+      cooldown_secs: 0
+      half_open_window_secs: 60
+      quarantine_after_trips: {quarantine_after_trips}
+"#
+    );
+    AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap()
+}
+
+async fn healer(config: AppConfig, health: &HealthRegistry) -> ProcessHealer {
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_health(health.clone())
+}
+
+fn is_circuit_open(reason: Option<RecoverySkipReason>) -> bool {
+    matches!(reason, Some(RecoverySkipReason::CircuitOpen { .. }))
+}
+
+#[tokio::test]
+async fn repeated_trips_quarantine_the_process_until_resume() {
+    let health = HealthRegistry::new();
+    let mut healer = healer(config(3), &health).await;
+    let name = "flappy".to_string();
+
+    // 第一次恢复执行，第二次用尽重试次数：第 1 次熔断
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert!(is_circuit_open(healer.heal_process(&name).await));
+    // 半开试探执行，观察窗口内再次故障：第 2 次熔断
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert!(is_circuit_open(healer.heal_process(&name).await));
+    assert!(!health.is_quarantined(&name));
+
+    // 第 3 次熔断直接进入隔离
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert_eq!(
+        healer.heal_process(&name).await,
+        Some(RecoverySkipReason::Quarantined)
+    );
+    assert!(health.is_quarantined(&name));
+    assert_eq!(health.get(&name), HealthState::Quarantined);
+    assert_eq!(
+        health.circuit(&name).unwrap().state,
+        CircuitState::Quarantined
+    );
+
+    // 冷却期早已结束，隔离仍然有效
+    for _ in 0..3 {
+        assert_eq!(
+            healer.heal_process(&name).await,
+            Some(RecoverySkipReason::Quarantined)
+        );
+    }
+
+    // 手动解除后熔断器从头开始
+    assert!(health.resume(&name));
+    assert!(!health.resume(&name));
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert_eq!(health.circuit(&name).unwrap().state, CircuitState::Closed);
+    assert!(is_circuit_open(healer.heal_process(&name).await));
+}
+
+#[tokio::test]
+async fn quarantine_is_off_by_default() {
+    let health = HealthRegistry::new();
+    let mut healer = healer(config(0), &health).await;
+    let name = "flappy".to_string();
+
+    for _ in 0..5 {
+        assert_eq!(healer.heal_process(&name).await, None);
+        assert!(is_circuit_open(healer.heal_process(&name).await));
+    }
+    assert!(!health.is_quarantined(&name));
+}
+
+#[test]
+fn quarantine_after_trips_defaults_to_zero() {
+    let yaml = r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: { type: "pid", pid_file_path: "/run/svc.pid", interval_secs: 3 }
+    recovery: { type: "regular", retries: 3, retry_window_secs: 60 }
+"#;
+    let config = AppConfig::parse(yaml, ConfigFormat::Yaml).unwrap();
+    match &config.processes[0].recovery {
+        healer::config::RecoveryConfig::Regular(fields) => {
+            assert_eq!(fields.quarantine_after_trips, 0)
+        }
+        other => panic!("unexpected recovery config {:?}", other),
+    }
+}
+
+#[test]
+fn resume_all_lifts_every_quarantine() {
+    let health = HealthRegistry::new();
+    health.quarantine("a");
+    health.quarantine("b");
+    let mut resumed = health.resume_all();
+    resumed.sort();
+    assert_eq!(resumed, ["a", "b"]);
+    assert!(!health.is_quarantined("a"));
+    assert_eq!(health.get("a"), HealthState::Down);
+    assert!(health.resume_all().is_empty());
+}
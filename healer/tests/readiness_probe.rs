@@ -0,0 +1,280 @@
+mod common;
+
+use async_trait::async_trait;
+use healer::config::{
+    AppConfig, ChildOutput, ConfigFormat, DependencyConfig, DependencyKind, HttpReadinessFields,
+    MonitorConfig, OnFailure, PidMonitorFields, ProcessConfig, RawDependency, ReadinessProbe,
+    RecoveryConfig, RegularHealerFields, TcpReadinessFields,
+};
+use healer::coordinator::dependency_coordinator::DependencyCoordinator;
+use healer::coordinator::readiness::{probe_once, ReadinessChecker};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+use tokio::time::{timeout, Duration};
+
+fn mk_process(name: &str, deps: Vec<RawDependency>) -> ProcessConfig {
+    ProcessConfig {
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 1,
+            retry_window_secs: 5,
+            cooldown_secs: Some(5),
+            ..Default::default()
+        }),
+        dependencies: deps,
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(PidMonitorFields {
+                interval_secs: 60,
+                ..common::pid_fields(format!("/tmp/{name}.pid"))
+            }),
+        )
+    }
+}
+
+fn app_config(processes: Vec<ProcessConfig>) -> AppConfig {
+    AppConfig {
+        working_directory: Some(PathBuf::from("/")),
+        processes,
+        ..Default::default()
+    }
+}
+
+fn down(name: &str) -> ProcessEvent {
+    ProcessEvent::ProcessDown {
+        name: name.to_string(),
+        pid: 1,
+        at: SystemTime::now(),
+    }
+}
+
+// 只有 "db" 配置了探针，就绪状态由测试控制
+#[derive(Default)]
+struct ManualReadiness {
+    ready: AtomicBool,
+    checks: AtomicUsize,
+}
+
+#[async_trait]
+impl ReadinessChecker for ManualReadiness {
+    async fn check(&self, process: &str) -> Option<bool> {
+        if process != "db" {
+            return None;
+        }
+        self.checks.fetch_add(1, Ordering::SeqCst);
+        Some(self.ready.load(Ordering::SeqCst))
+    }
+}
+
+#[tokio::test]
+async fn dependent_waits_for_readiness_not_liveness() {
+    let dep = RawDependency::Detailed(DependencyConfig {
+        target: "db".to_string(),
+        kind: DependencyKind::Requires,
+        hard: true,
+        max_wait_secs: 60,
+        on_failure: OnFailure::Abort,
+    });
+    let shared = Arc::new(RwLock::new(app_config(vec![
+        mk_process("web", vec![dep]),
+        mk_process("db", vec![]),
+    ])));
+    let readiness = Arc::new(ManualReadiness::default());
+
+    let in_tx = create_event_sender();
+    let in_rx = in_tx.subscribe();
+    let out_tx = create_event_sender();
+    let mut out_rx = out_tx.subscribe();
+    let coordinator = DependencyCoordinator::new(in_rx, out_tx.clone(), Arc::clone(&shared))
+        .with_readiness_checker(readiness.clone());
+    tokio::spawn(coordinator.run_loop());
+
+    // db 进程存在（上报了 ProcessUp，从未处于恢复中）但尚未就绪：web 的恢复应被延迟
+    let _ = in_tx
+        .send(ProcessEvent::ProcessUp {
+            name: "db".to_string(),
+            at: SystemTime::now(),
+        })
+        .await;
+    let _ = in_tx.send(down("web")).await;
+
+    let mut forwarded_early = false;
+    while let Ok(Ok(event)) = timeout(Duration::from_millis(500), out_rx.recv()).await {
+        if event.process_name() == Some("web") {
+            forwarded_early = true;
+        }
+    }
+    assert!(!forwarded_early, "web should wait until db is ready");
+    assert!(readiness.checks.load(Ordering::SeqCst) >= 1);
+
+    // 探针通过后，下一次重试（约 5s）放行
+    readiness.ready.store(true, Ordering::SeqCst);
+    let released = timeout(Duration::from_secs(8), async {
+        loop {
+            if let Ok(ProcessEvent::ProcessDown { name, .. }) = out_rx.recv().await {
+                if name == "web" {
+                    break;
+                }
+            }
+        }
+    })
+    .await;
+    assert!(
+        released.is_ok(),
+        "web should be released once db's readiness probe passes"
+    );
+}
+
+#[tokio::test]
+async fn dependency_without_probe_does_not_block_when_alive() {
+    let shared = Arc::new(RwLock::new(app_config(vec![
+        mk_process("web", vec![RawDependency::Simple("cache".to_string())]),
+        mk_process("cache", vec![]),
+    ])));
+    let in_tx = create_event_sender();
+    let in_rx = in_tx.subscribe();
+    let out_tx = create_event_sender();
+    let mut out_rx = out_tx.subscribe();
+    let coordinator = DependencyCoordinator::new(in_rx, out_tx.clone(), Arc::clone(&shared))
+        .with_readiness_checker(Arc::new(ManualReadiness::default()));
+    tokio::spawn(coordinator.run_loop());
+
+    let _ = in_tx.send(down("web")).await;
+    let event = timeout(Duration::from_secs(1), out_rx.recv())
+        .await
+        .expect("web should be forwarded immediately")
+        .unwrap();
+    assert_eq!(event.process_name(), Some("web"));
+}
+
+// "db" 的探针要很久才返回
+struct SlowReadiness;
+
+#[async_trait]
+impl ReadinessChecker for SlowReadiness {
+    async fn check(&self, process: &str) -> Option<bool> {
+        if process != "db" {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        Some(true)
+    }
+}
+
+#[tokio::test]
+async fn slow_probe_does_not_hold_other_events() {
+    let dep = RawDependency::Detailed(DependencyConfig {
+        target: "db".to_string(),
+        kind: DependencyKind::Requires,
+        hard: true,
+        max_wait_secs: 60,
+        on_failure: OnFailure::Abort,
+    });
+    let shared = Arc::new(RwLock::new(app_config(vec![
+        mk_process("web", vec![dep]),
+        mk_process("db", vec![]),
+        mk_process("api", vec![]),
+    ])));
+    let in_tx = create_event_sender();
+    let in_rx = in_tx.subscribe();
+    let out_tx = create_event_sender();
+    let mut out_rx = out_tx.subscribe();
+    let coordinator = DependencyCoordinator::new(in_rx, out_tx.clone(), Arc::clone(&shared))
+        .with_readiness_checker(Arc::new(SlowReadiness));
+    tokio::spawn(coordinator.run_loop());
+
+    // web 的依赖探测还在进行时，没有依赖的 api 应立即放行
+    let _ = in_tx.send(down("web")).await;
+    let _ = in_tx.send(down("api")).await;
+    let event = timeout(Duration::from_secs(1), out_rx.recv())
+        .await
+        .expect("api should not wait for web's readiness probe")
+        .unwrap();
+    assert_eq!(event.process_name(), Some("api"));
+
+    // 探针通过后 web 随之放行
+    let event = timeout(Duration::from_secs(5), out_rx.recv())
+        .await
+        .expect("web should be forwarded once the probe passes")
+        .unwrap();
+    assert_eq!(event.process_name(), Some("web"));
+}
+
+#[tokio::test]
+async fn http_and_tcp_probes() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await;
+            });
+        }
+    });
+    let client = reqwest::Client::new();
+    let http = |url: String| {
+        ReadinessProbe::Http(HttpReadinessFields {
+            url,
+            timeout_secs: 2,
+        })
+    };
+    let tcp = |address: String| {
+        ReadinessProbe::Tcp(TcpReadinessFields {
+            address,
+            timeout_secs: 2,
+        })
+    };
+    assert!(probe_once(&client, &http(format!("http://{address}/ready"))).await);
+    assert!(probe_once(&client, &tcp(address.to_string())).await);
+
+    let closed = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap()
+    };
+    assert!(!probe_once(&client, &http(format!("http://{closed}/ready"))).await);
+    assert!(!probe_once(&client, &tcp(closed.to_string())).await);
+}
+
+#[test]
+fn readiness_config_parses() {
+    let yaml = |readiness: &str| {
+        format!(
+            r#"
+processes:
+  - name: "db"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "pid", pid_file_path: "/run/db.pid", interval_secs: 3 }}
+    readiness: {readiness}
+"#
+        )
+    };
+    let config = AppConfig::parse(
+        &yaml(r#"{ type: "http", url: "http://127.0.0.1:5432/ready" }"#),
+        ConfigFormat::Yaml,
+    )
+    .expect("readiness probe should parse");
+    match &config.processes[0].readiness {
+        Some(ReadinessProbe::Http(fields)) => assert_eq!(fields.timeout_secs, 2),
+        other => panic!("unexpected readiness: {other:?}"),
+    }
+
+    let err = AppConfig::parse(
+        &yaml(r#"{ type: "tcp", address: "127.0.0.1:5432", timeout_secs: 0 }"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("readiness"), "{err}");
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::subscriber::process_healer::ProcessHealer;
+use healer::subscriber::Subscriber;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// 每次被恢复时向共享文件追加一行，用行数统计实际执行的恢复命令
+fn keyed_process(name: &str, log: &Path, coalesce_key: Option<&str>) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec!["-c".into(), format!("echo {name} >> {}", log.display())],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 10,
+            cooldown_secs: Some(60),
+            coalesce_key: coalesce_key.map(String::from),
+            coalesce_window_secs: 1,
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(
+                log.with_file_name(format!("{name}.pid")),
+            )),
+        )
+    }
+}
+
+async fn healer_for(processes: Vec<ProcessConfig>) -> ProcessHealer {
+    let config = AppConfig {
+        processes,
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+async fn fire_down(healer: &mut ProcessHealer, name: &str) {
+    healer
+        .handle_event(ProcessEvent::ProcessDown {
+            name: name.into(),
+            pid: 0,
+            at: SystemTime::now(),
+        })
+        .await;
+}
+
+async fn restarts(log: &Path) -> Vec<String> {
+    sleep(Duration::from_millis(300)).await;
+    std::fs::read_to_string(log)
+        .unwrap_or_default()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+#[tokio::test]
+async fn shared_key_runs_recovery_once_within_window() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let log = temp_dir.path().join("restarts.log");
+    let mut healer = healer_for(vec![
+        keyed_process("a", &log, Some("shared")),
+        keyed_process("b", &log, Some("shared")),
+        keyed_process("c", &log, Some("shared")),
+        keyed_process("other", &log, Some("other")),
+    ])
+    .await;
+
+    for name in ["a", "b", "c", "other"] {
+        fire_down(&mut healer, name).await;
+    }
+
+    assert_eq!(
+        restarts(&log).await,
+        vec!["a", "other"],
+        "only the first recovery per coalesce key should run"
+    );
+}
+
+#[tokio::test]
+async fn shared_key_runs_again_after_window() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let log = temp_dir.path().join("restarts.log");
+    let mut healer = healer_for(vec![
+        keyed_process("a", &log, Some("shared")),
+        keyed_process("b", &log, Some("shared")),
+    ])
+    .await;
+
+    fire_down(&mut healer, "a").await;
+    sleep(Duration::from_millis(1200)).await;
+    fire_down(&mut healer, "b").await;
+
+    assert_eq!(restarts(&log).await, vec!["a", "b"]);
+}
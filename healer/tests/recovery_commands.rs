@@ -0,0 +1,149 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, CommandConfig, FailureKind, MonitorConfig, ProcessConfig,
+    RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::subscriber::{process_healer::ProcessHealer, Subscriber};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+fn write_marker(marker: &Path) -> CommandConfig {
+    CommandConfig {
+        command: "/bin/sh".into(),
+        args: vec!["-c".into(), format!("echo run >> {}", marker.display())],
+    }
+}
+
+fn process_with_commands(name: &str, dir: &Path) -> ProcessConfig {
+    let default = write_marker(&dir.join("default"));
+    let recovery_commands = HashMap::from([
+        (FailureKind::Down, write_marker(&dir.join("down"))),
+        (
+            FailureKind::Disconnected,
+            write_marker(&dir.join("disconnected")),
+        ),
+        (FailureKind::Resource, write_marker(&dir.join("resource"))),
+    ]);
+    ProcessConfig {
+        command: default.command,
+        args: default.args,
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 10,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        recovery_commands,
+        ..common::process(
+            name,
+            MonitorConfig::Network(common::network_fields("http://127.0.0.1:1/health")),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+async fn wait_for(path: &Path) -> bool {
+    for _ in 0..20 {
+        if path.exists() {
+            return true;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn each_failure_kind_runs_its_own_command() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let dir = temp_dir.path();
+    let name = "by_kind".to_string();
+    let mut healer = healer_for(process_with_commands(&name, dir)).await;
+
+    let cases = [
+        (
+            ProcessEvent::ProcessDown {
+                name: name.clone(),
+                pid: 1,
+                at: SystemTime::now(),
+            },
+            "down",
+        ),
+        (
+            ProcessEvent::ProcessDisconnected {
+                name: name.clone(),
+                url: "http://127.0.0.1:1/health".into(),
+                at: SystemTime::now(),
+            },
+            "disconnected",
+        ),
+        (
+            ProcessEvent::ProcessResourceExceeded {
+                name: name.clone(),
+                pid: 1,
+                at: SystemTime::now(),
+            },
+            "resource",
+        ),
+    ];
+    for (event, marker) in cases {
+        healer.handle_event(event).await;
+        assert!(
+            wait_for(&dir.join(marker)).await,
+            "the '{}' recovery command should have run",
+            marker
+        );
+    }
+
+    for marker in ["down", "disconnected", "resource"] {
+        let content = std::fs::read_to_string(dir.join(marker)).expect("marker should exist");
+        assert_eq!(
+            content.lines().count(),
+            1,
+            "'{}' command should run exactly once",
+            marker
+        );
+    }
+    assert!(
+        !dir.join("default").exists(),
+        "the default command should not run when every kind has its own command"
+    );
+}
+
+#[tokio::test]
+async fn unconfigured_kind_falls_back_to_default_command() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let dir = temp_dir.path();
+    let name = "fallback".to_string();
+    let mut process = process_with_commands(&name, dir);
+    process.recovery_commands.remove(&FailureKind::Disconnected);
+    let mut healer = healer_for(process).await;
+
+    healer
+        .handle_event(ProcessEvent::ProcessDisconnected {
+            name: name.clone(),
+            url: "http://127.0.0.1:1/health".into(),
+            at: SystemTime::now(),
+        })
+        .await;
+
+    assert!(
+        wait_for(&dir.join("default")).await,
+        "kinds without a dedicated command should use command/args"
+    );
+    assert!(!dir.join("disconnected").exists());
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, HookConfig, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+fn guard(exit_code: u8) -> HookConfig {
+    HookConfig {
+        command: "/bin/sh".into(),
+        args: vec!["-c".into(), format!("exit {exit_code}")],
+        timeout_secs: 5,
+    }
+}
+
+// 被恢复的进程只负责创建 marker 文件，用于判断是否发生了 spawn
+fn guarded_process(name: &str, marker: &Path, recovery_guard: HookConfig) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/touch".into(),
+        args: vec![marker.display().to_string()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 1,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        recovery_guard: Some(recovery_guard),
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+async fn wait_for(path: &Path) -> bool {
+    for _ in 0..20 {
+        if path.exists() {
+            return true;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn failing_guard_prevents_spawn() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("spawned");
+    let mut healer = healer_for(guarded_process("guarded", &marker, guard(1))).await;
+
+    healer.heal_process(&"guarded".to_string()).await;
+
+    assert!(
+        !wait_for(&marker).await,
+        "process must not be spawned when the guard exits non-zero"
+    );
+}
+
+#[tokio::test]
+async fn passing_guard_allows_spawn() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("spawned");
+    let mut healer = healer_for(guarded_process("guarded_ok", &marker, guard(0))).await;
+
+    healer.heal_process(&"guarded_ok".to_string()).await;
+
+    assert!(
+        wait_for(&marker).await,
+        "process should be spawned when the guard exits 0"
+    );
+}
+
+#[tokio::test]
+async fn skipped_recoveries_do_not_trip_the_breaker() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("spawned");
+    let name = "guarded_breaker".to_string();
+    let mut healer = healer_for(guarded_process(&name, &marker, guard(1))).await;
+
+    // retries 为 1，若被跳过的恢复也计数，熔断器会在第二次尝试时打开
+    for _ in 0..3 {
+        healer.heal_process(&name).await;
+    }
+    healer.app_config.write().await.processes[0].recovery_guard = Some(guard(0));
+    healer.heal_process(&name).await;
+
+    assert!(
+        wait_for(&marker).await,
+        "guard-skipped recoveries must not count against the circuit breaker"
+    );
+}
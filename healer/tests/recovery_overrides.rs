@@ -0,0 +1,108 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, ConfigFormat, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RecoveryDefaults, RegularHealerFields,
+};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::subscriber::process_healer::ProcessHealer;
+use healer::subscriber::Subscriber;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// 每次被恢复时向共享文件追加一行，用行数统计实际执行的恢复命令
+fn logging_process(name: &str, log: &Path, dedup_window_secs: Option<u64>) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec!["-c".into(), format!("echo {name} >> {}", log.display())],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 10,
+            dedup_window_secs,
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(
+                log.with_file_name(format!("{name}.pid")),
+            )),
+        )
+    }
+}
+
+#[tokio::test]
+async fn dedup_windows_are_independent_per_process() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let log = temp_dir.path().join("restarts.log");
+    let config = AppConfig {
+        processes: vec![
+            // 沿用全局的 30 秒去重窗口
+            logging_process("database", &log, None),
+            // 批处理任务频繁重启是正常的，关闭去重
+            logging_process("batch", &log, Some(0)),
+        ],
+        recovery_defaults: RecoveryDefaults {
+            dedup_window_secs: 30,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await;
+
+    for _ in 0..2 {
+        for name in ["database", "batch"] {
+            healer
+                .handle_event(ProcessEvent::ProcessDown {
+                    name: name.into(),
+                    pid: 0,
+                    at: SystemTime::now(),
+                })
+                .await;
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+
+    let restarts = std::fs::read_to_string(&log).unwrap_or_default();
+    let count = |name: &str| restarts.lines().filter(|line| *line == name).count();
+    assert_eq!(
+        count("database"),
+        1,
+        "second failure should be deduplicated"
+    );
+    assert_eq!(count("batch"), 2, "batch has deduplication disabled");
+}
+
+#[test]
+fn unset_overrides_fall_back_to_global_defaults() {
+    let config = AppConfig::parse(
+        r#"
+recovery_defaults: { cooldown_secs: 600, half_open_window_secs: 30 }
+processes:
+  - name: "web"
+    enabled: true
+    command: "/usr/bin/web"
+    args: []
+    run_as_root: true
+    monitor: { type: "pid", pid_file_path: "/run/web.pid", interval_secs: 3 }
+    recovery: { type: "regular", retries: 3, retry_window_secs: 60, cooldown_secs: 20 }
+"#,
+        ConfigFormat::Yaml,
+    )
+    .expect("config should parse");
+    let RecoveryConfig::Regular(fields) = &config.processes[0].recovery else {
+        panic!("expected a regular recovery");
+    };
+    assert_eq!(
+        fields.anti_flap(&config.recovery_defaults),
+        RecoveryDefaults {
+            dedup_window_secs: 0,
+            cooldown_secs: 20,
+            half_open_window_secs: 30,
+        }
+    );
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::subscriber::child_registry::ChildRegistry;
+use healer::subscriber::process_healer::ProcessHealer;
+use healer::subscriber::Subscriber;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::RwLock;
+
+fn prioritized_process(name: &str, priority: i32) -> ProcessConfig {
+    ProcessConfig {
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retry_window_secs: 30,
+            cooldown_secs: Some(10),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        priority,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(format!("/tmp/{name}.pid"))),
+        )
+    }
+}
+
+fn down(name: &str) -> ProcessEvent {
+    ProcessEvent::ProcessDown {
+        name: name.into(),
+        pid: 0,
+        at: SystemTime::now(),
+    }
+}
+
+#[tokio::test]
+async fn high_priority_recovery_spawns_first() {
+    let config = AppConfig {
+        processes: vec![
+            prioritized_process("best_effort", -5),
+            prioritized_process("database", 10),
+        ],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    let children = ChildRegistry::new();
+    let mut healer = ProcessHealer::new(sender.subscribe_critical(), Arc::new(RwLock::new(config)))
+        .await
+        .with_children(children.clone());
+
+    // 低优先级的故障先到达，但两者在自愈器处理前都已排队
+    sender.send(down("best_effort")).await.unwrap();
+    sender.send(down("database")).await.unwrap();
+
+    let batch = healer.next_batch().await.expect("event channel closed");
+    let order: Vec<_> = batch.iter().filter_map(|e| e.process_name()).collect();
+    assert_eq!(order, vec!["database", "best_effort"]);
+
+    for event in batch {
+        healer.handle_event(event).await;
+    }
+    let database = children
+        .get("database")
+        .expect("database was not restarted");
+    let best_effort = children
+        .get("best_effort")
+        .expect("best_effort was not restarted");
+    assert!(database.started_at < best_effort.started_at);
+}
+
+#[tokio::test]
+async fn events_of_equal_priority_keep_arrival_order() {
+    let config = AppConfig {
+        processes: vec![prioritized_process("a", 0), prioritized_process("b", 0)],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    let mut healer =
+        ProcessHealer::new(sender.subscribe_critical(), Arc::new(RwLock::new(config))).await;
+
+    sender.send(down("b")).await.unwrap();
+    sender
+        .send(ProcessEvent::ProcessUp {
+            name: "b".into(),
+            at: SystemTime::now(),
+        })
+        .await
+        .unwrap();
+    sender.send(down("a")).await.unwrap();
+
+    let batch = healer.next_batch().await.expect("event channel closed");
+    let kinds: Vec<_> = batch
+        .iter()
+        .map(|e| match e {
+            ProcessEvent::ProcessDown { name, .. } => format!("down:{name}"),
+            ProcessEvent::ProcessUp { name, .. } => format!("up:{name}"),
+            other => format!("{other:?}"),
+        })
+        .collect();
+    assert_eq!(kinds, vec!["down:b", "up:b", "down:a"]);
+}
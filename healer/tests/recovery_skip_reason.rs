@@ -0,0 +1,173 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, HookConfig, MonitorConfig, NamespaceKind, NsenterConfig, ProcessConfig,
+    RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+// 被恢复的进程只创建 marker 文件
+fn process(name: &str, marker: &Path) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/touch".into(),
+        args: vec![marker.display().to_string()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 1,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+fn regular(process: &mut ProcessConfig) -> &mut RegularHealerFields {
+    match &mut process.recovery {
+        RecoveryConfig::Regular(fields) => fields,
+        RecoveryConfig::NotRegular(_) => unreachable!(),
+    }
+}
+
+fn guard(script: &str) -> HookConfig {
+    HookConfig {
+        command: "/bin/sh".into(),
+        args: vec!["-c".into(), script.into()],
+        timeout_secs: 5,
+    }
+}
+
+async fn healer_for(processes: Vec<ProcessConfig>) -> ProcessHealer {
+    let config = AppConfig {
+        processes,
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+// 对单个进程执行一次恢复，返回跳过原因
+async fn heal_once(process: ProcessConfig) -> Option<RecoverySkipReason> {
+    let name = process.name.clone();
+    let mut healer = healer_for(vec![process]).await;
+    let reason = healer.heal_process(&name).await;
+    assert_eq!(healer.last_skip_reason(&name), reason.as_ref());
+    reason
+}
+
+#[tokio::test]
+async fn expected_skips_report_their_reason() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("spawned");
+
+    let mut healer = healer_for(vec![process("svc", &marker)]).await;
+    assert_eq!(
+        healer.heal_process(&"ghost".to_string()).await,
+        Some(RecoverySkipReason::MissingConfig)
+    );
+
+    let mut disabled = process("svc", &marker);
+    disabled.enabled = false;
+    assert_eq!(
+        heal_once(disabled).await,
+        Some(RecoverySkipReason::Disabled)
+    );
+
+    let mut refused = process("svc", &marker);
+    refused.recovery_guard = Some(guard("exit 1"));
+    assert_eq!(
+        heal_once(refused).await,
+        Some(RecoverySkipReason::GuardRefused)
+    );
+    assert!(!marker.exists());
+}
+
+#[tokio::test]
+async fn duplicate_and_coalesced_events_are_skipped_after_a_recovery() {
+    let dir = TempDir::new().unwrap();
+    let mut deduped = process("deduped", &dir.path().join("deduped"));
+    regular(&mut deduped).dedup_window_secs = Some(60);
+    let mut first = process("first", &dir.path().join("first"));
+    regular(&mut first).coalesce_key = Some("shared-db".into());
+    let mut second = process("second", &dir.path().join("second"));
+    regular(&mut second).coalesce_key = Some("shared-db".into());
+    let mut healer = healer_for(vec![deduped, first, second]).await;
+
+    let name = "deduped".to_string();
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert_eq!(
+        healer.heal_process(&name).await,
+        Some(RecoverySkipReason::Duplicate)
+    );
+
+    assert_eq!(healer.heal_process(&"first".to_string()).await, None);
+    assert_eq!(
+        healer.heal_process(&"second".to_string()).await,
+        Some(RecoverySkipReason::Coalesced {
+            key: "shared-db".into()
+        })
+    );
+    assert_eq!(healer.last_skip_reason("first"), None);
+}
+
+#[tokio::test]
+async fn failed_recoveries_report_their_reason() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("spawned");
+
+    // retries 为 1：第二次恢复被熔断器拦下
+    let name = "flapping".to_string();
+    let mut healer = healer_for(vec![process(&name, &marker)]).await;
+    assert_eq!(healer.heal_process(&name).await, None);
+    assert!(matches!(
+        healer.heal_process(&name).await,
+        Some(RecoverySkipReason::CircuitOpen { .. })
+    ));
+    assert!(healer.last_skip_reason(&name).unwrap().is_failure());
+
+    let mut hung = process("hung", &marker);
+    hung.recovery_guard = Some(guard("sleep 30"));
+    hung.recovery_timeout_secs = Some(1);
+    assert_eq!(
+        heal_once(hung).await,
+        Some(RecoverySkipReason::GuardTimedOut)
+    );
+
+    let mut no_user = process("no_user", &marker);
+    no_user.run_as_root = false;
+    no_user.forbid_root_fallback = true;
+    assert_eq!(
+        heal_once(no_user).await,
+        Some(RecoverySkipReason::RootRefused)
+    );
+
+    let mut bad_namespace = process("bad_namespace", &marker);
+    bad_namespace.nsenter = Some(NsenterConfig {
+        target_pid: None,
+        namespaces: vec![],
+        paths: BTreeMap::from([(NamespaceKind::Net, dir.path().join("missing-netns"))]),
+    });
+    assert_eq!(
+        heal_once(bad_namespace).await,
+        Some(RecoverySkipReason::CommandUnavailable)
+    );
+}
+
+#[test]
+fn reasons_have_stable_names() {
+    assert_eq!(RecoverySkipReason::Duplicate.as_str(), "duplicate");
+    assert_eq!(
+        RecoverySkipReason::Coalesced { key: "db".into() }.to_string(),
+        "another process sharing coalesce key 'db' was just restarted"
+    );
+    assert!(!RecoverySkipReason::GuardRefused.is_failure());
+    assert!(RecoverySkipReason::RootRefused.is_failure());
+}
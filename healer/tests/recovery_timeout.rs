@@ -0,0 +1,141 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, HookConfig, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// guard 在后台派生一个长时间运行的子进程并记录其 PID，然后一直等待
+fn hanging_guard(child_pid_file: &Path) -> HookConfig {
+    HookConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            format!("sleep 30 & echo $! > {}; wait", child_pid_file.display()),
+        ],
+        timeout_secs: 60,
+    }
+}
+
+fn process(name: &str, marker: &Path, recovery_guard: HookConfig) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/touch".into(),
+        args: vec![marker.display().to_string()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 1,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        recovery_guard: Some(recovery_guard),
+        recovery_timeout_secs: Some(1),
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+// 僵尸进程已经退出，只是尚未被回收
+fn is_running(pid: i32) -> bool {
+    match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        Ok(stat) => stat
+            .rsplit_once(") ")
+            .map(|(_, rest)| !rest.starts_with('Z'))
+            .unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+async fn wait_for(path: &Path) -> bool {
+    for _ in 0..20 {
+        if path.exists() {
+            return true;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn hung_guard_is_killed_with_its_process_group() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("spawned");
+    let child_pid_file = temp_dir.path().join("guard_child.pid");
+    let name = "hung_guard".to_string();
+    let mut healer = healer_for(process(&name, &marker, hanging_guard(&child_pid_file))).await;
+
+    let started = Instant::now();
+    healer.heal_process(&name).await;
+    assert!(
+        started.elapsed() < Duration::from_secs(10),
+        "recovery_timeout_secs should bound the guard, not its own timeout_secs"
+    );
+
+    let child_pid: i32 = std::fs::read_to_string(&child_pid_file)
+        .expect("guard should have recorded its child pid")
+        .trim()
+        .parse()
+        .unwrap();
+    let mut killed = false;
+    for _ in 0..20 {
+        if !is_running(child_pid) {
+            killed = true;
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert!(
+        killed,
+        "processes forked by the hung guard should be killed"
+    );
+    assert!(
+        !marker.exists(),
+        "process must not be spawned after a hung guard"
+    );
+}
+
+#[tokio::test]
+async fn hung_guard_counts_as_a_failed_attempt() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("spawned");
+    let name = "hung_guard_breaker".to_string();
+    let mut healer = healer_for(process(
+        &name,
+        &marker,
+        hanging_guard(&temp_dir.path().join("guard_child.pid")),
+    ))
+    .await;
+
+    healer.heal_process(&name).await;
+
+    // retries 为 1：超时的那次已计入，下一次恢复即使 guard 通过也会被熔断器拦下
+    healer.app_config.write().await.processes[0].recovery_guard = Some(HookConfig {
+        command: "/bin/true".into(),
+        args: vec![],
+        timeout_secs: 5,
+    });
+    healer.heal_process(&name).await;
+
+    assert!(
+        !wait_for(&marker).await,
+        "a timed-out guard should count against the circuit breaker"
+    );
+}
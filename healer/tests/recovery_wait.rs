@@ -0,0 +1,159 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::health::{HealthRegistry, HealthState};
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+// 同步恢复：每次执行都向 marker 追加一行，然后按 script 退出
+fn process(name: &str, marker: &Path, script: &str) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            format!(r#"echo run >> "$1"; {script}"#),
+            "recover".into(),
+            marker.display().to_string(),
+        ],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 2,
+            cooldown_secs: Some(60),
+            dedup_window_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        wait_for_exit: true,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig, health: &HealthRegistry) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_health(health.clone())
+}
+
+fn runs(marker: &Path) -> usize {
+    std::fs::read_to_string(marker)
+        .map(|content| content.lines().count())
+        .unwrap_or(0)
+}
+
+#[tokio::test]
+async fn nonzero_exit_counts_as_a_failed_attempt() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("runs");
+    let health = HealthRegistry::new();
+    let name = "orchestrated".to_string();
+    let mut healer = healer_for(process(&name, &marker, "exit 3"), &health).await;
+
+    // 失败的恢复不进入去重窗口，下一次故障事件立即重试
+    for attempt in 1..=2 {
+        assert_eq!(healer.heal_process(&name).await, None);
+        healer.wait_for_recoveries().await;
+        match healer.last_skip_reason(&name) {
+            Some(RecoverySkipReason::RecoveryFailed { detail }) => {
+                assert!(detail.contains('3'), "unexpected detail: {detail}")
+            }
+            other => panic!("attempt {attempt}: expected a failed recovery, got {other:?}"),
+        }
+        assert_eq!(runs(&marker), attempt);
+        assert_eq!(health.get(&name), HealthState::Down);
+    }
+    // retries 为 2：两次失败后熔断
+    assert!(matches!(
+        healer.heal_process(&name).await,
+        Some(RecoverySkipReason::CircuitOpen { .. })
+    ));
+    assert_eq!(runs(&marker), 2);
+}
+
+#[tokio::test]
+async fn successful_exit_counts_as_recovered() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("runs");
+    let health = HealthRegistry::new();
+    let name = "orchestrated".to_string();
+    let mut healer = healer_for(process(&name, &marker, "exit 0"), &health).await;
+
+    assert_eq!(healer.heal_process(&name).await, None);
+    healer.wait_for_recoveries().await;
+    assert_eq!(healer.last_skip_reason(&name), None);
+    assert_eq!(runs(&marker), 1);
+    assert_eq!(health.get(&name), HealthState::Recovering);
+    assert_eq!(
+        healer.heal_process(&name).await,
+        Some(RecoverySkipReason::Duplicate)
+    );
+}
+
+#[tokio::test]
+async fn hung_recovery_command_times_out() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("runs");
+    let health = HealthRegistry::new();
+    let name = "orchestrated".to_string();
+    let mut process = process(&name, &marker, "exec sleep 30");
+    process.recovery_timeout_secs = Some(1);
+    let mut healer = healer_for(process, &health).await;
+
+    let started = std::time::Instant::now();
+    assert_eq!(healer.heal_process(&name).await, None);
+    healer.wait_for_recoveries().await;
+    assert_eq!(
+        healer.last_skip_reason(&name),
+        Some(&RecoverySkipReason::RecoveryFailed {
+            detail: "timed out after 1s".into()
+        })
+    );
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn running_recovery_command_does_not_hold_other_recoveries() {
+    let dir = TempDir::new().unwrap();
+    let slow_marker = dir.path().join("slow");
+    let fast_marker = dir.path().join("fast");
+    let health = HealthRegistry::new();
+    let config = AppConfig {
+        processes: vec![
+            process("slow", &slow_marker, "exec sleep 2"),
+            process("fast", &fast_marker, "exit 0"),
+        ],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_health(health.clone());
+
+    let started = std::time::Instant::now();
+    assert_eq!(healer.heal_process(&"slow".to_string()).await, None);
+    // 同一进程的故障事件在上一次恢复命令结束前跳过，不再拉起第二个实例
+    assert_eq!(
+        healer.heal_process(&"slow".to_string()).await,
+        Some(RecoverySkipReason::RecoveryInProgress)
+    );
+    assert_eq!(healer.heal_process(&"fast".to_string()).await, None);
+    assert!(started.elapsed() < std::time::Duration::from_secs(2));
+
+    healer.wait_for_recoveries().await;
+    assert_eq!(runs(&slow_marker), 1);
+    assert_eq!(runs(&fast_marker), 1);
+    assert_eq!(healer.last_skip_reason(&"slow".to_string()), None);
+    assert_eq!(health.get("slow"), HealthState::Recovering);
+}
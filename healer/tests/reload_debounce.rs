@@ -0,0 +1,83 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::{Healer, HealerHandle};
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout, Duration, Instant};
+
+async fn start(dir: &Path, reload_debounce_ms: u64) -> HealerHandle {
+    let config_path = dir.join("config.yaml");
+    let source = format!(
+        "log_directory: {:?}\npid_file_directory: {:?}\nreload_debounce_ms: {}\nprocesses: []\n",
+        dir.join("logs"),
+        dir,
+        reload_debounce_ms
+    );
+    std::fs::write(&config_path, &source).unwrap();
+    Healer::builder()
+        .config(AppConfig::parse(&source, ConfigFormat::Yaml).unwrap())
+        .config_path(&config_path)
+        .enable_ebpf(false)
+        .build()
+        .run()
+        .await
+        .expect("healer should start")
+}
+
+async fn reload_times(handle: &HealerHandle, times: usize) {
+    for _ in 0..times {
+        handle
+            .reload()
+            .await
+            .expect("reload request should be sent");
+    }
+}
+
+// 等待重载计数达到 expected
+async fn wait_for_reloads(handle: &HealerHandle, expected: u64) {
+    let stats = handle.reload_stats();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while stats.reloads() < expected {
+        assert!(Instant::now() < deadline, "reload did not happen in time");
+        sleep(Duration::from_millis(20)).await;
+    }
+}
+
+async fn stop(handle: HealerHandle) {
+    timeout(Duration::from_secs(5), handle.shutdown())
+        .await
+        .expect("shutdown timed out")
+        .expect("shutdown should succeed");
+}
+
+#[tokio::test]
+async fn reloads_within_the_window_collapse_into_one() {
+    let dir = TempDir::new().unwrap();
+    let handle = start(dir.path(), 300).await;
+    let stats = handle.reload_stats();
+
+    let requested = Instant::now();
+    reload_times(&handle, 3).await;
+    wait_for_reloads(&handle, 1).await;
+    assert!(requested.elapsed() >= Duration::from_millis(300));
+    // 再等一个窗口，确认没有额外的重载
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(stats.reloads(), 1);
+    assert_eq!(stats.throttled(), 2);
+
+    // 窗口结束后的请求重新计时
+    reload_times(&handle, 1).await;
+    wait_for_reloads(&handle, 2).await;
+    assert_eq!(stats.throttled(), 2);
+    stop(handle).await;
+}
+
+#[tokio::test]
+async fn without_debounce_every_request_reloads() {
+    let dir = TempDir::new().unwrap();
+    let handle = start(dir.path(), 0).await;
+
+    reload_times(&handle, 3).await;
+    wait_for_reloads(&handle, 3).await;
+    assert_eq!(handle.reload_stats().throttled(), 0);
+    stop(handle).await;
+}
@@ -0,0 +1,127 @@
+// 未启用 systemd feature 时 systemd 监控器一定无法启动，以此构造“新监控器启动失败”的重载
+#![cfg(not(feature = "systemd"))]
+
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+    SystemdMonitorFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::monitor_manager::MonitorManager;
+use healer::Healer;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout, Duration};
+
+fn process(name: &str, monitor: MonitorConfig) -> ProcessConfig {
+    ProcessConfig {
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            cooldown_secs: Some(30),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(name, monitor)
+    }
+}
+
+fn pid_process(name: &str, dir: &Path) -> ProcessConfig {
+    process(
+        name,
+        MonitorConfig::Pid(common::pid_fields(dir.join(format!("{name}.pid")))),
+    )
+}
+
+fn systemd_process(name: &str) -> ProcessConfig {
+    process(
+        name,
+        MonitorConfig::Systemd(SystemdMonitorFields {
+            unit: format!("{name}.service"),
+            interval_secs: 1,
+            check_timeout_secs: None,
+        }),
+    )
+}
+
+#[tokio::test]
+async fn failed_plan_keeps_running_monitors() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let mut manager = MonitorManager::new_without_ebpf(create_event_sender());
+    manager
+        .reconcile(&[pid_process("svc", temp_dir.path())])
+        .await
+        .expect("initial reconcile should succeed");
+
+    // 新配置移除 svc 并加入无法启动的 systemd 监控器
+    let staged = vec![systemd_process("unit")];
+    assert!(manager.plan(&staged).await.is_err());
+    assert_eq!(manager.running_monitor_names(), vec!["svc".to_string()]);
+
+    // 可以启动的新配置照常生效
+    let plan = manager
+        .plan(&[pid_process("other", temp_dir.path())])
+        .await
+        .expect("plan should succeed");
+    assert_eq!(manager.running_monitor_names(), vec!["svc".to_string()]);
+    manager.apply(plan).await.expect("apply should succeed");
+    assert_eq!(manager.running_monitor_names(), vec!["other".to_string()]);
+
+    manager.shutdown().await;
+}
+
+#[tokio::test]
+async fn reload_with_unstartable_monitor_keeps_old_config() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let config_path = temp_dir.path().join("config.yaml");
+    std::fs::write(
+        &config_path,
+        r#"
+processes:
+  - name: "unit"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: { type: "systemd", unit: "unit.service", interval_secs: 1 }
+    recovery: { type: "regular", retries: 3, retry_window_secs: 60, cooldown_secs: 30 }
+"#,
+    )
+    .expect("failed to write config");
+
+    let config = AppConfig {
+        log_directory: Some(temp_dir.path().join("logs")),
+        pid_file_directory: Some(temp_dir.path().join("pids")),
+        processes: vec![pid_process("svc", temp_dir.path())],
+        working_directory: Some(temp_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let handle = Healer::builder()
+        .config(config)
+        .config_path(&config_path)
+        .enable_ebpf(false)
+        .build()
+        .run()
+        .await
+        .expect("healer should start");
+
+    handle
+        .reload()
+        .await
+        .expect("reload request should be sent");
+    sleep(Duration::from_millis(500)).await;
+
+    let names: Vec<String> = handle
+        .config()
+        .read()
+        .await
+        .processes
+        .iter()
+        .map(|p| p.name.clone())
+        .collect();
+    assert_eq!(names, vec!["svc".to_string()]);
+
+    timeout(Duration::from_secs(5), handle.shutdown())
+        .await
+        .expect("shutdown timed out")
+        .expect("shutdown should succeed");
+}
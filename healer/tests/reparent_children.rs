@@ -0,0 +1,132 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, ConfigFormat, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::health::HealthState;
+use healer::{Healer, HealerHandle};
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout, Duration, Instant};
+
+// 记录每次启动并写入 PID 文件
+const SERVICE: &str = r#"echo $$ >> "$1"; echo $$ > "$2"; exec sleep 30"#;
+
+fn service(base: &Path) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            SERVICE.into(),
+            "svc".into(),
+            base.join("starts").display().to_string(),
+            base.join("svc.pid").display().to_string(),
+        ],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 5,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            "svc",
+            MonitorConfig::Pid(common::pid_fields(base.join("svc.pid"))),
+        )
+    }
+}
+
+async fn start(base: &Path) -> HealerHandle {
+    let config = AppConfig {
+        log_directory: Some(base.join("logs")),
+        pid_file_directory: Some(base.join("pids")),
+        processes: vec![service(base)],
+        working_directory: Some(base.to_path_buf()),
+        reparent_children: true,
+        ..Default::default()
+    };
+    Healer::builder()
+        .config(config)
+        .enable_ebpf(false)
+        .build()
+        .run()
+        .await
+        .expect("healer should start")
+}
+
+async fn stop(handle: HealerHandle) {
+    timeout(Duration::from_secs(5), handle.shutdown())
+        .await
+        .expect("shutdown should complete in time")
+        .expect("shutdown should be clean");
+}
+
+fn starts(base: &Path) -> Vec<i32> {
+    std::fs::read_to_string(base.join("starts"))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect()
+}
+
+async fn wait_for_starts(base: &Path, count: usize) -> Vec<i32> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let pids = starts(base);
+        if pids.len() >= count {
+            return pids;
+        }
+        assert!(Instant::now() < deadline, "service was not started in time");
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
+fn is_alive(pid: i32) -> bool {
+    kill(Pid::from_raw(pid), None).is_ok()
+}
+
+// /proc/<pid>/stat 的第 4 个字段；comm 可能含空格，从最后一个 ')' 之后开始解析
+fn parent_of(pid: i32) -> u32 {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap();
+    let after_comm = &stat[stat.rfind(')').unwrap() + 2..];
+    after_comm.split(' ').nth(1).unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn reparented_child_survives_healer_restart_and_is_monitored_again() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let base = temp_dir.path();
+
+    let handle = start(base).await;
+    let pid = wait_for_starts(base, 1).await[0];
+    // 经过两次 fork，被拉起的进程不再是 healer（即测试进程）的子进程
+    assert_ne!(parent_of(pid), std::process::id());
+    stop(handle).await;
+    assert!(is_alive(pid), "child should outlive the healer");
+
+    // 新的 healer 沿用仍在运行的进程，而不是再拉起一个
+    let handle = start(base).await;
+    let health = handle.health();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while health.get("svc") != HealthState::Healthy {
+        assert!(Instant::now() < deadline, "service was not seen as healthy");
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(starts(base), [pid]);
+
+    // 进程退出后照常由新的 healer 恢复
+    kill(Pid::from_raw(pid), Signal::SIGKILL).unwrap();
+    let pids = wait_for_starts(base, 2).await;
+    stop(handle).await;
+    for pid in pids {
+        let _ = kill(Pid::from_raw(pid), Signal::SIGKILL);
+    }
+}
+
+#[test]
+fn reparent_children_defaults_to_off() {
+    let config = AppConfig::parse("processes: []\n", ConfigFormat::Yaml).unwrap();
+    assert!(!config.reparent_children);
+}
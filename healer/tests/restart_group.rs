@@ -0,0 +1,94 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, ConfigFormat, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields, RestartGroupConfig,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// 每次被恢复时向共享文件追加一行，用行数统计重启次数
+fn grouped_process(name: &str, log: &Path) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec!["-c".into(), format!("echo {name} >> {}", log.display())],
+        // 自身预算很宽松，只有组预算会生效
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 10,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        restart_group: Some("backend".into()),
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(
+                log.with_file_name(format!("{name}.pid")),
+            )),
+        )
+    }
+}
+
+#[tokio::test]
+async fn grouped_processes_share_one_restart_budget() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let log = temp_dir.path().join("restarts.log");
+    let config = AppConfig {
+        processes: vec![
+            grouped_process("api", &log),
+            grouped_process("worker", &log),
+        ],
+        restart_groups: vec![RestartGroupConfig {
+            name: "backend".into(),
+            retries: 3,
+            retry_window_secs: 60,
+            cooldown_secs: 60,
+        }],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await;
+
+    // 两个成员交替失败，共 3 次后组预算耗尽，之后两者都不再重启
+    for name in ["api", "worker", "api", "worker", "api", "worker"] {
+        healer.heal_process(&name.to_string()).await;
+        sleep(Duration::from_millis(100)).await;
+    }
+    sleep(Duration::from_millis(300)).await;
+
+    let restarts = std::fs::read_to_string(&log).unwrap_or_default();
+    let restarts: Vec<&str> = restarts.lines().collect();
+    assert_eq!(
+        restarts,
+        vec!["api", "worker", "api"],
+        "the group budget of 3 should be shared by both members"
+    );
+}
+
+#[test]
+fn unknown_restart_group_is_rejected() {
+    let yaml = r#"
+restart_groups:
+  - name: "backend"
+    retries: 3
+    retry_window_secs: 60
+    cooldown_secs: 180
+processes:
+  - name: "api"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    restart_group: "bakend"
+    monitor: { type: "pid", pid_file_path: "/run/api.pid", interval_secs: 5 }
+"#;
+    let err = AppConfig::parse(yaml, ConfigFormat::Yaml).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("'api'"), "{message}");
+    assert!(message.contains("'bakend'"), "{message}");
+}
@@ -0,0 +1,148 @@
+use healer::config::{AppConfig, ConfigFormat, RecoveryAction};
+use healer::config_graph::{subtree_restart_order, SubtreeError};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+// 每个进程的恢复命令把自己的名字追加到 log，wait_for_exit 保证上一个命令结束后才重启下一个依赖方
+fn process_yaml(name: &str, depends_on: &[&str], log: &Path, extra: &str) -> String {
+    format!(
+        r#"
+  - name: "{name}"
+    enabled: true
+    command: "/bin/sh"
+    args: ["-c", "echo {name} >> {log}"]
+    run_as_root: true
+    child_output: "null"
+    wait_for_exit: true
+    monitor: {{ type: "pid", pid_file_path: "/nonexistent/{name}.pid", interval_secs: 60 }}
+    dependencies: [{deps}]
+{extra}"#,
+        log = log.display(),
+        deps = depends_on
+            .iter()
+            .map(|d| format!("\"{d}\""))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+const SUBTREE: &str = "    recovery_action: { type: \"restart_subtree\" }\n";
+
+fn parse(processes: &[String]) -> AppConfig {
+    let yaml = format!("processes:{}", processes.concat());
+    AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap()
+}
+
+#[tokio::test]
+async fn root_failure_restarts_dependents_in_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let log = temp_dir.path().join("order.log");
+    // 配置顺序与依赖顺序相反，验证按拓扑顺序而不是配置顺序重启
+    let config = parse(&[
+        process_yaml("c", &["b"], &log, ""),
+        process_yaml("b", &["a"], &log, ""),
+        process_yaml("a", &[], &log, SUBTREE),
+    ]);
+    assert_eq!(
+        config.processes[2].recovery_action,
+        RecoveryAction::RestartSubtree {
+            max_dependents: 16,
+            stop_timeout_secs: 10
+        }
+    );
+
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await;
+    assert_eq!(healer.heal_process(&"a".to_string()).await, None);
+    healer.wait_for_recoveries().await;
+
+    let order = std::fs::read_to_string(&log).unwrap();
+    assert_eq!(order.lines().collect::<Vec<_>>(), ["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn dependents_are_not_restarted_without_restart_subtree() {
+    let temp_dir = TempDir::new().unwrap();
+    let log = temp_dir.path().join("order.log");
+    let config = parse(&[
+        process_yaml("a", &[], &log, ""),
+        process_yaml("b", &["a"], &log, ""),
+    ]);
+
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await;
+    assert_eq!(healer.heal_process(&"a".to_string()).await, None);
+    healer.wait_for_recoveries().await;
+
+    assert_eq!(std::fs::read_to_string(&log).unwrap(), "a\n");
+}
+
+#[test]
+fn restart_order_follows_the_dependency_graph() {
+    let log = Path::new("/dev/null");
+    // 菱形依赖：d 依赖 b 与 c，b、c 都依赖 a；e 被禁用
+    let mut config = parse(&[
+        process_yaml("d", &["b", "c"], log, ""),
+        process_yaml("c", &["a"], log, ""),
+        process_yaml("b", &["a"], log, ""),
+        process_yaml("a", &[], log, ""),
+        process_yaml("e", &["a"], log, ""),
+    ]);
+    config.processes[4].enabled = false;
+
+    assert_eq!(
+        subtree_restart_order(&config, "a", 16).unwrap(),
+        ["c", "b", "d"]
+    );
+    assert_eq!(subtree_restart_order(&config, "b", 16).unwrap(), ["d"]);
+    assert!(subtree_restart_order(&config, "d", 16).unwrap().is_empty());
+    assert_eq!(
+        subtree_restart_order(&config, "a", 2),
+        Err(SubtreeError::TooLarge { count: 3, max: 2 })
+    );
+}
+
+#[test]
+fn cycles_are_refused() {
+    let log = Path::new("/dev/null");
+    let config = parse(&[
+        process_yaml("a", &["c"], log, ""),
+        process_yaml("b", &["a"], log, ""),
+        process_yaml("c", &["b"], log, ""),
+    ]);
+    assert!(matches!(
+        subtree_restart_order(&config, "a", 16),
+        Err(SubtreeError::Cycle(_))
+    ));
+
+    // 子树内部成环（root 不在环上）
+    let config = parse(&[
+        process_yaml("root", &[], log, ""),
+        process_yaml("x", &["root", "y"], log, ""),
+        process_yaml("y", &["x"], log, ""),
+    ]);
+    assert_eq!(
+        subtree_restart_order(&config, "root", 16),
+        Err(SubtreeError::Cycle(vec!["x".into(), "y".into()]))
+    );
+}
+
+#[test]
+fn zero_max_dependents_is_rejected() {
+    let log = Path::new("/dev/null");
+    let yaml = format!(
+        "processes:{}",
+        process_yaml(
+            "a",
+            &[],
+            log,
+            "    recovery_action: { type: \"restart_subtree\", max_dependents: 0 }\n"
+        )
+    );
+    let err = AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap_err();
+    assert!(err.to_string().contains("max_dependents"), "{err}");
+}
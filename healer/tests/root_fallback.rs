@@ -0,0 +1,117 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// run_as_root 为 false 且没有 run_as_user，只能回退为 root 运行
+fn unprivileged_process(name: &str, marker: &Path, forbid_root_fallback: bool) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/touch".into(),
+        args: vec![marker.display().to_string()],
+        run_as_root: false,
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 1,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        forbid_root_fallback,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig, global_forbid: bool) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        forbid_root_fallback: global_forbid,
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+async fn wait_for(path: &Path) -> bool {
+    for _ in 0..20 {
+        if path.exists() {
+            return true;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn missing_user_is_refused_when_fallback_forbidden() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("spawned");
+    let name = "no_root_fallback".to_string();
+    let mut healer = healer_for(unprivileged_process(&name, &marker, true), false).await;
+
+    healer.heal_process(&name).await;
+
+    assert!(
+        !wait_for(&marker).await,
+        "no child should be spawned when the user is missing and root fallback is forbidden"
+    );
+}
+
+#[tokio::test]
+async fn unknown_user_is_refused_by_global_switch() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("spawned");
+    let name = "global_no_root_fallback".to_string();
+    let mut process = unprivileged_process(&name, &marker, false);
+    process.run_as_user = Some("healer-test-no-such-user".into());
+    let mut healer = healer_for(process, true).await;
+
+    healer.heal_process(&name).await;
+
+    assert!(
+        !wait_for(&marker).await,
+        "the AppConfig-wide switch should forbid the root fallback as well"
+    );
+}
+
+#[tokio::test]
+async fn fallback_still_allowed_by_default() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("spawned");
+    let name = "root_fallback_allowed".to_string();
+    let mut healer = healer_for(unprivileged_process(&name, &marker, false), false).await;
+
+    healer.heal_process(&name).await;
+
+    assert!(
+        wait_for(&marker).await,
+        "without the switch the previous fallback behaviour is kept"
+    );
+}
+
+#[tokio::test]
+async fn refused_recovery_counts_against_the_breaker() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let marker = temp_dir.path().join("spawned");
+    let name = "refused_breaker".to_string();
+    let mut healer = healer_for(unprivileged_process(&name, &marker, true), false).await;
+
+    // retries 为 1：被拒绝的一次恢复应计入熔断，之后即使修正配置也处于熔断中
+    healer.heal_process(&name).await;
+    healer.app_config.write().await.processes[0].run_as_root = true;
+    healer.heal_process(&name).await;
+
+    assert!(
+        !wait_for(&marker).await,
+        "a refused recovery should be counted as a failed attempt"
+    );
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, PidMonitorFields, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::control::ControlState;
+use healer::event_bus::create_event_sender;
+use healer::monitor_manager::MonitorManager;
+use healer::signal_handler::SignalEvent;
+use healer::subscriber::{event_history::EventHistory, process_healer::ProcessHealer};
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{sleep, timeout, Duration};
+
+fn pid_process(name: &str, dir: &Path) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            format!("echo run >> {}", dir.join("recovered").display()),
+        ],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(PidMonitorFields {
+                interval_secs: 60,
+                ..common::pid_fields(dir.join(format!("{name}.pid")))
+            }),
+        )
+    }
+}
+
+async fn reconcile_from(manager: &mut MonitorManager, config: &Arc<RwLock<AppConfig>>) {
+    let processes = config.read().await.processes.clone();
+    manager.reconcile(&processes).await.expect("reconcile");
+}
+
+async fn expect_reconcile(runtime_rx: &mut mpsc::Receiver<SignalEvent>) {
+    let event = timeout(Duration::from_secs(1), runtime_rx.recv())
+        .await
+        .expect("reconcile request in time");
+    assert!(
+        matches!(event, Some(SignalEvent::Reconcile)),
+        "got {event:?}"
+    );
+}
+
+#[tokio::test]
+async fn disabled_process_stops_monitoring_and_recovery() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let dir = temp_dir.path();
+    let config = Arc::new(RwLock::new(AppConfig {
+        processes: vec![pid_process("svc", dir)],
+        ..Default::default()
+    }));
+    let (runtime_tx, mut runtime_rx) = mpsc::channel(8);
+    let state = ControlState {
+        history: EventHistory::new(8),
+        config: Arc::clone(&config),
+        runtime_tx,
+        children: Default::default(),
+        event_tx: create_event_sender(),
+        health: Default::default(),
+    };
+    let mut manager = MonitorManager::new_without_ebpf(create_event_sender());
+    reconcile_from(&mut manager, &config).await;
+    assert_eq!(manager.running_monitor_names(), vec!["svc".to_string()]);
+
+    let response = state
+        .handle_line(r#"{"cmd":"disable","process":"svc"}"#)
+        .await;
+    assert_eq!(response["ok"], true, "{response}");
+    assert_eq!(response["enabled"], false);
+    expect_reconcile(&mut runtime_rx).await;
+    reconcile_from(&mut manager, &config).await;
+    assert!(
+        manager.running_monitor_names().is_empty(),
+        "monitor should stop once the process is disabled"
+    );
+
+    // 停用后即使收到故障事件也不再恢复
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(sender.subscribe(), Arc::clone(&config)).await;
+    healer.heal_process(&"svc".to_string()).await;
+    sleep(Duration::from_millis(300)).await;
+    assert!(
+        !dir.join("recovered").exists(),
+        "disabled process must not be recovered"
+    );
+
+    let response = state
+        .handle_line(r#"{"cmd":"enable","process":"svc"}"#)
+        .await;
+    assert_eq!(response["ok"], true, "{response}");
+    expect_reconcile(&mut runtime_rx).await;
+    reconcile_from(&mut manager, &config).await;
+    assert_eq!(manager.running_monitor_names(), vec!["svc".to_string()]);
+
+    let unknown = state
+        .handle_line(r#"{"cmd":"disable","process":"missing"}"#)
+        .await;
+    assert_eq!(unknown["ok"], false);
+
+    manager.shutdown().await;
+}
@@ -0,0 +1,30 @@
+use healer::config::AppConfig;
+use healer::core_logic::build_runtime;
+use tempfile::TempDir;
+
+#[test]
+fn runtime_uses_requested_worker_threads() {
+    let rt = build_runtime(3).expect("failed to build runtime");
+    assert_eq!(rt.metrics().num_workers(), 3);
+}
+
+#[test]
+fn worker_threads_default_to_cpu_count() {
+    let config: AppConfig = serde_yaml::from_str("processes: []").unwrap();
+    let cpus = std::thread::available_parallelism().unwrap().get();
+    assert_eq!(config.worker_threads(), cpus);
+
+    let config: AppConfig =
+        serde_yaml::from_str("processes: []\nruntime_worker_threads: 2\n").unwrap();
+    assert_eq!(config.worker_threads(), 2);
+}
+
+#[test]
+fn zero_worker_threads_is_rejected() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let path = temp_dir.path().join("healer.yaml");
+    std::fs::write(&path, "processes: []\nruntime_worker_threads: 0\n").unwrap();
+
+    let err = AppConfig::load_from_file(&path).expect_err("0 worker threads should be rejected");
+    assert!(err.to_string().contains("runtime_worker_threads"));
+}
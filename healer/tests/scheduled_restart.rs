@@ -0,0 +1,138 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::restart_scheduler::RestartScheduler;
+use healer::subscriber::child_registry::ChildRegistry;
+use nix::sys::signal::Signal;
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::process::Command;
+use tokio::sync::RwLock;
+use tokio::time::{timeout, Duration, Instant};
+
+fn config_with_schedule(schedule: &str) -> Result<AppConfig, healer::config::ConfigError> {
+    config_with_pid_file(schedule, Path::new("/run/leaky.pid"))
+}
+
+fn config_with_pid_file(
+    schedule: &str,
+    pid_file: &Path,
+) -> Result<AppConfig, healer::config::ConfigError> {
+    AppConfig::parse(
+        &format!(
+            r#"
+processes:
+  - name: "leaky"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "pid", pid_file_path: "{pid_file}", interval_secs: 3 }}
+    scheduled_restart: {schedule}
+"#,
+            pid_file = pid_file.display()
+        ),
+        ConfigFormat::Yaml,
+    )
+}
+
+#[tokio::test]
+async fn frequent_schedule_restarts_on_time_and_stops_the_old_instance() {
+    let config = config_with_schedule(r#"{ cron: "* * * * * *", stop_timeout_secs: 1 }"#)
+        .expect("schedule should parse");
+    let children = ChildRegistry::new();
+    let old = children
+        .track("leaky", Command::new("sleep").arg("30").spawn().unwrap())
+        .expect("child should be tracked");
+
+    let sender = create_event_sender();
+    let mut rx = sender.subscribe();
+    let task = RestartScheduler::spawn(
+        sender.clone(),
+        Arc::new(RwLock::new(config)),
+        children.clone(),
+    );
+
+    let mut fired = Vec::new();
+    for _ in 0..2 {
+        let event = timeout(Duration::from_secs(3), rx.recv())
+            .await
+            .expect("scheduled restart should fire every second")
+            .unwrap();
+        match event {
+            ProcessEvent::ProcessDown { name, pid, .. } => {
+                assert_eq!(name, "leaky");
+                fired.push((Instant::now(), pid));
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+    task.abort();
+
+    // 第一次触发终止了仍在运行的旧实例，第二次已没有需要终止的实例
+    assert_eq!(fired[0].1, old.pid);
+    assert!(
+        old.exit().is_some(),
+        "old instance should have been stopped"
+    );
+    assert_eq!(fired[1].1, 0);
+    let gap = fired[1].0 - fired[0].0;
+    assert!(
+        gap >= Duration::from_millis(500) && gap <= Duration::from_millis(1800),
+        "restarts should follow the schedule, gap was {gap:?}"
+    );
+}
+
+#[tokio::test]
+async fn instance_not_started_by_healer_is_stopped_through_the_pid_file() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let pid_file = temp_dir.path().join("leaky.pid");
+    let mut old = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let old_pid = old.id();
+    std::fs::write(&pid_file, old_pid.to_string()).unwrap();
+    // 由单独的线程等待，旧实例退出后立即被回收
+    let exited = std::thread::spawn(move || old.wait().unwrap());
+    let config = config_with_pid_file(
+        r#"{ cron: "* * * * * *", stop_timeout_secs: 1 }"#,
+        &pid_file,
+    )
+    .expect("schedule should parse");
+
+    let sender = create_event_sender();
+    let mut rx = sender.subscribe();
+    let task = RestartScheduler::spawn(
+        sender.clone(),
+        Arc::new(RwLock::new(config)),
+        ChildRegistry::new(),
+    );
+    let event = timeout(Duration::from_secs(3), rx.recv())
+        .await
+        .expect("scheduled restart should fire every second")
+        .unwrap();
+    task.abort();
+
+    match event {
+        ProcessEvent::ProcessDown { pid, .. } => assert_eq!(pid, old_pid),
+        other => panic!("unexpected event {other:?}"),
+    }
+    let status = exited.join().unwrap();
+    assert_eq!(status.signal(), Some(Signal::SIGTERM as i32));
+}
+
+#[test]
+fn cron_expression_is_validated() {
+    let config = config_with_schedule(r#"{ cron: "0 0 4 * * *" }"#).expect("valid cron");
+    let schedule = config.processes[0].scheduled_restart.as_ref().unwrap();
+    assert_eq!(schedule.stop_timeout_secs, 10);
+
+    let now = chrono::Local::now();
+    let next = RestartScheduler::next_fire(&schedule.cron, now).unwrap();
+    assert!(next > now && next - now <= chrono::Duration::days(1));
+
+    let err = config_with_schedule(r#"{ cron: "every day" }"#).unwrap_err();
+    assert!(err.to_string().contains("scheduled_restart"), "{err}");
+}
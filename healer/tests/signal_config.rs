@@ -0,0 +1,100 @@
+use healer::config::{AppConfig, ConfigFormat, SignalConfig, SignalName};
+use healer::signal_handler::{SignalEvent, SignalHandler};
+use nix::sys::signal::{raise, Signal};
+use tokio::time::{timeout, Duration};
+
+fn config_with_signals(signals: &str) -> String {
+    format!(
+        r#"
+processes: []
+signals: {signals}
+"#
+    )
+}
+
+fn name(name: &str) -> SignalName {
+    name.parse().expect("signal name should parse")
+}
+
+#[test]
+fn signal_names_accept_common_spellings() {
+    assert_eq!(name("SIGUSR1").signal(), Signal::SIGUSR1);
+    assert_eq!(name("USR1").signal(), Signal::SIGUSR1);
+    assert_eq!(name("hup").signal(), Signal::SIGHUP);
+    assert_eq!(name("sigterm").to_string(), "SIGTERM");
+    assert!("SIGNOPE".parse::<SignalName>().is_err());
+}
+
+#[test]
+fn defaults_are_unchanged() {
+    let config = AppConfig::parse("processes: []", ConfigFormat::Yaml).unwrap();
+    assert_eq!(config.signals.reload, vec![name("SIGHUP")]);
+    assert_eq!(
+        config.signals.shutdown,
+        vec![name("SIGTERM"), name("SIGINT")]
+    );
+}
+
+#[test]
+fn custom_reload_signal_parses() {
+    let config = AppConfig::parse(
+        &config_with_signals(r#"{ reload: ["SIGUSR1"] }"#),
+        ConfigFormat::Yaml,
+    )
+    .expect("custom reload signal should parse");
+    assert_eq!(config.signals.reload, vec![name("SIGUSR1")]);
+    // 未设置的一侧保持默认
+    assert_eq!(
+        config.signals.shutdown,
+        vec![name("SIGTERM"), name("SIGINT")]
+    );
+}
+
+#[test]
+fn reload_and_shutdown_must_differ() {
+    let err = AppConfig::parse(
+        &config_with_signals(r#"{ reload: ["SIGTERM"] }"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("both reload and shutdown"),
+        "{err}"
+    );
+
+    let err = AppConfig::parse(
+        &config_with_signals(r#"{ reload: ["SIGKILL"] }"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("SIGKILL"), "{err}");
+
+    assert!(AppConfig::parse(
+        &config_with_signals(r#"{ reload: ["SIGWHAT"] }"#),
+        ConfigFormat::Yaml,
+    )
+    .is_err());
+}
+
+#[tokio::test]
+async fn configured_signals_map_to_events() {
+    let mut handler = SignalHandler::register(&SignalConfig {
+        reload: vec![name("SIGUSR1")],
+        shutdown: vec![name("SIGUSR2")],
+    })
+    .expect("signals should register");
+
+    raise(Signal::SIGUSR1).unwrap();
+    let event = timeout(Duration::from_secs(2), handler.wait_for_signal())
+        .await
+        .expect("SIGUSR1 should be received")
+        .unwrap();
+    assert!(matches!(event, SignalEvent::ConfigReload), "{event:?}");
+
+    raise(Signal::SIGUSR2).unwrap();
+    let event = timeout(Duration::from_secs(2), handler.wait_for_signal())
+        .await
+        .expect("SIGUSR2 should be received")
+        .unwrap();
+    assert!(matches!(event, SignalEvent::Shutdown), "{event:?}");
+}
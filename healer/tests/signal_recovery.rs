@@ -0,0 +1,160 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, ConfigFormat, MonitorConfig, ProcessConfig, RecoveryAction,
+    RecoveryConfig, RegularHealerFields, SignalPidSource,
+};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+// 记录收到的信号，不会因 HUP / USR2 退出
+const TRAP_SCRIPT: &str = r#"
+trap 'echo HUP >> "$1"' HUP
+trap 'echo USR2 >> "$1"' USR2
+while :; do sleep 0.1; done
+"#;
+
+fn spawn_trap_handler(record: &Path) -> Child {
+    Command::new("/bin/sh")
+        .args(["-c", TRAP_SCRIPT, "trap-handler"])
+        .arg(record)
+        .spawn()
+        .expect("failed to spawn trap handler")
+}
+
+fn process(name: &str, marker: &Path, action: RecoveryAction) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/touch".into(),
+        args: vec![marker.display().to_string()],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retries: 1,
+            cooldown_secs: Some(60),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        recovery_action: action,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(marker.with_extension("pid"))),
+        )
+    }
+}
+
+async fn healer_for(process: ProcessConfig) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+fn signal_action(signal: &str, pid_file: PathBuf) -> RecoveryAction {
+    RecoveryAction::Signal {
+        signal: signal.parse().unwrap(),
+        pid_source: SignalPidSource::PidFile { path: pid_file },
+    }
+}
+
+async fn read_record(record: &Path) -> String {
+    for _ in 0..30 {
+        if let Ok(content) = std::fs::read_to_string(record) {
+            if !content.is_empty() {
+                return content;
+            }
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    String::new()
+}
+
+#[tokio::test]
+async fn signal_recovery_delivers_the_configured_signal_instead_of_spawning() {
+    let dir = TempDir::new().unwrap();
+    let record = dir.path().join("received");
+    let marker = dir.path().join("spawned");
+    let pid_file = dir.path().join("service.pid");
+    let mut handler = spawn_trap_handler(&record);
+    std::fs::write(&pid_file, handler.id().to_string()).unwrap();
+    // 等待 shell 安装好 trap
+    sleep(Duration::from_millis(300)).await;
+
+    let name = "reloadable".to_string();
+    let mut healer = healer_for(process(&name, &marker, signal_action("usr2", pid_file))).await;
+    assert_eq!(healer.heal_process(&name).await, None);
+
+    let received = read_record(&record).await;
+    handler.kill().unwrap();
+    handler.wait().unwrap();
+    assert_eq!(received.trim(), "USR2");
+    assert!(
+        !marker.exists(),
+        "signal recovery must not spawn the command"
+    );
+}
+
+#[tokio::test]
+async fn missing_target_counts_as_a_failed_attempt() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("spawned");
+    let name = "gone".to_string();
+    let mut healer = healer_for(process(
+        &name,
+        &marker,
+        signal_action("SIGHUP", dir.path().join("missing.pid")),
+    ))
+    .await;
+
+    assert_eq!(healer.heal_process(&name).await, None);
+    // retries 为 1：找不到进程的那次已计入熔断器
+    assert!(matches!(
+        healer.heal_process(&name).await,
+        Some(RecoverySkipReason::CircuitOpen { .. })
+    ));
+    assert!(!marker.exists());
+}
+
+#[test]
+fn recovery_action_is_parsed() {
+    let yaml = |action: &str| {
+        format!(
+            r#"
+processes:
+  - name: "web"
+    enabled: true
+    command: "/usr/sbin/nginx"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "pid", pid_file_path: "/run/nginx.pid", interval_secs: 5 }}
+    {action}
+"#
+        )
+    };
+    let config = AppConfig::parse(&yaml(""), ConfigFormat::Yaml).unwrap();
+    assert_eq!(config.processes[0].recovery_action, RecoveryAction::Spawn);
+
+    let config = AppConfig::parse(
+        &yaml(r#"recovery_action: { type: "signal", signal: "HUP", pid_source: { type: "exe_scan" } }"#),
+        ConfigFormat::Yaml,
+    )
+    .unwrap();
+    assert_eq!(
+        config.processes[0].recovery_action,
+        RecoveryAction::Signal {
+            signal: "SIGHUP".parse().unwrap(),
+            pid_source: SignalPidSource::ExeScan { exe: None },
+        }
+    );
+
+    assert!(AppConfig::parse(
+        &yaml(r#"recovery_action: { type: "signal", signal: "NOPE", pid_source: { type: "pid_file", path: "/run/a.pid" } }"#),
+        ConfigFormat::Yaml,
+    )
+    .is_err());
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, ControlConfig, MonitorConfig, ProcessConfig, RecoveryConfig,
+    RegularHealerFields,
+};
+use healer::control::ControlState;
+use healer::coordinator::dependency_coordinator::DependencyCoordinator;
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::subscriber::event_history::EventHistory;
+use healer::subscriber::process_healer::ProcessHealer;
+use healer::subscriber::Subscriber;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{sleep, timeout, Duration};
+
+fn marker_process(name: &str, marker: &Path) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            format!("echo recovered >> {}", marker.display()),
+        ],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            retry_window_secs: 30,
+            cooldown_secs: Some(10),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(format!("/tmp/{name}.pid"))),
+        )
+    }
+}
+
+fn shared_config(processes: Vec<ProcessConfig>, allow_simulation: bool) -> Arc<RwLock<AppConfig>> {
+    Arc::new(RwLock::new(AppConfig {
+        processes,
+        control: ControlConfig {
+            allow_simulation,
+            ..Default::default()
+        },
+        ..Default::default()
+    }))
+}
+
+#[tokio::test]
+async fn simulated_failure_flows_through_coordinator_to_recovery() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("marker");
+    let config = shared_config(vec![marker_process("chaos", &marker)], true);
+
+    // 与核心运行时相同的拓扑：监控器事件 -> 依赖协调器 -> 自愈器
+    let monitor_tx = create_event_sender();
+    let coordinator_rx = monitor_tx.subscribe();
+    let healer_tx = create_event_sender();
+    let mut healer = ProcessHealer::new(healer_tx.subscribe(), Arc::clone(&config)).await;
+    tokio::spawn(
+        DependencyCoordinator::new(coordinator_rx, healer_tx.clone(), Arc::clone(&config))
+            .run_loop(),
+    );
+
+    let (runtime_tx, _runtime_rx) = mpsc::channel(1);
+    let state = ControlState {
+        history: EventHistory::new(8),
+        config,
+        runtime_tx,
+        children: Default::default(),
+        event_tx: monitor_tx,
+        health: Default::default(),
+    };
+    let response = state
+        .handle_line(r#"{"cmd":"simulate-failure","process":"chaos","kind":"down"}"#)
+        .await;
+    assert_eq!(response["ok"], true, "{response}");
+    assert_eq!(response["kind"], "down");
+
+    let events = timeout(Duration::from_secs(2), healer.next_batch())
+        .await
+        .expect("simulated event should reach the healer")
+        .unwrap();
+    assert!(matches!(
+        events.as_slice(),
+        [ProcessEvent::ProcessDown { name, .. }] if name == "chaos"
+    ));
+    for event in events {
+        healer.handle_event(event).await;
+    }
+
+    let recovered = timeout(Duration::from_secs(5), async {
+        while !marker.exists() {
+            sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await;
+    assert!(recovered.is_ok(), "recovery command should have run");
+}
+
+#[tokio::test]
+async fn simulation_is_refused_unless_allowed() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("marker");
+    let config = shared_config(vec![marker_process("chaos", &marker)], false);
+    let monitor_tx = create_event_sender();
+    let mut rx = monitor_tx.subscribe();
+    let (runtime_tx, _runtime_rx) = mpsc::channel(1);
+    let state = ControlState {
+        history: EventHistory::new(8),
+        config: Arc::clone(&config),
+        runtime_tx,
+        children: Default::default(),
+        event_tx: monitor_tx,
+        health: Default::default(),
+    };
+
+    let response = state
+        .handle_line(r#"{"cmd":"simulate-failure","process":"chaos","kind":"down"}"#)
+        .await;
+    assert_eq!(response["ok"], false);
+    assert!(
+        response["error"]
+            .as_str()
+            .unwrap()
+            .contains("allow_simulation"),
+        "{response}"
+    );
+    assert!(rx.try_recv().is_err(), "no event should be published");
+
+    config.write().await.control.allow_simulation = true;
+    for (line, error) in [
+        (
+            r#"{"cmd":"simulate-failure","process":"ghost","kind":"down"}"#,
+            "unknown process",
+        ),
+        (
+            r#"{"cmd":"simulate-failure","process":"chaos","kind":"oom"}"#,
+            "oom",
+        ),
+    ] {
+        let response = state.handle_line(line).await;
+        assert_eq!(response["ok"], false, "{line}");
+        assert!(
+            response["error"].as_str().unwrap().contains(error),
+            "{response}"
+        );
+    }
+
+    let response = state
+        .handle_line(r#"{"cmd":"simulate-failure","process":"chaos","kind":"resource"}"#)
+        .await;
+    assert_eq!(response["ok"], true, "{response}");
+    assert!(matches!(
+        rx.try_recv(),
+        Ok(ProcessEvent::ProcessResourceExceeded { name, .. }) if name == "chaos"
+    ));
+}
@@ -0,0 +1,111 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+// retries 足够多，第二次恢复只会被拉起失败的退避拦下
+async fn healer_for(command: &Path) -> ProcessHealer {
+    let yaml = format!(
+        r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "{}"
+    args: []
+    run_as_root: true
+    child_output: "null"
+    monitor: {{ type: "pid", pid_file_path: "/nonexistent/svc.pid", interval_secs: 60 }}
+    recovery: {{ type: "regular", retries: 10, retry_window_secs: 60, cooldown_secs: 60 }}
+"#,
+        command.display()
+    );
+    let config = AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap();
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config))).await
+}
+
+// 先后两次恢复的跳过原因
+async fn heal_twice(command: &Path) -> (RecoverySkipReason, RecoverySkipReason) {
+    let mut healer = healer_for(command).await;
+    let name = "svc".to_string();
+    let first = healer.heal_process(&name).await.expect("spawn should fail");
+    let second = healer
+        .heal_process(&name)
+        .await
+        .expect("retry should back off");
+    (first, second)
+}
+
+#[tokio::test]
+async fn permission_denied_backs_off_with_a_chmod_hint() {
+    let dir = TempDir::new().unwrap();
+    // 没有执行位，root 也无法 exec
+    let script = dir.path().join("not-executable.sh");
+    std::fs::write(&script, "#!/bin/sh\nexit 0\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    let (first, second) = heal_twice(&script).await;
+    assert_eq!(
+        first,
+        RecoverySkipReason::PermissionDenied {
+            program: script.clone(),
+            missing_exec_bit: true,
+        }
+    );
+    assert_eq!(first.as_str(), "permission_denied");
+    assert!(first.is_failure());
+    assert!(first.to_string().contains("chmod +x"), "{first}");
+    assert!(matches!(second, RecoverySkipReason::SpawnBackoff { .. }));
+}
+
+#[tokio::test]
+async fn missing_command_backs_off_with_a_path_hint() {
+    let dir = TempDir::new().unwrap();
+    let missing = dir.path().join("missing");
+
+    let (first, second) = heal_twice(&missing).await;
+    assert_eq!(
+        first,
+        RecoverySkipReason::CommandNotFound {
+            program: missing.clone()
+        }
+    );
+    assert_eq!(first.as_str(), "command_not_found");
+    assert!(first.to_string().contains("does not exist"), "{first}");
+    assert!(matches!(second, RecoverySkipReason::SpawnBackoff { .. }));
+}
+
+#[test]
+fn permission_and_missing_command_are_reported_differently() {
+    let program = PathBuf::from("/opt/app/bin/app");
+    let denied = RecoverySkipReason::PermissionDenied {
+        program: program.clone(),
+        missing_exec_bit: true,
+    };
+    let missing = RecoverySkipReason::CommandNotFound { program };
+    assert_ne!(denied.as_str(), missing.as_str());
+    assert_ne!(denied.to_string(), missing.to_string());
+    assert!(!missing.to_string().contains("chmod"));
+}
+
+#[test]
+fn chmod_hint_is_only_given_for_a_missing_execute_bit() {
+    let program = PathBuf::from("/opt/app/bin/app");
+    let executable = RecoverySkipReason::PermissionDenied {
+        program: program.clone(),
+        missing_exec_bit: false,
+    };
+    assert!(!executable.to_string().contains("chmod"), "{executable}");
+    assert!(executable.to_string().contains("noexec"), "{executable}");
+
+    // 切换身份或进入命名空间失败（EPERM）与文件权限无关
+    let privilege = RecoverySkipReason::PrivilegeDropFailed { program };
+    assert_eq!(privilege.as_str(), "privilege_drop_failed");
+    assert!(privilege.is_failure());
+    assert!(!privilege.to_string().contains("chmod"), "{privilege}");
+    assert!(privilege.to_string().contains("run_as_user"), "{privilege}");
+}
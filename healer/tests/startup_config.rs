@@ -0,0 +1,92 @@
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+// 启动时配置无法加载的退出码（EX_CONFIG）
+const EXIT_CONFIG: i32 = 78;
+
+fn healer(config_path: &Path, extra: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_healer"));
+    command
+        .arg("--config")
+        .arg(config_path)
+        .args(extra)
+        .stdin(Stdio::null());
+    command
+}
+
+fn assert_clean_config_exit(output: &Output) {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(EXIT_CONFIG), "stderr:\n{stderr}");
+    assert!(!stderr.contains("panicked"), "stderr:\n{stderr}");
+    assert!(stderr.contains("Error:"), "stderr:\n{stderr}");
+}
+
+#[test]
+fn missing_config_exits_cleanly_with_ex_config() {
+    let dir = TempDir::new().unwrap();
+    let output = healer(&dir.path().join("missing.yaml"), &["--foreground"])
+        .output()
+        .unwrap();
+    assert_clean_config_exit(&output);
+}
+
+#[test]
+fn invalid_config_exits_cleanly_with_a_diagnostic() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("config.yaml");
+    std::fs::write(&config_path, "processes: [ {").unwrap();
+    let output = healer(&config_path, &["oneshot"]).output().unwrap();
+    assert_clean_config_exit(&output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("could not be loaded"));
+}
+
+#[test]
+fn config_deployed_after_startup_is_picked_up_by_retries() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("config.yaml");
+    let child = healer(
+        &config_path,
+        &[
+            "--config-retries",
+            "5",
+            "--config-retry-interval",
+            "1",
+            "oneshot",
+        ],
+    )
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap();
+
+    std::thread::sleep(Duration::from_millis(1500));
+    std::fs::write(&config_path, "processes: []\n").unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr:\n{stderr}");
+    assert!(stderr.contains("retrying in 1s (1/5)"), "stderr:\n{stderr}");
+}
+
+#[test]
+fn retries_give_up_after_the_limit() {
+    let dir = TempDir::new().unwrap();
+    let started = Instant::now();
+    let output = healer(
+        &dir.path().join("never.yaml"),
+        &[
+            "--config-retries",
+            "2",
+            "--config-retry-interval",
+            "1",
+            "oneshot",
+        ],
+    )
+    .output()
+    .unwrap();
+    assert_clean_config_exit(&output);
+    assert!(started.elapsed() >= Duration::from_secs(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("(2/2)"));
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use healer::config::{
+    AppConfig, ChildOutput, MonitorConfig, ProcessConfig, RecoveryConfig, RegularHealerFields,
+};
+use healer::event_bus::{EventSender, ProcessEvent};
+use healer::Healer;
+use std::path::Path;
+use std::time::SystemTime;
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout, Duration};
+
+fn down(name: &str) -> ProcessEvent {
+    ProcessEvent::ProcessDown {
+        name: name.into(),
+        pid: 0,
+        at: SystemTime::now(),
+    }
+}
+
+#[tokio::test]
+async fn gated_sender_holds_events_until_ready() {
+    let sender = EventSender::gated();
+    assert!(!sender.is_ready());
+
+    // 订阅者就绪之前发布的事件不应因无人订阅而丢失
+    let publisher = {
+        let sender = sender.clone();
+        tokio::spawn(async move { sender.send(down("early")).await })
+    };
+    sleep(Duration::from_millis(100)).await;
+    assert!(!publisher.is_finished(), "send should wait for readiness");
+
+    let mut rx = sender.subscribe_critical();
+    sender.mark_ready();
+
+    let delivered = timeout(Duration::from_secs(1), publisher)
+        .await
+        .expect("send should complete once ready")
+        .unwrap()
+        .expect("event should have a subscriber");
+    assert_eq!(delivered, 1);
+    let event = rx.recv().await.expect("event should be queued");
+    assert_eq!(event.process_name(), Some("early"));
+}
+
+#[tokio::test]
+async fn ungated_sender_publishes_immediately() {
+    let sender = EventSender::new();
+    assert!(sender.is_ready());
+    assert!(sender.send(down("nobody")).await.is_err());
+}
+
+// 启动时进程已不在运行（PID 文件不存在）：第一次检查就会发出 ProcessDown
+fn missing_process(name: &str, base: &Path) -> ProcessConfig {
+    ProcessConfig {
+        command: "/bin/sh".into(),
+        args: vec![
+            "-c".into(),
+            format!("echo recovered >> {}", base.join("recovered").display()),
+        ],
+        recovery: RecoveryConfig::Regular(RegularHealerFields {
+            cooldown_secs: Some(30),
+            ..Default::default()
+        }),
+        child_output: ChildOutput::Null,
+        ..common::process(
+            name,
+            MonitorConfig::Pid(common::pid_fields(base.join(format!("{name}.pid")))),
+        )
+    }
+}
+
+#[tokio::test]
+async fn process_down_at_startup_is_recovered() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let config = AppConfig {
+        log_directory: Some(temp_dir.path().join("logs")),
+        pid_file_directory: Some(temp_dir.path().join("pids")),
+        processes: vec![missing_process("early", temp_dir.path())],
+        working_directory: Some(temp_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let handle = Healer::builder()
+        .config(config)
+        .enable_ebpf(false)
+        .build()
+        .run()
+        .await
+        .expect("healer should start");
+
+    let marker = temp_dir.path().join("recovered");
+    let recovered = timeout(Duration::from_secs(5), async {
+        while !marker.exists() {
+            sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await;
+    assert!(
+        recovered.is_ok(),
+        "process found down at startup should be recovered"
+    );
+
+    timeout(Duration::from_secs(5), handle.shutdown())
+        .await
+        .expect("shutdown timed out")
+        .expect("shutdown should succeed");
+}
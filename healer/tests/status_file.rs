@@ -0,0 +1,99 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::event_bus::ProcessEvent;
+use healer::health::{HealthRegistry, HealthState};
+use healer::status_file::StatusFile;
+use healer::subscriber::event_history::EventHistory;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+fn config(status_file: &Path) -> AppConfig {
+    let yaml = format!(
+        r#"
+status_file: "{}"
+status_file_interval_secs: 1
+processes:
+  - name: "web"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "pid", pid_file_path: "/nonexistent/web.pid", interval_secs: 60 }}
+    recovery: {{ type: "regular", retries: 3, retry_window_secs: 60 }}
+"#,
+        status_file.display()
+    );
+    AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap()
+}
+
+// 等待状态文件出现并满足条件，每次读取都必须是完整的 JSON
+async fn wait_for(path: &Path, accept: impl Fn(&Value) -> bool) -> Value {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    loop {
+        if let Ok(text) = std::fs::read_to_string(path) {
+            let status: Value = serde_json::from_str(&text).expect("status file is not valid JSON");
+            if accept(&status) {
+                return status;
+            }
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "status file was not updated in time"
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test]
+async fn status_file_is_written_and_refreshed() {
+    let temp_dir = TempDir::new().expect("failed to create temp dir");
+    let path = temp_dir.path().join("status.json");
+    let health = HealthRegistry::new();
+    let history = EventHistory::new(16);
+    let handle = StatusFile::spawn(
+        Arc::new(RwLock::new(config(&path))),
+        health.clone(),
+        history.clone(),
+    );
+
+    let first = wait_for(&path, |_| true).await;
+    assert_eq!(first["pid"], std::process::id());
+    assert_eq!(first["processes"]["web"]["enabled"], true);
+    assert_eq!(first["processes"]["web"]["state"], "unknown");
+    assert!(first["processes"]["web"]["last_event"].is_null());
+
+    health.set("web", HealthState::Recovering);
+    history.push(ProcessEvent::ProcessDown {
+        name: "web".to_string(),
+        pid: 42,
+        at: SystemTime::now(),
+    });
+    let updated = wait_for(&path, |status| {
+        status["processes"]["web"]["state"] == "recovering"
+    })
+    .await;
+    assert_eq!(
+        updated["processes"]["web"]["last_event"]["kind"],
+        "process_down"
+    );
+    assert_eq!(updated["processes"]["web"]["last_event"]["pid"], 42);
+    assert!(updated["uptime_secs"].as_u64() >= first["uptime_secs"].as_u64());
+    assert_ne!(updated["at"], first["at"]);
+
+    // 只留下目标文件，临时文件已被 rename
+    handle.abort();
+    let names: Vec<_> = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(names, ["status.json"]);
+}
+
+#[test]
+fn zero_interval_is_rejected() {
+    let yaml = "status_file_interval_secs: 0\nprocesses: []\n";
+    assert!(AppConfig::parse(yaml, ConfigFormat::Yaml).is_err());
+}
@@ -0,0 +1,130 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::control::ControlState;
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::health::{HealthRegistry, HealthState};
+use healer::subscriber::child_registry::ChildRegistry;
+use healer::subscriber::event_history::EventHistory;
+use healer::subscriber::process_healer::{ProcessHealer, RecoverySkipReason};
+use nix::sys::signal::Signal;
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{mpsc as std_mpsc, Arc};
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{sleep, timeout, Duration};
+
+fn config(dir: &Path) -> AppConfig {
+    let yaml = format!(
+        r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/bin/sh"
+    args: ["-c", "echo run >> {recovered}"]
+    run_as_root: true
+    child_output: "null"
+    monitor: {{ type: "pid", pid_file_path: "{pid_file}", interval_secs: 60 }}
+"#,
+        recovered = dir.join("recovered").display(),
+        pid_file = dir.join("svc.pid").display(),
+    );
+    AppConfig::parse(&yaml, ConfigFormat::Yaml).unwrap()
+}
+
+#[tokio::test]
+async fn stop_kills_the_process_and_start_brings_it_back() {
+    let dir = TempDir::new().unwrap();
+    let config = Arc::new(RwLock::new(config(dir.path())));
+    let recovered = dir.path().join("recovered");
+
+    // 不是由自愈器拉起的进程，只能通过 PID 文件找到；在独立线程中回收，避免留下僵尸进程
+    let mut running = Command::new("sleep").arg("30").spawn().unwrap();
+    let running_pid = running.id();
+    std::fs::write(dir.path().join("svc.pid"), running_pid.to_string()).unwrap();
+    let (exit_tx, exit_rx) = std_mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = exit_tx.send(running.wait().unwrap());
+    });
+
+    let health = HealthRegistry::new();
+    let children = ChildRegistry::new();
+    let event_tx = create_event_sender();
+    let mut event_rx = event_tx.subscribe();
+    let control = ControlState {
+        history: EventHistory::new(8),
+        config: config.clone(),
+        runtime_tx: mpsc::channel(1).0,
+        children: children.clone(),
+        event_tx: event_tx.clone(),
+        health: health.clone(),
+    };
+    let mut healer = ProcessHealer::new(create_event_sender().subscribe(), config)
+        .await
+        .with_health(health.clone())
+        .with_children(children);
+
+    let reply = control
+        .handle_line(r#"{"cmd":"stop","process":"svc","stop_timeout_secs":5}"#)
+        .await;
+    assert_eq!(reply["ok"], true, "{reply}");
+    assert_eq!(reply["pid"], running_pid, "{reply}");
+    let status = exit_rx
+        .recv_timeout(std::time::Duration::from_secs(5))
+        .expect("the process should be stopped");
+    assert_eq!(status.signal(), Some(Signal::SIGTERM as i32));
+    assert_eq!(health.get("svc"), HealthState::Stopped);
+
+    // 进程退出后监控报告的故障不会再把它拉起
+    assert_eq!(
+        healer.heal_process(&"svc".to_string()).await,
+        Some(RecoverySkipReason::Paused)
+    );
+    assert!(!recovered.exists());
+
+    let reply = control
+        .handle_line(r#"{"cmd":"start","process":"svc"}"#)
+        .await;
+    assert_eq!(reply["started"], true, "{reply}");
+    let event = timeout(Duration::from_secs(1), event_rx.recv())
+        .await
+        .expect("start should publish a failure event")
+        .unwrap();
+    assert!(
+        matches!(event, ProcessEvent::ProcessDown { ref name, .. } if name == "svc"),
+        "{event:?}"
+    );
+    assert_eq!(healer.heal_process(&"svc".to_string()).await, None);
+    for _ in 0..50 {
+        if recovered.exists() {
+            break;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    assert!(recovered.exists(), "start should restart the process");
+
+    // 没有被 stop 的进程，start 什么也不做
+    let reply = control
+        .handle_line(r#"{"cmd":"start","process":"svc"}"#)
+        .await;
+    assert_eq!(reply["started"], false, "{reply}");
+}
+
+#[tokio::test]
+async fn stop_rejects_unknown_processes() {
+    let dir = TempDir::new().unwrap();
+    let health = HealthRegistry::new();
+    let control = ControlState {
+        history: EventHistory::new(8),
+        config: Arc::new(RwLock::new(config(dir.path()))),
+        runtime_tx: mpsc::channel(1).0,
+        children: Default::default(),
+        event_tx: create_event_sender(),
+        health: health.clone(),
+    };
+    let reply = control
+        .handle_line(r#"{"cmd":"stop","process":"missing"}"#)
+        .await;
+    assert_eq!(reply["ok"], false, "{reply}");
+    assert!(!health.is_paused("missing"));
+}
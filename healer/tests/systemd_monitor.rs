@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use healer::config::SystemdMonitorConfig;
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::monitor::systemd_monitor::{SystemdMonitor, UnitStateSource};
+use healer::monitor::Monitor;
+use std::sync::{Arc, Mutex};
+use tokio::time::{timeout, Duration};
+use tokio_util::sync::CancellationToken;
+
+// 返回预设状态的 unit 状态来源，替代真实的 D-Bus 查询
+struct FakeUnits {
+    state: Mutex<String>,
+}
+
+impl FakeUnits {
+    fn new(state: &str) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(state.into()),
+        })
+    }
+}
+
+#[async_trait]
+impl UnitStateSource for FakeUnits {
+    async fn active_state(&self, unit: &str) -> anyhow::Result<String> {
+        assert_eq!(unit, "demo.service");
+        Ok(self.state.lock().unwrap().clone())
+    }
+}
+
+fn monitor_config(name: &str) -> SystemdMonitorConfig {
+    SystemdMonitorConfig {
+        name: name.into(),
+        unit: "demo.service".into(),
+        interval_secs: 1,
+        check_timeout_secs: 1,
+    }
+}
+
+#[tokio::test]
+async fn failed_unit_is_reported_down() {
+    let event_tx = create_event_sender();
+    let mut event_rx = event_tx.subscribe();
+    let monitor = SystemdMonitor::new(
+        monitor_config("unit_failed"),
+        event_tx,
+        FakeUnits::new("failed"),
+    );
+    let shutdown = CancellationToken::new();
+    tokio::spawn(monitor.run(shutdown.clone()));
+
+    let event = timeout(Duration::from_secs(3), event_rx.recv())
+        .await
+        .expect("expected an event for a failed unit")
+        .expect("event channel closed");
+    assert!(matches!(
+        event,
+        ProcessEvent::ProcessDown { ref name, pid: 0, .. } if name == "unit_failed"
+    ));
+    shutdown.cancel();
+}
+
+#[tokio::test]
+async fn active_unit_is_reported_up_once() {
+    let event_tx = create_event_sender();
+    let mut event_rx = event_tx.subscribe();
+    let monitor = SystemdMonitor::new(
+        monitor_config("unit_active"),
+        event_tx,
+        FakeUnits::new("active"),
+    );
+    let shutdown = CancellationToken::new();
+    tokio::spawn(monitor.run(shutdown.clone()));
+
+    let event = timeout(Duration::from_secs(3), event_rx.recv())
+        .await
+        .expect("an active unit should be confirmed healthy")
+        .expect("event channel closed");
+    assert!(
+        matches!(event, ProcessEvent::ProcessUp { ref name, .. } if name == "unit_active"),
+        "expected ProcessUp for 'unit_active', got {:?}",
+        event
+    );
+    assert!(
+        timeout(Duration::from_millis(2500), event_rx.recv())
+            .await
+            .is_err(),
+        "a unit that stays active should not produce further events"
+    );
+    shutdown.cancel();
+}
+
+#[tokio::test]
+async fn check_once_follows_unit_state_changes() {
+    let units = FakeUnits::new("active");
+    let monitor = SystemdMonitor::new(
+        monitor_config("unit_changes"),
+        create_event_sender(),
+        units.clone(),
+    );
+    assert!(monitor.check_once().await.is_none());
+
+    *units.state.lock().unwrap() = "inactive".into();
+    assert!(matches!(
+        monitor.check_once().await,
+        Some(ProcessEvent::ProcessDown { pid: 0, .. })
+    ));
+}
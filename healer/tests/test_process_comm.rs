@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn spawn_helper(pid_file: &Path, comm: &str) -> Child {
+    Command::new(env!("CARGO_BIN_EXE_simple_test_process"))
+        .arg("--pid-file")
+        .arg(pid_file)
+        .args(["--comm", comm])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to spawn simple_test_process")
+}
+
+// 辅助进程在设置 comm 之后才写入 PID 文件，等到文件出现即可读取 comm
+fn comm_of_helper(comm: &str) -> String {
+    let dir = TempDir::new().unwrap();
+    let pid_file = dir.path().join("helper.pid");
+    let mut child = spawn_helper(&pid_file, comm);
+    let mut pid = None;
+    for _ in 0..50 {
+        if let Some(found) = fs::read_to_string(&pid_file)
+            .ok()
+            .and_then(|content| content.trim().parse::<u32>().ok())
+        {
+            pid = Some(found);
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let actual = pid.and_then(|pid| fs::read_to_string(format!("/proc/{pid}/comm")).ok());
+    let _ = child.kill();
+    let _ = child.wait();
+    assert_eq!(pid, Some(child.id()), "helper did not write its pid file");
+    actual
+        .expect("failed to read helper comm")
+        .trim_end()
+        .to_string()
+}
+
+#[test]
+fn helper_sets_the_configured_comm() {
+    assert_eq!(comm_of_helper("counter_ebpf"), "counter_ebpf");
+}
+
+#[test]
+fn helper_comm_is_truncated_like_the_kernel() {
+    assert_eq!(comm_of_helper("a-very-long-thread-name"), "a-very-long-thr");
+}
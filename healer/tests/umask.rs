@@ -0,0 +1,72 @@
+use healer::config::{AppConfig, ConfigFormat, Umask};
+use healer::event_bus::create_event_sender;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+// 被恢复的进程以默认权限（0666 & ~umask）创建 created 文件
+fn yaml(umask: &str, created: &Path) -> String {
+    format!(
+        r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/bin/sh"
+    args: ["-c", "echo > {created}"]
+    run_as_root: true
+    child_output: "null"
+    wait_for_exit: true
+    umask: {umask}
+    monitor: {{ type: "pid", pid_file_path: "/nonexistent/svc.pid", interval_secs: 60 }}
+    recovery: {{ type: "regular", retries: 5, retry_window_secs: 60 }}
+"#,
+        created = created.display()
+    )
+}
+
+fn config(umask: &str, created: &Path) -> AppConfig {
+    AppConfig::parse(&yaml(umask, created), ConfigFormat::Yaml).unwrap()
+}
+
+async fn created_mode(umask: &str) -> u32 {
+    let dir = TempDir::new().unwrap();
+    let created = dir.path().join("created");
+    let sender = create_event_sender();
+    let mut healer = ProcessHealer::new(
+        sender.subscribe(),
+        Arc::new(RwLock::new(config(umask, &created))),
+    )
+    .await;
+    assert_eq!(healer.heal_process(&"svc".to_string()).await, None);
+    healer.wait_for_recoveries().await;
+    std::fs::metadata(&created).unwrap().permissions().mode() & 0o777
+}
+
+#[tokio::test]
+async fn recovered_child_uses_the_configured_umask() {
+    assert_eq!(created_mode(r#""0077""#).await, 0o600);
+    assert_eq!(created_mode(r#""002""#).await, 0o664);
+    assert_eq!(created_mode(r#""0o000""#).await, 0o666);
+}
+
+#[test]
+fn umask_is_parsed_as_octal() {
+    assert_eq!("0027".parse::<Umask>().unwrap().bits(), 0o027);
+    assert_eq!("0o22".parse::<Umask>().unwrap().bits(), 0o022);
+    assert_eq!("0022".parse::<Umask>().unwrap().to_string(), "0022");
+    assert!("0800".parse::<Umask>().is_err());
+    assert!("1777".parse::<Umask>().is_err());
+
+    let dir = TempDir::new().unwrap();
+    let config = config("null", &dir.path().join("created"));
+    assert_eq!(config.processes[0].umask, None);
+    let err = AppConfig::parse(
+        &yaml(r#""9""#, Path::new("/tmp/created")),
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("umask"), "{err}");
+}
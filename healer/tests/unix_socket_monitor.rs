@@ -0,0 +1,109 @@
+use healer::config::{AppConfig, ConfigFormat, UnixSocketMonitorConfig};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::monitor::unix_socket_monitor::{probe_socket, SocketState, UnixSocketMonitor};
+use healer::monitor::Monitor;
+use std::os::unix::net::UnixListener;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::{timeout, Duration};
+use tokio_util::sync::CancellationToken;
+
+fn monitor_config(name: &str, path: &Path) -> UnixSocketMonitorConfig {
+    UnixSocketMonitorConfig {
+        name: name.into(),
+        path: path.to_path_buf(),
+        interval_secs: 1,
+        check_timeout_secs: 1,
+    }
+}
+
+#[tokio::test]
+async fn probe_distinguishes_refused_from_missing() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("svc.sock");
+
+    let listener = UnixListener::bind(&path).unwrap();
+    assert_eq!(probe_socket(&path).await, SocketState::Listening);
+
+    // 关闭监听后套接字文件仍然存在
+    drop(listener);
+    assert!(path.exists());
+    assert_eq!(probe_socket(&path).await, SocketState::Refused);
+
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(probe_socket(&path).await, SocketState::Missing);
+}
+
+#[tokio::test]
+async fn closed_listener_is_reported_down() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("svc.sock");
+    let listener = UnixListener::bind(&path).unwrap();
+
+    let event_tx = create_event_sender();
+    let mut event_rx = event_tx.subscribe();
+    let monitor = UnixSocketMonitor::new(monitor_config("sock_svc", &path), event_tx);
+    let shutdown = CancellationToken::new();
+    tokio::spawn(monitor.run(shutdown.clone()));
+
+    let event = timeout(Duration::from_secs(3), event_rx.recv())
+        .await
+        .expect("a listening socket should be confirmed healthy")
+        .expect("event channel closed");
+    assert!(
+        matches!(event, ProcessEvent::ProcessUp { ref name, .. } if name == "sock_svc"),
+        "expected ProcessUp for 'sock_svc', got {:?}",
+        event
+    );
+
+    drop(listener);
+    let event = timeout(Duration::from_secs(3), event_rx.recv())
+        .await
+        .expect("expected an event after the listener closed")
+        .expect("event channel closed");
+    let expected_url = format!("unix:{}", path.display());
+    assert!(
+        matches!(
+            event,
+            ProcessEvent::ProcessDisconnected { ref name, ref url, .. }
+                if name == "sock_svc" && *url == expected_url
+        ),
+        "expected ProcessDisconnected for 'sock_svc', got {:?}",
+        event
+    );
+    shutdown.cancel();
+}
+
+#[tokio::test]
+async fn check_once_reports_missing_socket() {
+    let temp_dir = TempDir::new().unwrap();
+    let monitor = UnixSocketMonitor::new(
+        monitor_config("sock_missing", &temp_dir.path().join("absent.sock")),
+        create_event_sender(),
+    );
+    assert!(matches!(
+        monitor.check_once().await,
+        Some(ProcessEvent::ProcessDisconnected { .. })
+    ));
+}
+
+#[test]
+fn unix_socket_monitor_is_parsed() {
+    let yaml = r#"
+processes:
+  - name: "svc"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: { type: "unix_socket", path: "/run/svc.sock", interval_secs: 5 }
+"#;
+    let config = AppConfig::parse(yaml, ConfigFormat::Yaml).unwrap();
+    let socket = config.processes[0]
+        .get_unix_socket_monitor_config()
+        .expect("unix_socket monitor config");
+    assert_eq!(socket.path, Path::new("/run/svc.sock"));
+    assert_eq!(socket.interval_secs, 5);
+    assert_eq!(socket.check_timeout_secs, 5);
+    assert_eq!(socket.url(), "unix:/run/svc.sock");
+}
@@ -0,0 +1,119 @@
+use healer::config::{AppConfig, ConfigFormat};
+use healer::event_bus::{create_event_sender, ProcessEvent};
+use healer::monitor::{network_monitor::NetworkMonitor, Monitor};
+use healer::utils::{expand_url_template, UrlTemplateError};
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+// 对每个连接都返回 200 的最小 HTTP 服务
+async fn serve_ok() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK",
+                    )
+                    .await;
+            });
+        }
+    });
+    address
+}
+
+fn parse(monitor: &str) -> Result<AppConfig, healer::config::ConfigError> {
+    let yaml = format!(
+        r#"
+processes:
+  - name: "api"
+    enabled: true
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: {{ type: "network", interval_secs: 5, {monitor} }}
+"#
+    );
+    AppConfig::parse(&yaml, ConfigFormat::Yaml)
+}
+
+fn monitor_for(config: &AppConfig) -> NetworkMonitor {
+    let network = config.processes[0].get_network_monitor_config().unwrap();
+    NetworkMonitor::new(network, create_event_sender())
+}
+
+#[tokio::test]
+async fn env_placeholder_resolves_to_the_monitored_url() {
+    let address = serve_ok().await;
+    std::env::set_var("HEALER_TEST_URL_TEMPLATE_PORT", address.port().to_string());
+    let config =
+        parse(r#"target_url: "http://127.0.0.1:${HEALER_TEST_URL_TEMPLATE_PORT}/health""#).unwrap();
+
+    let network = config.processes[0].get_network_monitor_config().unwrap();
+    assert_eq!(network.target_url, format!("http://{address}/health"));
+    assert!(monitor_for(&config).check_once().await.is_none());
+}
+
+#[tokio::test]
+async fn port_placeholder_uses_the_port_field() {
+    let address = serve_ok().await;
+    let config = parse(&format!(
+        r#"target_url: "http://127.0.0.1:{{port}}/health", port: {}"#,
+        address.port()
+    ))
+    .unwrap();
+    assert!(monitor_for(&config).check_once().await.is_none());
+
+    // 端口上没有服务时，断连事件中的地址也是展开后的地址
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed = listener.local_addr().unwrap().port();
+    drop(listener);
+    let config = parse(&format!(
+        r#"target_url: "http://127.0.0.1:{{port}}/health", port: {closed}"#
+    ))
+    .unwrap();
+    match monitor_for(&config).check_once().await {
+        Some(ProcessEvent::ProcessDisconnected { url, .. }) => {
+            assert_eq!(url, format!("http://127.0.0.1:{closed}/health"));
+        }
+        other => panic!("expected a disconnect, got {other:?}"),
+    }
+}
+
+#[test]
+fn unresolvable_templates_are_rejected() {
+    let err =
+        parse(r#"target_url: "http://127.0.0.1:${HEALER_TEST_URL_TEMPLATE_UNSET}/""#).unwrap_err();
+    assert!(
+        err.to_string().contains("HEALER_TEST_URL_TEMPLATE_UNSET"),
+        "{err}"
+    );
+
+    let err = parse(r#"target_url: "http://127.0.0.1:{port}/""#).unwrap_err();
+    assert!(err.to_string().contains("no port"), "{err}");
+
+    let err = parse(r#"target_url: "not a url""#).unwrap_err();
+    assert!(err.to_string().contains("not a valid URL"), "{err}");
+}
+
+#[test]
+fn expand_url_template_handles_each_placeholder() {
+    assert_eq!(
+        expand_url_template("http://localhost:8080/", None),
+        Ok("http://localhost:8080/".to_string())
+    );
+    assert_eq!(
+        expand_url_template("http://localhost:{port}/{port}", Some(9000)),
+        Ok("http://localhost:9000/9000".to_string())
+    );
+    assert_eq!(
+        expand_url_template("http://localhost:${OPEN", None),
+        Err(UrlTemplateError::Unterminated(
+            "http://localhost:${OPEN".to_string()
+        ))
+    );
+}
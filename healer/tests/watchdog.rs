@@ -0,0 +1,71 @@
+use healer::watchdog::{Heartbeat, Watchdog};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn stalled_heartbeat_fires_the_watchdog() {
+    let heartbeat = Heartbeat::new();
+    let (fired_tx, fired_rx) = mpsc::channel();
+    let started = Instant::now();
+    Watchdog::spawn(heartbeat.clone(), Duration::from_secs(1), move || {
+        fired_tx.send(Instant::now()).unwrap();
+    })
+    .expect("failed to start watchdog");
+
+    // 没有任何心跳：看门狗应在超时后触发
+    let fired_at = fired_rx
+        .recv_timeout(Duration::from_secs(5))
+        .expect("watchdog did not fire for a stalled heartbeat");
+    assert!(fired_at - started >= Duration::from_secs(1));
+}
+
+#[test]
+fn regular_heartbeat_keeps_the_watchdog_quiet() {
+    let heartbeat = Heartbeat::new();
+    let (fired_tx, fired_rx) = mpsc::channel();
+    let watchdog = Watchdog::spawn(heartbeat.clone(), Duration::from_secs(2), move || {
+        fired_tx.send(()).unwrap();
+    })
+    .expect("failed to start watchdog");
+
+    let until = Instant::now() + Duration::from_secs(4);
+    while Instant::now() < until {
+        heartbeat.beat();
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    assert!(
+        fired_rx.try_recv().is_err(),
+        "watchdog fired despite heartbeats"
+    );
+
+    // 正常退出时停止看门狗，之后不再触发
+    heartbeat.disarm();
+    watchdog.join().unwrap();
+    assert!(fired_rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn long_operation_keeps_beating() {
+    let heartbeat = Heartbeat::new();
+    let (fired_tx, fired_rx) = mpsc::channel();
+    let watchdog = Watchdog::spawn(heartbeat.clone(), Duration::from_secs(1), move || {
+        fired_tx.send(()).unwrap();
+    })
+    .expect("failed to start watchdog");
+
+    // 比超时更长、但一直在推进的操作（如重载时等待监控器停止）不应被当作卡死
+    let output = heartbeat
+        .beat_while(async {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            7
+        })
+        .await;
+    assert_eq!(output, 7);
+    assert!(
+        fired_rx.try_recv().is_err(),
+        "watchdog fired during a long operation"
+    );
+
+    heartbeat.disarm();
+    watchdog.join().unwrap();
+}
@@ -0,0 +1,102 @@
+use healer::config::{AppConfig, ConfigFormat};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+const DISABLED_ONLY: &str = r#"
+processes:
+  - name: "svc"
+    enabled: false
+    command: "/bin/true"
+    args: []
+    run_as_root: true
+    monitor: { type: "pid", pid_file_path: "/run/svc.pid", interval_secs: 3 }
+"#;
+
+fn write_config(dir: &Path, extra: &str) -> std::path::PathBuf {
+    let config_path = dir.join("config.yaml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "log_directory: {:?}\npid_file_directory: {:?}\nprocesses: []\n{}",
+            dir.join("logs"),
+            dir,
+            extra
+        ),
+    )
+    .unwrap();
+    config_path
+}
+
+fn healer(config_path: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_healer"));
+    command
+        .arg("--config")
+        .arg(config_path)
+        .arg("--foreground")
+        .env("RUST_LOG", "off")
+        .stdin(Stdio::null());
+    command
+}
+
+#[test]
+fn all_disabled_processes_are_counted_as_empty() {
+    let config = AppConfig::parse(DISABLED_ONLY, ConfigFormat::Yaml).unwrap();
+    assert_eq!(config.enabled_process_count(), 0);
+    assert!(!config.require_processes);
+
+    let strict = format!("require_processes: true\n{DISABLED_ONLY}");
+    let err = AppConfig::parse(&strict, ConfigFormat::Yaml).unwrap_err();
+    assert!(err.to_string().contains("require_processes"), "{err}");
+}
+
+#[test]
+fn empty_config_starts_with_a_warning() {
+    let dir = TempDir::new().unwrap();
+    let config_path = write_config(dir.path(), "");
+
+    let mut child = healer(&config_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stderr = child.stderr.take().unwrap();
+    let (line_tx, line_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if line_tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let warning = line_rx
+        .recv_timeout(Duration::from_secs(10))
+        .expect("healer should warn about the empty config");
+    assert!(
+        warning.contains("no enabled processes"),
+        "unexpected stderr line: {warning}"
+    );
+    // 只是警告：healer 仍在运行
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(
+        child.try_wait().unwrap().is_none(),
+        "healer should keep running"
+    );
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[test]
+fn require_processes_refuses_an_empty_config() {
+    let dir = TempDir::new().unwrap();
+    let config_path = write_config(dir.path(), "require_processes: true\n");
+
+    let output = healer(&config_path).output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(78), "stderr:\n{stderr}");
+    assert!(stderr.contains("require_processes"), "stderr:\n{stderr}");
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use healer::config::{AppConfig, ChildOutput, HookConfig, MonitorConfig, ProcessConfig};
+use healer::event_bus::create_event_sender;
+use healer::reaper;
+use healer::service_manager::ServiceManager;
+use healer::subscriber::child_registry::ChildRegistry;
+use healer::subscriber::process_healer::ProcessHealer;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Duration};
+
+// 回收服务作用于整个测试进程，因此这些测试单独放在一个文件（一个测试进程）中
+
+fn is_zombie(pid: u32) -> bool {
+    // /proc/<pid>/stat 中 comm 之后的第一个字段是状态，Z 表示已退出、尚未被回收
+    std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .ok()
+        .and_then(|stat| {
+            stat.rsplit_once(") ")
+                .map(|(_, rest)| rest.starts_with('Z'))
+        })
+        .unwrap_or(false)
+}
+
+// 持续执行回收，模拟 SIGCHLD 频繁到达时回收服务与等待方的竞争
+fn busy_reaper(children: ChildRegistry) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            ServiceManager::reap_zombies(&children);
+            sleep(Duration::from_millis(1)).await;
+        }
+    })
+}
+
+async fn healer_for(process: ProcessConfig, children: &ChildRegistry) -> ProcessHealer {
+    let config = AppConfig {
+        processes: vec![process],
+        ..Default::default()
+    };
+    let sender = create_event_sender();
+    ProcessHealer::new(sender.subscribe(), Arc::new(RwLock::new(config)))
+        .await
+        .with_children(children.clone())
+}
+
+async fn wait_for_zombie(pid: u32) {
+    for _ in 0..100 {
+        if is_zombie(pid) {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("child {pid} did not exit");
+}
+
+#[tokio::test]
+async fn exempted_child_keeps_its_exit_status() {
+    let mut command = tokio::process::Command::new("/bin/sh");
+    command.args(["-c", "exit 7"]);
+    let (mut child, _exemption) = reaper::spawn(|| command.spawn()).unwrap();
+    wait_for_zombie(child.id().unwrap()).await;
+
+    ServiceManager::reap_zombies(&ChildRegistry::new());
+    let status = child
+        .wait()
+        .await
+        .expect("an exempted child must be left to its waiter");
+    assert_eq!(status.code(), Some(7));
+}
+
+#[tokio::test]
+async fn other_children_are_still_reaped() {
+    let pid = std::process::Command::new("/bin/true")
+        .spawn()
+        .unwrap()
+        .id();
+    wait_for_zombie(pid).await;
+
+    // 其他测试登记的子进程排在前面时本轮回收会停下，稍后重试
+    for _ in 0..100 {
+        ServiceManager::reap_zombies(&ChildRegistry::new());
+        if !Path::new(&format!("/proc/{pid}")).exists() {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("zombie {pid} was not reaped");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recovery_guard_is_left_to_its_waiter() {
+    let children = ChildRegistry::new();
+    let reaping = busy_reaper(children.clone());
+    for attempt in 0..10 {
+        let process = ProcessConfig {
+            child_output: ChildOutput::Null,
+            recovery_guard: Some(HookConfig {
+                command: "/bin/true".into(),
+                args: vec![],
+                timeout_secs: 5,
+            }),
+            ..common::process(
+                "guarded",
+                MonitorConfig::Pid(common::pid_fields("/nonexistent/guarded.pid")),
+            )
+        };
+        let mut healer = healer_for(process, &children).await;
+        assert_eq!(
+            healer.heal_process(&"guarded".to_string()).await,
+            None,
+            "attempt {attempt}"
+        );
+    }
+    reaping.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fast_exiting_recovery_command_keeps_its_exit_status() {
+    let children = ChildRegistry::new();
+    let reaping = busy_reaper(children.clone());
+    for attempt in 0..10 {
+        let name = format!("fast_exit_{attempt}");
+        let process = ProcessConfig {
+            command: "/bin/sh".into(),
+            args: vec!["-c".into(), "exit 3".into()],
+            child_output: ChildOutput::Null,
+            ..common::process(
+                &name,
+                MonitorConfig::Pid(common::pid_fields("/nonexistent/fast_exit.pid")),
+            )
+        };
+        let mut healer = healer_for(process, &children).await;
+        assert_eq!(healer.heal_process(&name).await, None);
+        let exit = children
+            .get(&name)
+            .expect("the recovery command should be tracked")
+            .wait()
+            .await
+            .expect("the tracked child should report its exit");
+        assert_eq!(
+            exit.status.and_then(|status| status.code()),
+            Some(3),
+            "attempt {attempt}"
+        );
+    }
+    reaping.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn waited_recovery_command_keeps_its_exit_status() {
+    let children = ChildRegistry::new();
+    let reaping = busy_reaper(children.clone());
+    for attempt in 0..10 {
+        let name = format!("waited_{attempt}");
+        let process = ProcessConfig {
+            command: "/bin/true".into(),
+            args: vec![],
+            child_output: ChildOutput::Null,
+            wait_for_exit: true,
+            ..common::process(
+                &name,
+                MonitorConfig::Pid(common::pid_fields("/nonexistent/waited.pid")),
+            )
+        };
+        let mut healer = healer_for(process, &children).await;
+        assert_eq!(healer.heal_process(&name).await, None);
+        // 等待在后台进行期间豁免仍然有效，回收服务不会取走退出状态
+        healer.wait_for_recoveries().await;
+        assert_eq!(healer.last_skip_reason(&name), None, "attempt {attempt}");
+    }
+    reaping.abort();
+}
@@ -1,6 +1,7 @@
 // src/main.rs
 
 use std::env;
+use std::ffi::CString;
 use std::fs;
 use std::io::{self, Write};
 use std::path::PathBuf;
@@ -8,7 +9,64 @@ use std::process;
 use std::thread;
 use std::time::Duration;
 
-fn resolve_pid_file() -> PathBuf {
+// 命令行参数：--pid-file <路径>、--comm <进程名>，也接受 --pid-file=<路径> 的写法
+struct Options {
+    pid_file: Option<PathBuf>,
+    comm: Option<String>,
+}
+
+fn parse_options() -> Options {
+    let mut options = Options {
+        pid_file: None,
+        comm: None,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        if flag != "--pid-file" && flag != "--comm" {
+            continue;
+        }
+        let Some(value) = inline_value.or_else(|| args.next()) else {
+            eprintln!("错误：{} 需要一个参数", flag);
+            process::exit(2);
+        };
+        if flag == "--pid-file" {
+            options.pid_file = Some(PathBuf::from(value));
+        } else {
+            options.comm = Some(value);
+        }
+    }
+    options
+}
+
+// 通过 prctl(PR_SET_NAME) 设置自己的 comm，使 eBPF 测试可以按固定的进程名匹配；内核截断为 15 字节
+fn set_comm(comm: &str) {
+    let Ok(name) = CString::new(comm) else {
+        eprintln!("错误：--comm 不能包含 NUL 字符");
+        process::exit(2);
+    };
+    let ret = unsafe { libc::prctl(libc::PR_SET_NAME, name.as_ptr() as libc::c_ulong, 0, 0, 0) };
+    if ret != 0 {
+        eprintln!(
+            "错误：无法设置进程名 {}: {}",
+            comm,
+            io::Error::last_os_error()
+        );
+        process::exit(1);
+    }
+}
+
+// PID 文件路径优先级：--pid-file 参数 > HEALER_TEST_PID_FILE 环境变量 > HEALER_DEMO_BASE/run/simple_counter.pid
+fn resolve_pid_file(options: &Options) -> PathBuf {
+    if let Some(path) = &options.pid_file {
+        return path.clone();
+    }
+    if let Ok(path) = env::var("HEALER_TEST_PID_FILE") {
+        return PathBuf::from(path);
+    }
     let base = env::var("HEALER_DEMO_BASE").ok().map(PathBuf::from).unwrap_or_else(|| {
         std::env::current_dir()
             .map(|p| p.join("healer-demo"))
@@ -20,7 +78,12 @@ fn resolve_pid_file() -> PathBuf {
 fn main() {
     // 1. 获取并打印自己的进程ID (PID)
     //    这是最重要的信息，你的 healer 程序需要监控这个 PID
-    let pid_file_path = resolve_pid_file();
+    let options = parse_options();
+    // 先设置 comm 再写 PID 文件：PID 文件出现时进程名已经生效
+    if let Some(comm) = &options.comm {
+        set_comm(comm);
+    }
+    let pid_file_path = resolve_pid_file(&options);
     let my_pid = process::id();
     println!("测试进程已启动！PID: {}", my_pid);
     if let Some(parent_dir) = pid_file_path.parent() {